@@ -162,4 +162,12 @@ pub struct ClipboardEvent {
     pub file: Option<Vec<u8>>,
     // 文件路径   文件类型使用
     pub file_path_vec: Option<Vec<String>>,
+    // 富文本格式（Html/Rtf）  文本内容同时带有富文本格式时使用，content 保留纯文本
+    pub rich_type: Option<ClipType>,
+    // 富文本内容
+    pub rich_content: Option<String>,
+    // 复制来源标记为隐藏内容（密码管理器等设置的 ConcealedType / ExcludeClipboardContentFromMonitorProcessing 提示）
+    pub concealed: bool,
+    // 图片的原始格式（MIME类型，如 image/gif、image/apng），按原格式读取的动图使用，统一转为png的图片为None
+    pub image_format: Option<String>,
 }
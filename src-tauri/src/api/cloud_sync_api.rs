@@ -1,8 +1,13 @@
 use crate::{
     api::{api_get, api_post, api_post_with_timeout},
     biz::clip_record::ClipRecord,
-    utils::http_client::HttpError,
+    utils::{
+        aes_util::to_uncompressed_ciphertext,
+        device_info::GLOBAL_DEVICE_ID,
+        http_client::{HttpClient, HttpError},
+    },
 };
+use clipboard_listener::ClipType;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
@@ -44,6 +49,12 @@ pub struct ClipRecordParam {
     pub version: Option<i32>,
     // 是否逻辑删除
     pub del_flag: Option<i32>,
+    // 编号槽位（1-9）
+    #[serde(default)]
+    pub slot: Option<i32>,
+    // 标签，多个标签用逗号连接
+    #[serde(default)]
+    pub tags: Option<String>,
     // 本地文件地址
     #[serde(skip)]
     pub local_file_path: Option<String>,
@@ -68,8 +79,38 @@ impl ClipRecordParam {
             del_flag: self.del_flag,
             cloud_source: Some(0),
             skip_type: None,
+            del_time: None,
+            raw_content: None,
+            slot: self.slot,
+            file_modified: None,
+            file_created: None,
+            sensitive: None,
+            rich_type: None,
+            rich_content: None,
+            ocr_text: None,
+            favorite_flag: None,
+            source_app: None,
+            title: None,
+            use_count: None,
+            tags: self.tags.clone(),
+            meta_only_sync: None,
+            modified_time: None,
         }
     }
+
+    /// 转换为上传云端的参数，文本记录压缩存储的密文转换为未压缩格式，压缩只在本地生效
+    pub fn for_upload(record: ClipRecord) -> Self {
+        let mut param: ClipRecordParam = record.into();
+        if param.r#type.as_deref() == Some(ClipType::Text.to_string().as_str()) {
+            if let Value::String(content) = &param.content {
+                match to_uncompressed_ciphertext(content) {
+                    Ok(content) => param.content = Value::String(content),
+                    Err(e) => log::warn!("转换上传内容格式失败，按原内容上传: {}", e),
+                }
+            }
+        }
+        param
+    }
 }
 
 impl From<ClipRecord> for ClipRecordParam {
@@ -88,6 +129,8 @@ impl From<ClipRecord> for ClipRecordParam {
             device_id: record.device_id,
             version: record.version.into(),
             del_flag: record.del_flag.into(),
+            slot: record.slot,
+            tags: record.tags,
             local_file_path: record.local_file_path,
         }
     }
@@ -112,7 +155,19 @@ pub async fn sync_clipboard(
 // -------------------------------------------获取服务器时间--------------------------------------------------------------
 
 pub async fn sync_server_time() -> Result<Option<u64>, HttpError> {
-    api_get("clipPal-sync/public/now").await
+    api_get(SYNC_HEALTH_PATH).await
+}
+
+// -------------------------------------------检测同步服务连接--------------------------------------------------------------
+
+// 同步服务健康检查路径，使用不需要登录的获取服务器时间接口
+pub const SYNC_HEALTH_PATH: &str = "clipPal-sync/public/now";
+
+// 请求指定服务地址的健康检查接口，返回HTTP状态码（10秒超时）
+pub async fn check_sync_health(base_url: &str) -> Result<u16, HttpError> {
+    let url = format!("{}/{}", base_url.trim_end_matches('/'), SYNC_HEALTH_PATH);
+    let resp = HttpClient::new().timeout(10).get_raw::<Value>(&url).await?;
+    Ok(resp.status)
 }
 
 // -------------------------------------------处理单个记录的新增或者删除--------------------------------------------------
@@ -123,9 +178,39 @@ pub struct SingleCloudSyncResponse {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SingleCloudSyncParam {
     pub r#type: i32,
     pub clip: ClipRecordParam,
+    // 幂等键，同一条记录同一版本的同一操作重试时保持不变，服务端据此去重
+    pub idempotency_key: String,
+}
+
+impl SingleCloudSyncParam {
+    pub fn new(r#type: i32, clip: ClipRecordParam) -> Self {
+        let idempotency_key = Self::build_idempotency_key(
+            r#type,
+            clip.id.as_deref().unwrap_or_default(),
+            clip.version,
+        );
+        Self {
+            r#type,
+            clip,
+            idempotency_key,
+        }
+    }
+
+    /// 由设备id、记录id、版本号和操作类型生成幂等键，不直接暴露本地记录id
+    pub fn build_idempotency_key(r#type: i32, record_id: &str, version: Option<i32>) -> String {
+        let raw = format!(
+            "{}:{}:{}:{}",
+            GLOBAL_DEVICE_ID.as_str(),
+            record_id,
+            version.unwrap_or(0),
+            r#type
+        );
+        format!("{:x}", md5::compute(raw.as_bytes()))
+    }
 }
 
 pub async fn sync_single_clip_record(
@@ -191,3 +276,17 @@ pub async fn get_dowload_url(
 
     api_post("clipPal-sync/sync/getDownloadUrl", Some(record)).await
 }
+
+// ----------------------------------------------通知服务端清理过期的删除记录------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompactTombstoneParam {
+    // 删除时间早于该时间戳（毫秒）的删除记录可以被物理删除
+    pub before: u64,
+    pub device_id: String,
+}
+
+pub async fn compact_tombstones(param: &CompactTombstoneParam) -> Result<Option<bool>, HttpError> {
+    api_post("clipPal-sync/sync/compact", Some(param)).await
+}
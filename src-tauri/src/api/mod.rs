@@ -1,7 +1,9 @@
 #![allow(dead_code)]
 
+use crate::biz::system_setting::{check_request_signing_enabled, get_sync_base_url};
 use crate::utils::config::get_cloud_sync_domain;
 use crate::utils::http_client::{ApiResponse, HttpClient, HttpError};
+use crate::utils::request_signer::build_sign_headers;
 use crate::utils::token_manager::{get_valid_access_token, refresh_access_token};
 use std::collections::HashMap;
 use std::path::Path;
@@ -10,8 +12,11 @@ pub mod cloud_sync_api;
 pub mod user_auth_api;
 pub mod vip_api;
 
-/// 获取 API 域名
-fn get_api_domain() -> Result<String, HttpError> {
+/// 获取 API 域名，设置了自建同步服务地址时优先使用
+pub fn get_api_domain() -> Result<String, HttpError> {
+    if let Some(base_url) = get_sync_base_url() {
+        return Ok(base_url);
+    }
     get_cloud_sync_domain()
         .map(|s| s.to_string())
         .map_err(|e| HttpError::RequestFailed(format!("获取云同步请求域名失败: {}", e)))
@@ -59,26 +64,35 @@ where
         }
     };
 
-    let headers = get_common_headers(&token);
     let client = HttpClient::new().timeout(timeout_secs);
 
-    let resp: ApiResponse<T> = match method {
-        "GET" => {
-            let headers = get_common_headers_without_content_type(&token);
-            client
-                .request_with_headers("GET", &url, None::<&()>, Some(headers))
-                .await?
-        }
+    let (mut headers, body) = match method {
+        "GET" => (get_common_headers_without_content_type(&token), None),
         "POST" => {
-            client
-                .request_with_headers("POST", &url, payload, Some(headers))
-                .await?
+            let body = payload
+                .map(serde_json::to_string)
+                .transpose()
+                .map_err(|e| {
+                    HttpError::SerializationFailed(format!("序列化请求数据失败: {}", e))
+                })?;
+            (get_common_headers(&token), body)
         }
         _ => {
             return Err(HttpError::RequestFailed("不支持的HTTP方法".to_string()));
         }
     };
 
+    // 开启请求签名时，对请求方法、路径、时间戳、nonce和请求体原文计算HMAC签名
+    if check_request_signing_enabled() {
+        let sign_headers = build_sign_headers(method, path, body.as_deref().unwrap_or(""))
+            .map_err(|e| HttpError::RequestFailed(format!("请求签名失败: {}", e)))?;
+        headers.extend(sign_headers);
+    }
+
+    let resp: ApiResponse<T> = client
+        .request_json_with_headers(method, &url, body, Some(headers))
+        .await?;
+
     match resp.code {
         200 => Ok(resp.data),
         401 if retry_on_401 => {
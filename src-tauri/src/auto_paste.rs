@@ -1,4 +1,7 @@
 use crate::errors::{AppError, AppResult};
+#[cfg(target_os = "macos")]
+use crate::utils::sensitive_log::is_clipboard_sensitive;
+use serde::Serialize;
 
 #[cfg(windows)]
 use once_cell::sync::Lazy;
@@ -10,10 +13,20 @@ use once_cell::sync::Lazy;
 #[cfg(target_os = "macos")]
 use std::sync::{Arc, Mutex};
 
+#[cfg(target_os = "linux")]
+use once_cell::sync::Lazy;
+#[cfg(target_os = "linux")]
+use std::{process::Command, sync::Mutex};
+
+#[cfg(windows)]
+use windows::core::PWSTR;
 #[cfg(windows)]
 use windows::Win32::{
-    Foundation::HWND,
-    System::Threading::GetCurrentProcessId,
+    Foundation::{CloseHandle, HWND},
+    System::Threading::{
+        GetCurrentProcessId, OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    },
     UI::{
         Input::KeyboardAndMouse::{
             INPUT, INPUT_KEYBOARD, KEYBDINPUT, KEYEVENTF_KEYUP, SendInput, VK_CONTROL, VK_V,
@@ -25,6 +38,42 @@ use windows::Win32::{
     },
 };
 
+/// 自动粘贴的目标应用信息
+#[derive(Debug, Clone, Serialize)]
+pub struct PasteTarget {
+    // 应用名称
+    pub name: String,
+    // 进程ID
+    pub pid: i64,
+    // 窗口标题（仅Windows和Linux X11）
+    pub title: Option<String>,
+    // 目标应用是否仍在运行，为false时粘贴会失败
+    pub alive: bool,
+}
+
+/// 实际使用的自动粘贴方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PasteMethod {
+    // 模拟粘贴快捷键
+    KeyEvent,
+    // 通过辅助功能点击目标应用的“粘贴”菜单（仅macOS）
+    MenuItem,
+}
+
+/// 获取当前保存的自动粘贴目标
+#[tauri::command]
+pub fn get_paste_target() -> Option<PasteTarget> {
+    current_paste_target()
+}
+
+/// 清除保存的自动粘贴目标
+#[tauri::command]
+pub fn clear_paste_target() {
+    reset_paste_target();
+    log::info!("已清除自动粘贴目标");
+}
+
 #[cfg(windows)]
 static PREVIOUS_WINDOW: Lazy<Arc<Mutex<Option<WindowInfo>>>> =
     Lazy::new(|| Arc::new(Mutex::new(None)));
@@ -85,9 +134,80 @@ pub fn save_foreground_window() {
     }
 }
 
+/// 根据进程ID获取进程的可执行文件名
+#[cfg(windows)]
+fn process_name_by_pid(process_id: u32) -> Option<String> {
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id).ok()?;
+        let mut buffer = [0u16; 260];
+        let mut size = buffer.len() as u32;
+        let result = QueryFullProcessImageNameW(
+            handle,
+            PROCESS_NAME_WIN32,
+            PWSTR(buffer.as_mut_ptr()),
+            &mut size,
+        );
+        let _ = CloseHandle(handle);
+        result.ok()?;
+        let path = String::from_utf16_lossy(&buffer[..size as usize]);
+        std::path::Path::new(&path)
+            .file_stem()
+            .map(|name| name.to_string_lossy().to_string())
+    }
+}
+
+/// 获取当前前台应用的进程名，前台是ClipPal自己的窗口时返回None - Windows版本
+#[cfg(windows)]
+pub fn foreground_app_name() -> Option<String> {
+    let hwnd = unsafe { GetForegroundWindow() };
+    if hwnd.0.is_null() {
+        return None;
+    }
+    let mut process_id = 0u32;
+    unsafe {
+        GetWindowThreadProcessId(hwnd, Some(&mut process_id));
+    }
+    if process_id == 0 || process_id == unsafe { GetCurrentProcessId() } {
+        return None;
+    }
+    process_name_by_pid(process_id)
+}
+
+/// 获取保存的目标窗口，并检查窗口是否仍然属于原来的进程 - Windows版本
+#[cfg(windows)]
+fn current_paste_target() -> Option<PasteTarget> {
+    let window_info = PREVIOUS_WINDOW.lock().ok()?.clone()?;
+    let hwnd = HWND(window_info.hwnd as *mut std::ffi::c_void);
+
+    // 窗口句柄可能被系统复用，需要同时检查所属进程
+    let alive = unsafe {
+        let mut process_id = 0u32;
+        IsWindow(hwnd).as_bool()
+            && GetWindowThreadProcessId(hwnd, Some(&mut process_id)) != 0
+            && process_id == window_info.process_id
+    };
+    let name =
+        process_name_by_pid(window_info.process_id).unwrap_or_else(|| window_info.title.clone());
+
+    Some(PasteTarget {
+        name,
+        pid: window_info.process_id as i64,
+        title: Some(window_info.title),
+        alive,
+    })
+}
+
+/// 清除保存的目标窗口 - Windows版本
+#[cfg(windows)]
+fn reset_paste_target() {
+    if let Ok(mut previous) = PREVIOUS_WINDOW.lock() {
+        *previous = None;
+    }
+}
+
 /// 执行自动粘贴到之前的窗口 - Windows版本
 #[cfg(windows)]
-pub fn auto_paste_to_previous_window() -> AppResult<()> {
+pub fn auto_paste_to_previous_window() -> AppResult<PasteMethod> {
     let window_info = {
         let previous = PREVIOUS_WINDOW
             .lock()
@@ -134,7 +254,7 @@ pub fn auto_paste_to_previous_window() -> AppResult<()> {
     send_ctrl_v_windows()?;
 
     log::debug!("自动粘贴完成");
-    Ok(())
+    Ok(PasteMethod::KeyEvent)
 }
 
 /// 发送 Ctrl+V 按键组合 - Windows版本
@@ -266,9 +386,61 @@ pub fn save_foreground_window() {
     }
 }
 
+/// 获取保存的前台应用，并检查应用是否仍在运行 - macOS版本
+#[cfg(target_os = "macos")]
+fn current_paste_target() -> Option<PasteTarget> {
+    use cocoa::base::id;
+
+    let pid = (*PREVIOUS_APP_PID.lock().ok()?)?;
+
+    unsafe {
+        let cls = objc::class!(NSRunningApplication);
+        let app: id = msg_send![cls, runningApplicationWithProcessIdentifier: pid];
+
+        if app == nil {
+            return Some(PasteTarget {
+                name: format!("PID:{}", pid),
+                pid: pid as i64,
+                title: None,
+                alive: false,
+            });
+        }
+
+        let terminated: bool = msg_send![app, isTerminated];
+        let app_name: id = msg_send![app, localizedName];
+        let name = if app_name != nil {
+            let name_ptr: *const i8 = msg_send![app_name, UTF8String];
+            if !name_ptr.is_null() {
+                std::ffi::CStr::from_ptr(name_ptr)
+                    .to_string_lossy()
+                    .to_string()
+            } else {
+                format!("PID:{}", pid)
+            }
+        } else {
+            format!("PID:{}", pid)
+        };
+
+        Some(PasteTarget {
+            name,
+            pid: pid as i64,
+            title: None,
+            alive: !terminated,
+        })
+    }
+}
+
+/// 清除保存的前台应用 - macOS版本
+#[cfg(target_os = "macos")]
+fn reset_paste_target() {
+    if let Ok(mut previous) = PREVIOUS_APP_PID.lock() {
+        *previous = None;
+    }
+}
+
 /// 执行自动粘贴
 #[cfg(target_os = "macos")]
-pub fn auto_paste_to_previous_window() -> AppResult<()> {
+pub fn auto_paste_to_previous_window() -> AppResult<PasteMethod> {
     use crate::CONTEXT;
     use tauri::{AppHandle, Manager};
 
@@ -338,18 +510,192 @@ pub fn auto_paste_to_previous_window() -> AppResult<()> {
     }
 
     if !verified {
-        log::warn!("前台应用验证失败，但仍然尝试发送按键");
+        log::warn!("前台应用验证失败，但仍然尝试粘贴");
     }
 
     // 优化：缩短最后等待时间到 50ms
     std::thread::sleep(std::time::Duration::from_millis(50));
 
-    log::info!("开始执行粘贴操作 (使用 CGEvent)");
-    // 使用 CGEvent 发送 Cmd+V
-    send_cmd_v()?;
+    let method = paste_with_configured_method(saved_pid, verified)?;
 
-    log::info!("macOS 自动粘贴完成");
-    Ok(())
+    log::info!("macOS 自动粘贴完成，粘贴方式: {:?}", method);
+    Ok(method)
+}
+
+/// 按设置中的粘贴方式执行粘贴，返回实际使用的方式
+#[cfg(target_os = "macos")]
+fn paste_with_configured_method(
+    pid: Option<i32>,
+    frontmost_verified: bool,
+) -> AppResult<PasteMethod> {
+    use crate::biz::system_setting::{
+        get_paste_method, PASTE_METHOD_KEY_EVENT, PASTE_METHOD_MENU_ITEM,
+    };
+
+    match get_paste_method() {
+        PASTE_METHOD_KEY_EVENT => {
+            log::info!("开始执行粘贴操作 (使用 CGEvent)");
+            send_cmd_v()?;
+            Ok(PasteMethod::KeyEvent)
+        }
+        PASTE_METHOD_MENU_ITEM => {
+            let pid = pid.ok_or_else(|| {
+                AppError::AutoPaste("没有保存的目标应用，无法点击粘贴菜单".to_string())
+            })?;
+            trigger_paste_menu_item(pid)?;
+            Ok(PasteMethod::MenuItem)
+        }
+        _ => {
+            // 安全输入模式下按键事件会被系统拦截，前台应用不对时按键会发给错误的应用
+            // 这两种情况下优先直接点击目标应用的粘贴菜单
+            let secure_input = is_secure_event_input_enabled();
+            if let Some(pid) = pid {
+                if secure_input || !frontmost_verified {
+                    log::info!(
+                        "按键粘贴可能无效（安全输入: {}, 前台应用已验证: {}），尝试点击粘贴菜单",
+                        secure_input,
+                        frontmost_verified
+                    );
+                    match trigger_paste_menu_item(pid) {
+                        Ok(()) => return Ok(PasteMethod::MenuItem),
+                        Err(e) => log::warn!("点击粘贴菜单失败，改用按键粘贴: {}", e),
+                    }
+                }
+            }
+            log::info!("开始执行粘贴操作 (使用 CGEvent)");
+            send_cmd_v()?;
+            Ok(PasteMethod::KeyEvent)
+        }
+    }
+}
+
+/// 检查系统是否处于安全输入模式（密码框等获得焦点时），此时模拟的按键事件不会被目标应用接收
+#[cfg(target_os = "macos")]
+fn is_secure_event_input_enabled() -> bool {
+    #[link(name = "Carbon", kind = "framework")]
+    extern "C" {
+        fn IsSecureEventInputEnabled() -> bool;
+    }
+
+    unsafe { IsSecureEventInputEnabled() }
+}
+
+/// 读取辅助功能元素的属性，调用方持有返回值的所有权
+#[cfg(target_os = "macos")]
+unsafe fn ax_copy_attribute(
+    element: accessibility_sys::AXUIElementRef,
+    attribute: &'static str,
+) -> Option<core_foundation::base::CFType> {
+    use accessibility_sys::{kAXErrorSuccess, AXUIElementCopyAttributeValue};
+    use core_foundation::base::{CFType, CFTypeRef, TCFType};
+    use core_foundation::string::CFString;
+
+    let attribute = CFString::from_static_string(attribute);
+    let mut value: CFTypeRef = std::ptr::null();
+    let result =
+        AXUIElementCopyAttributeValue(element, attribute.as_concrete_TypeRef(), &mut value);
+    if result != kAXErrorSuccess || value.is_null() {
+        return None;
+    }
+    Some(CFType::wrap_under_create_rule(value))
+}
+
+/// 获取辅助功能元素的子元素
+#[cfg(target_os = "macos")]
+unsafe fn ax_children(
+    element: accessibility_sys::AXUIElementRef,
+) -> Vec<core_foundation::base::CFType> {
+    use accessibility_sys::kAXChildrenAttribute;
+    use core_foundation::array::{CFArrayGetCount, CFArrayGetTypeID, CFArrayGetValueAtIndex};
+    use core_foundation::base::{CFType, TCFType};
+
+    let Some(value) = ax_copy_attribute(element, kAXChildrenAttribute) else {
+        return Vec::new();
+    };
+    if value.type_of() != CFArrayGetTypeID() {
+        return Vec::new();
+    }
+    let array = value.as_CFTypeRef() as core_foundation::array::CFArrayRef;
+    (0..CFArrayGetCount(array))
+        .map(|index| CFType::wrap_under_get_rule(CFArrayGetValueAtIndex(array, index)))
+        .collect()
+}
+
+/// 判断菜单项的快捷键是否为 Cmd+V
+#[cfg(target_os = "macos")]
+unsafe fn is_paste_menu_item(item: accessibility_sys::AXUIElementRef) -> bool {
+    use accessibility_sys::{kAXMenuItemCmdCharAttribute, kAXMenuItemCmdModifiersAttribute};
+    use core_foundation::number::CFNumber;
+    use core_foundation::string::CFString;
+
+    let cmd_char = ax_copy_attribute(item, kAXMenuItemCmdCharAttribute)
+        .and_then(|value| value.downcast::<CFString>())
+        .map(|value| value.to_string());
+    if !cmd_char.is_some_and(|cmd_char| cmd_char.eq_ignore_ascii_case("v")) {
+        return false;
+    }
+    // 修饰键为0表示只有 Command 键，属性缺失时也按只有 Command 键处理
+    let modifiers = ax_copy_attribute(item, kAXMenuItemCmdModifiersAttribute)
+        .and_then(|value| value.downcast::<CFNumber>())
+        .and_then(|value| value.to_i64())
+        .unwrap_or(0);
+    modifiers == 0
+}
+
+/// 通过辅助功能点击目标应用中快捷键为 Cmd+V 的菜单项
+/// 按快捷键而不是菜单标题查找，不受应用界面语言影响
+#[cfg(target_os = "macos")]
+fn trigger_paste_menu_item(pid: i32) -> AppResult<()> {
+    use accessibility_sys::{
+        kAXErrorSuccess, kAXMenuBarAttribute, kAXPressAction, AXUIElementCreateApplication,
+        AXUIElementPerformAction, AXUIElementRef,
+    };
+    use core_foundation::base::{CFType, TCFType};
+    use core_foundation::string::CFString;
+
+    if !check_accessibility_permissions() {
+        return Err(AppError::AutoPaste(
+            "需要辅助功能权限才能执行自动粘贴。请在系统设置中授予权限。".to_string(),
+        ));
+    }
+
+    log::info!("通过辅助功能点击粘贴菜单 (PID: {})", pid);
+    unsafe {
+        let app_ref = AXUIElementCreateApplication(pid);
+        if app_ref.is_null() {
+            return Err(AppError::AutoPaste("无法访问目标应用".to_string()));
+        }
+        // 转为CFType管理引用计数
+        let app = CFType::wrap_under_create_rule(app_ref as _);
+        let menu_bar = ax_copy_attribute(app.as_CFTypeRef() as AXUIElementRef, kAXMenuBarAttribute)
+            .ok_or_else(|| AppError::AutoPaste("目标应用没有菜单栏".to_string()))?;
+
+        // 菜单栏项 -> 菜单 -> 菜单项
+        for bar_item in ax_children(menu_bar.as_CFTypeRef() as AXUIElementRef) {
+            for menu in ax_children(bar_item.as_CFTypeRef() as AXUIElementRef) {
+                for item in ax_children(menu.as_CFTypeRef() as AXUIElementRef) {
+                    let item_ref = item.as_CFTypeRef() as AXUIElementRef;
+                    if !is_paste_menu_item(item_ref) {
+                        continue;
+                    }
+                    let action = CFString::from_static_string(kAXPressAction);
+                    let result = AXUIElementPerformAction(item_ref, action.as_concrete_TypeRef());
+                    if result != kAXErrorSuccess {
+                        return Err(AppError::AutoPaste(format!(
+                            "点击粘贴菜单失败，错误码: {}",
+                            result
+                        )));
+                    }
+                    log::info!("已点击目标应用的粘贴菜单");
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    Err(AppError::AutoPaste(
+        "目标应用中没有找到粘贴菜单".to_string(),
+    ))
 }
 
 /// 根据PID激活应用
@@ -496,6 +842,12 @@ fn get_frontmost_app_name() -> Option<String> {
     None
 }
 
+/// 获取当前前台应用的名称 - macOS版本
+#[cfg(target_os = "macos")]
+pub fn foreground_app_name() -> Option<String> {
+    get_frontmost_app_name()
+}
+
 /// 检查辅助功能权限
 #[cfg(target_os = "macos")]
 fn check_accessibility_permissions() -> bool {
@@ -561,7 +913,9 @@ fn send_cmd_v() -> AppResult<()> {
     }
 
     // 检查剪贴板内容
-    if let Some(content) = check_clipboard_content() {
+    if is_clipboard_sensitive() {
+        log::info!("剪贴板内容来自敏感记录，不输出预览");
+    } else if let Some(content) = check_clipboard_content() {
         let preview = if content.len() > 50 {
             format!("{}...", &content[..50])
         } else {
@@ -624,16 +978,242 @@ fn send_cmd_v() -> AppResult<()> {
     Ok(())
 }
 
+// Linux 下使用的显示服务器
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DisplayServer {
+    X11,
+    Wayland,
+}
+
+#[cfg(target_os = "linux")]
+static PREVIOUS_WINDOW: Lazy<Mutex<Option<LinuxWindowInfo>>> = Lazy::new(|| Mutex::new(None));
+
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone)]
+struct LinuxWindowInfo {
+    // X11 窗口ID，Wayland 下无法获取其他应用的窗口，为None
+    window_id: Option<String>,
+    title: Option<String>,
+    pid: Option<u32>,
+    display_server: DisplayServer,
+}
+
+/// 根据环境变量判断当前会话使用的显示服务器
+#[cfg(target_os = "linux")]
+fn detect_display_server() -> DisplayServer {
+    let session_type = std::env::var("XDG_SESSION_TYPE").unwrap_or_default();
+    if session_type.eq_ignore_ascii_case("wayland") || std::env::var_os("WAYLAND_DISPLAY").is_some()
+    {
+        DisplayServer::Wayland
+    } else {
+        DisplayServer::X11
+    }
+}
+
+/// 在 PATH 中查找命令行工具
+#[cfg(target_os = "linux")]
+fn find_tool(name: &str) -> Option<std::path::PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+    std::env::split_paths(&paths)
+        .map(|dir| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// 执行 xdotool 命令并返回标准输出
+#[cfg(target_os = "linux")]
+fn run_xdotool(args: &[&str]) -> AppResult<String> {
+    let output = Command::new("xdotool")
+        .args(args)
+        .output()
+        .map_err(|e| AppError::AutoPaste(format!("执行xdotool失败: {}", e)))?;
+    if !output.status.success() {
+        return Err(AppError::AutoPaste(format!(
+            "xdotool执行失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// 读取进程名称
+#[cfg(target_os = "linux")]
+fn process_name_by_pid(pid: u32) -> Option<String> {
+    std::fs::read_to_string(format!("/proc/{}/comm", pid))
+        .ok()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+/// 获取当前前台应用的进程名 - Linux版本
+/// 只支持X11（包括XWayland应用），Wayland原生应用无法获取
+#[cfg(target_os = "linux")]
+pub fn foreground_app_name() -> Option<String> {
+    if find_tool("xdotool").is_none() || std::env::var_os("DISPLAY").is_none() {
+        return None;
+    }
+    let pid: u32 = run_xdotool(&["getactivewindow", "getwindowpid"])
+        .ok()?
+        .parse()
+        .ok()?;
+    if pid == std::process::id() {
+        return None;
+    }
+    process_name_by_pid(pid)
+}
+
+/// 保存当前获得焦点的窗口信息 - Linux版本
+/// X11 通过 xdotool 获取活动窗口；Wayland 不允许获取其他应用的窗口，粘贴时依赖合成器把焦点还给上一个窗口
+#[cfg(target_os = "linux")]
+pub fn save_foreground_window() {
+    let display_server = detect_display_server();
+    let mut info = LinuxWindowInfo {
+        window_id: None,
+        title: None,
+        pid: None,
+        display_server,
+    };
+
+    // Wayland 下的 XWayland 应用仍然可以通过 xdotool 获取
+    if find_tool("xdotool").is_some() && std::env::var_os("DISPLAY").is_some() {
+        match run_xdotool(&["getactivewindow"]) {
+            Ok(window_id) if !window_id.is_empty() => {
+                info.pid = run_xdotool(&["getwindowpid", window_id.as_str()])
+                    .ok()
+                    .and_then(|pid| pid.parse().ok());
+                // 焦点在ClipPal自己的窗口上时不保存
+                if info.pid == Some(std::process::id()) {
+                    log::debug!("当前焦点窗口是ClipPal，不保存");
+                    return;
+                }
+                info.title = run_xdotool(&["getwindowname", window_id.as_str()]).ok();
+                info.window_id = Some(window_id);
+            }
+            Ok(_) => {}
+            Err(e) => log::debug!("获取活动窗口失败: {}", e),
+        }
+    }
+
+    log::debug!("保存目标窗口: {:?}", info);
+    if let Ok(mut previous) = PREVIOUS_WINDOW.lock() {
+        *previous = Some(info);
+    }
+}
+
+/// 获取保存的目标窗口 - Linux版本
+#[cfg(target_os = "linux")]
+fn current_paste_target() -> Option<PasteTarget> {
+    let info = PREVIOUS_WINDOW.lock().ok()?.clone()?;
+    let pid = info.pid?;
+    let name = process_name_by_pid(pid)
+        .or_else(|| info.title.clone())
+        .unwrap_or_else(|| format!("PID:{}", pid));
+
+    Some(PasteTarget {
+        name,
+        pid: pid as i64,
+        title: info.title,
+        alive: std::path::Path::new(&format!("/proc/{}", pid)).exists(),
+    })
+}
+
+/// 清除保存的目标窗口 - Linux版本
+#[cfg(target_os = "linux")]
+fn reset_paste_target() {
+    if let Ok(mut previous) = PREVIOUS_WINDOW.lock() {
+        *previous = None;
+    }
+}
+
+/// 执行自动粘贴到之前的窗口 - Linux版本
+/// X11 使用 xdotool 激活窗口并发送 Ctrl+V，Wayland 使用 ydotool 发送 Ctrl+V
+#[cfg(target_os = "linux")]
+pub fn auto_paste_to_previous_window() -> AppResult<PasteMethod> {
+    let info = PREVIOUS_WINDOW
+        .lock()
+        .map_err(|e| AppError::Lock(format!("获取窗口信息锁失败: {}", e)))?
+        .clone();
+    let display_server = info
+        .as_ref()
+        .map(|info| info.display_server)
+        .unwrap_or_else(detect_display_server);
+    let has_xdotool = find_tool("xdotool").is_some() && std::env::var_os("DISPLAY").is_some();
+    let has_ydotool = find_tool("ydotool").is_some();
+
+    // 保存了X11窗口时先激活该窗口
+    if let Some(window_id) = info.as_ref().and_then(|info| info.window_id.as_deref()) {
+        if has_xdotool {
+            if let Err(e) = run_xdotool(&["windowactivate", "--sync", window_id]) {
+                log::warn!("激活目标窗口失败: {}", e);
+            }
+        }
+    }
+
+    // 等待一小段时间让窗口切换完成
+    std::thread::sleep(std::time::Duration::from_millis(50));
+
+    match display_server {
+        DisplayServer::Wayland if has_ydotool => send_ctrl_v_ydotool()?,
+        // Wayland 下没有ydotool时，XWayland 应用仍然可以接收 xdotool 发送的按键
+        _ if has_xdotool => {
+            run_xdotool(&["key", "--clearmodifiers", "ctrl+v"])?;
+        }
+        DisplayServer::X11 if has_ydotool => send_ctrl_v_ydotool()?,
+        _ => {
+            log::warn!("未找到xdotool或ydotool，无法自动粘贴");
+            return Err(AppError::AutoPaste(
+                "自动粘贴需要安装xdotool（X11）或ydotool（Wayland）".to_string(),
+            ));
+        }
+    }
+
+    log::debug!("自动粘贴完成");
+    Ok(PasteMethod::KeyEvent)
+}
+
+/// 通过 ydotool 发送 Ctrl+V（需要 ydotoold 服务在运行）
+#[cfg(target_os = "linux")]
+fn send_ctrl_v_ydotool() -> AppResult<()> {
+    // 29 为 KEY_LEFTCTRL，47 为 KEY_V
+    let output = Command::new("ydotool")
+        .args(["key", "29:1", "47:1", "47:0", "29:0"])
+        .output()
+        .map_err(|e| AppError::AutoPaste(format!("执行ydotool失败: {}", e)))?;
+    if !output.status.success() {
+        return Err(AppError::AutoPaste(format!(
+            "ydotool执行失败，请确认ydotoold服务已启动: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
 /// 不支持平台的占位实现
-#[cfg(not(any(windows, target_os = "macos")))]
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
 pub fn save_foreground_window() {
-    log::warn!("自动粘贴功能仅支持 Windows 和 macOS 平台");
+    log::warn!("自动粘贴功能仅支持 Windows、macOS 和 Linux 平台");
+}
+
+/// 不支持平台的占位实现
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+pub fn foreground_app_name() -> Option<String> {
+    None
 }
 
 /// 不支持平台的占位实现
-#[cfg(not(any(windows, target_os = "macos")))]
-pub fn auto_paste_to_previous_window() -> AppResult<()> {
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+fn current_paste_target() -> Option<PasteTarget> {
+    None
+}
+
+/// 不支持平台的占位实现
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+fn reset_paste_target() {}
+
+/// 不支持平台的占位实现
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+pub fn auto_paste_to_previous_window() -> AppResult<PasteMethod> {
     Err(AppError::AutoPaste(
-        "自动粘贴功能仅在Windows和macOS平台支持".to_string(),
+        "自动粘贴功能仅在Windows、macOS和Linux平台支持".to_string(),
     ))
 }
@@ -0,0 +1,280 @@
+use std::future::Future;
+
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use rbatis::RBatis;
+use serde::Serialize;
+use tokio::sync::watch;
+use tokio::time::{sleep, Duration, Instant};
+
+use crate::{
+    biz::{clip_async_queue::AsyncQueue, clip_record::ClipRecord},
+    CONTEXT,
+};
+
+// 后台任务名称
+pub const TASK_CLOUD_SYNC: &str = "cloud_sync";
+pub const TASK_FILE_UPLOAD: &str = "file_upload";
+pub const TASK_FILE_DOWNLOAD: &str = "file_download";
+pub const TASK_CLIP_QUEUE: &str = "clip_queue";
+
+// 超过 间隔 * 倍数 + 宽限时间 仍未执行下一轮，认为任务可能已经停止
+static STALL_INTERVAL_FACTOR: u64 = 3;
+static STALL_GRACE_MILLIS: u64 = 60 * 1000;
+
+// 任务异常退出后重启的最大等待时间（秒）
+static RESTART_MAX_BACKOFF_SECS: u64 = 60;
+
+// 任务持续运行超过这个时间后再退出，重新从最短等待时间开始重启
+static RESTART_BACKOFF_RESET_SECS: u64 = 5 * 60;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackgroundTaskStatus {
+    pub name: String,
+    // 任务循环的间隔（秒）
+    pub interval_secs: u64,
+    // 最近一轮开始执行的时间戳（毫秒）
+    pub last_tick: Option<u64>,
+    // 最近一轮执行结束的时间戳（毫秒）
+    pub last_finished: Option<u64>,
+    // 是否正在执行
+    pub running: bool,
+    // 是否长时间没有执行下一轮（任务可能已经停止或卡住）
+    pub stalled: bool,
+    // 异常退出后被重启的次数
+    pub restart_count: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackgroundTasksStatus {
+    pub tasks: Vec<BackgroundTaskStatus>,
+    // 待同步队列中的记录数
+    pub queue_len: usize,
+    pub queue_capacity: Option<usize>,
+    // 后台任务是否已暂停（开发者模式）
+    pub paused: bool,
+}
+
+static TASK_STATUS: Lazy<DashMap<&'static str, BackgroundTaskStatus>> = Lazy::new(DashMap::new);
+
+// 正在上传或下载文件的记录，记录ID -> 所属任务（TASK_FILE_UPLOAD / TASK_FILE_DOWNLOAD）
+static ACTIVE_FILE_TRANSFERS: Lazy<DashMap<String, &'static str>> = Lazy::new(DashMap::new);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncQueueStatus {
+    // 等待上传文件的本地记录数（包括正在上传的）
+    pub pending_upload: i64,
+    // 等待下载文件的云端记录数（包括正在下载的）
+    pub pending_download: i64,
+    // 正在上传文件的记录ID
+    pub uploading: Vec<String>,
+    // 正在下载文件的记录ID
+    pub downloading: Vec<String>,
+}
+
+// 后台任务是否暂停，暂停时受监管的任务被取消，恢复后重新启动
+static TASKS_PAUSED: Lazy<watch::Sender<bool>> = Lazy::new(|| watch::channel(false).0);
+
+/// 暂停或恢复所有受监管的后台任务
+pub fn set_background_tasks_paused(paused: bool) {
+    if TASKS_PAUSED.send_replace(paused) != paused {
+        log::info!("后台任务已{}", if paused { "暂停" } else { "恢复" });
+    }
+}
+
+/// 后台任务是否已暂停
+pub fn is_background_tasks_paused() -> bool {
+    *TASKS_PAUSED.borrow()
+}
+
+fn current_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn new_task_status(name: &'static str, interval_secs: u64) -> BackgroundTaskStatus {
+    BackgroundTaskStatus {
+        name: name.to_string(),
+        interval_secs,
+        last_tick: None,
+        last_finished: None,
+        running: false,
+        stalled: false,
+        restart_count: 0,
+    }
+}
+
+/// 登记后台任务（任务启动时调用），任务被重启时保留重启次数
+pub fn register_background_task(name: &'static str, interval_secs: u64) {
+    TASK_STATUS
+        .entry(name)
+        .and_modify(|status| {
+            status.interval_secs = interval_secs;
+            status.running = false;
+        })
+        .or_insert_with(|| new_task_status(name, interval_secs));
+}
+
+fn record_task_restart(name: &'static str) {
+    TASK_STATUS
+        .entry(name)
+        .or_insert_with(|| new_task_status(name, 0))
+        .restart_count += 1;
+}
+
+/// 以受监管的方式启动长期运行的后台任务
+/// 任务panic或意外结束时记录日志，并按指数退避等待后重新启动
+/// 后台任务暂停时取消正在运行的任务，恢复后重新启动
+pub fn spawn_supervised<F, Fut>(name: &'static str, task_factory: F)
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut paused_rx = TASKS_PAUSED.subscribe();
+        let mut backoff_secs = 1;
+        loop {
+            if *paused_rx.borrow_and_update() {
+                log::info!("后台任务 {} 已暂停，等待恢复", name);
+                let resumed = paused_rx.wait_for(|paused| !*paused).await.is_ok();
+                if !resumed {
+                    return;
+                }
+                log::info!("后台任务 {} 已恢复", name);
+            }
+
+            let started = Instant::now();
+            let mut handle = tokio::spawn(task_factory());
+            let result = tokio::select! {
+                result = &mut handle => result,
+                _ = async {
+                    let _ = paused_rx.wait_for(|paused| *paused).await;
+                } => {
+                    handle.abort();
+                    continue;
+                }
+            };
+            match result {
+                Ok(()) => log::warn!("后台任务 {} 意外结束", name),
+                Err(e) if e.is_panic() => {
+                    let panic = e.into_panic();
+                    let message = panic
+                        .downcast_ref::<&str>()
+                        .map(|s| s.to_string())
+                        .or_else(|| panic.downcast_ref::<String>().cloned())
+                        .unwrap_or_default();
+                    log::error!("后台任务 {} 发生panic: {}", name, message);
+                }
+                Err(e) => {
+                    log::info!("后台任务 {} 已取消: {}", name, e);
+                    return;
+                }
+            }
+
+            if started.elapsed() >= Duration::from_secs(RESTART_BACKOFF_RESET_SECS) {
+                backoff_secs = 1;
+            }
+            record_task_restart(name);
+            log::warn!("后台任务 {} 将在{}秒后重启", name, backoff_secs);
+            sleep(Duration::from_secs(backoff_secs)).await;
+            backoff_secs = (backoff_secs * 2).min(RESTART_MAX_BACKOFF_SECS);
+        }
+    });
+}
+
+/// 记录后台任务开始新一轮执行，返回的 Guard 离开作用域时（包括 panic）标记本轮结束
+pub fn begin_task_tick(name: &'static str) -> TaskTickGuard {
+    if let Some(mut status) = TASK_STATUS.get_mut(name) {
+        status.last_tick = Some(current_millis());
+        status.running = true;
+    }
+    TaskTickGuard(name)
+}
+
+pub struct TaskTickGuard(&'static str);
+
+impl Drop for TaskTickGuard {
+    fn drop(&mut self) {
+        if let Some(mut status) = TASK_STATUS.get_mut(self.0) {
+            status.last_finished = Some(current_millis());
+            status.running = false;
+        }
+    }
+}
+
+/// 记录开始上传或下载文件，返回的 Guard 离开作用域时移除
+pub fn begin_file_transfer(task: &'static str, record_id: &str) -> FileTransferGuard {
+    ACTIVE_FILE_TRANSFERS.insert(record_id.to_string(), task);
+    FileTransferGuard(record_id.to_string())
+}
+
+pub struct FileTransferGuard(String);
+
+impl Drop for FileTransferGuard {
+    fn drop(&mut self) {
+        ACTIVE_FILE_TRANSFERS.remove(&self.0);
+    }
+}
+
+fn active_file_transfers(task: &'static str) -> Vec<String> {
+    let mut ids: Vec<String> = ACTIVE_FILE_TRANSFERS
+        .iter()
+        .filter(|entry| *entry.value() == task)
+        .map(|entry| entry.key().clone())
+        .collect();
+    ids.sort();
+    ids
+}
+
+/// 获取文件上传、下载队列的状态：等待上传和下载的记录数，以及正在处理的记录
+#[tauri::command]
+pub async fn get_sync_queue_status() -> Result<SyncQueueStatus, String> {
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let counts = ClipRecord::count_file_sync_queue(rb).await.map_err(|e| {
+        log::error!("统计文件同步队列失败: {}", e);
+        format!("获取同步队列状态失败: {}", e)
+    })?;
+
+    Ok(SyncQueueStatus {
+        pending_upload: counts.pending_upload,
+        pending_download: counts.pending_download,
+        uploading: active_file_transfers(TASK_FILE_UPLOAD),
+        downloading: active_file_transfers(TASK_FILE_DOWNLOAD),
+    })
+}
+
+/// 获取后台定时任务和同步队列的运行状态
+#[tauri::command]
+pub fn get_background_tasks_status() -> BackgroundTasksStatus {
+    let now = current_millis();
+    let paused = is_background_tasks_paused();
+    let mut tasks: Vec<BackgroundTaskStatus> = TASK_STATUS
+        .iter()
+        .map(|entry| {
+            let mut status = entry.value().clone();
+            let stall_after =
+                status.interval_secs * 1000 * STALL_INTERVAL_FACTOR + STALL_GRACE_MILLIS;
+            status.stalled = !paused
+                && status
+                    .last_tick
+                    .map(|last_tick| now.saturating_sub(last_tick) > stall_after)
+                    .unwrap_or(false);
+            status
+        })
+        .collect();
+    tasks.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let (queue_len, queue_capacity) = match CONTEXT.try_get::<AsyncQueue<ClipRecord>>() {
+        Some(queue) => (queue.len(), queue.capacity()),
+        None => (0, None),
+    };
+
+    BackgroundTasksStatus {
+        tasks,
+        queue_len,
+        queue_capacity,
+        paused,
+    }
+}
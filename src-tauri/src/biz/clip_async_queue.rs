@@ -1,13 +1,17 @@
 #![allow(dead_code)]
 
 use async_channel::{bounded, Receiver, Sender, TryRecvError};
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
 use rbatis::RBatis;
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter};
-use tokio::task;
 use tokio::time::{sleep, Duration};
 
 use crate::api::cloud_sync_api::{sync_single_clip_record, ClipRecordParam, SingleCloudSyncParam};
+use crate::biz::background_task_status::{
+    begin_task_tick, register_background_task, spawn_supervised, TASK_CLIP_QUEUE,
+};
 use crate::biz::clip_record::{
     ClipRecord, NOT_SYNCHRONIZED, SKIP_SYNC, SYNCHRONIZED, SYNCHRONIZING,
 };
@@ -15,10 +19,26 @@ use crate::biz::vip_checker::VipChecker;
 use crate::errors::{AppError, AppResult};
 use crate::utils::file_dir::get_resources_dir;
 use crate::utils::lock_utils::GlobalSyncLock;
+use crate::utils::sensitive_log::with_content_log_suppressed;
 use crate::CONTEXT;
 use clipboard_listener::ClipType;
 use std::path::PathBuf;
 
+// 记录最多保存的最近同步尝试数，超过后清空重新记录
+const MAX_SYNC_ATTEMPTS: usize = 1000;
+
+/// 单条记录最近一次同步尝试
+#[derive(Clone, Debug)]
+struct SyncAttempt {
+    // 尝试时使用的幂等键
+    idempotency_key: String,
+    // 同步成功后的状态，为None表示尚未确认成功
+    acked_status: Option<i32>,
+}
+
+// 记录id -> 最近一次同步尝试，相同状态已确认成功时不再重复发送
+static SYNC_ATTEMPTS: Lazy<DashMap<String, SyncAttempt>> = Lazy::new(DashMap::new);
+
 #[derive(Clone, Debug)]
 pub enum QueueEvent<T> {
     Add(T),
@@ -76,79 +96,95 @@ impl<T: Clone + Send + 'static> AsyncQueue<T> {
     }
 }
 
+/// 启动同步队列消费任务
 pub fn consume_clip_record_queue(queue: AsyncQueue<ClipRecord>) {
-    task::spawn(async move {
-        let sync_lock: &GlobalSyncLock = CONTEXT.get::<GlobalSyncLock>();
-
-        loop {
-            // 先尝试拿锁，拿不到就等待一会儿再重试
-            if let Some(_guard) = sync_lock.try_lock() {
-                log::debug!("开始处理同步队列");
-
-                // 循环接收并处理队列数据
-                loop {
-                    match queue.try_recv() {
-                        Ok(event) => {
-                            // 处理数据
-                            match event {
-                                QueueEvent::Add(item) => {
-                                    let param = SingleCloudSyncParam {
-                                        r#type: 1,
-                                        clip: item.clone().into(),
-                                    };
-                                    let res = handle_sync_inner(param.clone()).await;
-                                    if let Ok(final_status) = res {
-                                        // 根据实际处理结果通知前端
-                                        notify_frontend_sync_status_with_flag(
-                                            vec![item.id],
-                                            final_status,
-                                        )
-                                        .await;
-                                    }
+    spawn_supervised(TASK_CLIP_QUEUE, move || {
+        run_clip_record_queue(queue.clone())
+    });
+}
+
+/// 同步队列消费主循环
+async fn run_clip_record_queue(queue: AsyncQueue<ClipRecord>) {
+    let sync_lock: &GlobalSyncLock = CONTEXT.get::<GlobalSyncLock>();
+    register_background_task(TASK_CLIP_QUEUE, 1);
+
+    loop {
+        let tick = begin_task_tick(TASK_CLIP_QUEUE);
+
+        // 先尝试拿锁，拿不到就等待一会儿再重试
+        if let Some(_guard) = sync_lock.try_lock() {
+            log::debug!("开始处理同步队列");
+
+            // 循环接收并处理队列数据
+            loop {
+                match queue.try_recv() {
+                    Ok(event) => {
+                        // 处理数据
+                        match event {
+                            QueueEvent::Add(item) => {
+                                let param = SingleCloudSyncParam::new(
+                                    1,
+                                    ClipRecordParam::for_upload(item.clone()),
+                                );
+                                // 敏感记录的同步请求不输出内容日志
+                                let res = with_content_log_suppressed(
+                                    item.is_sensitive(),
+                                    handle_sync_inner(param.clone()),
+                                )
+                                .await;
+                                if let Ok(final_status) = res {
+                                    // 根据实际处理结果通知前端
+                                    notify_frontend_sync_status_with_flag(
+                                        vec![item.id],
+                                        final_status,
+                                    )
+                                    .await;
                                 }
-                                QueueEvent::Delete(item) => {
-                                    let param = SingleCloudSyncParam {
-                                        r#type: 2,
-                                        clip: item.clone().into(),
-                                    };
-                                    let rb: &RBatis = CONTEXT.get::<RBatis>();
-                                    let record = ClipRecord::select_by_id(rb, &item.id).await;
-                                    match record {
-                                        Ok(rec) => {
-                                            if !rec.is_empty() && rec[0].del_flag == Some(0) {
-                                                // 说明这个记录现在不是已删除状态了
-                                                break;
-                                            }
+                            }
+                            QueueEvent::Delete(item) => {
+                                let param = SingleCloudSyncParam::new(2, item.clone().into());
+                                let rb: &RBatis = CONTEXT.get::<RBatis>();
+                                let record = ClipRecord::select_by_id(rb, &item.id).await;
+                                match record {
+                                    Ok(rec) => {
+                                        if !rec.is_empty() && rec[0].del_flag == Some(0) {
+                                            // 说明这个记录现在不是已删除状态了
+                                            break;
                                         }
-                                        Err(e) => {
-                                            log::error!(
-                                                "同步已删除记录时，检查已删除记录状态出现异常：{}",
-                                                e
-                                            )
-                                        }
-                                    };
-                                    let _ = handle_sync_inner(param).await;
-                                }
-                            };
-                        }
-                        Err(TryRecvError::Empty) => {
-                            // 队列空了，跳出内层循环，释放锁
-                            log::debug!("同步队列处理完成");
-                            break;
-                        }
-                        Err(e) => {
-                            log::error!("队列消息处理错误: {}", e);
-                            break;
-                        }
+                                    }
+                                    Err(e) => {
+                                        log::error!(
+                                            "同步已删除记录时，检查已删除记录状态出现异常：{}",
+                                            e
+                                        )
+                                    }
+                                };
+                                let _ = with_content_log_suppressed(
+                                    item.is_sensitive(),
+                                    handle_sync_inner(param),
+                                )
+                                .await;
+                            }
+                        };
+                    }
+                    Err(TryRecvError::Empty) => {
+                        // 队列空了，跳出内层循环，释放锁
+                        log::debug!("同步队列处理完成");
+                        break;
+                    }
+                    Err(e) => {
+                        log::error!("队列消息处理错误: {}", e);
+                        break;
                     }
                 }
-            } else {
-                // 锁被占用，短暂休眠避免忙等
-                log::debug!("同步锁被占用，等待重试");
             }
-            sleep(Duration::from_millis(500)).await;
+        } else {
+            // 锁被占用，短暂休眠避免忙等
+            log::debug!("同步锁被占用，等待重试");
         }
-    });
+        drop(tick);
+        sleep(Duration::from_millis(500)).await;
+    }
 }
 
 async fn handle_sync_inner(param: SingleCloudSyncParam) -> AppResult<i32> {
@@ -192,6 +228,27 @@ async fn handle_sync_inner(param: SingleCloudSyncParam) -> AppResult<i32> {
         }
     }
 
+    // 相同状态（幂等键相同）已经同步成功过，不再重复发送
+    if let Some(attempt) = SYNC_ATTEMPTS.get(&record_id) {
+        if attempt.idempotency_key == param.idempotency_key {
+            if let Some(status) = attempt.acked_status {
+                log::debug!("记录 {} 当前状态已同步，跳过重复发送", record_id);
+                return Ok(status);
+            }
+            log::info!("重试同步记录 {}，使用相同的幂等键", record_id);
+        }
+    }
+    if SYNC_ATTEMPTS.len() >= MAX_SYNC_ATTEMPTS {
+        SYNC_ATTEMPTS.clear();
+    }
+    SYNC_ATTEMPTS.insert(
+        record_id.clone(),
+        SyncAttempt {
+            idempotency_key: param.idempotency_key.clone(),
+            acked_status: None,
+        },
+    );
+
     // 执行实际同步
     match sync_single_clip_record(&param).await {
         Ok(Some(success)) => {
@@ -199,6 +256,9 @@ async fn handle_sync_inner(param: SingleCloudSyncParam) -> AppResult<i32> {
             let final_status = determine_final_sync_status(&record_type, &param.clip).await;
 
             update_sync_status(rb, &record_id, final_status, success.timestamp).await?;
+            if let Some(mut attempt) = SYNC_ATTEMPTS.get_mut(&record_id) {
+                attempt.acked_status = Some(final_status);
+            }
 
             log::info!(
                 "同步成功: 记录ID={}, 类型={}, 状态={}",
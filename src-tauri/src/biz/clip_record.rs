@@ -1,6 +1,9 @@
 #![allow(dead_code)]
 
+use crate::biz::preview_cache::{invalidate_preview_cache, invalidate_preview_cache_by_ids};
 use crate::errors::{AppError, AppResult};
+use crate::utils::retry_helper::retry_on_db_locked;
+use clipboard_listener::ClipType;
 use rbatis::{crud, impl_select, Error, RBatis};
 use rbs::to_value;
 use serde::{Deserialize, Serialize};
@@ -11,6 +14,75 @@ pub static SYNCHRONIZING: i32 = 1; // 同步中
 pub static SYNCHRONIZED: i32 = 2; // 已同步
 pub static SKIP_SYNC: i32 = 3; // 不支持同步（多文件、超大文件等）
 
+pub static SKIP_TYPE_SYNC_TEXT_LIMIT: i32 = 3; // 文本超过同步大小限制，调大限制后可再次同步
+pub static SKIP_TYPE_STALE_TRANSFER: i32 = 4; // 同步长时间卡住或传输失败
+
+pub static DEL_FLAG_OVER_LIMIT: i32 = 2; // VIP降级后超出记录数限制，宽限期内隐藏
+
+/// 记录的同步状态，由 sync_flag 和 skip_type 组合得出，返回给前端展示
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncStatus {
+    // 等待同步
+    Pending,
+    // 同步中
+    Syncing,
+    // 已同步
+    Synced,
+    // 多文件等不支持同步的内容
+    SkippedUnsupported,
+    // 超过VIP同步大小限制
+    SkippedTooLarge,
+    // 文本超过设置中的同步大小限制
+    SkippedTextLimit,
+    // 同步长时间卡住或传输失败
+    SkippedStale,
+}
+
+impl SyncStatus {
+    pub fn from_flags(sync_flag: Option<i32>, skip_type: Option<i32>) -> Self {
+        match sync_flag {
+            Some(flag) if flag == SYNCHRONIZING => SyncStatus::Syncing,
+            Some(flag) if flag == SYNCHRONIZED => SyncStatus::Synced,
+            Some(flag) if flag == SKIP_SYNC => match skip_type {
+                Some(1) => SyncStatus::SkippedUnsupported,
+                Some(skip_type) if skip_type == SKIP_TYPE_SYNC_TEXT_LIMIT => {
+                    SyncStatus::SkippedTextLimit
+                }
+                Some(skip_type) if skip_type == SKIP_TYPE_STALE_TRANSFER => {
+                    SyncStatus::SkippedStale
+                }
+                // skip_type为空时是上传阶段发现文件超过大小限制
+                _ => SyncStatus::SkippedTooLarge,
+            },
+            _ => SyncStatus::Pending,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SyncStatus::Pending => "等待同步",
+            SyncStatus::Syncing => "同步中",
+            SyncStatus::Synced => "已同步",
+            SyncStatus::SkippedUnsupported => "跳过：不支持同步的内容",
+            SyncStatus::SkippedTooLarge => "跳过：超过同步大小限制",
+            SyncStatus::SkippedTextLimit => "跳过：文本超过设置的同步大小",
+            SyncStatus::SkippedStale => "跳过：同步传输失败",
+        }
+    }
+}
+
+/// 云端拉取的记录在本地列表中的插入位置
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CloudInsertPosition {
+    // 按同步时间合并到对应位置，较新的记录会排到最前面
+    BySyncTime,
+    // 按同步时间合并，但不会排到当前第一条记录之前
+    BelowTop,
+    // 插入到列表最后，不影响已有记录的顺序
+    Bottom,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
 pub struct ClipRecord {
     pub id: String,
@@ -38,12 +110,42 @@ pub struct ClipRecord {
     pub device_id: Option<String>,
     // 云同步版本号（预留字段）
     pub version: Option<i32>,
-    // 是否逻辑删除 0:未删除 1:已删除
+    // 是否逻辑删除 0:未删除 1:已删除 2:超出VIP记录数限制被隐藏
     pub del_flag: Option<i32>,
     // 是否是云端同步下来的数据
     pub cloud_source: Option<i32>,
     // 跳过云同步的原因类型  跳过后是否可以再次尝试同步 （None：不是跳过的，1：不支持再次同步，2：vip限制，可再次同步）
     pub skip_type: Option<i32>,
+    // 逻辑删除时间戳（毫秒），用于清理超过保留期的删除记录
+    pub del_time: Option<u64>,
+    // 清理不可见字符前的原始文本（加密），仅在开启保留原始文本且内容被清理时存在
+    pub raw_content: Option<String>,
+    // 编号槽位（1-9），对应编号快速粘贴，每个槽位只能分配给一条记录
+    pub slot: Option<i32>,
+    // 单文件记录原文件的修改时间和创建时间（毫秒），粘贴时还原到临时文件
+    pub file_modified: Option<u64>,
+    pub file_created: Option<u64>,
+    // 是否为敏感记录 0:否 1:是，敏感记录的内容不输出到日志，列表中默认隐藏内容（仅本地生效，不同步）
+    pub sensitive: Option<i32>,
+    // 文本记录同时复制到的富文本格式（Html/Rtf）及其内容（加密），粘贴时与纯文本一起写入剪贴板（仅本地保存，不同步）
+    pub rich_type: Option<String>,
+    pub rich_content: Option<String>,
+    // 图片记录OCR识别出的文字（加密），用于搜索图片内容（仅本地保存，不同步）
+    pub ocr_text: Option<String>,
+    // 是否收藏 0:否 1:是，可以同时收藏多条记录，收藏的记录不会被自动清理（仅本地生效，不同步）
+    pub favorite_flag: Option<i32>,
+    // 复制时的前台应用名称，用于审计记录来源（仅本地保存，不同步）
+    pub source_app: Option<String>,
+    // 内容为单个网址的文本记录对应网页的标题，复制后在后台获取（仅本地保存，不同步）
+    pub title: Option<String>,
+    // 从历史记录复制到剪贴板的次数，用于常用记录排序（仅本地生效，不同步）
+    pub use_count: Option<i32>,
+    // 记录的标签，多个标签用逗号连接，随云同步到其他设备
+    pub tags: Option<String>,
+    // 已同步的图片、文件记录只修改了标签等元数据时为1，下次同步只上传元数据，不重新上传文件（仅本地生效，不同步）
+    pub meta_only_sync: Option<i32>,
+    // 本地最后一次修改置顶、排序、标签的时间（毫秒），按最新修改优先处理冲突时使用（仅本地生效，不同步）
+    pub modified_time: Option<u64>,
 }
 
 crud!(ClipRecord {}, "clip_record");
@@ -54,29 +156,82 @@ impl_select!(ClipRecord{select_where_order_by_limit(content: &str, limit:i32, of
 //  根据limit和offset 查询   获取limit条数据(-1表示全部)   跳过前offset条数据
 impl_select!(ClipRecord{select_order_by_limit(limit:i32, offset:i32) =>"` where del_flag = 0 order by pinned_flag desc, sort desc, created desc limit #{limit} offset #{offset}`"});
 // 根据type和content 查看是否有重复的    有的话取出一个
-impl_select!(ClipRecord{check_by_type_and_md5(content_type:&str, md5_str:&str) =>"`where type = #{content_type} and md5_str = #{md5_str} limit 1`"});
+impl_select!(ClipRecord{check_by_type_and_md5(content_type:&str, md5_str:&str) =>"`where type = #{content_type} and md5_str = #{md5_str} order by (del_flag is null or del_flag = 0) desc, created desc limit 1`"});
 impl_select!(ClipRecord{check_by_type_and_md5_active(content_type:&str, md5_str:&str) =>"`where type = #{content_type} and md5_str = #{md5_str} and (del_flag is null or del_flag = 0) limit 1`"});
 // 取出最大的sort数据
 impl_select!(ClipRecord{select_max_sort() =>"`order by sort desc, created desc limit 1`"});
 // 根据sync_flag查询记录
 impl_select!(ClipRecord{select_by_sync_flag(sync_flag: i32) =>"`where sync_flag = #{sync_flag} and content IS NOT NULL order by created desc`"});
 // 根据sync_flag查询记录
-impl_select!(ClipRecord{select_by_sync_flag_limit(sync_flag: i32, cloud_source:i32, limit: i32) =>"`where sync_flag = #{sync_flag} and cloud_source = #{cloud_source} order by created desc limit #{limit}`"});
+impl_select!(ClipRecord{select_by_sync_flag_limit(sync_flag: i32, cloud_source:i32, limit: i32) =>"`where sync_flag = #{sync_flag} and cloud_source = #{cloud_source} and (del_flag is null or del_flag != 2) order by created desc limit #{limit}`"});
 // 根据created时间戳查询下一条记录
 impl_select!(ClipRecord{select_order_by_created(created: u64) =>"`where created >= #{created} order by created desc limit 1`"});
 // 查询已经逻辑删除并且已同步的数据
 impl_select!(ClipRecord{select_invalid() =>"`where sync_flag = 2 and del_flag = 1`"});
+// 查询删除时间早于指定时间的删除记录
+impl_select!(ClipRecord{select_expired_tombstones(before: u64) =>"`where del_flag = 1 and del_time < #{before} and sync_flag != 1`"});
+// 查询删除时间早于指定时间、且已经同步完成的删除记录（开启云同步时未同步的删除需要先同步到云端）
+impl_select!(ClipRecord{select_expired_synced_tombstones(before: u64) =>"`where del_flag = 1 and del_time < #{before} and sync_flag in (2, 3)`"});
+// 查询创建时间早于指定时间且未置顶、未收藏的有效记录，用于按时间自动过期
+impl_select!(ClipRecord{select_expired_unpinned(before: u64) =>"`where del_flag = 0 and pinned_flag = 0 and IFNULL(favorite_flag, 0) = 0 and created < #{before}`"});
+// 查询删除时间不早于指定时间的删除记录，最近删除的排在前面
+impl_select!(ClipRecord{select_recently_deleted(after: u64, limit: i32, offset: i32) =>"`where del_flag = 1 and del_time >= #{after} order by del_time desc limit #{limit} offset #{offset}`"});
+// 按类型分页查询记录（包括已删除的记录）
+impl_select!(ClipRecord{select_by_type_limit(content_type: &str, limit: i32, offset: i32) =>"`where type = #{content_type} order by id limit #{limit} offset #{offset}`"});
 // 根据sync_flag和skip_type查询记录
 impl_select!(ClipRecord{select_by_sync_flag_and_skip_type(sync_flag: i32, skip_type: i32) =>"`where sync_flag = #{sync_flag} and skip_type = #{skip_type} and del_flag = 0`"});
+// 查询收藏的有效记录
+impl_select!(ClipRecord{select_by_favorite() =>"`where favorite_flag = 1 and del_flag = 0 order by sort desc, created desc`"});
+// 按复制次数倒序查询使用过的记录，次数相同时按排序和创建时间
+impl_select!(ClipRecord{select_order_by_use_count(limit: i32) =>"`where del_flag = 0 and use_count > 0 order by use_count desc, sort desc, created desc limit #{limit}`"});
+// 按标签分页查询有效记录，标签需要完全匹配
+impl_select!(ClipRecord{select_by_tag(tag: &str, limit: i32, offset: i32) =>"`where del_flag = 0 and instr(',' || tags || ',', ',' || #{tag} || ',') > 0 order by pinned_flag desc, sort desc, created desc limit #{limit} offset #{offset}`"});
+// 查询最近复制的有效记录，不考虑置顶，用于依次粘贴最近几条记录
+impl_select!(ClipRecord{select_recent(limit: i32) =>"`where del_flag = 0 order by sort desc, created desc limit #{limit}`"});
+// 根据编号槽位查询记录
+impl_select!(ClipRecord{select_by_slot(slot: i32) =>"`where slot = #{slot} and del_flag = 0 limit 1`"});
 
 impl ClipRecord {
+    pub fn is_sensitive(&self) -> bool {
+        self.sensitive.unwrap_or(0) == 1
+    }
+
+    pub fn is_favorite(&self) -> bool {
+        self.favorite_flag.unwrap_or(0) == 1
+    }
+
     pub async fn update_content(rb: &RBatis, id: &str, content: &str) -> AppResult<()> {
-        let sql = "UPDATE clip_record SET content = ? WHERE id = ?";
-        let tx = rb.acquire_begin().await?;
-        let _ = tx.exec(sql, vec![to_value!(content), to_value!(id)]).await;
-        tx.commit()
-            .await
-            .map_err(|e| AppError::Database(rbatis::Error::from(e)))
+        // 内容被编辑后原始文本不再对应，一并清除
+        let sql = "UPDATE clip_record SET content = ?, raw_content = NULL WHERE id = ?";
+        exec_in_tx(rb, vec![(sql, vec![to_value!(content), to_value!(id)])]).await?;
+        // 内容已变化，缓存的解密内容失效
+        invalidate_preview_cache(id);
+        Ok(())
+    }
+
+    /// 把槽位分配给指定记录，槽位原来的记录会被取消分配
+    /// 已同步的记录改为待同步，让槽位变化同步到云端
+    pub async fn assign_slot(rb: &RBatis, id: &str, slot: i32) -> AppResult<()> {
+        exec_in_tx(
+            rb,
+            vec![
+                (
+                    "UPDATE clip_record SET slot = NULL, sync_flag = CASE WHEN sync_flag = 2 THEN 0 ELSE sync_flag END, version = IFNULL(version, 0) + 1 WHERE slot = ? AND id != ?",
+                    vec![to_value!(slot), to_value!(id)],
+                ),
+                (
+                    "UPDATE clip_record SET slot = ?, sync_flag = CASE WHEN sync_flag = 2 THEN 0 ELSE sync_flag END, version = IFNULL(version, 0) + 1 WHERE id = ?",
+                    vec![to_value!(slot), to_value!(id)],
+                ),
+            ],
+        )
+        .await
+    }
+
+    /// 取消槽位的分配
+    pub async fn clear_slot(rb: &RBatis, slot: i32) -> AppResult<()> {
+        let sql = "UPDATE clip_record SET slot = NULL, sync_flag = CASE WHEN sync_flag = 2 THEN 0 ELSE sync_flag END, version = IFNULL(version, 0) + 1 WHERE slot = ?";
+        exec_in_tx(rb, vec![(sql, vec![to_value!(slot)])]).await
     }
 
     pub async fn get_next_sort(rb: &RBatis) -> i32 {
@@ -87,31 +242,192 @@ impl ClipRecord {
             .unwrap_or(0)
     }
 
-    pub async fn update_sort(rb: &RBatis, id: &str, sort: i32) -> AppResult<()> {
-        // 更新排序的时候，同时也要给版本号自增1
-        let sql = "UPDATE clip_record SET sort = ?, version = IFNULL(version, 0) + 1 WHERE id = ?";
-        let tx = rb.acquire_begin().await?;
-        let _ = tx.exec(sql, vec![to_value!(sort), to_value!(id)]).await;
-        tx.commit()
+    /// 获取当前最大的排序值
+    pub async fn max_sort(rb: &RBatis) -> i32 {
+        ClipRecord::select_max_sort(rb)
             .await
-            .map_err(|e| AppError::Database(rbatis::Error::from(e)))
+            .ok()
+            .and_then(|records| records.first().map(|r| r.sort))
+            .unwrap_or(0)
+    }
+
+    /// 把所有记录的排序值重新编号为从0开始的连续值，保持原有顺序（置顶通过pinned_flag单独排序，不受影响）
+    /// 只整理本地的排序，不修改版本号，不需要同步
+    pub async fn normalize_sort(rb: &RBatis) -> AppResult<()> {
+        exec_in_tx(
+            rb,
+            vec![
+                ("DROP TABLE IF EXISTS temp.sort_map", vec![]),
+                (
+                    "CREATE TEMP TABLE sort_map (id TEXT PRIMARY KEY, new_sort INTEGER NOT NULL)",
+                    vec![],
+                ),
+                (
+                    "INSERT INTO sort_map (id, new_sort) SELECT id, ROW_NUMBER() OVER (ORDER BY sort ASC, created ASC, id ASC) - 1 FROM clip_record",
+                    vec![],
+                ),
+                (
+                    "UPDATE clip_record SET sort = (SELECT new_sort FROM sort_map WHERE sort_map.id = clip_record.id)",
+                    vec![],
+                ),
+                ("DROP TABLE temp.sort_map", vec![]),
+            ],
+        )
+        .await
+    }
+
+    pub async fn update_sort(rb: &RBatis, id: &str, sort: i32) -> AppResult<()> {
+        // 更新排序的时候，同时也要给版本号自增1，并记录本地修改时间
+        let sql = "UPDATE clip_record SET sort = ?, modified_time = ?, version = IFNULL(version, 0) + 1 WHERE id = ?";
+        exec_in_tx(
+            rb,
+            vec![(
+                sql,
+                vec![to_value!(sort), to_value!(current_millis()), to_value!(id)],
+            )],
+        )
+        .await
+    }
+
+    /// 设置记录的创建时间（毫秒），导入的记录使用在原软件中的复制时间
+    pub async fn update_created(rb: &RBatis, id: &str, created: u64) -> AppResult<()> {
+        let sql = "UPDATE clip_record SET created = ? WHERE id = ?";
+        exec_in_tx(rb, vec![(sql, vec![to_value!(created), to_value!(id)])]).await
+    }
+
+    /// 保存文本记录的富文本格式，rich_content 为加密后的内容
+    pub async fn update_rich_content(
+        rb: &RBatis,
+        id: &str,
+        rich_type: &str,
+        rich_content: &str,
+    ) -> AppResult<()> {
+        let sql = "UPDATE clip_record SET rich_type = ?, rich_content = ? WHERE id = ?";
+        exec_in_tx(
+            rb,
+            vec![(
+                sql,
+                vec![to_value!(rich_type), to_value!(rich_content), to_value!(id)],
+            )],
+        )
+        .await
+    }
+
+    /// 保存图片记录的OCR文字，ocr_text 为加密后的内容，仅本地生效，不修改版本号
+    pub async fn update_ocr_text(rb: &RBatis, id: &str, ocr_text: &str) -> AppResult<()> {
+        let sql = "UPDATE clip_record SET ocr_text = ? WHERE id = ?";
+        exec_in_tx(rb, vec![(sql, vec![to_value!(ocr_text), to_value!(id)])]).await
+    }
+
+    /// 复制次数加一，仅本地生效，不修改版本号、排序和同步状态
+    pub async fn increment_use_count(rb: &RBatis, id: &str) -> AppResult<()> {
+        let sql = "UPDATE clip_record SET use_count = IFNULL(use_count, 0) + 1 WHERE id = ?";
+        exec_in_tx(rb, vec![(sql, vec![to_value!(id)])]).await
+    }
+
+    /// 修改记录的标签，tags 为None时清空标签
+    /// 已同步的记录改为待同步，让标签变化同步到云端
+    /// 已同步的图片、文件记录的文件已在云端，只标记元数据待同步，不重新上传文件
+    /// 同时记录本地修改时间，按最新修改优先处理冲突时与云端比较
+    pub async fn update_tags(rb: &RBatis, id: &str, tags: Option<&str>) -> AppResult<()> {
+        let sql = "UPDATE clip_record SET tags = ?, modified_time = ?, meta_only_sync = CASE WHEN sync_flag = 2 AND type IN (?, ?) THEN 1 ELSE meta_only_sync END, sync_flag = CASE WHEN sync_flag = 2 THEN 0 ELSE sync_flag END, version = IFNULL(version, 0) + 1 WHERE id = ?";
+        let params = vec![
+            to_value!(tags),
+            to_value!(current_millis()),
+            to_value!(ClipType::Image.to_string()),
+            to_value!(ClipType::File.to_string()),
+            to_value!(id),
+        ];
+        exec_in_tx(rb, vec![(sql, params)]).await
+    }
+
+    /// 采用云端记录的标签，不修改版本号和同步状态
+    pub async fn apply_cloud_tags(rb: &RBatis, id: &str, tags: Option<&str>) -> AppResult<()> {
+        let sql = "UPDATE clip_record SET tags = ? WHERE id = ?";
+        exec_in_tx(rb, vec![(sql, vec![to_value!(tags), to_value!(id)])]).await
+    }
+
+    /// 查询所有有效记录的标签列
+    pub async fn select_all_tags(rb: &RBatis) -> Result<Vec<String>, Error> {
+        let rows: Vec<TagsRow> = rb
+            .query_decode(
+                "SELECT tags FROM clip_record WHERE del_flag = 0 AND tags IS NOT NULL AND tags != ''",
+                vec![],
+            )
+            .await?;
+        Ok(rows.into_iter().filter_map(|row| row.tags).collect())
+    }
+
+    /// 查询标签中包含关键词的有效记录ID（不区分大小写）
+    pub async fn select_ids_by_tag_keyword(
+        rb: &RBatis,
+        keyword: &str,
+    ) -> Result<Vec<String>, Error> {
+        let rows: Vec<IdRow> = rb
+            .query_decode(
+                "SELECT id FROM clip_record WHERE del_flag = 0 AND tags IS NOT NULL AND instr(lower(tags), lower(?)) > 0",
+                vec![to_value!(keyword)],
+            )
+            .await?;
+        Ok(rows.into_iter().map(|row| row.id).collect())
+    }
+
+    /// 按列表顺序查询全部有效记录的ID，用于在固定的快照上分批读取记录
+    pub async fn select_active_ids(rb: &RBatis) -> Result<Vec<String>, Error> {
+        let rows: Vec<IdRow> = rb
+            .query_decode(
+                "SELECT id FROM clip_record WHERE del_flag = 0 ORDER BY pinned_flag DESC, sort DESC, created DESC",
+                vec![],
+            )
+            .await?;
+        Ok(rows.into_iter().map(|row| row.id).collect())
+    }
+
+    /// 保存网址记录的网页标题，仅本地生效，不修改版本号
+    pub async fn update_title(rb: &RBatis, id: &str, title: &str) -> AppResult<()> {
+        let sql = "UPDATE clip_record SET title = ? WHERE id = ?";
+        exec_in_tx(rb, vec![(sql, vec![to_value!(title), to_value!(id)])]).await
+    }
+
+    /// 保存记录的来源应用，仅本地生效，不修改版本号
+    pub async fn update_source_app(rb: &RBatis, id: &str, source_app: &str) -> AppResult<()> {
+        let sql = "UPDATE clip_record SET source_app = ? WHERE id = ?";
+        exec_in_tx(rb, vec![(sql, vec![to_value!(source_app), to_value!(id)])]).await
+    }
+
+    /// 设置敏感标记，仅本地生效，不修改版本号
+    pub async fn update_sensitive(rb: &RBatis, id: &str, sensitive: i32) -> AppResult<()> {
+        let sql = "UPDATE clip_record SET sensitive = ? WHERE id = ?";
+        exec_in_tx(rb, vec![(sql, vec![to_value!(sensitive), to_value!(id)])]).await
+    }
+
+    /// 设置收藏标记，可以同时收藏多条记录，仅本地生效，不修改版本号
+    pub async fn update_favorite(rb: &RBatis, id: &str, favorite_flag: i32) -> AppResult<()> {
+        let sql = "UPDATE clip_record SET favorite_flag = ? WHERE id = ?";
+        exec_in_tx(
+            rb,
+            vec![(sql, vec![to_value!(favorite_flag), to_value!(id)])],
+        )
+        .await
     }
 
     pub async fn update_pinned(rb: &RBatis, id: &str, pinned_flag: i32) -> AppResult<()> {
-        let sql =
-            "UPDATE clip_record SET pinned_flag = ?, version = IFNULL(version, 0) + 1 WHERE id = ?";
-        let tx = rb.acquire_begin().await?;
+        let sql = "UPDATE clip_record SET pinned_flag = ?, modified_time = ?, version = IFNULL(version, 0) + 1 WHERE id = ?";
+        let mut statements = Vec::new();
         if pinned_flag == 1 {
             // 置顶某一条的时候  先把其他的置顶都取消
             let sql1 = "UPDATE clip_record SET pinned_flag = 0 WHERE pinned_flag = 1";
-            let _ = tx.exec(sql1, vec![]).await;
+            statements.push((sql1, vec![]));
         }
-        let _ = tx
-            .exec(sql, vec![to_value!(pinned_flag), to_value!(id)])
-            .await;
-        tx.commit()
-            .await
-            .map_err(|e| AppError::Database(rbatis::Error::from(e)))
+        statements.push((
+            sql,
+            vec![
+                to_value!(pinned_flag),
+                to_value!(current_millis()),
+                to_value!(id),
+            ],
+        ));
+        exec_in_tx(rb, statements).await
     }
 
     pub async fn update_sync_flag(
@@ -121,30 +437,20 @@ impl ClipRecord {
         sync_time: u64,
     ) -> AppResult<()> {
         let sql = format!(
-            "UPDATE clip_record SET sync_flag = ?, sync_time = ? WHERE id in ({})",
+            "UPDATE clip_record SET sync_flag = ?, sync_time = ?, meta_only_sync = NULL WHERE id in ({})",
             ids.iter().map(|_| "?").collect::<Vec<_>>().join(",")
         );
         let mut args = vec![to_value!(sync_flag), to_value!(sync_time)];
         for id in ids {
             args.push(to_value!(id));
         }
-        let tx = rb.acquire_begin().await?;
-        tx.exec(&sql, args).await?;
-        tx.commit()
-            .await
-            .map_err(|e| AppError::Database(rbatis::Error::from(e)))
+        exec_in_tx(rb, vec![(sql.as_str(), args)]).await
     }
 
     /// 更新local_file_path字段
     pub async fn update_local_file_path(rb: &RBatis, id: &str, local_path: &str) -> AppResult<()> {
         let sql = "UPDATE clip_record SET local_file_path = ? WHERE id = ?";
-        let tx = rb.acquire_begin().await?;
-        let _ = tx
-            .exec(sql, vec![to_value!(local_path), to_value!(id)])
-            .await;
-        tx.commit()
-            .await
-            .map_err(|e| AppError::Database(rbatis::Error::from(e)))
+        exec_in_tx(rb, vec![(sql, vec![to_value!(local_path), to_value!(id)])]).await
     }
 
     /// 更新云文件下载后的记录状态
@@ -157,20 +463,13 @@ impl ClipRecord {
         let sql =
             "UPDATE clip_record SET content = ?, local_file_path = ?, sync_flag = ? WHERE id = ?";
 
-        let tx = rb.acquire_begin().await?;
-        tx.exec(
-            sql,
-            vec![
-                to_value!(filename),
-                to_value!(absolute_path),
-                to_value!(SYNCHRONIZED),
-                to_value!(id),
-            ],
-        )
-        .await?;
-        tx.commit()
-            .await
-            .map_err(|e| AppError::Database(rbatis::Error::from(e)))
+        let params = vec![
+            to_value!(filename),
+            to_value!(absolute_path),
+            to_value!(SYNCHRONIZED),
+            to_value!(id),
+        ];
+        exec_in_tx(rb, vec![(sql, params)]).await
     }
 
     /// 获取已逻辑删除且已同步的数据数量
@@ -203,16 +502,34 @@ impl ClipRecord {
     /// 逻辑删除 并标记为待同步状态
     pub async fn update_del_by_ids(rb: &RBatis, ids: &Vec<String>) -> AppResult<()> {
         let sql = format!(
-            "UPDATE clip_record SET del_flag = 1, sync_flag = 0 WHERE id IN ({})",
+            "UPDATE clip_record SET del_flag = 1, sync_flag = 0, del_time = ? WHERE id IN ({})",
             ids.iter().map(|_| "?").collect::<Vec<_>>().join(",")
         );
-        let tx = rb.acquire_begin().await?;
         // 转换ids为Vec<Value>
-        let params = ids.into_iter().map(|id| to_value!(id)).collect::<Vec<_>>();
-        let _ = tx.exec(&sql, params).await?;
-        tx.commit()
-            .await
-            .map_err(|e| AppError::Database(rbatis::Error::from(e)))
+        let mut params = ids.into_iter().map(|id| to_value!(id)).collect::<Vec<_>>();
+        params.insert(0, to_value!(current_millis()));
+        exec_in_tx(rb, vec![(sql.as_str(), params)]).await?;
+        invalidate_preview_cache_by_ids(ids);
+        Ok(())
+    }
+
+    /// 撤销逻辑删除，恢复删除前的同步状态
+    pub async fn restore_deleted(rb: &RBatis, id: &str, sync_flag: i32) -> AppResult<()> {
+        let sql =
+            "UPDATE clip_record SET del_flag = 0, sync_flag = ?, del_time = NULL WHERE id = ?";
+        exec_in_tx(rb, vec![(sql, vec![to_value!(sync_flag), to_value!(id)])]).await
+    }
+
+    /// 批量恢复逻辑删除的记录，并标记为待同步状态（不支持同步的记录保持跳过状态）
+    /// 恢复时递增版本号，让恢复后的新增与删除前已同步的新增使用不同的幂等键
+    pub async fn restore_by_ids(rb: &RBatis, ids: &Vec<String>) -> AppResult<()> {
+        let sql = format!(
+            "UPDATE clip_record SET del_flag = 0, sync_flag = CASE WHEN skip_type = 1 THEN ? ELSE ? END, del_time = NULL, version = IFNULL(version, 0) + 1 WHERE del_flag = 1 AND id IN ({})",
+            ids.iter().map(|_| "?").collect::<Vec<_>>().join(",")
+        );
+        let mut params = vec![to_value!(SKIP_SYNC), to_value!(NOT_SYNCHRONIZED)];
+        params.extend(ids.iter().map(|id| to_value!(id)));
+        exec_in_tx(rb, vec![(sql.as_str(), params)]).await
     }
 
     /// 更新已删除记录的所有字段（相当于创建新记录但保持原ID）
@@ -221,8 +538,7 @@ impl ClipRecord {
         id: &str,
         new_record: &ClipRecord,
     ) -> AppResult<()> {
-        let sql = "UPDATE clip_record SET type = ?, content = ?, md5_str = ?, local_file_path = ?, created = ?, os_type = ?, sort = ?, pinned_flag = ?, sync_flag = ?, sync_time = ?, device_id = ?, version = ?, del_flag = ?, cloud_source = ? WHERE id = ?";
-        let tx = rb.acquire_begin().await?;
+        let sql = "UPDATE clip_record SET type = ?, content = ?, md5_str = ?, local_file_path = ?, created = ?, os_type = ?, sort = ?, pinned_flag = ?, sync_flag = ?, sync_time = ?, device_id = ?, version = ?, del_flag = ?, cloud_source = ?, raw_content = ?, slot = ?, file_modified = ?, file_created = ?, sensitive = ?, rich_type = ?, rich_content = ?, ocr_text = ?, favorite_flag = ?, source_app = ?, title = ?, use_count = ?, tags = ?, meta_only_sync = ?, modified_time = ? WHERE id = ?";
         let params = vec![
             to_value!(&new_record.r#type),
             to_value!(&new_record.content),
@@ -238,12 +554,41 @@ impl ClipRecord {
             to_value!(&new_record.version),
             to_value!(&new_record.del_flag),
             to_value!(&new_record.cloud_source),
+            to_value!(&new_record.raw_content),
+            to_value!(&new_record.slot),
+            to_value!(&new_record.file_modified),
+            to_value!(&new_record.file_created),
+            to_value!(&new_record.sensitive),
+            to_value!(&new_record.rich_type),
+            to_value!(&new_record.rich_content),
+            to_value!(&new_record.ocr_text),
+            to_value!(&new_record.favorite_flag),
+            to_value!(&new_record.source_app),
+            to_value!(&new_record.title),
+            to_value!(&new_record.use_count),
+            to_value!(&new_record.tags),
+            to_value!(&new_record.meta_only_sync),
+            to_value!(&new_record.modified_time),
             to_value!(id),
         ];
-        let _ = tx.exec(sql, params).await?;
-        tx.commit()
-            .await
-            .map_err(|e| AppError::Database(rbatis::Error::from(e)))
+        exec_in_tx(rb, vec![(sql, params)]).await?;
+        // 记录被新内容替换，缓存的解密内容失效
+        invalidate_preview_cache(id);
+        Ok(())
+    }
+
+    /// 按冲突策略采用云端的置顶状态，不修改版本号和同步状态
+    /// 排序值只在本设备内有意义（会被重新编号），不采用云端的排序
+    /// 云端记录置顶时先取消本地其他记录的置顶
+    pub async fn apply_cloud_state(rb: &RBatis, id: &str, pinned_flag: i32) -> AppResult<()> {
+        let sql = "UPDATE clip_record SET pinned_flag = ? WHERE id = ?";
+        let mut statements = Vec::new();
+        if pinned_flag == 1 {
+            let sql1 = "UPDATE clip_record SET pinned_flag = 0 WHERE pinned_flag = 1 AND id != ?";
+            statements.push((sql1, vec![to_value!(id)]));
+        }
+        statements.push((sql, vec![to_value!(pinned_flag), to_value!(id)]));
+        exec_in_tx(rb, statements).await
     }
 
     /// 更新sync_flag和skip_type
@@ -259,16 +604,12 @@ impl ClipRecord {
             "UPDATE clip_record SET sync_flag = ?, skip_type = NULL, version = IFNULL(version, 0) + 1 WHERE id = ?"
         };
 
-        let tx = rb.acquire_begin().await?;
         let params = if let Some(st) = skip_type {
             vec![to_value!(sync_flag), to_value!(st), to_value!(id)]
         } else {
             vec![to_value!(sync_flag), to_value!(id)]
         };
-        let _ = tx.exec(sql, params).await?;
-        tx.commit()
-            .await
-            .map_err(|e| AppError::Database(rbatis::Error::from(e)))
+        exec_in_tx(rb, vec![(sql, params)]).await
     }
 
     /// 标记数据为云端已删除的数据  本地数据也需要逻辑删除并且标记为已同步
@@ -277,14 +618,12 @@ impl ClipRecord {
             "UPDATE clip_record SET del_flag = 1, sync_flag = 2, sync_time = ? WHERE id IN ({})",
             ids.iter().map(|_| "?").collect::<Vec<_>>().join(",")
         );
-        let tx = rb.acquire_begin().await?;
         // 转换ids为Vec<Value>
         let mut params = ids.into_iter().map(|id| to_value!(id)).collect::<Vec<_>>();
         params.insert(0, to_value!(sync_time));
-        let _ = tx.exec(&sql, params).await?;
-        tx.commit()
-            .await
-            .map_err(|e| AppError::Database(rbatis::Error::from(e)))
+        exec_in_tx(rb, vec![(sql.as_str(), params)]).await?;
+        invalidate_preview_cache_by_ids(ids);
+        Ok(())
     }
 
     pub async fn del_by_ids(rb: &RBatis, ids: &Vec<String>) -> AppResult<()> {
@@ -292,28 +631,25 @@ impl ClipRecord {
             "DELETE FROM clip_record WHERE id IN ({})",
             ids.iter().map(|_| "?").collect::<Vec<_>>().join(",")
         );
-        let tx = rb.acquire_begin().await?;
         // 转换ids为Vec<Value>
         let params = ids.into_iter().map(|id| to_value!(id)).collect::<Vec<_>>();
-        let _ = tx.exec(&sql, params).await?;
-        tx.commit()
-            .await
-            .map_err(|e| AppError::Database(rbatis::Error::from(e)))
+        exec_in_tx(rb, vec![(sql.as_str(), params)]).await?;
+        invalidate_preview_cache_by_ids(ids);
+        Ok(())
     }
 
     /// 逻辑删除数据并标记为未同步
     pub async fn tombstone_by_ids(rb: &RBatis, ids: &Vec<String>) -> AppResult<()> {
         let sql = format!(
-            "UPDATE clip_record set sync_flag = 0, del_flag = 1 WHERE id IN ({})",
+            "UPDATE clip_record set sync_flag = 0, del_flag = 1, del_time = ? WHERE id IN ({})",
             ids.iter().map(|_| "?").collect::<Vec<_>>().join(",")
         );
-        let tx = rb.acquire_begin().await?;
         // 转换ids为Vec<Value>
-        let params = ids.into_iter().map(|id| to_value!(id)).collect::<Vec<_>>();
-        let _ = tx.exec(&sql, params).await?;
-        tx.commit()
-            .await
-            .map_err(|e| AppError::Database(rbatis::Error::from(e)))
+        let mut params = ids.into_iter().map(|id| to_value!(id)).collect::<Vec<_>>();
+        params.insert(0, to_value!(current_millis()));
+        exec_in_tx(rb, vec![(sql.as_str(), params)]).await?;
+        invalidate_preview_cache_by_ids(ids);
+        Ok(())
     }
 
     pub async fn select_by_ids(
@@ -339,31 +675,49 @@ impl ClipRecord {
         Ok(res)
     }
 
-    pub async fn insert_by_created_sort(rb: &RBatis, mut record: ClipRecord) -> AppResult<()> {
-        let tx = rb.acquire_begin().await?;
-        let next_record = ClipRecord::select_order_by_created(rb, record.created).await?;
-        if next_record.is_empty() {
-            // 获取最新的排序值
-            record.sort = ClipRecord::get_next_sort(rb).await;
-            ClipRecord::insert(&tx, &record).await?;
-        } else {
-            let sql = "UPDATE clip_record SET sort = IFNULL(sort, 0) + 1 WHERE created >= ?";
-            tx.exec(sql, vec![to_value!(next_record[0].created)])
-                .await?;
-            record.sort = next_record[0].sort;
-            ClipRecord::insert(&tx, &record).await?;
-        }
-        tx.commit()
-            .await
-            .map_err(|e| AppError::Database(rbatis::Error::from(e)))
+    pub async fn insert_by_created_sort(rb: &RBatis, record: ClipRecord) -> AppResult<()> {
+        retry_on_db_locked(|| {
+            let mut record = record.clone();
+            async move {
+                let tx = rb.acquire_begin().await?;
+                let result: AppResult<()> = async {
+                    let next_record =
+                        ClipRecord::select_order_by_created(rb, record.created).await?;
+                    if next_record.is_empty() {
+                        // 获取最新的排序值
+                        record.sort = ClipRecord::get_next_sort(rb).await;
+                        ClipRecord::insert(&tx, &record).await?;
+                    } else {
+                        let sql =
+                            "UPDATE clip_record SET sort = IFNULL(sort, 0) + 1 WHERE created >= ?";
+                        tx.exec(sql, vec![to_value!(next_record[0].created)])
+                            .await?;
+                        record.sort = next_record[0].sort;
+                        ClipRecord::insert(&tx, &record).await?;
+                    }
+                    Ok(())
+                }
+                .await;
+                if let Err(e) = result {
+                    let _ = tx.rollback().await;
+                    return Err(e);
+                }
+                tx.commit()
+                    .await
+                    .map_err(|e| AppError::Database(rbatis::Error::from(e)))
+            }
+        })
+        .await
     }
 
     /// 批量按同步时间合并插入记录（容错式时间合并）
     /// records: 需要插入的记录列表，必须包含sync_time字段
     /// 返回: (成功插入的记录数量, 失败的记录数量)
+    /// position: 新记录的插入位置策略
     pub async fn insert_batch_merge_by_sync_time(
         rb: &RBatis,
         mut records: Vec<ClipRecord>,
+        position: CloudInsertPosition,
     ) -> AppResult<(usize, usize)> {
         if records.is_empty() {
             return Ok((0, 0));
@@ -375,6 +729,10 @@ impl ClipRecord {
             let b_time = b.sync_time.unwrap_or(0);
             a_time.cmp(&b_time)
         });
+        // 插入到最后时从新到旧依次追加，保证新记录之间仍然是较新的在前
+        if position == CloudInsertPosition::Bottom {
+            records.reverse();
+        }
 
         log::debug!("开始批量时间合并，待合并记录数量: {}", records.len());
 
@@ -393,7 +751,13 @@ impl ClipRecord {
             while retry_count < MAX_RETRIES && !record_success {
                 let tx = rb.acquire_begin().await?;
 
-                match Self::insert_single_record_with_merge(&tx, &mut record, cloud_sync_time).await
+                match Self::insert_single_record_with_merge(
+                    &tx,
+                    &mut record,
+                    cloud_sync_time,
+                    position,
+                )
+                .await
                 {
                     Ok(()) => {
                         // 成功插入，提交事务
@@ -486,6 +850,7 @@ impl ClipRecord {
         tx: &rbatis::executor::RBatisTxExecutor,
         record: &mut ClipRecord,
         cloud_sync_time: u64,
+        position: CloudInsertPosition,
     ) -> AppResult<()> {
         #[derive(serde::Deserialize)]
         struct SortRecord {
@@ -493,6 +858,28 @@ impl ClipRecord {
             sync_time: Option<u64>,
         }
 
+        if position == CloudInsertPosition::Bottom {
+            // 插入到最后，不需要调整其他记录的sort
+            let min_sort_records: Vec<SortRecord> = tx
+                .query_decode(
+                    "SELECT sort, sync_time FROM clip_record WHERE del_flag = 0 ORDER BY sort ASC LIMIT 1",
+                    vec![],
+                )
+                .await
+                .map_err(|e| AppError::Database(e))?;
+            record.sort = min_sort_records.first().map(|r| r.sort - 1).unwrap_or(0);
+
+            log::debug!(
+                "插入到最后, 新sort: {}, 云端sync_time: {}",
+                record.sort,
+                cloud_sync_time
+            );
+            ClipRecord::insert(tx, record)
+                .await
+                .map_err(|e| AppError::Database(e))?;
+            return Ok(());
+        }
+
         // 查找插入位置
         let target_records: Vec<SortRecord> = tx.query_decode(
             "SELECT sort, sync_time FROM clip_record WHERE del_flag = 0 AND (sync_time >= ? OR sync_time IS NULL) ORDER BY sync_time ASC, sort ASC LIMIT 1",
@@ -518,6 +905,35 @@ impl ClipRecord {
                 .map_err(|e| AppError::Database(e))?;
 
             record.sort = target_sort;
+        } else if position == CloudInsertPosition::BelowTop {
+            // 保持当前第一条记录不变，插入到它的下面
+            let max_sort_records: Vec<SortRecord> = tx
+                .query_decode(
+                    "SELECT sort, sync_time FROM clip_record WHERE del_flag = 0 ORDER BY sort DESC LIMIT 1",
+                    vec![],
+                )
+                .await
+                .map_err(|e| AppError::Database(e))?;
+
+            match max_sort_records.first() {
+                Some(top_record) => {
+                    let top_sort = top_record.sort;
+                    tx.exec(
+                        "UPDATE clip_record SET sort = sort + 1 WHERE sort >= ? AND del_flag = 0",
+                        vec![to_value!(top_sort)],
+                    )
+                    .await
+                    .map_err(|e| AppError::Database(e))?;
+                    record.sort = top_sort;
+                }
+                None => record.sort = 0,
+            }
+
+            log::debug!(
+                "插入到第一条记录下面, 新sort: {}, 云端sync_time: {}",
+                record.sort,
+                cloud_sync_time
+            );
         } else {
             // 插入到最前面
             let max_sort_records: Vec<SortRecord> = tx
@@ -584,15 +1000,304 @@ impl ClipRecord {
         }
     }
 
-    /// 删除最旧的记录（用于VIP记录数限制清理）
+    /// 按类型分组统计有效记录数量
+    pub async fn count_by_type(rb: &RBatis) -> Result<Vec<TypeCount>, Error> {
+        let sql =
+            "SELECT type, COUNT(*) as count FROM clip_record WHERE del_flag = 0 GROUP BY type";
+        rb.query_decode(sql, vec![]).await
+    }
+
+    /// 统计有效记录中置顶、待同步和跳过同步的数量
+    pub async fn count_status_summary(rb: &RBatis) -> Result<StatusCount, Error> {
+        let sql = "SELECT \
+            COALESCE(SUM(CASE WHEN pinned_flag = 1 THEN 1 ELSE 0 END), 0) as pinned, \
+            COALESCE(SUM(CASE WHEN sync_flag IN (?, ?) THEN 1 ELSE 0 END), 0) as pending_sync, \
+            COALESCE(SUM(CASE WHEN sync_flag = ? THEN 1 ELSE 0 END), 0) as skipped_sync \
+            FROM clip_record WHERE del_flag = 0";
+        let result: Vec<StatusCount> = rb
+            .query_decode(
+                sql,
+                vec![
+                    to_value!(NOT_SYNCHRONIZED),
+                    to_value!(SYNCHRONIZING),
+                    to_value!(SKIP_SYNC),
+                ],
+            )
+            .await?;
+        Ok(result.into_iter().next().unwrap_or_default())
+    }
+
+    /// 统计等待上传（本地记录）和等待下载（云端记录）的文件同步记录数
+    pub async fn count_file_sync_queue(rb: &RBatis) -> Result<FileSyncQueueCount, Error> {
+        let sql = "SELECT \
+            COALESCE(SUM(CASE WHEN cloud_source = 0 THEN 1 ELSE 0 END), 0) as pending_upload, \
+            COALESCE(SUM(CASE WHEN cloud_source = 1 THEN 1 ELSE 0 END), 0) as pending_download \
+            FROM clip_record WHERE sync_flag = ? and (del_flag is null or del_flag != 2)";
+        let result: Vec<FileSyncQueueCount> =
+            rb.query_decode(sql, vec![to_value!(SYNCHRONIZING)]).await?;
+        Ok(result.into_iter().next().unwrap_or_default())
+    }
+
+    /// 删除最旧的记录（用于VIP记录数限制清理），置顶和收藏的记录不删除
     pub async fn delete_oldest_records(rb: &RBatis, count: i32) -> Result<(), Error> {
         let sql = "DELETE FROM clip_record WHERE id IN (
             SELECT id FROM clip_record 
-            WHERE del_flag = 0 AND pinned_flag = 0 
+            WHERE del_flag = 0 AND pinned_flag = 0 AND IFNULL(favorite_flag, 0) = 0 
             ORDER BY sort ASC, created ASC 
             LIMIT ?
         )";
         rb.exec(sql, vec![to_value!(count)]).await?;
         Ok(())
     }
+
+    /// 为没有删除时间的历史删除记录补充删除时间，保留期从现在开始计算
+    pub async fn fill_missing_del_time(rb: &RBatis) -> Result<(), Error> {
+        rb.exec(
+            "UPDATE clip_record SET del_time = ? WHERE del_flag = 1 AND del_time IS NULL",
+            vec![to_value!(current_millis())],
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// 隐藏最旧的非置顶、非收藏记录（VIP降级宽限期内使用，不删除数据）
+    pub async fn hide_oldest_records(rb: &RBatis, count: i32) -> Result<(), Error> {
+        let sql = "UPDATE clip_record SET del_flag = 2 WHERE id IN (
+            SELECT id FROM clip_record 
+            WHERE del_flag = 0 AND pinned_flag = 0 AND IFNULL(favorite_flag, 0) = 0 
+            ORDER BY sort ASC, created ASC 
+            LIMIT ?
+        )";
+        rb.exec(sql, vec![to_value!(count)]).await?;
+        Ok(())
+    }
+
+    /// 恢复最新的count条被隐藏的超限记录
+    pub async fn restore_hidden_records(rb: &RBatis, count: i32) -> Result<(), Error> {
+        let sql = "UPDATE clip_record SET del_flag = 0 WHERE id IN (
+            SELECT id FROM clip_record 
+            WHERE del_flag = 2 
+            ORDER BY sort DESC, created DESC 
+            LIMIT ?
+        )";
+        rb.exec(sql, vec![to_value!(count)]).await?;
+        Ok(())
+    }
+
+    /// 获取被隐藏的超限记录数量
+    pub async fn count_hidden_records(rb: &RBatis) -> i64 {
+        let count_res: Result<i64, rbs::Error> = rb
+            .query_decode(
+                "SELECT COUNT(*) FROM clip_record where del_flag = 2",
+                vec![],
+            )
+            .await;
+        count_res.unwrap_or(0)
+    }
+
+    /// 物理删除所有被隐藏的超限记录
+    pub async fn delete_hidden_records(rb: &RBatis) -> Result<(), Error> {
+        rb.exec("DELETE FROM clip_record WHERE del_flag = 2", vec![])
+            .await?;
+        Ok(())
+    }
+}
+
+/// 按类型分组的记录数量
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TypeCount {
+    pub r#type: String,
+    pub count: i64,
+}
+
+/// 记录状态统计
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct StatusCount {
+    pub pinned: i64,
+    pub pending_sync: i64,
+    pub skipped_sync: i64,
+}
+
+/// 文件同步队列统计
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct FileSyncQueueCount {
+    pub pending_upload: i64,
+    pub pending_download: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagsRow {
+    tags: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdRow {
+    id: String,
+}
+
+/// 在同一个事务中依次执行写入语句，任意一条失败时回滚
+/// 遇到数据库被锁定（SQLITE_BUSY）等暂时性错误时自动退避重试，避免并发写入时记录丢失
+async fn exec_in_tx(rb: &RBatis, statements: Vec<(&str, Vec<rbs::Value>)>) -> AppResult<()> {
+    retry_on_db_locked(|| {
+        let statements = statements.clone();
+        async move {
+            let tx = rb.acquire_begin().await?;
+            for (sql, params) in statements {
+                if let Err(e) = tx.exec(sql, params).await {
+                    let _ = tx.rollback().await;
+                    return Err(AppError::Database(e));
+                }
+            }
+            tx.commit()
+                .await
+                .map_err(|e| AppError::Database(rbatis::Error::from(e)))
+        }
+    })
+    .await
+}
+
+fn current_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biz::clip_record_clean::expire_old_records;
+    use crate::sqlite_storage::test_db::{open_temp_db, remove_temp_db};
+
+    #[tokio::test]
+    async fn test_normalize_sort_keeps_order() {
+        let (rb, db_path) = open_temp_db("sort").await;
+
+        // 排序值不连续并且很大，相同排序值按创建时间区分先后
+        let sorts = [(1_000_000, 1), (5, 2), (2_000_000_000, 3), (5, 4)];
+        for (i, (sort, created)) in sorts.iter().enumerate() {
+            let record = ClipRecord {
+                id: format!("record-{}", i),
+                r#type: "Text".to_string(),
+                content: serde_json::Value::String("content".to_string()),
+                md5_str: format!("md5-{}", i),
+                created: *created,
+                sort: *sort,
+                del_flag: Some(0),
+                ..Default::default()
+            };
+            ClipRecord::insert(&rb, &record).await.unwrap();
+        }
+
+        ClipRecord::normalize_sort(&rb).await.unwrap();
+
+        let records = ClipRecord::select_order_by(&rb).await.unwrap();
+        let ids: Vec<&str> = records.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["record-2", "record-0", "record-3", "record-1"]);
+        let sorts: Vec<i32> = records.iter().map(|r| r.sort).collect();
+        assert_eq!(sorts, vec![3, 2, 1, 0]);
+        assert_eq!(ClipRecord::max_sort(&rb).await, 3);
+
+        remove_temp_db(rb, &db_path);
+    }
+
+    #[tokio::test]
+    async fn test_count_by_type_and_status() {
+        let (rb, db_path) = open_temp_db("stats").await;
+
+        // 类型、置顶、同步状态、删除标记
+        let rows = [
+            ("Text", 1, Some(0), 0),
+            ("Text", 0, Some(3), 0),
+            ("Image", 0, Some(1), 0),
+            ("File", 0, Some(2), 0),
+            ("Text", 1, Some(0), 1),
+        ];
+        for (i, (r#type, pinned_flag, sync_flag, del_flag)) in rows.iter().enumerate() {
+            let record = ClipRecord {
+                id: format!("record-{}", i),
+                r#type: r#type.to_string(),
+                content: serde_json::Value::String("content".to_string()),
+                md5_str: format!("md5-{}", i),
+                pinned_flag: *pinned_flag,
+                sync_flag: *sync_flag,
+                del_flag: Some(*del_flag),
+                ..Default::default()
+            };
+            ClipRecord::insert(&rb, &record).await.unwrap();
+        }
+
+        let mut counts: Vec<(String, i64)> = ClipRecord::count_by_type(&rb)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| (row.r#type, row.count))
+            .collect();
+        counts.sort();
+        assert_eq!(
+            counts,
+            vec![
+                ("File".to_string(), 1),
+                ("Image".to_string(), 1),
+                ("Text".to_string(), 2)
+            ]
+        );
+
+        let status = ClipRecord::count_status_summary(&rb).await.unwrap();
+        assert_eq!(status.pinned, 1);
+        assert_eq!(status.pending_sync, 2);
+        assert_eq!(status.skipped_sync, 1);
+
+        remove_temp_db(rb, &db_path);
+    }
+
+    #[tokio::test]
+    async fn test_auto_expire_keeps_pinned_records() {
+        let (rb, db_path) = open_temp_db("expire").await;
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        let day_millis = 24 * 60 * 60 * 1000;
+        // 记录ID、创建时间、是否置顶
+        let rows = [
+            ("expired", now - 10 * day_millis, 0),
+            ("expired-pinned", now - 10 * day_millis, 1),
+            ("recent", now - day_millis, 0),
+        ];
+        for (id, created, pinned_flag) in rows {
+            let record = ClipRecord {
+                id: id.to_string(),
+                r#type: "Text".to_string(),
+                content: serde_json::Value::String("content".to_string()),
+                md5_str: id.to_string(),
+                created,
+                pinned_flag,
+                sync_flag: Some(2),
+                del_flag: Some(0),
+                ..Default::default()
+            };
+            ClipRecord::insert(&rb, &record).await.unwrap();
+        }
+
+        let expired = expire_old_records(&rb, 7).await;
+        assert_eq!(expired, 1);
+
+        let mut remaining: Vec<String> = ClipRecord::select_order_by_limit(&rb, -1, 0)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|record| record.id)
+            .collect();
+        remaining.sort();
+        assert_eq!(remaining, vec!["expired-pinned", "recent"]);
+
+        // 过期记录逻辑删除并标记为未同步，等待同步删除到云端
+        let expired = &ClipRecord::select_by_id(&rb, "expired").await.unwrap()[0];
+        assert_eq!(expired.del_flag, Some(1));
+        assert_eq!(expired.sync_flag, Some(0));
+
+        remove_temp_db(rb, &db_path);
+    }
 }
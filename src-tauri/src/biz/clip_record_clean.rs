@@ -1,24 +1,96 @@
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc, RwLock,
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, RwLock,
+    },
 };
 
 use crate::{
+    api::cloud_sync_api::{compact_tombstones, CompactTombstoneParam},
     biz::{
-        clip_record::ClipRecord, content_search::remove_ids_from_index, system_setting::Settings,
+        clip_record::ClipRecord,
+        content_search::remove_ids_from_index,
+        history_cursor::reset_history_cursor,
+        system_setting::{
+            check_kiosk_mode_enabled, Settings, DEFAULT_SORT_NORMALIZE_THRESHOLD,
+            DEFAULT_TOMBSTONE_RETENTION_DAYS,
+        },
+        vip_checker::VipChecker,
     },
     utils::{
-        file_dir::get_resources_dir, lock_utils::lock_utils::safe_read_lock,
-        path_utils::to_safe_string,
+        device_info::GLOBAL_DEVICE_ID, file_dir::get_resources_dir, image_info::thumbnail_filename,
+        lock_utils::lock_utils::safe_read_lock, path_utils::to_safe_string,
+        token_manager::has_valid_auth,
     },
     CONTEXT,
 };
 use clipboard_listener::ClipType;
 use once_cell::sync::Lazy;
 use rbatis::RBatis;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
 
 static IS_CLEANING: Lazy<AtomicBool> = Lazy::new(|| AtomicBool::new(false));
 
+// 上次通知云端清理删除记录的时间（毫秒）
+static LAST_COMPACT_TIME: AtomicU64 = AtomicU64::new(0);
+static COMPACT_INTERVAL_MILLIS: u64 = 24 * 60 * 60 * 1000;
+
+// 排序值达到该值时无论设置如何都重新编号，避免i32溢出
+const SORT_OVERFLOW_GUARD: i32 = i32::MAX / 2;
+
+// 剩余可用条数占比低于该值时提示即将清理
+static NEAR_LIMIT_RATIO: f64 = 0.1;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RecordLimitStatus {
+    // 当前有效记录数
+    pub count: i64,
+    // 设置中的最大记录数，超出后最旧的记录会被清理
+    pub max_records: u32,
+    // 当前账号允许设置的最大记录数
+    pub vip_limit: u32,
+    // 距离触发清理还可以新增的记录数
+    pub remaining: i64,
+    // 是否接近上限
+    pub near_limit: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+struct RecordTrimmedPayload {
+    // 被清理的记录数
+    count: usize,
+    max_records: u32,
+}
+
+/// 获取记录数量与上限的状态
+#[tauri::command]
+pub async fn get_record_limit_status() -> Result<RecordLimitStatus, String> {
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let count = ClipRecord::count_effective(rb).await;
+
+    let max_records = {
+        let lock = CONTEXT.get::<Arc<RwLock<Settings>>>().clone();
+        let current = safe_read_lock(&lock).map_err(|e| e.to_string())?;
+        current.max_records
+    };
+    let vip_limit = VipChecker::get_max_records_limit()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let remaining = (max_records as i64 - count).max(0);
+    let near_limit = (remaining as f64) <= max_records as f64 * NEAR_LIMIT_RATIO;
+
+    Ok(RecordLimitStatus {
+        count,
+        max_records,
+        vip_limit,
+        remaining,
+        near_limit,
+    })
+}
+
 // AtomicBool状态保护器
 struct CleaningGuard;
 
@@ -42,6 +114,11 @@ pub async fn try_clean_clip_record() {
 }
 
 async fn clip_record_clean() {
+    // 只读模式下不删除任何记录
+    if check_kiosk_mode_enabled() {
+        log::debug!("只读模式已开启，跳过记录清理");
+        return;
+    }
     let rb: &RBatis = CONTEXT.get::<RBatis>();
 
     let system_settings = {
@@ -56,46 +133,63 @@ async fn clip_record_clean() {
         result
     };
     let max_num = system_settings.max_records;
+    let retention_days = system_settings
+        .tombstone_retention_days
+        .unwrap_or(DEFAULT_TOMBSTONE_RETENTION_DAYS);
 
     // 数据清理有两个部分
     // 1. 逻辑删除超过系统设置的最大记录数的剪贴板记录，但是逻辑删除的数据需要标记为未同步，等待定时任务同步删除的数据
     // 2. 还有一部分数据就是已经同步并且被逻辑删除的数据，这部分数据可以直接物理删除
 
-    // 查询页面会展示的有效数据数量
-    let count = ClipRecord::count_effective(rb).await;
-    if count > max_num as i64 {
-        let clip_records = ClipRecord::select_order_by_limit(rb, -1, max_num as i32)
-            .await
-            .unwrap_or(vec![]);
-        if clip_records.len() > 0 {
-            let mut resource_files_to_delete: Vec<String> = vec![];
-            let mut del_ids: Vec<String> = vec![];
-
-            for record in clip_records {
-                // 收集需要删除的resources目录下的文件
-                collect_resource_files_to_delete(&record, &mut resource_files_to_delete);
-                del_ids.push(record.id);
-            }
+    // 查询超过最大记录数需要清理的记录
+    let clip_records = select_over_limit_records(rb, max_num).await;
+    if !clip_records.is_empty() {
+        let mut resource_files_to_delete: Vec<String> = vec![];
+        let mut del_ids: Vec<String> = vec![];
 
-            let del_res = ClipRecord::tombstone_by_ids(rb, &del_ids).await;
-            match del_res {
-                Ok(_) => {
-                    log::info!("删除超限数据成功, 数量: {}", del_ids.len());
-                    // 同步删除搜索索引
-                    let _ = remove_ids_from_index(&del_ids).await;
+        for record in clip_records {
+            // 收集需要删除的resources目录下的文件
+            collect_resource_files_to_delete(&record, &mut resource_files_to_delete);
+            del_ids.push(record.id);
+        }
 
-                    // 删除resources目录下的文件
-                    delete_resource_files(&resource_files_to_delete).await;
-                }
-                Err(e) => {
-                    log::error!("删除过期数据异常:{}", e)
+        let del_res = ClipRecord::tombstone_by_ids(rb, &del_ids).await;
+        match del_res {
+            Ok(_) => {
+                log::info!("删除超限数据成功, 数量: {}", del_ids.len());
+                // 通知前端最旧的记录已被清理
+                if let Some(app_handle) = CONTEXT.try_get::<AppHandle>() {
+                    let payload = RecordTrimmedPayload {
+                        count: del_ids.len(),
+                        max_records: max_num,
+                    };
+                    let _ = app_handle.emit("clip_record_trimmed", payload);
                 }
+                // 同步删除搜索索引
+                let _ = remove_ids_from_index(&del_ids).await;
+
+                // 删除resources目录下的文件
+                delete_resource_files(&resource_files_to_delete).await;
+            }
+            Err(e) => {
+                log::error!("删除过期数据异常:{}", e)
             }
         }
     }
 
+    // 逻辑删除创建时间超过自动过期天数的未置顶记录
+    let expire_days = system_settings.auto_expire_days.unwrap_or(0);
+    if expire_days > 0 {
+        expire_old_records(rb, expire_days).await;
+    }
+
     // 查询已同步并且已逻辑删除的数据数量   这些数据需要物理删除
-    let invalid_count = ClipRecord::count_invalid(rb).await;
+    // 设置了删除记录保留期时，删除记录在保留期内可以从最近删除中恢复，到期后由保留期清理统一物理删除
+    let invalid_count = if retention_days == 0 {
+        ClipRecord::count_invalid(rb).await
+    } else {
+        0
+    };
     if invalid_count > 0 {
         let invalid_data = ClipRecord::select_invalid(rb).await;
         match invalid_data {
@@ -131,6 +225,180 @@ async fn clip_record_clean() {
             }
         }
     }
+
+    // 清理超过保留期的删除记录
+    if retention_days > 0 {
+        purge_expired_tombstones(rb, retention_days, system_settings.cloud_sync == 1).await;
+    }
+
+    // 排序值过大时重新编号
+    let sort_threshold = system_settings
+        .sort_normalize_threshold
+        .unwrap_or(DEFAULT_SORT_NORMALIZE_THRESHOLD);
+    normalize_sort_if_needed(rb, sort_threshold).await;
+}
+
+/// 查询超过最大记录数、清理时会被逻辑删除的记录，收藏的记录不会被清理
+async fn select_over_limit_records(rb: &RBatis, max_records: u32) -> Vec<ClipRecord> {
+    let count = ClipRecord::count_effective(rb).await;
+    if count <= max_records as i64 {
+        return vec![];
+    }
+    ClipRecord::select_order_by_limit(rb, -1, max_records as i32)
+        .await
+        .unwrap_or(vec![])
+        .into_iter()
+        .filter(|r| !r.is_favorite())
+        .collect()
+}
+
+// 自动过期的截止时间（毫秒），创建时间早于该时间的记录会过期
+fn expire_before(expire_days: u32) -> u64 {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0);
+    now.saturating_sub(expire_days as u64 * 24 * 60 * 60 * 1000)
+}
+
+/// 按给定的最大记录数和自动过期天数，统计下次清理会逻辑删除的记录数，与实际清理使用相同的筛选条件
+pub(crate) async fn count_records_to_clean(
+    rb: &RBatis,
+    max_records: u32,
+    auto_expire_days: u32,
+) -> i64 {
+    let mut ids: HashSet<String> = select_over_limit_records(rb, max_records)
+        .await
+        .into_iter()
+        .map(|r| r.id)
+        .collect();
+    if auto_expire_days > 0 {
+        let expired = ClipRecord::select_expired_unpinned(rb, expire_before(auto_expire_days))
+            .await
+            .unwrap_or(vec![]);
+        ids.extend(expired.into_iter().map(|r| r.id));
+    }
+    ids.len() as i64
+}
+
+/// 逻辑删除创建时间早于过期天数的未置顶记录，并标记为未同步以便删除同步到云端，返回删除的记录数
+pub(crate) async fn expire_old_records(rb: &RBatis, expire_days: u32) -> usize {
+    let expired = match ClipRecord::select_expired_unpinned(rb, expire_before(expire_days)).await {
+        Ok(data) => data,
+        Err(e) => {
+            log::error!("查询自动过期记录异常:{}", e);
+            return 0;
+        }
+    };
+    if expired.is_empty() {
+        return 0;
+    }
+
+    let mut resource_files_to_delete: Vec<String> = vec![];
+    let mut del_ids: Vec<String> = vec![];
+    for record in expired {
+        collect_resource_files_to_delete(&record, &mut resource_files_to_delete);
+        del_ids.push(record.id);
+    }
+
+    match ClipRecord::tombstone_by_ids(rb, &del_ids).await {
+        Ok(_) => {
+            log::info!("删除自动过期记录成功, 数量: {}", del_ids.len());
+            let _ = remove_ids_from_index(&del_ids).await;
+            delete_resource_files(&resource_files_to_delete).await;
+            del_ids.len()
+        }
+        Err(e) => {
+            log::error!("删除自动过期记录异常:{}", e);
+            0
+        }
+    }
+}
+
+/// 排序值超过阈值或接近i32上限时，把排序值重新编号为连续值
+/// 排序值每次复制都会递增，长期使用后会越来越大，按创建时间插入时需要整体后移的范围也随之变大
+async fn normalize_sort_if_needed(rb: &RBatis, threshold: u32) {
+    let max_sort = ClipRecord::max_sort(rb).await;
+    let near_overflow = max_sort >= SORT_OVERFLOW_GUARD;
+    if !near_overflow && (threshold == 0 || (max_sort as i64) < threshold as i64) {
+        return;
+    }
+    match ClipRecord::normalize_sort(rb).await {
+        Ok(_) => {
+            reset_history_cursor();
+            log::info!(
+                "排序值已重新编号，整理前最大值: {}, 整理后最大值: {}",
+                max_sort,
+                ClipRecord::max_sort(rb).await
+            );
+        }
+        Err(e) => log::error!("排序值重新编号失败: {}", e),
+    }
+}
+
+/// 物理删除超过保留期的删除记录，并通知云端清理同样过期的删除记录
+async fn purge_expired_tombstones(rb: &RBatis, retention_days: u32, cloud_sync_enabled: bool) {
+    if let Err(e) = ClipRecord::fill_missing_del_time(rb).await {
+        log::error!("补充删除时间失败:{}", e);
+        return;
+    }
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0);
+    let before = now.saturating_sub(retention_days as u64 * 24 * 60 * 60 * 1000);
+
+    // 开启云同步时，还没有同步到云端的删除需要保留，否则其他设备无法得知删除
+    let expired = if cloud_sync_enabled {
+        ClipRecord::select_expired_synced_tombstones(rb, before).await
+    } else {
+        ClipRecord::select_expired_tombstones(rb, before).await
+    };
+    let expired = match expired {
+        Ok(data) => data,
+        Err(e) => {
+            log::error!("查询过期删除记录异常:{}", e);
+            return;
+        }
+    };
+
+    if !expired.is_empty() {
+        let mut resource_files_to_delete: Vec<String> = vec![];
+        let mut del_ids: Vec<String> = vec![];
+        for record in expired {
+            collect_resource_files_to_delete(&record, &mut resource_files_to_delete);
+            del_ids.push(record.id);
+        }
+
+        match ClipRecord::del_by_ids(rb, &del_ids).await {
+            Ok(_) => {
+                log::info!("清理超过保留期的删除记录成功, 数量: {}", del_ids.len());
+                let _ = remove_ids_from_index(&del_ids).await;
+                delete_resource_files(&resource_files_to_delete).await;
+            }
+            Err(e) => {
+                log::error!("清理超过保留期的删除记录异常:{}", e);
+                return;
+            }
+        }
+    }
+
+    // 通知云端清理过期的删除记录，保持同步数据集精简（每天最多通知一次）
+    let last_compact = LAST_COMPACT_TIME.load(Ordering::SeqCst);
+    if cloud_sync_enabled
+        && has_valid_auth()
+        && now.saturating_sub(last_compact) >= COMPACT_INTERVAL_MILLIS
+    {
+        LAST_COMPACT_TIME.store(now, Ordering::SeqCst);
+        let param = CompactTombstoneParam {
+            before,
+            device_id: GLOBAL_DEVICE_ID.clone(),
+        };
+        if let Err(e) = compact_tombstones(&param).await {
+            log::warn!("通知云端清理过期删除记录失败: {}", e);
+        }
+    }
 }
 
 /// 收集需要删除的resources目录下的文件
@@ -145,12 +413,17 @@ fn collect_resource_files_to_delete(record: &ClipRecord, resource_files: &mut Ve
         x if x == ClipType::Image.to_string() => {
             // 图片文件都存储在resources根目录下，直接添加
             resource_files.push(content_str.to_string());
+            // 同时删除缩略图
+            resource_files.push(thumbnail_filename(content_str));
         }
         x if x == ClipType::File.to_string() => {
             // 文件类型需要判断是否为相对路径（resources中的文件）
             if content_str.starts_with("files/") {
                 // 这是复制到resources/files/下的文件，需要删除
                 resource_files.push(content_str.to_string());
+            } else if record.is_sensitive() {
+                // 敏感记录不输出文件路径
+                log::debug!("跳过绝对路径文件的删除: 敏感记录{}", record.id);
             } else if content_str.contains(":::") {
                 // 多文件不删除（原本就是绝对路径）
                 log::debug!("跳过多文件记录的文件删除: {}", content_str);
@@ -1,79 +1,508 @@
 use std::{
+    borrow::Cow,
+    collections::HashMap,
     fs::File,
+    future::Future,
     io::{Read, Write},
     path::PathBuf,
-    time::{SystemTime, UNIX_EPOCH},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use chrono::Local;
 use clipboard_listener::{ClipBoardEventListener, ClipType, ClipboardEvent};
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
 use rbatis::RBatis;
+use serde::Serialize;
 use serde_json::Value;
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_clipboard_pal::desktop::ClipboardPal;
+use tokio::sync::Notify;
 use uuid::Uuid;
 
 use crate::{
+    auto_paste::foreground_app_name,
     biz::clip_record::{ClipRecord, NOT_SYNCHRONIZED, SKIP_SYNC},
     biz::vip_checker::VipChecker,
-    utils::{file_dir::get_resources_dir, file_ext::extract_full_extension},
+    utils::{
+        file_dir::{get_resources_dir, is_in_paste_temp_dir},
+        file_ext::extract_full_extension,
+        file_times::{apply_file_times, read_file_times},
+    },
     CONTEXT,
 };
 use crate::{
     biz::{
-        clip_async_queue::AsyncQueue, clip_record_clean::try_clean_clip_record,
-        content_search::add_content_to_index, system_setting::check_cloud_sync_enabled,
+        clip_async_queue::AsyncQueue,
+        clip_record_clean::try_clean_clip_record,
+        content_processor::ContentProcessor,
+        content_search::add_content_to_index,
+        copy_clip_record::propagate_delete,
+        history_cursor::reset_history_cursor,
+        image_ocr::spawn_image_ocr,
+        image_thumbnail::spawn_thumbnail_generation,
+        link_title::{detect_single_url, spawn_link_title_fetch},
+        system_setting::{
+            check_capture_enabled, check_capture_type_enabled, check_cloud_sync_enabled,
+            check_developer_mode_enabled, check_keep_raw_text_enabled, check_kiosk_mode_enabled,
+            check_monitoring_enabled, check_respect_concealed_hint, check_source_app_denied,
+            check_store_unknown_types_enabled, check_text_encoding_recovery_enabled,
+            check_text_sanitize_enabled, ensure_not_kiosk_mode, get_capture_coalesce_delay,
+            get_dedup_window, get_disabled_capture_types, get_event_process_timeout,
+            get_files_overflow_mode, get_max_files_per_record, get_min_image_limits,
+            get_redaction_rules, get_symlink_mode, get_text_compression_min_bytes,
+            get_text_transforms, should_keep_animated_image, FILES_OVERFLOW_SPLIT,
+            SYMLINK_MODE_FOLLOW, SYMLINK_MODE_REFERENCE, SYMLINK_MODE_SKIP,
+        },
     },
     errors::AppError,
     utils::{
-        aes_util::encrypt_content,
+        aes_util::encrypt_content_compressed,
         device_info::{GLOBAL_DEVICE_ID, GLOBAL_OS_TYPE},
+        image_info::{animated_image_extension, image_dimensions, thumbnail_filename},
         path_utils::to_safe_string,
+        rich_text::{html_to_text, rtf_to_text},
+        text_encoding::recover_mojibake,
+        text_redaction::redact_text,
+        text_sanitizer::sanitize_text,
     },
 };
 
+// 被忽略的剪贴板类型统计  类型 -> 次数
+static IGNORED_CLIP_TYPES: Lazy<DashMap<String, u64>> = Lazy::new(DashMap::new);
+
+// 因记录类型设置而未记录的剪贴板内容统计  类型 -> 次数
+static SKIPPED_CAPTURE_TYPES: Lazy<DashMap<String, u64>> = Lazy::new(DashMap::new);
+
+// 数据库和搜索索引是否已准备好记录剪贴板内容
+static CAPTURE_READY: AtomicBool = AtomicBool::new(false);
+static CAPTURE_READY_NOTIFY: Lazy<Notify> = Lazy::new(Notify::new);
+
+// 暂停记录剪贴板内容（例如恢复本地备份期间），暂停期间的剪贴板事件直接丢弃
+static CAPTURE_PAUSED: AtomicBool = AtomicBool::new(false);
+
+// 剪贴板事件的序号，合并等待期间有新事件时放弃较早的事件
+static CAPTURE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+// 启动阶段等待记录就绪的最长时间，超时后不再等待，直接记录
+static CAPTURE_READY_TIMEOUT_SECS: u64 = 30;
+
+tokio::task_local! {
+    // 当前异步任务是否在导入其他剪贴板管理器的历史记录
+    static IMPORTING: bool;
+}
+
+/// 以导入历史记录的方式执行保存：遇到相同内容的已有记录时直接跳过，不移动已有记录
+pub(crate) async fn with_importing<F: Future>(fut: F) -> F::Output {
+    IMPORTING.scope(true, fut).await
+}
+
+fn is_importing() -> bool {
+    IMPORTING.try_with(|importing| *importing).unwrap_or(false)
+}
+
 #[derive(Debug, Clone)]
 pub struct ClipboardEventTigger;
 
 #[async_trait::async_trait]
 impl ClipBoardEventListener<ClipboardEvent> for ClipboardEventTigger {
     async fn handle_event(&self, event: &ClipboardEvent) {
-        let rb: &RBatis = CONTEXT.get::<RBatis>();
-        let next_sort = ClipRecord::get_next_sort(rb).await;
-
-        let record_result = match event.r#type {
-            ClipType::Text => handle_text(rb, &event.content, next_sort).await,
-            ClipType::Image => handle_image(rb, event.file.as_ref(), next_sort).await,
-            ClipType::File => handle_file(rb, event.file_path_vec.as_ref(), next_sort).await,
-            _ => Ok(None),
+        // 用户临时暂停了记录，不记录任何内容
+        if !check_capture_enabled() {
+            log::debug!("剪贴板记录已暂停，忽略剪贴板事件");
+            return;
+        }
+        // 用户关闭了剪贴板监听，不记录也不同步
+        if !check_monitoring_enabled() {
+            log::debug!("剪贴板监听已关闭，忽略剪贴板事件");
+            return;
+        }
+        // 开发者模式下不自动记录，只通过 capture_now 手动记录
+        if check_developer_mode_enabled() {
+            log::debug!("开发者模式已开启，忽略剪贴板事件");
+            return;
+        }
+        // 只读模式下不记录新内容
+        if check_kiosk_mode_enabled() {
+            log::debug!("只读模式已开启，忽略剪贴板事件");
+            return;
+        }
+        // 密码管理器等标记为隐藏的内容不记录，也不参与合并
+        if is_discarded_concealed(event) {
+            return;
+        }
+        // 关闭记录的类型不等待合并，直接丢弃
+        if !check_capture_type_enabled(&event.r#type) {
+            record_skipped_capture_type(&event.r#type);
+            return;
+        }
+        // 合并等待前先记下前台应用，等待期间用户可能已切换窗口
+        let source_app = current_source_app().await;
+        if let Some(source_app) = &source_app {
+            if check_source_app_denied(source_app) {
+                log::debug!("来源应用 {} 在不记录名单中，忽略剪贴板事件", source_app);
+                return;
+            }
+        }
+        if let Some(event) = coalesce_clipboard_event(event).await {
+            process_clipboard_event(&event, source_app.as_deref()).await;
+        }
+    }
+}
+
+/// 检查是否为需要丢弃的隐藏内容（密码管理器复制的密码等），丢弃时不输出内容
+fn is_discarded_concealed(event: &ClipboardEvent) -> bool {
+    if event.concealed && check_respect_concealed_hint() {
+        log::debug!("剪贴板内容带有隐藏提示，已忽略");
+        return true;
+    }
+    false
+}
+
+/// 获取复制内容的来源应用（当前前台应用），获取不到时返回None
+async fn current_source_app() -> Option<String> {
+    tokio::task::spawn_blocking(foreground_app_name)
+        .await
+        .ok()
+        .flatten()
+}
+
+/// 标记剪贴板记录已就绪（数据库和搜索索引初始化完成后调用），等待中的剪贴板事件会继续处理
+pub fn mark_capture_ready() {
+    if CAPTURE_READY.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    CAPTURE_READY_NOTIFY.notify_waiters();
+    log::info!("剪贴板记录已就绪");
+    if let Some(app_handle) = CONTEXT.try_get::<AppHandle>() {
+        let _ = app_handle.emit("capture_ready", ());
+    }
+}
+
+/// 查询剪贴板记录是否已就绪
+#[tauri::command]
+pub fn is_capture_ready() -> bool {
+    CAPTURE_READY.load(Ordering::SeqCst)
+}
+
+/// 合并一次复制产生的连续剪贴板事件
+/// 应用复制时可能先后写入纯文本、HTML、RTF等多种格式并触发多次事件，等待片刻后只处理最后一次事件，
+/// 并重新读取剪贴板，文本内容优先保存HTML/RTF格式，返回None表示该事件已被后续事件合并
+async fn coalesce_clipboard_event(event: &ClipboardEvent) -> Option<ClipboardEvent> {
+    let Some(delay) = get_capture_coalesce_delay() else {
+        return Some(event.clone());
+    };
+    let generation = CAPTURE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    tokio::time::sleep(delay).await;
+    if CAPTURE_GENERATION.load(Ordering::SeqCst) != generation {
+        log::debug!("剪贴板事件已合并到后续事件");
+        return None;
+    }
+
+    let app_handle = CONTEXT.get::<AppHandle>();
+    match app_handle.state::<ClipboardPal>().read_current_rich_event() {
+        Ok(Some(latest)) => Some(latest),
+        Ok(None) => Some(event.clone()),
+        Err(e) => {
+            log::warn!("重新读取剪贴板内容失败，使用原始事件: {}", e);
+            Some(event.clone())
+        }
+    }
+}
+
+/// 暂停或恢复记录剪贴板内容
+pub(crate) fn set_capture_paused(paused: bool) {
+    CAPTURE_PAUSED.store(paused, Ordering::SeqCst);
+    log::info!("剪贴板记录已{}", if paused { "暂停" } else { "恢复" });
+}
+
+/// 启动阶段先暂存剪贴板事件，等待数据库和搜索索引就绪后再处理
+async fn wait_capture_ready() {
+    if CAPTURE_READY.load(Ordering::SeqCst) {
+        return;
+    }
+    // 先创建等待通知，再检查一次状态，避免错过就绪通知
+    let notified = CAPTURE_READY_NOTIFY.notified();
+    if CAPTURE_READY.load(Ordering::SeqCst) {
+        return;
+    }
+    log::debug!("剪贴板记录尚未就绪，等待初始化完成");
+    let timeout = tokio::time::Duration::from_secs(CAPTURE_READY_TIMEOUT_SECS);
+    if tokio::time::timeout(timeout, notified).await.is_err() {
+        log::warn!("等待剪贴板记录就绪超时，继续处理剪贴板事件");
+    }
+}
+
+/// 统计因未开启记录而丢弃的剪贴板类型
+fn record_skipped_capture_type(clip_type: &ClipType) {
+    *SKIPPED_CAPTURE_TYPES
+        .entry(clip_type.to_string())
+        .or_insert(0) += 1;
+    log::debug!("剪贴板类型 {} 未开启记录，已跳过", clip_type);
+}
+
+/// 处理剪贴板事件：保存记录、通知前端并发送到同步队列，返回是否新增了记录
+/// source_app 为复制时的前台应用，会保存到新记录中
+pub(crate) async fn process_clipboard_event(
+    event: &ClipboardEvent,
+    source_app: Option<&str>,
+) -> bool {
+    wait_capture_ready().await;
+
+    if CAPTURE_PAUSED.load(Ordering::SeqCst) {
+        log::debug!("剪贴板记录已暂停，丢弃剪贴板事件");
+        return false;
+    }
+
+    // 合并后重新读取或手动补录的内容同样检查隐藏提示
+    if is_discarded_concealed(event) {
+        return false;
+    }
+
+    // 用户关闭了该类型的记录，直接丢弃，不保存到本地，也不通知前端刷新
+    if !check_capture_type_enabled(&event.r#type) {
+        record_skipped_capture_type(&event.r#type);
+        return false;
+    }
+
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let next_sort = ClipRecord::get_next_sort(rb).await;
+
+    let record_result = match event.r#type {
+        ClipType::Text => handle_text(rb, &event.content, next_sort).await,
+        ClipType::Image => {
+            handle_image(
+                rb,
+                event.file.as_ref(),
+                event.image_format.as_deref(),
+                next_sort,
+            )
+            .await
+        }
+        ClipType::File => handle_file(rb, event.file_path_vec.as_ref(), next_sort).await,
+        ClipType::Rtf => handle_rtf(rb, &event.content, next_sort).await,
+        ClipType::Html => handle_html(rb, &event.content, next_sort).await,
+        _ => handle_unsupported(rb, event, next_sort).await,
+    };
+
+    // 处理错误情况
+    if let Err(e) = &record_result {
+        log::error!("处理剪贴板事件失败: {:?}", e);
+    }
+
+    tokio::spawn(async {
+        // 清理过期数据
+        try_clean_clip_record().await;
+    });
+
+    // 通知前端粘贴板变更
+    let app_handle = CONTEXT.get::<AppHandle>();
+    let _ = app_handle.emit("clip_record_change", ());
+
+    if let Ok(Some(mut item)) = record_result {
+        // 保存同时复制的富文本格式，粘贴时与纯文本一起写入
+        save_rich_flavor(rb, &mut item, event).await;
+
+        // 保存来源应用，用于审计
+        if let Some(source_app) = source_app {
+            match ClipRecord::update_source_app(rb, &item.id, source_app).await {
+                Ok(()) => item.source_app = Some(source_app.to_string()),
+                Err(e) => log::warn!("保存来源应用失败: {}", e),
+            }
+        }
+
+        // 有新记录加入，重置上一条/下一条导航游标
+        reset_history_cursor();
+
+        send_new_record_to_sync(&item).await;
+        return true;
+    }
+    false
+}
+
+/// 如果有新增记录，发送到异步队列   前提是开启了云同步开关且记录不跳过同步
+pub(crate) async fn send_new_record_to_sync(item: &ClipRecord) {
+    if item.sync_flag != Some(SKIP_SYNC) && check_cloud_sync_enabled().await {
+        let async_queue = CONTEXT.get::<AsyncQueue<ClipRecord>>();
+        if !async_queue.is_full() {
+            let send_res = async_queue.send_add(item.clone()).await;
+            if let Err(e) = send_res {
+                if item.is_sensitive() {
+                    log::error!("异步队列发送失败，敏感记录：{}, 异常:{}", item.id, e);
+                } else {
+                    log::error!("异步队列发送失败，粘贴内容：{:?}, 异常:{}", item, e);
+                }
+            }
+        }
+    }
+}
+
+/// 保存文本记录同时复制的HTML/RTF格式（加密），保存失败时只保留纯文本
+async fn save_rich_flavor(rb: &RBatis, item: &mut ClipRecord, event: &ClipboardEvent) {
+    let (Some(rich_type), Some(rich_content)) = (&event.rich_type, &event.rich_content) else {
+        return;
+    };
+    if item.r#type != ClipType::Text.to_string() || rich_content.is_empty() {
+        return;
+    }
+    // 富文本格式中有需要脱敏的内容时不保存，避免粘贴时写回纯文本中已去掉的敏感内容
+    let Some(rich_content) = rich_flavor_to_keep(rich_type, rich_content, &get_redaction_rules())
+    else {
+        log::info!("{}格式中有需要脱敏的内容，只保存纯文本", rich_type);
+        return;
+    };
+    let encrypted =
+        match encrypt_content_compressed(&rich_content, get_text_compression_min_bytes()) {
+            Ok(encrypted) => encrypted,
+            Err(e) => {
+                log::warn!("富文本内容加密失败，只保存纯文本: {}", e);
+                return;
+            }
         };
+    let rich_type = rich_type.to_string();
+    if let Err(e) = ClipRecord::update_rich_content(rb, &item.id, &rich_type, &encrypted).await {
+        log::warn!("保存富文本格式失败: {}", e);
+        return;
+    }
+    item.rich_type = Some(rich_type);
+    item.rich_content = Some(encrypted);
+}
+
+/// 获取被忽略的剪贴板类型统计
+#[tauri::command]
+pub fn get_ignored_clip_type_stats() -> HashMap<String, u64> {
+    IGNORED_CLIP_TYPES
+        .iter()
+        .map(|entry| (entry.key().clone(), *entry.value()))
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptureStatus {
+    // 未开启记录的类型
+    pub disabled_types: Vec<String>,
+    // 因未开启记录而跳过的次数  类型 -> 次数
+    pub skipped: HashMap<String, u64>,
+}
+
+/// 获取剪贴板类型记录状态
+#[tauri::command]
+pub fn get_capture_status() -> CaptureStatus {
+    CaptureStatus {
+        disabled_types: get_disabled_capture_types()
+            .iter()
+            .map(|clip_type| clip_type.to_string())
+            .collect(),
+        skipped: SKIPPED_CAPTURE_TYPES
+            .iter()
+            .map(|entry| (entry.key().clone(), *entry.value()))
+            .collect(),
+    }
+}
+
+/// 立即读取当前剪贴板内容并按正常流程记录（监听器漏掉事件时手动补录）
+/// 返回是否新增了记录，内容已存在时只更新排序
+#[tauri::command]
+pub async fn capture_now(app_handle: AppHandle) -> Result<bool, String> {
+    ensure_not_kiosk_mode()?;
+    if !check_monitoring_enabled() {
+        return Err("剪贴板监听已关闭".to_string());
+    }
+    if !check_capture_enabled() {
+        return Err("剪贴板记录已暂停".to_string());
+    }
+    let event = app_handle
+        .state::<ClipboardPal>()
+        .read_current_rich_event()
+        .map_err(|e| {
+            log::error!("读取剪贴板内容失败: {}", e);
+            format!("读取剪贴板内容失败: {}", e)
+        })?;
+    let Some(event) = event else {
+        log::debug!("剪贴板中没有可记录的内容");
+        return Ok(false);
+    };
+    // 手动补录时前台是ClipPal自己，无法得知内容来源
+    Ok(process_clipboard_event(&event, None).await)
+}
+
+/// 按符号链接处理方式转换文件路径列表
+/// 返回处理后的路径列表，以及是否包含只记录目标路径的符号链接
+fn apply_symlink_mode(paths: Vec<String>) -> (Vec<String>, bool) {
+    let mode = get_symlink_mode();
+    if mode == SYMLINK_MODE_FOLLOW {
+        return (paths, false);
+    }
 
-        // 处理错误情况
-        if let Err(e) = &record_result {
-            log::error!("处理剪贴板事件失败: {:?}", e);
+    let mut result = Vec::with_capacity(paths.len());
+    let mut has_reference = false;
+    for path_str in paths {
+        let path = std::path::Path::new(&path_str);
+        let is_symlink = std::fs::symlink_metadata(path)
+            .map(|metadata| metadata.file_type().is_symlink())
+            .unwrap_or(false);
+        if !is_symlink {
+            result.push(path_str);
+            continue;
         }
 
-        tokio::spawn(async {
-            // 清理过期数据
-            try_clean_clip_record().await;
-        });
-
-        // 通知前端粘贴板变更
-        let app_handle = CONTEXT.get::<AppHandle>();
-        let _ = app_handle.emit("clip_record_change", ());
-
-        if let Ok(Some(item)) = record_result {
-            // 如果有新增记录，发送到异步队列   前提是开启了云同步开关
-            if item.sync_flag != Some(SKIP_SYNC) && check_cloud_sync_enabled().await {
-                let async_queue = CONTEXT.get::<AsyncQueue<ClipRecord>>();
-                if !async_queue.is_full() {
-                    let send_res = async_queue.send_add(item.clone()).await;
-                    if let Err(e) = send_res {
-                        log::error!("异步队列发送失败，粘贴内容：{:?}, 异常:{}", item, e);
-                    }
+        if mode == SYMLINK_MODE_SKIP {
+            log::info!("跳过符号链接: {}", path_str);
+            continue;
+        }
+        if mode != SYMLINK_MODE_REFERENCE {
+            result.push(path_str);
+            continue;
+        }
+
+        // 相对路径的链接目标基于链接所在目录解析
+        match std::fs::read_link(path) {
+            Ok(target) => {
+                let target = match path.parent() {
+                    Some(parent) if target.is_relative() => parent.join(target),
+                    _ => target,
+                };
+                if !target.exists() {
+                    log::warn!("符号链接目标不存在，跳过: {} -> {:?}", path_str, target);
+                    continue;
                 }
+                log::info!("符号链接只记录目标路径: {} -> {:?}", path_str, target);
+                result.push(to_safe_string(&target));
+                has_reference = true;
             }
+            Err(e) => log::warn!("读取符号链接失败，跳过: {}, 错误: {}", path_str, e),
         }
     }
+    (result, has_reference)
+}
+
+/// 处理暂不支持的剪贴板类型
+/// 开启设置时保存一条文本占位记录，否则只记录日志和统计
+async fn handle_unsupported(
+    rb: &RBatis,
+    event: &ClipboardEvent,
+    sort: i32,
+) -> Result<Option<ClipRecord>, AppError> {
+    let type_name = event.r#type.to_string();
+    *IGNORED_CLIP_TYPES.entry(type_name.clone()).or_insert(0) += 1;
+
+    if !check_store_unknown_types_enabled() {
+        log::info!("忽略不支持的剪贴板类型: {}", type_name);
+        return Ok(None);
+    }
+
+    // 优先使用可用的文本表示，没有时保存占位文本
+    let placeholder = if event.content.trim().is_empty() {
+        format!("[不支持的剪贴板内容: {}]", type_name)
+    } else {
+        event.content.clone()
+    };
+    log::info!("不支持的剪贴板类型 {} 已保存为文本占位记录", type_name);
+    handle_text(rb, &placeholder, sort).await
 }
 
 fn current_timestamp() -> u64 {
@@ -86,8 +515,55 @@ fn current_timestamp() -> u64 {
         })
 }
 
+/// 在阻塞线程中执行耗时的文件操作（计算MD5、复制文件），超过设置的处理时间后不再等待
+/// 超时或执行失败返回None；阻塞线程中的操作无法中断，会在后台继续执行完
+async fn run_blocking_with_timeout<T, F>(task: &'static str, f: F) -> Option<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let handle = tokio::task::spawn_blocking(f);
+    let result = match get_event_process_timeout() {
+        Some(timeout) => match tokio::time::timeout(timeout, handle).await {
+            Ok(result) => result,
+            Err(_) => {
+                log::warn!(
+                    "{}超过{}秒仍未完成，不再等待，避免阻塞剪贴板记录",
+                    task,
+                    timeout.as_secs()
+                );
+                if let Some(app_handle) = CONTEXT.try_get::<AppHandle>() {
+                    let _ = app_handle.emit("clip_process_timeout", task);
+                }
+                return None;
+            }
+        },
+        None => handle.await,
+    };
+    match result {
+        Ok(value) => Some(value),
+        Err(e) => {
+            log::error!("{}执行失败: {}", task, e);
+            None
+        }
+    }
+}
+
+/// 带超时地计算文件内容的MD5，超时返回None
+async fn file_content_md5_with_timeout(
+    file_path: std::path::PathBuf,
+) -> Option<Result<String, std::io::Error>> {
+    let runtime = tokio::runtime::Handle::current();
+    run_blocking_with_timeout("计算文件MD5", move || {
+        runtime.block_on(compute_file_content_md5(&file_path))
+    })
+    .await
+}
+
 /// 计算文件内容的MD5值（智能策略：小文件全读，大文件采样）
-async fn compute_file_content_md5(file_path: &std::path::Path) -> Result<String, std::io::Error> {
+pub(crate) async fn compute_file_content_md5(
+    file_path: &std::path::Path,
+) -> Result<String, std::io::Error> {
     const SMALL_FILE_THRESHOLD: u64 = 10 * 1024 * 1024; // 10MB
 
     let metadata = std::fs::metadata(file_path)?;
@@ -103,7 +579,9 @@ async fn compute_file_content_md5(file_path: &std::path::Path) -> Result<String,
 }
 
 /// 计算完整文件内容的MD5
-async fn compute_full_file_md5(file_path: &std::path::Path) -> Result<String, std::io::Error> {
+pub(crate) async fn compute_full_file_md5(
+    file_path: &std::path::Path,
+) -> Result<String, std::io::Error> {
     let mut file = std::fs::File::open(file_path)?;
     let mut buffer = [0; 8192]; // 8KB缓冲区
     let mut context = md5::Context::new();
@@ -230,6 +708,22 @@ fn build_clip_record(
         del_flag: Some(0),
         cloud_source: Some(0),
         skip_type: None,
+        del_time: None,
+        raw_content: None,
+        slot: None,
+        file_modified: None,
+        file_created: None,
+        sensitive: None,
+        rich_type: None,
+        rich_content: None,
+        ocr_text: None,
+        favorite_flag: Some(0),
+        source_app: None,
+        title: None,
+        use_count: Some(0),
+        tags: None,
+        meta_only_sync: None,
+        modified_time: None,
     }
 }
 
@@ -287,19 +781,85 @@ fn build_multiple_files_record(
     record
 }
 
-async fn handle_text(
+/// 保存文本的结果
+pub(crate) enum SavedText {
+    // 新保存的记录（包括复用已删除记录的ID），需要同步到云端
+    New(ClipRecord),
+    // 与已有的活跃记录内容相同，已有记录已移到最前，值为已有记录的ID
+    Existing(String),
+    // 空文本，没有保存
+    Empty,
+}
+
+pub(crate) async fn handle_text(
     rb: &RBatis,
     content: &str,
     sort: i32,
 ) -> Result<Option<ClipRecord>, AppError> {
+    match save_text(rb, content, sort, false).await? {
+        SavedText::New(record) => Ok(Some(record)),
+        SavedText::Existing(_) | SavedText::Empty => Ok(None),
+    }
+}
+
+/// 保存文本记录，sensitive 为 true 时新记录在写入时即标记为敏感，内容相同的已有记录同样标记为敏感
+pub(crate) async fn save_text(
+    rb: &RBatis,
+    content: &str,
+    sort: i32,
+    sensitive: bool,
+) -> Result<SavedText, AppError> {
+    // 开启乱码恢复时先把文本还原为正确的UTF-8内容，后续保存和索引都使用恢复后的内容
+    let recovered = if check_text_encoding_recovery_enabled() {
+        recover_mojibake(content)
+    } else {
+        None
+    };
+    let content = match &recovered {
+        Some((text, encoding)) => {
+            log::info!("检测到乱码文本，已按{}编码恢复", encoding);
+            text.as_str()
+        }
+        None => content,
+    };
+
+    // 开启文本清理时去除不可见字符，保存、去重和搜索索引都使用清理后的内容
+    let raw_trimmed = content.trim();
+    let sanitized = if check_text_sanitize_enabled() {
+        sanitize_text(raw_trimmed)
+    } else {
+        Cow::Borrowed(raw_trimmed)
+    };
+    // 按脱敏规则替换API密钥、银行卡号等敏感内容，保存、去重和搜索索引都使用脱敏后的内容
+    let redacted = redact_text(sanitized.trim(), &get_redaction_rules());
+    // 按设置的文本转换（去除行尾空白、统一换行符等）处理，MD5、保存和搜索索引都使用转换后的内容
+    let transformed = ContentProcessor::apply_text_transforms(&redacted, &get_text_transforms());
+    let compression_min_bytes = get_text_compression_min_bytes();
+    // 原始文本包含被脱敏的内容，不保留原始文本
+    let raw_content = if redacted != sanitized.trim() {
+        None
+    } else if (sanitized != raw_trimmed || transformed != redacted) && check_keep_raw_text_enabled()
+    {
+        match encrypt_content_compressed(raw_trimmed, compression_min_bytes) {
+            Ok(encrypted_raw) => Some(encrypted_raw),
+            Err(e) => {
+                log::warn!("原始文本加密失败，不保留原始文本: {:?}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
     // 过滤空文本，空文本不进行记录
-    let trimmed_content = content.trim();
+    let trimmed_content = transformed.trim();
     if trimmed_content.is_empty() {
         log::debug!("跳过空文本记录");
-        return Ok(None);
+        return Ok(SavedText::Empty);
     }
 
-    let encrypt_res = encrypt_content(trimmed_content);
+    // 先压缩后加密，MD5仍然基于原始文本计算，去重不受影响
+    let encrypt_res = encrypt_content_compressed(trimmed_content, compression_min_bytes);
     match encrypt_res {
         Ok(encrypted) => {
             let md5_str = format!("{:x}", md5::compute(trimmed_content));
@@ -312,8 +872,8 @@ async fn handle_text(
             .await?;
 
             if let Some(record) = existing.first() {
-                if record.del_flag == Some(1) {
-                    // 已删除的记录，更新为新记录的所有字段
+                if matches!(record.del_flag, Some(1) | Some(2)) {
+                    // 已删除或超限隐藏的记录，更新为新记录的所有字段
                     let mut new_record = build_clip_record(
                         record.id.clone(), // 保持原ID
                         ClipType::Text.to_string(),
@@ -321,10 +881,12 @@ async fn handle_text(
                         md5_str,
                         sort,
                     );
+                    new_record.raw_content = raw_content;
+                    new_record.sensitive = sensitive.then_some(1);
 
                     // 检查VIP文本大小限制（加密后的字节大小）
                     let content_size = encrypted.as_bytes().len() as u64;
-                    let max_file_size = VipChecker::get_cached_max_file_size().unwrap_or(0);
+                    let max_file_size = VipChecker::get_cached_size_limits().text;
 
                     if max_file_size > 0 && content_size > max_file_size {
                         // 超出VIP限制，设置为跳过同步
@@ -353,15 +915,22 @@ async fn handle_text(
                         }
                     });
 
+                    if let Some(url) = detect_single_url(trimmed_content) {
+                        spawn_link_title_fetch(record.id.clone(), url.to_string());
+                    }
+
                     log::info!("更新已删除的文本记录为新数据: {}", record.id);
-                    return Ok(Some(new_record));
-                } else {
-                    // 活跃记录，只更新排序
-                    if let Err(e) = ClipRecord::update_sort(rb, &record.id, sort).await {
+                    return Ok(SavedText::New(new_record));
+                } else if !retire_if_outside_dedup_window(rb, record).await {
+                    // 活跃记录并且在去重时间窗口内，只更新排序；超过窗口的旧记录已删除，按新记录保存
+                    if let Err(e) = move_duplicate_to_top(rb, &record.id, sort).await {
                         log::error!("更新排序失败: {}", e);
                         return Err(e);
                     }
-                    return Ok(None);
+                    if sensitive && !record.is_sensitive() {
+                        ClipRecord::update_sensitive(rb, &record.id, 1).await?;
+                    }
+                    return Ok(SavedText::Existing(record.id.clone()));
                 }
             }
 
@@ -373,10 +942,12 @@ async fn handle_text(
                 md5_str,
                 sort,
             );
+            record.raw_content = raw_content;
+            record.sensitive = sensitive.then_some(1);
 
             // 检查VIP文本大小限制（加密后的字节大小）
             let content_size = encrypted.as_bytes().len() as u64;
-            let max_file_size = VipChecker::get_cached_max_file_size().unwrap_or(0);
+            let max_file_size = VipChecker::get_cached_size_limits().text;
 
             if max_file_size > 0 && content_size > max_file_size {
                 // 超出VIP限制，设置为跳过同步
@@ -400,7 +971,11 @@ async fn handle_text(
                             log::error!("搜索索引更新失败: {}", e);
                         }
                     });
-                    Ok(Some(record))
+                    // 内容为单个网址时在后台获取网页标题，不影响已保存的内容和MD5
+                    if let Some(url) = detect_single_url(trimmed_content) {
+                        spawn_link_title_fetch(record.id.clone(), url.to_string());
+                    }
+                    Ok(SavedText::New(record))
                 }
                 Err(e) => {
                     log::error!("插入文本记录失败: {}", e);
@@ -419,12 +994,248 @@ async fn handle_text(
     }
 }
 
-async fn handle_image(
+/// 保存RTF记录，内容为RTF原文
+pub(crate) async fn handle_rtf(
+    rb: &RBatis,
+    content: &str,
+    sort: i32,
+) -> Result<Option<ClipRecord>, AppError> {
+    handle_rich_text(rb, ClipType::Rtf, content, rtf_to_text, sort).await
+}
+
+/// 保存HTML记录，内容为HTML原文
+pub(crate) async fn handle_html(
+    rb: &RBatis,
+    content: &str,
+    sort: i32,
+) -> Result<Option<ClipRecord>, AppError> {
+    handle_rich_text(rb, ClipType::Html, content, html_to_text, sort).await
+}
+
+/// 按脱敏规则替换富文本标记中的敏感内容，并从替换后的标记中提取纯文本
+/// 返回脱敏后的标记和纯文本，敏感内容被标签拆开等原因无法在标记中替换时标记返回None
+fn redact_rich_text(
+    markup: &str,
+    to_text: fn(&str) -> String,
+    rules: &[String],
+) -> (Option<String>, String) {
+    let markup = redact_text(markup, rules).into_owned();
+    let plain_text = to_text(&markup);
+    let redacted_plain = redact_text(&plain_text, rules);
+    if redacted_plain != plain_text {
+        return (None, redacted_plain.into_owned());
+    }
+    (Some(markup), plain_text)
+}
+
+/// 判断同时复制的富文本格式能否保存，标记或其中的文字需要脱敏时返回None（整个格式丢弃）
+fn rich_flavor_to_keep<'a>(
+    rich_type: &ClipType,
+    rich_content: &'a str,
+    rules: &[String],
+) -> Option<&'a str> {
+    let to_text: fn(&str) -> String = match rich_type {
+        ClipType::Html => html_to_text,
+        ClipType::Rtf => rtf_to_text,
+        _ => return None,
+    };
+    match redact_rich_text(rich_content, to_text, rules) {
+        (Some(markup), _) if markup == rich_content => Some(rich_content),
+        _ => None,
+    }
+}
+
+/// 保存富文本记录：加密保存脱敏后的标记，MD5基于该标记计算，搜索索引使用提取出的纯文本
+async fn handle_rich_text(
+    rb: &RBatis,
+    clip_type: ClipType,
+    markup: &str,
+    to_text: fn(&str) -> String,
+    sort: i32,
+) -> Result<Option<ClipRecord>, AppError> {
+    // 没有可显示文字的富文本不进行记录
+    if markup.trim().is_empty() || to_text(markup).is_empty() {
+        log::debug!("跳过空的{}记录", clip_type);
+        return Ok(None);
+    }
+
+    // 脱敏后再计算MD5、加密和建立索引，避免只以HTML/RTF复制的敏感内容被保存和同步
+    let (markup, plain_text) = match redact_rich_text(markup, to_text, &get_redaction_rules()) {
+        (Some(markup), plain_text) => (markup, plain_text),
+        (None, plain_text) => {
+            log::info!(
+                "{}中的敏感内容无法在标记中替换，只保存脱敏后的纯文本",
+                clip_type
+            );
+            return handle_text(rb, &plain_text, sort).await;
+        }
+    };
+    let markup = markup.as_str();
+
+    let type_str = clip_type.to_string();
+    let encrypted =
+        encrypt_content_compressed(markup, get_text_compression_min_bytes()).map_err(|e| {
+            log::error!("{}内容加密失败，无法保存记录: {:?}", type_str, e);
+            AppError::Clipboard(format!("{}内容加密失败: {:?}", type_str, e))
+        })?;
+    let md5_str = format!("{:x}", md5::compute(markup));
+
+    let existing = ClipRecord::check_by_type_and_md5(rb, &type_str, &md5_str).await?;
+    let record_id = match existing.first() {
+        Some(record) if matches!(record.del_flag, Some(1) | Some(2)) => Some(record.id.clone()),
+        Some(record) => {
+            if !retire_if_outside_dedup_window(rb, record).await {
+                // 活跃记录并且在去重时间窗口内，只更新排序
+                move_duplicate_to_top(rb, &record.id, sort).await?;
+                return Ok(None);
+            }
+            None
+        }
+        None => None,
+    };
+
+    let mut record = build_clip_record(
+        record_id
+            .clone()
+            .unwrap_or_else(|| Uuid::new_v4().to_string()),
+        type_str.clone(),
+        Value::String(encrypted.clone()),
+        md5_str,
+        sort,
+    );
+
+    // 检查VIP文本大小限制（加密后的字节大小）
+    let content_size = encrypted.as_bytes().len() as u64;
+    let max_file_size = VipChecker::get_cached_size_limits().text;
+    if max_file_size > 0 && content_size > max_file_size {
+        record.sync_flag = Some(SKIP_SYNC);
+        record.skip_type = Some(2); // 2: VIP限制，可再次同步
+        log::info!(
+            "{}超出VIP限制，设置为跳过同步: 大小={}字节, 限制={}字节",
+            type_str,
+            content_size,
+            max_file_size
+        );
+    }
+
+    match &record_id {
+        // 已删除或超限隐藏的记录，更新为新记录的所有字段
+        Some(id) => {
+            ClipRecord::update_deleted_record_as_new(rb, id, &record).await?;
+            log::info!("更新已删除的{}记录为新数据: {}", type_str, id);
+        }
+        None => {
+            ClipRecord::insert(rb, &record).await.map_err(|e| {
+                log::error!("插入{}记录失败: {}", type_str, e);
+                AppError::Database(e)
+            })?;
+        }
+    }
+
+    let index_id = record.id.clone();
+    tokio::spawn(async move {
+        if let Err(e) = add_content_to_index(&index_id, &plain_text).await {
+            log::error!("搜索索引更新失败: {}", e);
+        }
+    });
+    Ok(Some(record))
+}
+
+/// 在去重时间窗口内遇到相同内容的活跃记录时，把已有记录移到最前
+/// 导入历史记录时已有记录保持原来的位置
+async fn move_duplicate_to_top(rb: &RBatis, id: &str, sort: i32) -> Result<(), AppError> {
+    if is_importing() {
+        return Ok(());
+    }
+    ClipRecord::update_sort(rb, id, sort).await
+}
+
+/// 相同内容的活跃记录早于去重时间窗口时删除旧记录，返回true，调用方按新记录保存（新的id和创建时间）
+/// 带有用户设置（置顶、编号槽位、收藏、标签、敏感标记）的记录保留原记录，只更新排序，导入历史记录时不删除已有记录
+async fn retire_if_outside_dedup_window(rb: &RBatis, record: &ClipRecord) -> bool {
+    if is_importing() {
+        return false;
+    }
+    let Some(window) = get_dedup_window() else {
+        return false;
+    };
+    if has_user_metadata(record) {
+        return false;
+    }
+    if !is_outside_dedup_window(record.created, current_timestamp(), window) {
+        return false;
+    }
+    if let Err(e) = ClipRecord::update_del_by_ids(rb, &vec![record.id.clone()]).await {
+        log::error!("删除超过去重时间窗口的旧记录失败: {}", e);
+        return false;
+    }
+    propagate_delete(record.clone()).await;
+    log::info!(
+        "相同内容的记录超过去重时间窗口，按新记录保存: {}",
+        record.id
+    );
+    true
+}
+
+/// 记录带有重新保存时会丢失的用户设置时返回true
+fn has_user_metadata(record: &ClipRecord) -> bool {
+    record.pinned_flag == 1
+        || record.slot.is_some()
+        || record.favorite_flag == Some(1)
+        || record.sensitive == Some(1)
+        || record.tags.as_deref().is_some_and(|tags| !tags.is_empty())
+}
+
+/// 记录创建时间（毫秒）距现在达到去重时间窗口时返回true，正好等于窗口长度也按窗口外处理
+fn is_outside_dedup_window(created: u64, now: u64, window: Duration) -> bool {
+    now.saturating_sub(created) >= window.as_millis() as u64
+}
+
+/// 检查图片是否小于设置的最小字节数或最小宽高（例如1x1跟踪像素、误复制的小图标）
+fn is_image_below_min_size(data: &[u8]) -> bool {
+    let (min_bytes, min_dimensions) = get_min_image_limits();
+    if min_bytes > 0 && (data.len() as u64) < min_bytes {
+        log::debug!(
+            "图片大小{}字节小于最小限制{}字节，跳过记录",
+            data.len(),
+            min_bytes
+        );
+        return true;
+    }
+    if min_dimensions > 0 {
+        if let Some((width, height)) = image_dimensions(data) {
+            if width < min_dimensions || height < min_dimensions {
+                log::debug!(
+                    "图片尺寸{}x{}小于最小限制{}像素，跳过记录",
+                    width,
+                    height,
+                    min_dimensions
+                );
+                return true;
+            }
+        }
+    }
+    false
+}
+
+pub(crate) async fn handle_image(
     rb: &RBatis,
     file_data: Option<&Vec<u8>>,
+    image_format: Option<&str>,
     sort: i32,
 ) -> Result<Option<ClipRecord>, AppError> {
+    // 关闭图片记录时在保存图片文件之前返回
+    if !check_capture_type_enabled(&ClipType::Image) {
+        return Ok(None);
+    }
     if let Some(data) = file_data {
+        if is_image_below_min_size(data) {
+            return Ok(None);
+        }
+
+        // GIF、APNG动图按原格式保存，其他图片已由监听器统一转为png
+        let (data, extension) = prepare_image_data(data, image_format);
+        let data = data.as_ref();
         let md5_str = format!("{:x}", md5::compute(data));
 
         // 单次查询检查是否有相同内容的记录
@@ -433,12 +1244,12 @@ async fn handle_image(
                 .await?;
 
         if let Some(record) = existing.first() {
-            if record.del_flag == Some(1) {
-                // 已删除的记录，更新为新记录的所有字段
+            if matches!(record.del_flag, Some(1) | Some(2)) {
+                // 已删除或超限隐藏的记录，更新为新记录的所有字段
                 let id = record.id.clone();
 
                 // 先生成文件名，然后保存图片
-                let filename = generate_unique_filename("png");
+                let filename = generate_unique_filename(extension);
                 if save_image_with_filename(&filename, data).await {
                     let mut new_record = build_clip_record(
                         id.clone(),
@@ -450,14 +1261,14 @@ async fn handle_image(
 
                     // 检查VIP图片大小限制
                     let image_size = data.len() as u64;
-                    let max_file_size = VipChecker::get_cached_max_file_size().unwrap_or(0);
+                    let max_file_size = VipChecker::get_cached_size_limits().image;
 
                     if max_file_size == 0 || image_size > max_file_size {
                         // 超出VIP限制，设置为跳过同步
                         new_record.sync_flag = Some(SKIP_SYNC);
                         new_record.skip_type = Some(2); // 2: VIP限制，可再次同步
                         log::info!(
-                            "图片超出VIP限制，设置为跳过同步: 图片大小={}, 限制={}",
+                            "图片超出同步大小限制，设置为跳过同步: 图片大小={}, 限制={}",
                             image_size,
                             max_file_size
                         );
@@ -473,14 +1284,15 @@ async fn handle_image(
                     }
 
                     log::info!("更新已删除的图片记录为新数据: {}", id);
+                    spawn_image_ocr(id, filename);
                     return Ok(Some(new_record));
                 } else {
                     log::error!("保存图片失败，无法更新记录");
                     return Err(AppError::Clipboard("保存图片失败".to_string()));
                 }
-            } else {
-                // 活跃记录，只更新排序
-                if let Err(e) = ClipRecord::update_sort(rb, &record.id, sort).await {
+            } else if !retire_if_outside_dedup_window(rb, record).await {
+                // 活跃记录并且在去重时间窗口内，只更新排序；超过窗口的旧记录已删除，按新记录保存
+                if let Err(e) = move_duplicate_to_top(rb, &record.id, sort).await {
                     log::error!("更新图片排序失败: {}", e);
                     return Err(e);
                 }
@@ -490,7 +1302,7 @@ async fn handle_image(
 
         // 创建新记录 - 先生成文件名，然后保存图片
         let id = Uuid::new_v4().to_string();
-        let filename = generate_unique_filename("png");
+        let filename = generate_unique_filename(extension);
 
         if save_image_with_filename(&filename, data).await {
             let mut record = build_clip_record(
@@ -503,14 +1315,14 @@ async fn handle_image(
 
             // 检查VIP图片大小限制
             let image_size = data.len() as u64;
-            let max_file_size = VipChecker::get_cached_max_file_size().unwrap_or(0);
+            let max_file_size = VipChecker::get_cached_size_limits().image;
 
             if max_file_size == 0 || image_size > max_file_size {
                 // 超出VIP限制，设置为跳过同步
                 record.sync_flag = Some(SKIP_SYNC);
                 record.skip_type = Some(2); // 2: VIP限制，可再次同步
                 log::info!(
-                    "图片超出VIP限制，设置为跳过同步: 图片大小={}, 限制={}",
+                    "图片超出同步大小限制，设置为跳过同步: 图片大小={}, 限制={}",
                     image_size,
                     max_file_size
                 );
@@ -519,6 +1331,7 @@ async fn handle_image(
             match ClipRecord::insert(rb, &record).await {
                 Ok(_) => {
                     log::info!("新增图片记录成功，ID: {}, 文件名: {}", id, filename);
+                    spawn_image_ocr(id, filename);
                     Ok(Some(record))
                 }
                 Err(e) => {
@@ -537,12 +1350,47 @@ async fn handle_image(
     }
 }
 
-async fn handle_file(
+pub(crate) async fn handle_file(
     rb: &RBatis,
     file_paths: Option<&Vec<String>>,
     sort: i32,
 ) -> Result<Option<ClipRecord>, AppError> {
+    // 关闭文件记录时在复制文件之前返回
+    if !check_capture_type_enabled(&ClipType::File) {
+        return Ok(None);
+    }
     if let Some(paths) = file_paths {
+        // 过滤掉粘贴时生成的临时文件，避免把自己的临时文件重复记录
+        let paths: Vec<String> = paths
+            .iter()
+            .filter(|path| !is_in_paste_temp_dir(std::path::Path::new(path)))
+            .cloned()
+            .collect();
+        if paths.is_empty() {
+            log::debug!("复制的文件均为ClipPal临时文件，跳过记录");
+            return Ok(None);
+        }
+
+        // 按设置处理符号链接
+        let (paths, has_reference) = apply_symlink_mode(paths);
+        if paths.is_empty() {
+            log::debug!("复制的文件均为被跳过的符号链接，跳过记录");
+            return Ok(None);
+        }
+        let paths = &paths;
+
+        // 单个符号链接只记录目标路径，不复制内容，按本地记录保存
+        if has_reference && paths.len() == 1 {
+            return handle_multiple_files(rb, paths, sort).await;
+        }
+
+        // 文件数超过单条记录上限时，在计算MD5之前截断或拆分
+        if let Some(max_files) = get_max_files_per_record() {
+            if paths.len() > max_files {
+                return handle_files_overflow(rb, paths, max_files, sort).await;
+            }
+        }
+
         // 多文件不支持云同步（技术限制）
         if paths.len() > 1 {
             log::info!(
@@ -570,12 +1418,16 @@ async fn handle_file(
             };
 
             // 使用文件内容计算MD5
-            let md5_str = match compute_file_content_md5(path).await {
-                Ok(hash) => hash,
-                Err(e) => {
+            let md5_str = match file_content_md5_with_timeout(path.to_path_buf()).await {
+                Some(Ok(hash)) => hash,
+                Some(Err(e)) => {
                     log::error!("无法读取文件内容生成MD5: {}, 文件: {}", e, file_path);
                     return Ok(None); // 无法读取文件则跳过
                 }
+                None => {
+                    log::warn!("计算文件MD5超时或失败，跳过记录: {}", file_path);
+                    return Ok(None);
+                }
             };
 
             // 单次查询检查是否有相同内容的记录
@@ -588,8 +1440,8 @@ async fn handle_file(
 
             // 判断同样的文件复制记录是否已存在
             if let Some(record) = existing.first() {
-                if record.del_flag == Some(1) {
-                    // 已删除的记录，复制文件并更新记录
+                if matches!(record.del_flag, Some(1) | Some(2)) {
+                    // 已删除或超限隐藏的记录，复制文件并更新记录
                     let original_filename = std::path::Path::new(file_path)
                         .file_name()
                         .and_then(|name| name.to_str())
@@ -606,6 +1458,9 @@ async fn handle_file(
                             build_sync_eligible_file_record(&record.id, file_path, &md5_str, sort);
                         new_record.content = Value::String(original_filename.to_string());
                         new_record.local_file_path = Some(absolute_path.clone());
+                        let times = read_file_times(&file_path_buf);
+                        new_record.file_modified = times.modified;
+                        new_record.file_created = times.created;
 
                         if let Err(e) =
                             ClipRecord::update_deleted_record_as_new(rb, &record.id, &new_record)
@@ -657,9 +1512,9 @@ async fn handle_file(
                     let updated_record =
                         build_sync_eligible_file_record(&record.id, file_path, &md5_str, sort);
                     return Ok(Some(updated_record));
-                } else {
-                    // 活跃记录，只更新排序
-                    if let Err(e) = ClipRecord::update_sort(rb, &record.id, sort).await {
+                } else if !retire_if_outside_dedup_window(rb, record).await {
+                    // 活跃记录并且在去重时间窗口内，只更新排序；超过窗口的旧记录已删除，按新记录保存
+                    if let Err(e) = move_duplicate_to_top(rb, &record.id, sort).await {
                         log::error!("更新文件排序失败: {}", e);
                         return Err(e);
                     }
@@ -674,6 +1529,74 @@ async fn handle_file(
     Ok(None)
 }
 
+#[derive(Debug, Clone, Serialize)]
+struct FilesOverflowPayload {
+    // 复制的文件总数
+    total: usize,
+    // 实际记录的文件数
+    recorded: usize,
+    // 生成的记录数
+    record_count: usize,
+    // 是否拆分为多条记录（否则为截断）
+    split: bool,
+}
+
+/// 处理文件数超过单条记录上限的情况：按设置截断或拆分为多条记录，并通知前端
+async fn handle_files_overflow(
+    rb: &RBatis,
+    paths: &Vec<String>,
+    max_files: usize,
+    sort: i32,
+) -> Result<Option<ClipRecord>, AppError> {
+    let split = get_files_overflow_mode() == FILES_OVERFLOW_SPLIT;
+    let mut payload = FilesOverflowPayload {
+        total: paths.len(),
+        recorded: 0,
+        record_count: 0,
+        split,
+    };
+
+    let result = if split {
+        log::info!(
+            "复制的文件数({})超过单条记录上限({})，拆分为多条记录",
+            paths.len(),
+            max_files
+        );
+        let mut last_record = None;
+        for (index, chunk) in paths.chunks(max_files).enumerate() {
+            // 第一组沿用传入的排序，后续每组重新获取排序
+            let chunk_sort = if index == 0 {
+                sort
+            } else {
+                ClipRecord::get_next_sort(rb).await
+            };
+            if let Some(record) = handle_multiple_files(rb, &chunk.to_vec(), chunk_sort).await? {
+                last_record = Some(record);
+            }
+            payload.recorded += chunk.len();
+            payload.record_count += 1;
+        }
+        Ok(last_record)
+    } else {
+        log::info!(
+            "复制的文件数({})超过单条记录上限({})，只记录前{}个文件",
+            paths.len(),
+            max_files,
+            max_files
+        );
+        let truncated = paths[..max_files].to_vec();
+        payload.recorded = truncated.len();
+        payload.record_count = 1;
+        handle_multiple_files(rb, &truncated, sort).await
+    };
+
+    // 通知前端复制的文件被截断或拆分
+    if let Some(app_handle) = CONTEXT.try_get::<AppHandle>() {
+        let _ = app_handle.emit("clip_files_overflow", payload);
+    }
+    result
+}
+
 /// 处理多文件情况（不支持云同步）
 async fn handle_multiple_files(
     rb: &RBatis,
@@ -681,10 +1604,19 @@ async fn handle_multiple_files(
     sort: i32,
 ) -> Result<Option<ClipRecord>, AppError> {
     // 使用文件内容组合计算MD5
-    let md5_str = match compute_multiple_files_md5(paths).await {
-        Ok(hash) => hash,
-        Err(e) => {
-            log::error!("无法计算多文件组合MD5: {}", e);
+    let runtime = tokio::runtime::Handle::current();
+    let md5_paths = paths.clone();
+    let md5_result = run_blocking_with_timeout("计算多文件MD5", move || {
+        runtime.block_on(compute_multiple_files_md5(&md5_paths))
+    })
+    .await;
+    let md5_str = match md5_result {
+        Some(Ok(hash)) => hash,
+        other => {
+            match other {
+                Some(Err(e)) => log::error!("无法计算多文件组合MD5: {}", e),
+                _ => log::warn!("计算多文件组合MD5超时或失败，改用文件名计算"),
+            }
             // 回退到文件名组合MD5（不包含路径信息）
             let mut filenames: Vec<String> = paths
                 .iter()
@@ -708,8 +1640,8 @@ async fn handle_multiple_files(
             .await?;
 
     if let Some(record) = existing.first() {
-        if record.del_flag == Some(1) {
-            // 已删除的记录，更新为新记录
+        if matches!(record.del_flag, Some(1) | Some(2)) {
+            // 已删除或超限隐藏的记录，更新为新记录
             let new_record = build_multiple_files_record(&record.id, paths, &md5_str, sort);
             if let Err(e) =
                 ClipRecord::update_deleted_record_as_new(rb, &record.id, &new_record).await
@@ -729,9 +1661,9 @@ async fn handle_multiple_files(
 
             log::info!("更新已删除的多文件记录为新数据: {}", record.id);
             return Ok(Some(new_record));
-        } else {
-            // 活跃记录，只更新排序
-            if let Err(e) = ClipRecord::update_sort(rb, &record.id, sort).await {
+        } else if !retire_if_outside_dedup_window(rb, record).await {
+            // 活跃记录并且在去重时间窗口内，只更新排序；超过窗口的旧记录已删除，按新记录保存
+            if let Err(e) = move_duplicate_to_top(rb, &record.id, sort).await {
                 log::error!("更新多文件排序失败: {}", e);
                 return Err(e);
             }
@@ -825,18 +1757,22 @@ async fn handle_sync_eligible_file(
 
         // 设置本地文件路径为复制后的路径
         record.local_file_path = Some(absolute_path.clone());
+        // 保存原文件的时间，粘贴时还原
+        let times = read_file_times(&file_path_buf);
+        record.file_modified = times.modified;
+        record.file_created = times.created;
 
         // 检查VIP文件大小限制
         if let Ok(metadata) = std::fs::metadata(&absolute_path) {
             let file_size = metadata.len();
-            let max_file_size = VipChecker::get_cached_max_file_size().unwrap_or(0);
+            let max_file_size = VipChecker::get_cached_size_limits().file;
 
             if max_file_size == 0 || file_size > max_file_size {
                 // 超出VIP限制，设置为跳过同步
                 record.sync_flag = Some(SKIP_SYNC);
                 record.skip_type = Some(2); // 2: VIP限制，可再次同步
                 log::info!(
-                    "文件超出VIP限制，设置为跳过同步: 文件大小={}, 限制={}",
+                    "文件超出同步大小限制，设置为跳过同步: 文件大小={}, 限制={}",
                     file_size,
                     max_file_size
                 );
@@ -965,10 +1901,33 @@ async fn copy_file_to_resources(
         let relative_path = format!("files/{}", new_filename);
         let absolute_path = target_path.to_string_lossy().to_string();
 
-        // 复制文件
-        match std::fs::copy(file_path, &target_path) {
+        // 复制文件，超时后放弃，后台完成的复制文件会被删除
+        let abandoned = Arc::new(AtomicBool::new(false));
+        let copy_abandoned = abandoned.clone();
+        let copy_source = file_path.clone();
+        let copy_target = target_path.clone();
+        let copy_result = run_blocking_with_timeout("复制文件", move || {
+            let result = std::fs::copy(&copy_source, &copy_target);
+            if result.is_ok() && copy_abandoned.load(Ordering::SeqCst) {
+                let _ = std::fs::remove_file(&copy_target);
+            }
+            result
+        })
+        .await;
+        let Some(copy_result) = copy_result else {
+            abandoned.store(true, Ordering::SeqCst);
+            // 超时时复制可能已经完成，直接删除
+            let _ = std::fs::remove_file(&target_path);
+            log::warn!("复制文件超时或失败，按不支持同步处理: {:?}", file_path);
+            return None;
+        };
+        match copy_result {
             Ok(_) => {
                 log::debug!("文件复制成功: {:?} -> {:?}", file_path, target_path);
+                // 复制后的文件保留原文件的修改时间和创建时间
+                if let Err(e) = apply_file_times(&target_path, &read_file_times(file_path)) {
+                    log::warn!("设置复制文件的时间失败: {:?}, 错误: {}", target_path, e);
+                }
                 Some((relative_path, absolute_path))
             }
             Err(e) => {
@@ -994,8 +1953,57 @@ fn generate_unique_filename(extension: &str) -> String {
     format!("{}_{}.{}", now, uid, extension)
 }
 
-/// 使用指定的文件名保存图片
-async fn save_image_with_filename(filename: &str, image: &Vec<u8>) -> bool {
+/// 确定图片的保存格式，返回保存的数据和扩展名
+/// GIF、APNG动图按原格式保存（APNG的扩展名仍为png），关闭保存动图或超过大小限制时转为第一帧的png
+/// image_format 为剪贴板提供的原始格式，没有时根据文件头判断
+fn prepare_image_data<'a>(
+    data: &'a [u8],
+    image_format: Option<&str>,
+) -> (Cow<'a, [u8]>, &'static str) {
+    let Some(extension) = animated_image_extension(data, image_format) else {
+        return (Cow::Borrowed(data), "png");
+    };
+    if should_keep_animated_image(data.len() as u64) {
+        return (Cow::Borrowed(data), extension);
+    }
+    match first_frame_png(data) {
+        Some(png) => {
+            log::info!("动图大小{}字节，只保存第一帧", data.len());
+            (Cow::Owned(png), "png")
+        }
+        None => (Cow::Borrowed(data), extension),
+    }
+}
+
+/// 解码图片第一帧并编码为png
+fn first_frame_png(data: &[u8]) -> Option<Vec<u8>> {
+    let image = match image::load_from_memory(data) {
+        Ok(image) => image,
+        Err(e) => {
+            log::warn!("解码动图第一帧失败: {}", e);
+            return None;
+        }
+    };
+    let mut png = std::io::Cursor::new(Vec::new());
+    if let Err(e) = image.write_to(&mut png, image::ImageFormat::Png) {
+        log::warn!("动图第一帧编码为png失败: {}", e);
+        return None;
+    }
+    Some(png.into_inner())
+}
+
+/// 使用指定的文件名保存图片，保存成功后在后台生成缩略图供列表预览
+async fn save_image_with_filename(filename: &str, image: &[u8]) -> bool {
+    if !write_resource_file(filename, image) {
+        return false;
+    }
+    // 缩略图生成失败不影响记录，读取缩略图时会重新生成
+    spawn_thumbnail_generation(filename.to_string());
+    true
+}
+
+/// 将数据写入resources目录下的指定文件
+fn write_resource_file(filename: &str, image: &[u8]) -> bool {
     if let Some(resource_path) = get_resources_dir() {
         // 拼接完整路径
         let mut full_path: PathBuf = resource_path.clone();
@@ -1036,6 +2044,7 @@ async fn delete_image_file(filename: &str) {
         } else {
             log::debug!("删除图片文件成功: {}", filename);
         }
+        let _ = std::fs::remove_file(resource_path.join(thumbnail_filename(filename)));
     }
 }
 
@@ -1048,3 +2057,101 @@ async fn delete_copied_file(file_path: &str) {
         log::debug!("删除已复制文件成功: {}", file_path);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dedup_window_boundary() {
+        let window = Duration::from_secs(60);
+        let now = 1_000_000;
+        // 窗口内只移到最前
+        assert!(!is_outside_dedup_window(now - 59_999, now, window));
+        // 正好等于窗口长度和超过窗口时按新记录保存
+        assert!(is_outside_dedup_window(now - 60_000, now, window));
+        assert!(is_outside_dedup_window(now - 60_001, now, window));
+        // 创建时间晚于当前时间（系统时间被调整）按窗口内处理
+        assert!(!is_outside_dedup_window(now + 1_000, now, window));
+    }
+
+    #[test]
+    fn test_has_user_metadata() {
+        let record = ClipRecord::default();
+        assert!(!has_user_metadata(&record));
+        assert!(has_user_metadata(&ClipRecord {
+            pinned_flag: 1,
+            ..Default::default()
+        }));
+        assert!(has_user_metadata(&ClipRecord {
+            slot: Some(1),
+            ..Default::default()
+        }));
+        assert!(has_user_metadata(&ClipRecord {
+            favorite_flag: Some(1),
+            ..Default::default()
+        }));
+        assert!(has_user_metadata(&ClipRecord {
+            sensitive: Some(1),
+            ..Default::default()
+        }));
+        assert!(has_user_metadata(&ClipRecord {
+            tags: Some("工作".to_string()),
+            ..Default::default()
+        }));
+        // 空标签和取消收藏不算用户设置
+        assert!(!has_user_metadata(&ClipRecord {
+            tags: Some(String::new()),
+            favorite_flag: Some(0),
+            ..Default::default()
+        }));
+    }
+
+    #[test]
+    fn test_redact_html_only_capture() {
+        let rules = vec![r"(?i)bearer\s+[A-Za-z0-9\-._~+/]+=*".to_string()];
+        let (markup, plain_text) = redact_rich_text(
+            "<p>token: <code>Bearer abc.DEF-123</code></p>",
+            html_to_text,
+            &rules,
+        );
+        assert_eq!(
+            markup.as_deref(),
+            Some("<p>token: <code>[REDACTED]</code></p>")
+        );
+        assert_eq!(plain_text, "token: [REDACTED]");
+
+        // 敏感内容被标签拆开时不保存标记，只保存脱敏后的纯文本
+        let (markup, plain_text) =
+            redact_rich_text("<p>Bearer <b>abc123</b></p>", html_to_text, &rules);
+        assert_eq!(markup, None);
+        assert_eq!(plain_text, "[REDACTED]");
+
+        let (markup, plain_text) = redact_rich_text("<p>hello</p>", html_to_text, &rules);
+        assert_eq!(markup.as_deref(), Some("<p>hello</p>"));
+        assert_eq!(plain_text, "hello");
+    }
+
+    #[test]
+    fn test_rich_flavor_to_keep() {
+        let rules = vec![r"(?i)bearer\s+[A-Za-z0-9\-._~+/]+=*".to_string()];
+        assert_eq!(
+            rich_flavor_to_keep(&ClipType::Html, "<p>hello</p>", &rules),
+            Some("<p>hello</p>")
+        );
+        // 标记中可以替换的敏感内容同样丢弃整个格式
+        assert_eq!(
+            rich_flavor_to_keep(&ClipType::Html, "<p>Bearer abc123</p>", &rules),
+            None
+        );
+        // 敏感内容被标签拆开时丢弃
+        assert_eq!(
+            rich_flavor_to_keep(&ClipType::Html, "<p>Bearer <b>abc123</b></p>", &rules),
+            None
+        );
+        assert_eq!(
+            rich_flavor_to_keep(&ClipType::Rtf, r"{\rtf1 Bearer abc123}", &rules),
+            None
+        );
+    }
+}
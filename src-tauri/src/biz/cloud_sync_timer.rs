@@ -1,106 +1,220 @@
 use clipboard_listener::ClipType;
 use log;
 use rbatis::RBatis;
-use std::sync::{Arc, OnceLock, RwLock};
-use tauri::{AppHandle, Emitter};
-use tokio::sync::mpsc;
-use tokio::time::Duration;
+use serde::Serialize;
+use std::sync::RwLock;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::{mpsc, oneshot, watch};
+use tokio::time::{Duration, Instant, Interval};
 use uuid::Uuid;
 
 use crate::api::cloud_sync_api::{
     sync_clipboard, sync_server_time, ClipRecordParam, CloudSyncRequest,
 };
-use crate::biz::clip_record::{NOT_SYNCHRONIZED, SKIP_SYNC, SYNCHRONIZED, SYNCHRONIZING};
+use crate::biz::background_task_status::{
+    begin_task_tick, register_background_task, TASK_CLOUD_SYNC,
+};
+use crate::biz::clip_record::{
+    CloudInsertPosition, DEL_FLAG_OVER_LIMIT, NOT_SYNCHRONIZED, SKIP_SYNC,
+    SKIP_TYPE_SYNC_TEXT_LIMIT, SYNCHRONIZED, SYNCHRONIZING,
+};
 use crate::biz::clip_record_clean::try_clean_clip_record;
 use crate::biz::content_search::add_content_to_index;
+use crate::biz::copy_clip_record::is_delete_pending;
+use crate::biz::record_slot::is_valid_slot;
+use crate::biz::record_tag::normalize_tags;
+use crate::biz::sync_conflict::{
+    cloud_tags_to_apply, detect_conflict, record_sync_conflicts, should_apply_cloud_state,
+};
 use crate::biz::sync_time::SyncTime;
-use crate::biz::system_setting::{check_cloud_sync_enabled, SYNC_INTERVAL_SECONDS};
-use crate::biz::vip_checker::VipChecker;
+use crate::biz::system_setting::{
+    check_cloud_sync_enabled, ensure_not_kiosk_mode, get_cloud_insert_policy,
+    get_cloud_sync_interval, get_max_sync_text_bytes, get_sync_conflict_policy,
+    CLOUD_INSERT_BOTTOM, CLOUD_INSERT_BY_TIME,
+};
+use crate::biz::vip_checker::{SizeLimits, VipChecker};
 use crate::errors::{AppError, AppResult};
 use crate::utils::config::get_max_file_size_bytes;
 use crate::utils::device_info::GLOBAL_DEVICE_ID;
 use crate::utils::file_dir::get_resources_dir;
-use crate::utils::lock_utils::lock_utils::safe_read_lock;
 use crate::utils::token_manager::has_valid_auth;
 use crate::{
-    biz::{clip_record::ClipRecord, system_setting::Settings},
-    utils::lock_utils::GlobalSyncLock,
+    biz::clip_record::ClipRecord,
+    utils::{lock_utils::GlobalSyncLock, sensitive_log::with_content_log_suppressed},
     CONTEXT,
 };
+use std::collections::HashSet;
 use std::path::PathBuf;
 
 pub struct CloudSyncTimer {
     app_handle: AppHandle,
     rb: RBatis,
-    trigger_receiver: Option<mpsc::UnboundedReceiver<()>>,
+    trigger_receiver: Option<mpsc::UnboundedReceiver<SyncTrigger>>,
+    interval_receiver: Option<watch::Receiver<u64>>,
+    // 连续同步失败次数，同步成功后清零
+    consecutive_failures: u32,
+    // 连续失败后暂停定时同步，到该时间之前的定时触发直接跳过
+    backoff_until: Option<Instant>,
 }
 
-// 全局触发器发送端
-static TRIGGER_SENDER: OnceLock<mpsc::UnboundedSender<()>> = OnceLock::new();
+// 连续失败达到该次数后开始延长同步间隔
+const SYNC_FAILURE_THRESHOLD: u32 = 3;
+// 延长后的同步间隔上限（秒）
+const MAX_SYNC_BACKOFF_SECS: u64 = 30 * 60;
+
+// 立即同步信号，携带结果通道时同步完成后回传本次同步结果
+type SyncTrigger = Option<oneshot::Sender<Result<SyncCounts, String>>>;
+
+// 全局触发器发送端（同步任务被重启时替换为新的发送端）
+static TRIGGER_SENDER: RwLock<Option<mpsc::UnboundedSender<SyncTrigger>>> = RwLock::new(None);
+
+// 全局同步间隔发送端，设置修改后通知定时任务使用新的间隔
+static INTERVAL_SENDER: RwLock<Option<watch::Sender<u64>>> = RwLock::new(None);
+
+/// 一次云同步上传和拉取的记录数
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SyncCounts {
+    // 上传的记录数
+    pub uploaded: usize,
+    // 从云端拉取并新增到本地的记录数
+    pub downloaded: usize,
+}
+
+/// 手动同步的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncNowResult {
+    pub uploaded: usize,
+    pub downloaded: usize,
+    // 同步失败或被跳过的原因，成功时为None
+    pub error: Option<String>,
+}
 
 impl CloudSyncTimer {
     pub fn new(app_handle: AppHandle, rb: RBatis) -> Self {
         // 创建触发器通道
         let (trigger_sender, trigger_receiver) = mpsc::unbounded_channel();
 
+        // 创建同步间隔通道
+        let (interval_sender, interval_receiver) = watch::channel(get_cloud_sync_interval());
+
         // 保存全局发送端
-        let _ = TRIGGER_SENDER.set(trigger_sender);
+        if let Ok(mut sender) = TRIGGER_SENDER.write() {
+            *sender = Some(trigger_sender);
+        }
+        if let Ok(mut sender) = INTERVAL_SENDER.write() {
+            *sender = Some(interval_sender);
+        }
 
         Self {
             app_handle,
             rb,
             trigger_receiver: Some(trigger_receiver),
+            interval_receiver: Some(interval_receiver),
+            consecutive_failures: 0,
+            backoff_until: None,
         }
     }
 
     /// 启动云同步定时任务
     pub async fn start(mut self) {
-        let cloud_sync_interval = {
-            let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
-            match safe_read_lock(&settings_lock) {
-                Ok(settings) => settings.cloud_sync_interval,
-                Err(e) => {
-                    log::warn!("无法获取设置: {}", e);
-                    SYNC_INTERVAL_SECONDS
-                }
-            }
-        };
+        let mut interval_receiver = self.interval_receiver.take().unwrap();
+        let mut cloud_sync_interval = *interval_receiver.borrow_and_update();
         log::info!("云同步服务已启动，间隔: {}秒", cloud_sync_interval);
+        register_background_task(TASK_CLOUD_SYNC, cloud_sync_interval);
 
         let sync_lock: &GlobalSyncLock = CONTEXT.get::<GlobalSyncLock>();
         let mut trigger_receiver = self.trigger_receiver.take().unwrap();
 
-        // 创建定时器
-        let mut timer = tokio::time::interval(Duration::from_secs(cloud_sync_interval as u64));
-        timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        // 创建定时器，启动后立即执行一次
+        let mut timer = new_sync_timer(Instant::now(), cloud_sync_interval);
 
         loop {
             tokio::select! {
                 // 定时器触发
                 _ = timer.tick() => {
-                    self.try_execute_sync(sync_lock, "定时任务").await;
+                    if self.in_backoff() {
+                        log::debug!("云同步连续失败，暂停中，跳过本次定时同步");
+                    } else {
+                        let _ = self.try_execute_sync(sync_lock, "定时任务").await;
+                    }
                 }
                 // 立即同步触发
-                _ = trigger_receiver.recv() => {
+                Some(reply) = trigger_receiver.recv() => {
                     log::debug!("收到立即同步信号");
-                    self.try_execute_sync(sync_lock, "立即同步").await;
+                    let result = self.try_execute_sync(sync_lock, "立即同步").await;
+                    if let Some(reply) = reply {
+                        let _ = reply.send(result);
+                    }
+                }
+                // 同步间隔修改，重建定时器，从现在起经过新的间隔后执行下一次同步
+                Ok(()) = interval_receiver.changed() => {
+                    let new_interval = *interval_receiver.borrow_and_update();
+                    if new_interval != cloud_sync_interval {
+                        log::info!("云同步间隔已修改: {}秒 -> {}秒", cloud_sync_interval, new_interval);
+                        cloud_sync_interval = new_interval;
+                        timer = new_sync_timer(
+                            Instant::now() + Duration::from_secs(new_interval),
+                            new_interval,
+                        );
+                        register_background_task(TASK_CLOUD_SYNC, new_interval);
+                    }
                 }
             }
         }
     }
 
-    /// 尝试执行同步任务
-    async fn try_execute_sync(&self, sync_lock: &GlobalSyncLock, source: &str) {
+    /// 是否处于连续失败后的暂停期
+    fn in_backoff(&self) -> bool {
+        self.backoff_until
+            .is_some_and(|until| Instant::now() < until)
+    }
+
+    /// 记录同步结果：连续失败达到阈值后按指数延长下一次定时同步的时间，成功后恢复正常间隔
+    /// 手动触发的同步不受暂停限制，成功同样会恢复正常间隔
+    fn record_sync_result(&mut self, success: bool) {
+        if success {
+            if self.consecutive_failures >= SYNC_FAILURE_THRESHOLD {
+                log::info!(
+                    "云同步已恢复，连续失败{}次后同步成功",
+                    self.consecutive_failures
+                );
+            }
+            self.consecutive_failures = 0;
+            self.backoff_until = None;
+            return;
+        }
+
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        if self.consecutive_failures < SYNC_FAILURE_THRESHOLD {
+            return;
+        }
+        let delay = backoff_delay_secs(get_cloud_sync_interval(), self.consecutive_failures);
+        self.backoff_until = Some(Instant::now() + Duration::from_secs(delay));
+        log::warn!(
+            "云同步连续失败{}次，{}秒内暂停定时同步",
+            self.consecutive_failures,
+            delay
+        );
+    }
+
+    /// 尝试执行同步任务，返回本次同步的记录数，跳过或失败时返回原因
+    async fn try_execute_sync(
+        &mut self,
+        sync_lock: &GlobalSyncLock,
+        source: &str,
+    ) -> Result<SyncCounts, String> {
+        let _tick = begin_task_tick(TASK_CLOUD_SYNC);
+
         // 检查云同步是否开启
         if !check_cloud_sync_enabled().await {
             log::debug!("云同步未开启，跳过{}同步", source);
-            return;
+            return Err("云同步未开启".to_string());
         }
 
         // 检查用户登录状态
         if !has_valid_auth() {
             log::debug!("用户未登录，跳过{}同步", source);
-            return;
+            return Err("用户未登录".to_string());
         }
 
         // 检查VIP云同步权限
@@ -108,13 +222,13 @@ impl CloudSyncTimer {
             Ok((allowed, message)) => {
                 if !allowed {
                     log::warn!("{}同步权限检查失败: {}", source, message);
-                    return;
+                    return Err(message);
                 }
                 log::debug!("{}同步权限检查通过: {}", source, message);
             }
             Err(e) => {
                 log::error!("{}同步权限检查出错: {}", source, e);
-                return;
+                return Err(format!("同步权限检查失败: {}", e));
             }
         }
 
@@ -134,12 +248,12 @@ impl CloudSyncTimer {
                     Ok((still_allowed, _)) => {
                         if !still_allowed {
                             log::warn!("刷新后{}同步权限检查失败", source);
-                            return;
+                            return Err("没有云同步权限".to_string());
                         }
                     }
                     Err(e) => {
                         log::error!("刷新后{}同步权限检查出错: {}", source, e);
-                        return;
+                        return Err(format!("同步权限检查失败: {}", e));
                     }
                 }
             }
@@ -150,18 +264,21 @@ impl CloudSyncTimer {
             log::info!("开始{}云同步", source);
             let result = self.execute_sync_task_with_source(source).await;
             drop(guard); // 显式释放锁
+            self.record_sync_result(result.is_ok());
 
-            if let Err(e) = result {
+            result.map_err(|e| {
                 log::error!("{}云同步失败: {}", source, e);
-            }
+                e.to_string()
+            })
         } else {
             // 获取不到锁，说明已有同步任务在执行，跳过避免重复同步
             log::info!("{}云同步在执行中，跳过本次同步", source);
+            Err("云同步正在进行中".to_string())
         }
     }
 
-    /// 执行同步任务（带来源标识）
-    pub async fn execute_sync_task_with_source(&self, source: &str) -> AppResult<()> {
+    /// 执行同步任务（带来源标识），返回本次上传和拉取的记录数
+    pub async fn execute_sync_task_with_source(&self, source: &str) -> AppResult<SyncCounts> {
         let last_sync_time = SyncTime::select_last_time(&self.rb).await;
 
         // 获取一次服务器时间，代表了本次同步的时间戳版本号
@@ -177,6 +294,18 @@ impl CloudSyncTimer {
             }
         };
 
+        // 本地水位比服务器时间还新（时钟异常等），按该水位拉取会一直漏掉记录，改为全量拉取
+        let last_sync_time = if server_time > 0 && last_sync_time > server_time {
+            log::warn!(
+                "同步水位{}超前于服务器时间{}，本次从0开始全量同步",
+                last_sync_time,
+                server_time
+            );
+            0
+        } else {
+            last_sync_time
+        };
+
         let unsynced_record = self.get_unsynced_records().await?;
         let _ids: Vec<String> = unsynced_record
             .iter()
@@ -187,7 +316,7 @@ impl CloudSyncTimer {
         let records = unsynced_record.clone();
         if !records.is_empty() {
             records.iter().for_each(|record| {
-                let param: ClipRecordParam = record.clone().into();
+                let param = ClipRecordParam::for_upload(record.clone());
                 params.push(param);
             });
         }
@@ -199,20 +328,27 @@ impl CloudSyncTimer {
             device_id: GLOBAL_DEVICE_ID.clone(),
         };
 
-        let response = match sync_clipboard(&sync_request).await {
-            Ok(resp) => resp,
-            Err(e) => {
-                log::error!(
-                    "云同步数据传输失败: {} (待同步记录数: {})",
-                    e,
-                    unsynced_record.len()
-                );
-                return Err(AppError::General(format!("云服务异常: {}", e)));
-            }
-        };
+        // 本次同步包含敏感记录时，请求和响应内容不输出到日志
+        let has_sensitive = unsynced_record.iter().any(|record| record.is_sensitive());
+        let response =
+            match with_content_log_suppressed(has_sensitive, sync_clipboard(&sync_request)).await {
+                Ok(resp) => resp,
+                Err(e) => {
+                    log::error!(
+                        "云同步数据传输失败: {} (待同步记录数: {})",
+                        e,
+                        unsynced_record.len()
+                    );
+                    return Err(AppError::General(format!("云服务异常: {}", e)));
+                }
+            };
 
         if let Some(cloud_sync_res) = response {
             let mut has_data_changed = false; // 标记是否有数据变化
+            let mut counts = SyncCounts {
+                uploaded: unsynced_record.len(),
+                downloaded: 0,
+            };
 
             if let Some(clips) = cloud_sync_res.clips {
                 log::info!(
@@ -226,6 +362,11 @@ impl CloudSyncTimer {
                 let mut new_records_to_insert = Vec::new();
                 let mut delete_operations = Vec::new();
                 let mut search_index_updates = Vec::new();
+                let mut conflicts = Vec::new();
+                let mut cloud_state_updates = Vec::new();
+                let mut cloud_tag_updates = Vec::new();
+                let mut assigned_slots = HashSet::new();
+                let conflict_policy = get_sync_conflict_policy();
 
                 // 预处理所有记录，分类处理
                 for clip in clips {
@@ -257,13 +398,46 @@ impl CloudSyncTimer {
                             // 如果从云端拉取下来的是图片或者文件类型   设置为同步中  等待拉取文件数据
                             obj.sync_flag = Some(SYNCHRONIZING);
                         }
+                        obj.tags = normalize_tags(obj.tags.as_deref());
                         obj.pinned_flag = 0; // 默认不置顶
                         obj.cloud_source = Some(1); // 云端同步下来的设置为1
 
+                        // 云端记录的槽位优先，本地占用该槽位的记录取消分配，同一批次中重复的槽位只保留第一个
+                        obj.slot = obj
+                            .slot
+                            .filter(|slot| is_valid_slot(*slot) && assigned_slots.insert(*slot));
+                        if let Some(slot) = obj.slot {
+                            ClipRecord::clear_slot(&self.rb, slot).await?;
+                        }
+
                         new_records_to_insert.push(obj);
                         search_index_updates.push((new_id, content));
                         has_data_changed = true;
                     } else {
+                        let local = check_res.first();
+                        // 记录合并时被覆盖或被忽略的修改
+                        if let Some(conflict) =
+                            local.and_then(|local| detect_conflict(local, &clip, conflict_policy))
+                        {
+                            conflicts.push(conflict);
+                        }
+                        // 置顶不一致时按冲突策略决定是否采用云端状态
+                        if let Some(local) = local
+                            .filter(|local| should_apply_cloud_state(local, &clip, conflict_policy))
+                        {
+                            cloud_state_updates.push((
+                                local.id.clone(),
+                                clip.pinned_flag.unwrap_or(local.pinned_flag),
+                            ));
+                            has_data_changed = true;
+                        }
+                        // 标签不一致时采用云端的标签，本地有未同步的修改时按冲突策略决定
+                        if let Some(local) = local {
+                            if let Some(tags) = cloud_tags_to_apply(local, &clip, conflict_policy) {
+                                cloud_tag_updates.push((local.id.clone(), tags));
+                                has_data_changed = true;
+                            }
+                        }
                         // 如果本地有这条记录，那么查看是不是云端同步的是被删除的，如果是那么本地也逻辑删除  并且把同步状态设置为已同步
                         if clip.del_flag.unwrap_or_default() == 1 {
                             log::debug!(
@@ -282,6 +456,7 @@ impl CloudSyncTimer {
                         ClipRecord::insert_batch_merge_by_sync_time(
                             &self.rb,
                             new_records_to_insert,
+                            self.cloud_insert_position(),
                         )
                         .await?;
                     log::info!(
@@ -289,6 +464,7 @@ impl CloudSyncTimer {
                         inserted_count,
                         failed_count
                     );
+                    counts.downloaded = inserted_count;
 
                     // 异步更新搜索索引
                     for (record_id, content) in search_index_updates {
@@ -309,6 +485,28 @@ impl CloudSyncTimer {
                     ClipRecord::sync_del_by_ids(&self.rb, &delete_operations, server_time).await?;
                     log::debug!("批量删除云记录: {}条", delete_operations.len());
                 }
+
+                // 采用云端的置顶状态
+                for (record_id, pinned_flag) in &cloud_state_updates {
+                    ClipRecord::apply_cloud_state(&self.rb, record_id, *pinned_flag).await?;
+                }
+                if !cloud_state_updates.is_empty() {
+                    log::debug!(
+                        "按冲突策略采用云端置顶状态: {}条",
+                        cloud_state_updates.len()
+                    );
+                }
+
+                // 采用云端的标签
+                for (record_id, tags) in &cloud_tag_updates {
+                    ClipRecord::apply_cloud_tags(&self.rb, record_id, tags.as_deref()).await?;
+                }
+                if !cloud_tag_updates.is_empty() {
+                    log::debug!("采用云端标签: {}条", cloud_tag_updates.len());
+                }
+
+                // 保存本次合并产生的冲突日志
+                record_sync_conflicts(&self.rb, conflicts).await;
             }
 
             // 根据记录类型分别处理同步状态
@@ -331,31 +529,86 @@ impl CloudSyncTimer {
                 try_clean_clip_record().await;
             });
 
-            Ok(())
+            Ok(counts)
         } else {
             log::error!("云同步异常: 服务器数据无效");
             Err(AppError::ClipSync("云服务返回异常数据".to_string()))
         }
     }
 
+    /// 根据设置和窗口状态确定云端记录的插入位置
+    /// 只有窗口显示时才调整，避免用户正在查看列表时第一条记录被挤下去
+    fn cloud_insert_position(&self) -> CloudInsertPosition {
+        let policy = get_cloud_insert_policy();
+        if policy == CLOUD_INSERT_BY_TIME {
+            return CloudInsertPosition::BySyncTime;
+        }
+        let window_active = self
+            .app_handle
+            .get_webview_window("main")
+            .map(|window| window.is_visible().unwrap_or(false))
+            .unwrap_or(false);
+        if !window_active {
+            return CloudInsertPosition::BySyncTime;
+        }
+        match policy {
+            CLOUD_INSERT_BOTTOM => CloudInsertPosition::Bottom,
+            _ => CloudInsertPosition::BelowTop,
+        }
+    }
+
     async fn get_unsynced_records(&self) -> AppResult<Vec<ClipRecord>> {
-        let all_records = ClipRecord::select_by_sync_flag(&self.rb, NOT_SYNCHRONIZED).await?;
+        let all_records: Vec<ClipRecord> =
+            ClipRecord::select_by_sync_flag(&self.rb, NOT_SYNCHRONIZED)
+                .await?
+                .into_iter()
+                // 撤销宽限期内的删除暂不同步
+                .filter(|record| !is_delete_pending(&record.id))
+                // VIP降级宽限期内被隐藏的超限记录暂停同步
+                .filter(|record| record.del_flag != Some(DEL_FLAG_OVER_LIMIT))
+                .collect();
 
-        // 获取当前用户的文件大小限制
-        let max_file_size = VipChecker::get_cached_max_file_size().unwrap_or(0);
+        // 获取当前用户各类型内容的同步大小限制（VIP限制内再应用用户设置的图片、文件上限）
+        let size_limits = VipChecker::get_cached_size_limits();
+        // 用户设置的同步文本大小限制，与VIP限制相互独立
+        let max_sync_text_bytes = get_max_sync_text_bytes();
 
         // 有文件大小限制的用户（各级VIP），检查每个文件的大小
         let mut filtered_records = Vec::new();
 
         for record in &all_records {
             match record.r#type.as_str() {
-                t if t == ClipType::Text.to_string() => {
-                    // 文本类型：检查内容大小（加密后的字节大小）
+                t if t == ClipType::Text.to_string()
+                    || t == ClipType::Rtf.to_string()
+                    || t == ClipType::Html.to_string() =>
+                {
+                    // 文本类型（包括HTML/RTF）：检查内容大小（加密后的字节大小）
                     if let Some(content_str) = record.content.as_str() {
                         // 获取加密后文本的实际字节大小
                         let content_size = content_str.as_bytes().len() as u64;
+                        if max_sync_text_bytes > 0 && content_size > max_sync_text_bytes {
+                            // 文本超过用户设置的同步大小限制，只保留在本地
+                            if let Err(e) = ClipRecord::update_sync_flag_and_skip_type(
+                                &self.rb,
+                                &record.id,
+                                SKIP_SYNC,
+                                Some(SKIP_TYPE_SYNC_TEXT_LIMIT),
+                            )
+                            .await
+                            {
+                                log::error!("更新文本记录为同步大小限制跳过失败: {}", e);
+                            } else {
+                                log::info!(
+                                    "文本超过同步大小限制，跳过同步: ID={}, 大小={}字节, 限制={}字节",
+                                    record.id,
+                                    content_size,
+                                    max_sync_text_bytes
+                                );
+                            }
+                            continue;
+                        }
                         // 对于VIP用户，检查文本大小是否超限
-                        if content_size <= max_file_size {
+                        if content_size <= size_limits.text {
                             filtered_records.push(record.clone());
                         } else {
                             // 文本内容超过VIP限制，更新为跳过状态
@@ -373,7 +626,7 @@ impl CloudSyncTimer {
                                     "文本超限，设置为VIP限制跳过: ID={}, 大小={}字节, 限制={}字节",
                                     record.id,
                                     content_size,
-                                    max_file_size
+                                    size_limits.text
                                 );
                             }
                         }
@@ -390,7 +643,7 @@ impl CloudSyncTimer {
                             file_path.push(content_str);
                             if file_path.exists() {
                                 if let Ok(metadata) = std::fs::metadata(&file_path) {
-                                    if metadata.len() <= max_file_size {
+                                    if metadata.len() <= size_limits.image {
                                         filtered_records.push(record.clone());
                                     } else {
                                         // 图片超过VIP限制，更新为跳过状态
@@ -408,7 +661,7 @@ impl CloudSyncTimer {
                                                 "图片超限，设置为VIP限制跳过: ID={}, 大小={}, 限制={}",
                                                 record.id,
                                                 metadata.len(),
-                                                max_file_size
+                                                size_limits.image
                                             );
                                         }
                                     }
@@ -423,7 +676,7 @@ impl CloudSyncTimer {
                         let paths: Vec<&str> = local_path.split(":::").collect();
                         if let Some(first_path) = paths.first() {
                             if let Ok(metadata) = std::fs::metadata(first_path) {
-                                if metadata.len() <= max_file_size {
+                                if metadata.len() <= size_limits.file {
                                     filtered_records.push(record.clone());
                                 } else {
                                     // 文件超过VIP限制，更新为跳过状态
@@ -441,7 +694,7 @@ impl CloudSyncTimer {
                                             "文件超限，设置为VIP限制跳过: ID={}, 大小={}, 限制={}",
                                             record.id,
                                             metadata.len(),
-                                            max_file_size
+                                            size_limits.file
                                         );
                                     }
                                 }
@@ -458,10 +711,10 @@ impl CloudSyncTimer {
 
         if filtered_records.len() != all_records.len() {
             log::info!(
-                "同步过滤（大小限制）: 总记录={}, 符合条件={}, 限制={}字节",
+                "同步过滤（大小限制）: 总记录={}, 符合条件={}, 限制={:?}",
                 all_records.len(),
                 filtered_records.len(),
-                max_file_size
+                size_limits
             );
         }
 
@@ -484,6 +737,10 @@ impl CloudSyncTimer {
                 t if t == ClipType::Text.to_string() => {
                     text_ids.push(record.id.clone());
                 }
+                // 只修改了标签等元数据的图片、文件记录，文件已在云端，与文本一样直接标记为已同步
+                _ if record.meta_only_sync == Some(1) => {
+                    text_ids.push(record.id.clone());
+                }
                 t if t == ClipType::Image.to_string() => {
                     image_records.push(record);
                 }
@@ -697,10 +954,12 @@ impl CloudSyncTimer {
         match std::fs::metadata(file_path) {
             Ok(metadata) => {
                 // 使用VIP检查器获取文件大小限制
-                let max_file_size = match VipChecker::get_max_file_size().await {
+                let vip_max_file_size = match VipChecker::get_max_file_size().await {
                     Ok(size) => size,
                     Err(_) => get_max_file_size_bytes().unwrap_or(5 * 1024 * 1024), // fallback
                 };
+                // 在VIP限制内应用用户设置的文件同步上限
+                let max_file_size = SizeLimits::from_vip_limit(vip_max_file_size).file;
 
                 if metadata.len() > max_file_size {
                     if max_file_size == 0 {
@@ -708,10 +967,17 @@ impl CloudSyncTimer {
                     } else {
                         let size_mb = metadata.len() as f64 / (1024.0 * 1024.0);
                         let max_mb = max_file_size as f64 / (1024.0 * 1024.0);
-                        Err(format!(
-                            "文件大小 {:.1}MB 超过限制 {:.1}MB，请升级VIP以支持更大文件",
-                            size_mb, max_mb
-                        ))
+                        if max_file_size < vip_max_file_size {
+                            Err(format!(
+                                "文件大小 {:.1}MB 超过设置的文件同步上限 {:.1}MB",
+                                size_mb, max_mb
+                            ))
+                        } else {
+                            Err(format!(
+                                "文件大小 {:.1}MB 超过限制 {:.1}MB，请升级VIP以支持更大文件",
+                                size_mb, max_mb
+                            ))
+                        }
                     }
                 } else {
                     Ok(())
@@ -722,10 +988,46 @@ impl CloudSyncTimer {
     }
 }
 
+/// 计算连续失败后的暂停时间（秒）：从达到阈值开始每多失败一次翻倍，不超过 MAX_SYNC_BACKOFF_SECS
+fn backoff_delay_secs(interval_secs: u64, consecutive_failures: u32) -> u64 {
+    let exponent = consecutive_failures
+        .saturating_sub(SYNC_FAILURE_THRESHOLD)
+        .saturating_add(1)
+        .min(16);
+    interval_secs
+        .saturating_mul(1u64 << exponent)
+        .min(MAX_SYNC_BACKOFF_SECS)
+}
+
+/// 创建云同步定时器，错过的执行直接跳过
+fn new_sync_timer(start: Instant, interval_secs: u64) -> Interval {
+    let mut timer = tokio::time::interval_at(start, Duration::from_secs(interval_secs));
+    timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    timer
+}
+
+/// 重新读取同步间隔设置，通知定时任务使用新的间隔
+pub fn reload_sync_interval() {
+    let interval = get_cloud_sync_interval();
+    let sender = INTERVAL_SENDER
+        .read()
+        .ok()
+        .and_then(|sender| sender.clone());
+    match sender {
+        Some(sender) => {
+            if sender.send(interval).is_err() {
+                log::warn!("同步间隔更新失败，同步任务未运行");
+            }
+        }
+        None => log::debug!("同步任务未启动，新的同步间隔将在启动时生效"),
+    }
+}
+
 /// 触发立即同步
 pub fn trigger_immediate_sync() -> Result<(), &'static str> {
-    if let Some(sender) = TRIGGER_SENDER.get() {
-        match sender.send(()) {
+    let sender = TRIGGER_SENDER.read().ok().and_then(|sender| sender.clone());
+    if let Some(sender) = sender {
+        match sender.send(None) {
             Ok(()) => Ok(()),
             Err(_) => {
                 log::warn!("立即同步触发信号发送失败，接收端已关闭");
@@ -738,8 +1040,101 @@ pub fn trigger_immediate_sync() -> Result<(), &'static str> {
     }
 }
 
+/// 立即执行一次云同步并等待结果，用于前端手动同步时显示进度和结果
+/// 已有同步任务在执行时不会重复同步，直接返回错误原因
+#[tauri::command]
+pub async fn sync_now() -> SyncNowResult {
+    // 只读模式下同步已暂停
+    if let Err(error) = ensure_not_kiosk_mode() {
+        return SyncNowResult {
+            uploaded: 0,
+            downloaded: 0,
+            error: Some(error),
+        };
+    }
+    let (reply_sender, reply_receiver) = oneshot::channel();
+    let sender = TRIGGER_SENDER.read().ok().and_then(|sender| sender.clone());
+    let sent = sender.is_some_and(|sender| sender.send(Some(reply_sender)).is_ok());
+    let result = if sent {
+        reply_receiver
+            .await
+            .unwrap_or_else(|_| Err("同步任务已停止".to_string()))
+    } else {
+        log::warn!("手动同步失败，同步任务未启动");
+        Err("同步任务未启动".to_string())
+    };
+
+    match result {
+        Ok(counts) => SyncNowResult {
+            uploaded: counts.uploaded,
+            downloaded: counts.downloaded,
+            error: None,
+        },
+        Err(error) => SyncNowResult {
+            uploaded: 0,
+            downloaded: 0,
+            error: Some(error),
+        },
+    }
+}
+
+/// 同步文本大小限制调整后，将不再超限的文本记录恢复为待同步，返回恢复的记录数
+pub(crate) async fn resume_sync_text_limit_skipped() -> usize {
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let records = match ClipRecord::select_by_sync_flag_and_skip_type(
+        rb,
+        SKIP_SYNC,
+        SKIP_TYPE_SYNC_TEXT_LIMIT,
+    )
+    .await
+    {
+        Ok(records) => records,
+        Err(e) => {
+            log::error!("查询超过同步大小限制的文本记录失败: {}", e);
+            return 0;
+        }
+    };
+
+    let max_sync_text_bytes = get_max_sync_text_bytes();
+    let mut resumed = 0;
+    for record in records {
+        let content_size = record.content.as_str().map_or(0, |c| c.len() as u64);
+        if max_sync_text_bytes > 0 && content_size > max_sync_text_bytes {
+            continue;
+        }
+        match ClipRecord::update_sync_flag_and_skip_type(rb, &record.id, NOT_SYNCHRONIZED, None)
+            .await
+        {
+            Ok(_) => resumed += 1,
+            Err(e) => log::error!("恢复文本记录{}为待同步失败: {}", record.id, e),
+        }
+    }
+
+    if resumed > 0 {
+        log::info!(
+            "同步文本大小限制调整后，已将{}条文本记录恢复为待同步",
+            resumed
+        );
+    }
+    resumed
+}
+
 /// 开始云同步定时任务（供外部调用）
 pub async fn start_cloud_sync_timer(app_handle: AppHandle, rb: RBatis) {
     let timer = CloudSyncTimer::new(app_handle, rb);
     timer.start().await;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_delay_doubles_until_cap() {
+        assert_eq!(backoff_delay_secs(30, SYNC_FAILURE_THRESHOLD), 60);
+        assert_eq!(backoff_delay_secs(30, SYNC_FAILURE_THRESHOLD + 1), 120);
+        assert_eq!(backoff_delay_secs(30, SYNC_FAILURE_THRESHOLD + 2), 240);
+        assert_eq!(backoff_delay_secs(30, 100), MAX_SYNC_BACKOFF_SECS);
+        assert_eq!(backoff_delay_secs(u64::MAX, 5), MAX_SYNC_BACKOFF_SECS);
+    }
+}
@@ -0,0 +1,102 @@
+use clipboard_listener::ClipType;
+use rbatis::RBatis;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::{
+    biz::{
+        clip_record::ClipRecord, content_search::add_content_to_index,
+        system_setting::ensure_not_kiosk_mode,
+    },
+    utils::aes_util::{decrypt_content, encrypt_content, looks_like_ciphertext},
+    CONTEXT,
+};
+
+// 每批检查的记录数
+static MIGRATE_BATCH_SIZE: i32 = 200;
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct MigrateLegacyResult {
+    // 检查的记录数
+    pub scanned: usize,
+    // 从明文迁移为密文的记录数
+    pub migrated: usize,
+    // 格式上是密文但无法解密的记录数（可能来自其他密钥或已损坏），不做处理
+    pub undecryptable: usize,
+    pub failed: usize,
+}
+
+/// 把未加密保存的旧记录（早期版本或导入的数据）加密后重新保存
+/// 无法用当前方案解密、且格式上不像密文的内容按明文处理
+#[tauri::command]
+pub async fn migrate_legacy_content() -> Result<MigrateLegacyResult, String> {
+    ensure_not_kiosk_mode()?;
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let mut result = MigrateLegacyResult::default();
+
+    for clip_type in [ClipType::Text, ClipType::Rtf, ClipType::Html] {
+        let type_str = clip_type.to_string();
+        let mut offset = 0;
+        loop {
+            let records =
+                ClipRecord::select_by_type_limit(rb, &type_str, MIGRATE_BATCH_SIZE, offset)
+                    .await
+                    .map_err(|e| {
+                        log::error!("查询待迁移记录失败: {}", e);
+                        "查询待迁移记录失败".to_string()
+                    })?;
+            if records.is_empty() {
+                break;
+            }
+            offset += records.len() as i32;
+
+            for record in records {
+                result.scanned += 1;
+                migrate_record(rb, &record, &mut result).await;
+            }
+        }
+    }
+
+    log::info!("旧数据加密迁移完成: {:?}", result);
+    if result.migrated > 0 {
+        let app_handle = CONTEXT.get::<AppHandle>();
+        let _ = app_handle.emit("clip_record_change", ());
+    }
+    Ok(result)
+}
+
+async fn migrate_record(rb: &RBatis, record: &ClipRecord, result: &mut MigrateLegacyResult) {
+    let Some(content) = record.content.as_str() else {
+        return;
+    };
+    if content.is_empty() || decrypt_content(content).is_ok() {
+        return;
+    }
+    if looks_like_ciphertext(content) {
+        log::warn!("记录内容无法解密，跳过迁移: {}", record.id);
+        result.undecryptable += 1;
+        return;
+    }
+
+    let encrypted = match encrypt_content(content) {
+        Ok(encrypted) => encrypted,
+        Err(e) => {
+            log::error!("加密旧记录失败: {}, 记录ID: {}", e, record.id);
+            result.failed += 1;
+            return;
+        }
+    };
+    if let Err(e) = ClipRecord::update_content(rb, &record.id, &encrypted).await {
+        log::error!("保存迁移后的记录失败: {}, 记录ID: {}", e, record.id);
+        result.failed += 1;
+        return;
+    }
+    result.migrated += 1;
+
+    // 之前解密失败的记录没有进入搜索索引，迁移后补充
+    if record.del_flag == Some(0) && record.r#type == ClipType::Text.to_string() {
+        if let Err(e) = add_content_to_index(&record.id, content).await {
+            log::error!("搜索索引更新失败: {}", e);
+        }
+    }
+}
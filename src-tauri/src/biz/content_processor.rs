@@ -1,7 +1,12 @@
+use std::borrow::Cow;
+
 use clipboard_listener::ClipType;
 use serde_json::Value;
 
-use crate::utils::aes_util::decrypt_content;
+use crate::utils::{
+    aes_util::decrypt_content,
+    rich_text::{html_to_text, rtf_to_text},
+};
 
 pub struct ContentProcessor;
 
@@ -30,7 +35,10 @@ impl ContentProcessor {
     /// 根据剪贴板类型处理内容
     pub fn process_by_clip_type(clip_type: &str, content: Value) -> String {
         match clip_type {
-            t if t == ClipType::Text.to_string() => {
+            t if t == ClipType::Text.to_string()
+                || t == ClipType::Rtf.to_string()
+                || t == ClipType::Html.to_string() =>
+            {
                 match decrypt_content(Self::process_text_content(content).as_str()) {
                     Ok(text) => text,
                     Err(e) => {
@@ -57,4 +65,205 @@ impl ContentProcessor {
             _ => String::new(),
         }
     }
+
+    /// 获取文本类记录的纯文本内容，HTML/RTF记录提取其中的文字，其他类型与 process_by_clip_type 相同
+    pub fn plain_text_by_clip_type(clip_type: &str, content: Value) -> String {
+        let processed = Self::process_by_clip_type(clip_type, content);
+        match clip_type {
+            t if t == ClipType::Html.to_string() => html_to_text(&processed),
+            t if t == ClipType::Rtf.to_string() => rtf_to_text(&processed),
+            _ => processed,
+        }
+    }
+
+    /// 按配置的转换名称依次处理复制的文本，没有配置或文本未变化时不分配新字符串
+    pub fn apply_text_transforms<'a>(text: &'a str, names: &[String]) -> Cow<'a, str> {
+        if names.is_empty() {
+            return Cow::Borrowed(text);
+        }
+        TextTransformPipeline::from_names(names).apply(text)
+    }
+}
+
+/// 文本转换，在保存复制的文本前对内容进行规范化处理
+pub trait TextTransform: Send + Sync {
+    /// 在 text_transforms 设置中使用的名称
+    fn name(&self) -> &'static str;
+
+    /// 转换文本，内容不需要变化时返回 Cow::Borrowed
+    fn apply<'a>(&self, text: &'a str) -> Cow<'a, str>;
+}
+
+/// 去除每行末尾的空格、制表符等空白字符（保留行尾的 \r）
+pub struct TrimTrailingWhitespace;
+
+impl TextTransform for TrimTrailingWhitespace {
+    fn name(&self) -> &'static str {
+        "trim_trailing_whitespace"
+    }
+
+    fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let changed = text.split('\n').any(|line| {
+            let body = line.strip_suffix('\r').unwrap_or(line);
+            body.len() != body.trim_end().len()
+        });
+        if !changed {
+            return Cow::Borrowed(text);
+        }
+        let lines: Vec<String> = text
+            .split('\n')
+            .map(|line| match line.strip_suffix('\r') {
+                Some(body) => format!("{}\r", body.trim_end()),
+                None => line.trim_end().to_string(),
+            })
+            .collect();
+        Cow::Owned(lines.join("\n"))
+    }
+}
+
+/// 把 Windows 的 \r\n 和旧版 Mac 的 \r 换行统一为 \n
+pub struct NormalizeNewlines;
+
+impl TextTransform for NormalizeNewlines {
+    fn name(&self) -> &'static str {
+        "normalize_newlines"
+    }
+
+    fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if !text.contains('\r') {
+            return Cow::Borrowed(text);
+        }
+        Cow::Owned(text.replace("\r\n", "\n").replace('\r', "\n"))
+    }
+}
+
+/// 把连续的多个空行合并为一个空行
+pub struct CollapseBlankLines;
+
+impl TextTransform for CollapseBlankLines {
+    fn name(&self) -> &'static str {
+        "collapse_blank_lines"
+    }
+
+    fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut lines: Vec<&str> = Vec::new();
+        let mut previous_blank = false;
+        let mut changed = false;
+        for line in text.split('\n') {
+            let blank = line.trim().is_empty();
+            if blank && previous_blank {
+                changed = true;
+                continue;
+            }
+            previous_blank = blank;
+            lines.push(line);
+        }
+        if !changed {
+            return Cow::Borrowed(text);
+        }
+        Cow::Owned(lines.join("\n"))
+    }
+}
+
+/// 按名称创建内置的文本转换，未知名称返回None
+pub fn builtin_text_transform(name: &str) -> Option<Box<dyn TextTransform>> {
+    let transform: Box<dyn TextTransform> = match name.trim().to_lowercase().as_str() {
+        "trim_trailing_whitespace" => Box::new(TrimTrailingWhitespace),
+        "normalize_newlines" => Box::new(NormalizeNewlines),
+        "collapse_blank_lines" => Box::new(CollapseBlankLines),
+        _ => return None,
+    };
+    Some(transform)
+}
+
+/// 文本转换管道，按顺序依次应用每个转换
+#[derive(Default)]
+pub struct TextTransformPipeline {
+    transforms: Vec<Box<dyn TextTransform>>,
+}
+
+impl TextTransformPipeline {
+    /// 按 text_transforms 设置中的名称创建管道，未知名称记录日志后跳过
+    pub fn from_names(names: &[String]) -> Self {
+        let transforms = names
+            .iter()
+            .filter_map(|name| {
+                let transform = builtin_text_transform(name);
+                if transform.is_none() {
+                    log::warn!("未知的文本转换，已跳过: {}", name);
+                }
+                transform
+            })
+            .collect();
+        Self { transforms }
+    }
+
+    /// 依次应用所有转换
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut result = Cow::Borrowed(text);
+        for transform in &self.transforms {
+            if let Cow::Owned(transformed) = transform.apply(&result) {
+                log::debug!("文本转换已应用: {}", transform.name());
+                result = Cow::Owned(transformed);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_trim_trailing_whitespace() {
+        assert_eq!(TrimTrailingWhitespace.apply("a  \nb\t\nc"), "a\nb\nc");
+        // 保留 CRLF 换行，只去掉换行前的空白
+        assert_eq!(TrimTrailingWhitespace.apply("a \r\nb"), "a\r\nb");
+        assert!(matches!(
+            TrimTrailingWhitespace.apply("a\r\nb"),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn test_normalize_newlines() {
+        assert_eq!(NormalizeNewlines.apply("a\r\nb\rc\nd"), "a\nb\nc\nd");
+        assert!(matches!(NormalizeNewlines.apply("a\nb"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_collapse_blank_lines() {
+        assert_eq!(CollapseBlankLines.apply("a\n\n\n\nb"), "a\n\nb");
+        assert_eq!(
+            CollapseBlankLines.apply("a\n  \n\t\nb\n\nc"),
+            "a\n  \nb\n\nc"
+        );
+        assert!(matches!(
+            CollapseBlankLines.apply("a\n\nb"),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn test_pipeline_applies_in_order_and_skips_unknown() {
+        let transforms = names(&[
+            "normalize_newlines",
+            "unknown",
+            "trim_trailing_whitespace",
+            "collapse_blank_lines",
+        ]);
+        assert_eq!(
+            ContentProcessor::apply_text_transforms("a \r\n\r\n\r\nb\t", &transforms),
+            "a\n\nb"
+        );
+        assert!(matches!(
+            ContentProcessor::apply_text_transforms("a \r\n", &[]),
+            Cow::Borrowed(_)
+        ));
+    }
 }
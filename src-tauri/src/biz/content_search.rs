@@ -1,17 +1,22 @@
 use crate::biz::clip_record::ClipRecord;
+use crate::biz::content_processor::ContentProcessor;
 use crate::biz::system_setting::{
     DEFAULT_BLOOM_FILTER_TRUST_THRESHOLD, DEFAULT_DIRECT_CONTAINS_THRESHOLD,
 };
 use crate::errors::AppResult;
-use crate::utils::lock_utils::lock_utils::safe_read_lock;
+use crate::utils::lock_utils::lock_utils::{safe_lock, safe_read_lock};
 use crate::{biz::system_setting::Settings, CONTEXT};
 use bloomfilter::Bloom;
 use clipboard_listener::ClipType;
 use dashmap::DashMap;
 use once_cell::sync::Lazy;
-use regex::Regex;
+use rbatis::RBatis;
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
 use std::collections::HashSet;
-use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use tauri::{AppHandle, Emitter};
 
 // 静态编译的正则表达式
 static WORD_REGEX: Lazy<Regex> =
@@ -212,32 +217,172 @@ impl RecordSearchData {
         // 直接字符串包含搜索
         normalized_content.contains(&normalized_query)
     }
+
+    /// 计算记录与查询的相关度
+    /// 完整短语命中权重高于单个关键词命中，得分按内容长度做对数归一化
+    fn rank(&self, matchers: &QueryMatchers) -> f32 {
+        let mut raw_score = 0.0f32;
+
+        if let Some(phrase) = &matchers.phrase {
+            raw_score += phrase.find_iter(&self.content).count() as f32 * PHRASE_MATCH_WEIGHT;
+        }
+
+        let mut matched_terms = 0;
+        for term in &matchers.terms {
+            let hits = term.find_iter(&self.content).count();
+            raw_score += hits as f32 * TERM_MATCH_WEIGHT;
+            if hits > 0 {
+                matched_terms += 1;
+            }
+        }
+        // 所有关键词都命中的记录额外加分
+        if !matchers.terms.is_empty() && matched_terms == matchers.terms.len() {
+            raw_score += ALL_TERMS_BONUS;
+        }
+
+        let length_norm = 1.0 + (self.content.len() as f32 + 1.0).ln();
+        raw_score / length_norm
+    }
+}
+
+/// 排序搜索时各类命中的权重
+const PHRASE_MATCH_WEIGHT: f32 = 2.0;
+const TERM_MATCH_WEIGHT: f32 = 1.0;
+const ALL_TERMS_BONUS: f32 = 1.0;
+
+/// 查询对应的大小写不敏感匹配器，一次查询只构建一次
+struct QueryMatchers {
+    // 完整查询短语，仅在查询包含多个关键词时使用
+    phrase: Option<Regex>,
+    // 按空白拆分后的各个关键词
+    terms: Vec<Regex>,
+}
+
+impl QueryMatchers {
+    fn new(query: &str) -> Self {
+        let build = |pattern: &str| {
+            RegexBuilder::new(&regex::escape(pattern))
+                .case_insensitive(true)
+                .build()
+                .ok()
+        };
+
+        let mut seen = HashSet::new();
+        let words: Vec<&str> = query
+            .split_whitespace()
+            .filter(|word| seen.insert(word.to_lowercase()))
+            .collect();
+        let phrase = if words.len() > 1 {
+            build(query.trim())
+        } else {
+            None
+        };
+
+        Self {
+            phrase,
+            terms: words.into_iter().filter_map(build).collect(),
+        }
+    }
+
+    /// 查找短语和各个关键词在文本中的命中区间，返回合并后的字节区间
+    fn spans(&self, text: &str) -> Vec<(usize, usize)> {
+        let spans = self
+            .phrase
+            .iter()
+            .chain(self.terms.iter())
+            .flat_map(|matcher| matcher.find_iter(text).map(|m| (m.start(), m.end())))
+            .collect();
+        merge_spans(spans)
+    }
+}
+
+/// 合并重叠或相邻的匹配区间，结果按起始位置排序
+fn merge_spans(mut spans: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+    spans.sort_unstable();
+    let mut merged: Vec<(usize, usize)> = Vec::with_capacity(spans.len());
+    for (start, end) in spans {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// 排序搜索的命中结果
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub id: String,
+    // 相关度得分，越大越相关
+    pub score: f32,
 }
 
 struct SimpleSearchIndex {
     records: DashMap<String, RecordSearchData>,
+    // 构建索引期间被新增、更新或删除的记录ID，构建时不再用旧的记录快照覆盖这些记录
+    live_updated: Mutex<Option<HashSet<String>>>,
 }
 
 impl SimpleSearchIndex {
     fn new() -> Self {
         Self {
             records: DashMap::new(),
+            live_updated: Mutex::new(None),
         }
     }
 
     /// 添加记录
     fn add_record(&self, id: &str, content: &str) {
         let search_data = RecordSearchData::new(content.to_string());
+        let mut live_updated = safe_lock(&self.live_updated).ok();
+        if let Some(ids) = live_updated.as_mut().and_then(|ids| ids.as_mut()) {
+            ids.insert(id.to_string());
+        }
         self.records.insert(id.to_string(), search_data);
     }
 
     /// 移除记录
     fn remove_records(&self, ids: &[String]) {
+        let mut live_updated = safe_lock(&self.live_updated).ok();
+        if let Some(updated) = live_updated.as_mut().and_then(|ids| ids.as_mut()) {
+            updated.extend(ids.iter().cloned());
+        }
         for id in ids {
             self.records.remove(id);
         }
     }
 
+    /// 构建索引时写入快照中的记录，构建开始后已被新增、更新或删除的记录保留实时的结果
+    fn add_snapshot_record(&self, id: &str, content: &str) {
+        let search_data = RecordSearchData::new(content.to_string());
+        let live_updated = safe_lock(&self.live_updated).ok();
+        if live_updated
+            .as_ref()
+            .and_then(|ids| ids.as_ref())
+            .is_some_and(|ids| ids.contains(id))
+        {
+            return;
+        }
+        self.records.insert(id.to_string(), search_data);
+    }
+
+    /// 开始构建索引，之后的实时更新会被记录下来
+    fn begin_build(&self) {
+        if let Ok(mut live_updated) = safe_lock(&self.live_updated) {
+            *live_updated = Some(HashSet::new());
+        }
+    }
+
+    /// 结束构建索引：移除既不在快照中、构建期间也没有新增的旧记录（例如恢复备份前的记录）
+    fn finish_build(&self, snapshot_ids: &HashSet<String>) {
+        let Ok(mut live_updated) = safe_lock(&self.live_updated) else {
+            return;
+        };
+        let updated_ids = live_updated.take().unwrap_or_default();
+        self.records
+            .retain(|id, _| snapshot_ids.contains(id) || updated_ids.contains(id));
+    }
+
     /// 搜索包含指定内容的记录ID
     fn search(&self, query: &str) -> Vec<String> {
         if query.is_empty() {
@@ -256,9 +401,26 @@ impl SimpleSearchIndex {
         results
     }
 
-    /// 清空所有记录
-    fn clear(&self) {
-        self.records.clear();
+    /// 搜索并按相关度从高到低排序，得分相同时保持ID顺序稳定
+    fn search_ranked(&self, query: &str) -> Vec<SearchHit> {
+        if query.trim().is_empty() {
+            return Vec::new();
+        }
+
+        let matchers = QueryMatchers::new(query);
+        let mut hits = Vec::new();
+        for entry in self.records.iter() {
+            let (id, search_data) = (entry.key(), entry.value());
+            if search_data.smart_search(query) {
+                hits.push(SearchHit {
+                    id: id.clone(),
+                    score: search_data.rank(&matchers),
+                });
+            }
+        }
+
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.id.cmp(&b.id)));
+        hits
     }
 
     /// 获取统计信息
@@ -282,11 +444,30 @@ pub async fn add_content_to_index(id: &str, content: &str) -> AppResult<()> {
     Ok(())
 }
 
+/// 把记录重新加入搜索索引（例如从最近删除中恢复的记录），返回成功索引的记录数
+pub fn add_records_to_index(records: &[ClipRecord]) -> usize {
+    records.iter().filter(|record| index_record(record)).count()
+}
+
 /// 根据内容搜索ID列表
 pub async fn search_ids_by_content(content: &str) -> Vec<String> {
     SEARCH_INDEX.search(content)
 }
 
+/// 根据内容搜索并按相关度排序，返回每条记录的得分和匹配位置
+pub async fn search_ranked_by_content(query: &str) -> Vec<SearchHit> {
+    SEARCH_INDEX.search_ranked(query)
+}
+
+/// 查找查询在展示文本中的命中字节区间，用于前端高亮
+/// 索引文本与展示文本可能不同（例如图片按识别出的文字索引），所以高亮需要按展示文本重新计算
+pub fn highlight_spans(text: &str, query: &str) -> Vec<(usize, usize)> {
+    if query.trim().is_empty() {
+        return Vec::new();
+    }
+    QueryMatchers::new(query).spans(text)
+}
+
 /// 删除ID并更新索引
 pub async fn remove_ids_from_index(ids: &[String]) -> AppResult<()> {
     if ids.is_empty() {
@@ -298,54 +479,155 @@ pub async fn remove_ids_from_index(ids: &[String]) -> AppResult<()> {
     Ok(())
 }
 
-/// 异步初始化搜索索引，从现有记录中构建
-pub async fn initialize_search_index(clips: Vec<ClipRecord>) -> AppResult<()> {
-    tokio::spawn(async move {
-        // 清空现有索引
-        SEARCH_INDEX.clear();
+/// 初始化搜索索引时每批处理的记录数
+const INDEX_BUILD_BATCH_SIZE: usize = 200;
+
+// 索引构建状态
+static INDEX_READY: AtomicBool = AtomicBool::new(false);
+static INDEX_BUILD_CANCELLED: AtomicBool = AtomicBool::new(false);
+static INDEX_BUILD_TOTAL: AtomicUsize = AtomicUsize::new(0);
+static INDEX_BUILD_PROCESSED: AtomicUsize = AtomicUsize::new(0);
+// 每次开始构建时递增，新的构建开始后旧的构建任务停止
+static INDEX_BUILD_GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+/// 搜索索引构建进度
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexBuildProgress {
+    // 需要处理的记录总数
+    pub total: usize,
+    // 已处理的记录数
+    pub processed: usize,
+    // 索引是否已构建完成
+    pub ready: bool,
+    // 构建是否被取消
+    pub cancelled: bool,
+}
 
-        let total_count = clips.len();
-        let mut indexed_count = 0;
+fn current_build_progress() -> IndexBuildProgress {
+    IndexBuildProgress {
+        total: INDEX_BUILD_TOTAL.load(Ordering::SeqCst),
+        processed: INDEX_BUILD_PROCESSED.load(Ordering::SeqCst),
+        ready: INDEX_READY.load(Ordering::SeqCst),
+        cancelled: INDEX_BUILD_CANCELLED.load(Ordering::SeqCst),
+    }
+}
 
-        // 处理记录
-        for record in clips {
-            let should_index = match record.r#type.as_str() {
-                x if x == ClipType::Text.to_string() => {
-                    if let Some(content) = record.content.as_str() {
-                        // 解密文本内容
-                        match crate::utils::aes_util::decrypt_content(content) {
-                            Ok(decrypted_content) => {
-                                SEARCH_INDEX.add_record(&record.id, &decrypted_content);
-                                true
-                            }
-                            Err(e) => {
-                                log::warn!(
-                                    "解密内容失败，跳过索引 - ID: {}, 错误: {}",
-                                    record.id,
-                                    e
-                                );
-                                false
-                            }
-                        }
-                    } else {
-                        false
-                    }
+fn emit_build_event(event: &str) {
+    if let Some(app_handle) = CONTEXT.try_get::<AppHandle>() {
+        let _ = app_handle.emit(event, current_build_progress());
+    }
+}
+
+/// 把单条记录写入索引，返回是否成功索引
+fn index_record(record: &ClipRecord) -> bool {
+    match searchable_text(record) {
+        Some(content) => {
+            SEARCH_INDEX.add_record(&record.id, &content);
+            true
+        }
+        None => false,
+    }
+}
+
+/// 构建索引时把快照中的单条记录写入索引，返回是否有可索引的内容
+fn index_snapshot_record(record: &ClipRecord) -> bool {
+    match searchable_text(record) {
+        Some(content) => {
+            SEARCH_INDEX.add_snapshot_record(&record.id, &content);
+            true
+        }
+        None => false,
+    }
+}
+
+/// 获取记录参与搜索的文本，没有可搜索的内容或解密失败时返回None
+pub fn searchable_text(record: &ClipRecord) -> Option<String> {
+    match record.r#type.as_str() {
+        x if x == ClipType::Text.to_string() => {
+            let content = record.content.as_str()?;
+            // 解密文本内容
+            match crate::utils::aes_util::decrypt_content(content) {
+                Ok(decrypted_content) => Some(decrypted_content),
+                Err(e) => {
+                    log::warn!("解密内容失败，跳过索引 - ID: {}, 错误: {}", record.id, e);
+                    None
                 }
-                x if x == ClipType::File.to_string() => {
-                    if let Some(file_paths) = record.content.as_str() {
-                        SEARCH_INDEX.add_record(&record.id, file_paths);
-                        true
-                    } else {
-                        false
-                    }
+            }
+        }
+        x if x == ClipType::Rtf.to_string() || x == ClipType::Html.to_string() => {
+            // 富文本记录使用提取出的纯文本建立索引
+            let content = ContentProcessor::plain_text_by_clip_type(x, record.content.clone());
+            (!content.is_empty()).then_some(content)
+        }
+        x if x == ClipType::File.to_string() => record.content.as_str().map(str::to_string),
+        x if x == ClipType::Image.to_string() => {
+            // 图片记录使用OCR识别出的文字建立索引，没有识别结果时不参与搜索
+            let ocr_text = record.ocr_text.as_deref()?;
+            match crate::utils::aes_util::decrypt_content(ocr_text) {
+                Ok(text) => (!text.is_empty()).then_some(text),
+                Err(e) => {
+                    log::warn!("解密OCR文字失败，跳过索引 - ID: {}, 错误: {}", record.id, e);
+                    None
                 }
-                _ => false, // 图片类型不参与搜索
-            };
+            }
+        }
+        _ => None,
+    }
+}
+
+/// 异步初始化搜索索引，从数据库中的现有记录分批构建
+/// 构建时不清空索引，与剪贴板监听、同步等实时写入的索引合并，构建完成后移除已不存在的旧记录
+/// 构建过程中搜索直接使用已构建的部分索引，构建完成后发送 search_index_ready 事件
+pub async fn initialize_search_index(rb: &RBatis) -> AppResult<()> {
+    let generation = INDEX_BUILD_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    // 先开始记录实时更新再查询记录，查询之后写入的记录不会被快照覆盖或移除
+    SEARCH_INDEX.begin_build();
+    let clips = ClipRecord::select_order_by(rb).await?;
+
+    INDEX_READY.store(false, Ordering::SeqCst);
+    INDEX_BUILD_CANCELLED.store(false, Ordering::SeqCst);
+    INDEX_BUILD_TOTAL.store(clips.len(), Ordering::SeqCst);
+    INDEX_BUILD_PROCESSED.store(0, Ordering::SeqCst);
+
+    tokio::spawn(async move {
+        let total_count = clips.len();
+        let mut indexed_count = 0;
+        let snapshot_ids: HashSet<String> = clips.iter().map(|record| record.id.clone()).collect();
 
-            if should_index {
-                indexed_count += 1;
+        for batch in clips.chunks(INDEX_BUILD_BATCH_SIZE) {
+            // 已开始新的构建，由新的构建任务完成
+            if INDEX_BUILD_GENERATION.load(Ordering::SeqCst) != generation {
+                return;
             }
+            if INDEX_BUILD_CANCELLED.load(Ordering::SeqCst) {
+                SEARCH_INDEX.finish_build(&snapshot_ids);
+                log::info!(
+                    "搜索索引构建已取消 - 已处理: {}/{}",
+                    INDEX_BUILD_PROCESSED.load(Ordering::SeqCst),
+                    total_count
+                );
+                emit_build_event("index_build_progress");
+                return;
+            }
+
+            for record in batch {
+                if index_snapshot_record(record) {
+                    indexed_count += 1;
+                }
+            }
+            INDEX_BUILD_PROCESSED.fetch_add(batch.len(), Ordering::SeqCst);
+            emit_build_event("index_build_progress");
+
+            // 每批处理完让出执行权，避免长时间占用运行时
+            tokio::task::yield_now().await;
+        }
+
+        if INDEX_BUILD_GENERATION.load(Ordering::SeqCst) != generation {
+            return;
         }
+        SEARCH_INDEX.finish_build(&snapshot_ids);
+        INDEX_READY.store(true, Ordering::SeqCst);
+        emit_build_event("search_index_ready");
 
         let record_count = SEARCH_INDEX.get_stats();
         log::info!(
@@ -358,3 +640,19 @@ pub async fn initialize_search_index(clips: Vec<ClipRecord>) -> AppResult<()> {
 
     Ok(())
 }
+
+/// 查询搜索索引构建进度
+#[tauri::command]
+pub fn get_search_index_status() -> IndexBuildProgress {
+    current_build_progress()
+}
+
+/// 取消正在进行的搜索索引构建
+#[tauri::command]
+pub fn cancel_search_index_build() -> Result<(), String> {
+    if INDEX_READY.load(Ordering::SeqCst) {
+        return Err("搜索索引已构建完成".to_string());
+    }
+    INDEX_BUILD_CANCELLED.store(true, Ordering::SeqCst);
+    Ok(())
+}
@@ -1,9 +1,19 @@
 use clipboard_listener::ClipType;
 
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
 use rbatis::RBatis;
 use serde::{Deserialize, Serialize};
-use std::sync::{Arc, RwLock};
-use tauri::{AppHandle, Manager};
+use std::{
+    fs::File,
+    io::{Read, Write},
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+};
+use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_clipboard_pal::desktop::ClipboardPal;
 use tauri_plugin_dialog::DialogExt;
 
@@ -11,15 +21,23 @@ use crate::{
     auto_paste,
     biz::{
         clip_async_queue::AsyncQueue,
-        clip_record::ClipRecord,
+        clip_record::{ClipRecord, NOT_SYNCHRONIZED},
         content_processor::ContentProcessor,
         content_search::remove_ids_from_index,
-        system_setting::{check_cloud_sync_enabled, Settings},
+        history_cursor::reset_history_cursor,
+        system_setting::{
+            check_cloud_sync_enabled, ensure_not_kiosk_mode, get_sequential_paste_count, Settings,
+            DEFAULT_DELETE_UNDO_SECONDS, MAX_SEQUENTIAL_PASTE_COUNT,
+        },
     },
     utils::{
         aes_util::decrypt_content,
+        file_times::{apply_file_times, read_file_times, FileTimestamps},
+        image_info::{detect_image_extension, flatten_alpha_onto_white, is_animated_png},
         lock_utils::lock_utils::safe_read_lock,
         path_utils::{generate_file_not_found_error, str_to_safe_string},
+        rich_text::{html_to_text, rtf_to_text},
+        sensitive_log::set_clipboard_sensitive,
     },
     window::{WindowHideFlag, WindowHideGuard},
     CONTEXT,
@@ -41,6 +59,8 @@ pub async fn copy_clip_record(param: CopyClipRecord) -> Result<String, String> {
     let app_handle = CONTEXT.get::<AppHandle>();
     let clipboard = app_handle.state::<ClipboardPal>();
     let clip_type: ClipType = record.r#type.parse().unwrap_or(ClipType::Text);
+    // 敏感记录写入剪贴板后，自动粘贴时不输出剪贴板内容预览
+    set_clipboard_sensitive(record.is_sensitive());
 
     match clip_type {
         ClipType::Text => {
@@ -53,7 +73,12 @@ pub async fn copy_clip_record(param: CopyClipRecord) -> Result<String, String> {
                     return Err("文本解密失败".to_string());
                 }
             };
-            let _ = clipboard.write_text(content);
+            write_text_with_rich_flavor(
+                &clipboard,
+                record.rich_type.as_deref(),
+                record.rich_content.as_deref(),
+                content,
+            );
         }
         ClipType::Image => {
             if let Some(path) = record.content.as_str() {
@@ -63,7 +88,7 @@ pub async fn copy_clip_record(param: CopyClipRecord) -> Result<String, String> {
                         return Err("图片资源不存在，无法复制".to_string());
                     }
                     if let Ok(img_bytes) = std::fs::read(abs_path) {
-                        let _ = clipboard.write_image_binary(img_bytes);
+                        write_image_bytes(&clipboard, img_bytes);
                     } else {
                         return Err("图片资源读取失败，无法复制".to_string());
                     }
@@ -108,7 +133,10 @@ pub async fn copy_clip_record(param: CopyClipRecord) -> Result<String, String> {
             }
 
             // 创建临时文件链接以使用正确的文件名
-            match create_temp_files_with_correct_names(&display_list, &actual_list).await {
+            let stored_times = record_file_times(&record);
+            match create_temp_files_with_correct_names(&display_list, &actual_list, stored_times)
+                .await
+            {
                 Ok(temp_files) => {
                     let _ = clipboard.write_files_uris(temp_files);
                 }
@@ -119,27 +147,88 @@ pub async fn copy_clip_record(param: CopyClipRecord) -> Result<String, String> {
                 }
             }
         }
+        ClipType::Rtf | ClipType::Html => {
+            let markup = match decrypt_content(
+                ContentProcessor::process_text_content(record.content).as_str(),
+            ) {
+                Ok(markup) => markup,
+                Err(e) => {
+                    log::error!("解密富文本内容失败: {}", e);
+                    return Err("富文本解密失败".to_string());
+                }
+            };
+            write_rich_text(&clipboard, &clip_type, markup);
+        }
         _ => {}
     }
 
-    // 检查是否启用自动粘贴功能
-    let auto_paste_enabled = {
-        let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
-        match safe_read_lock(&settings_lock) {
-            Ok(settings) => {
-                let enabled = settings.auto_paste == 1;
-                log::debug!("自动粘贴功能状态: {}", if enabled { "已启用" } else { "未启用" });
-                enabled
-            }
-            Err(e) => {
-                log::warn!("无法获取设置: {}", e);
-                false // 如果无法获取设置，默认不启用自动粘贴
+    record_use(rb, &record.id).await;
+    trigger_auto_paste(app_handle);
+
+    Ok(String::new())
+}
+
+/// 复制成功后把记录的复制次数加一，更新失败只记录日志，不影响复制结果
+async fn record_use(rb: &RBatis, record_id: &str) {
+    if let Err(e) = ClipRecord::increment_use_count(rb, record_id).await {
+        log::warn!("更新复制次数失败: {}, 记录ID: {}", e, record_id);
+    }
+}
+
+/// 检查是否启用自动粘贴功能
+fn check_auto_paste_enabled() -> bool {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    match safe_read_lock(&settings_lock) {
+        Ok(settings) => {
+            let enabled = settings.auto_paste == 1;
+            log::debug!(
+                "自动粘贴功能状态: {}",
+                if enabled { "已启用" } else { "未启用" }
+            );
+            enabled
+        }
+        Err(e) => {
+            log::warn!("无法获取设置: {}", e);
+            false // 如果无法获取设置，默认不启用自动粘贴
+        }
+    }
+}
+
+/// 把剪贴板内容粘贴到之前获得焦点的窗口，会阻塞当前线程，返回是否粘贴成功
+fn paste_to_previous_window(app_handle: &AppHandle) -> bool {
+    log::info!("开始执行自动粘贴");
+    // 尝试自动粘贴到之前获得焦点的窗口
+    let result = auto_paste::auto_paste_to_previous_window();
+    match result {
+        Err(e) => {
+            let error_msg = e.to_string();
+            log::warn!("自动粘贴失败: {}", error_msg);
+
+            // 检查是否是权限相关的错误
+            if error_msg.contains("辅助功能权限") || error_msg.contains("权限") {
+                log::error!("检测到辅助功能权限问题，准备提示用户");
+
+                // 在主线程中显示对话框
+                let app_handle_for_dialog = app_handle.clone();
+                let _ = app_handle.run_on_main_thread(move || {
+                    show_accessibility_permission_dialog(&app_handle_for_dialog);
+                });
             }
+            false
         }
-    };
+        Ok(method) => {
+            log::info!("自动粘贴执行完成，粘贴方式: {:?}", method);
+            // 通知前端本次实际使用的粘贴方式
+            let _ = app_handle.emit("auto_paste_completed", method);
+            true
+        }
+    }
+}
 
+/// 启用自动粘贴时，在独立线程中把剪贴板内容粘贴到之前获得焦点的窗口
+fn trigger_auto_paste(app_handle: &AppHandle) {
     // 只有在启用自动粘贴时才执行
-    if auto_paste_enabled {
+    if check_auto_paste_enabled() {
         log::info!("准备执行自动粘贴");
 
         // 克隆 app_handle 供线程使用
@@ -149,33 +238,115 @@ pub async fn copy_clip_record(param: CopyClipRecord) -> Result<String, String> {
         std::thread::spawn(move || {
             // 等待一小段时间确保剪贴板内容已经更新
             std::thread::sleep(std::time::Duration::from_millis(100));
-
-            log::info!("开始执行自动粘贴");
-            // 尝试自动粘贴到之前获得焦点的窗口
-            if let Err(e) = auto_paste::auto_paste_to_previous_window() {
-                let error_msg = e.to_string();
-                log::warn!("自动粘贴失败: {}", error_msg);
-
-                // 检查是否是权限相关的错误
-                if error_msg.contains("辅助功能权限") || error_msg.contains("权限") {
-                    log::error!("检测到辅助功能权限问题，准备提示用户");
-
-                    // 在主线程中显示对话框
-                    let app_handle_for_dialog = app_handle_clone.clone();
-                    let _ = app_handle_clone.run_on_main_thread(move || {
-                        show_accessibility_permission_dialog(&app_handle_for_dialog);
-                    });
-                }
-                // 自动粘贴失败不影响复制功能，只记录警告日志
-            } else {
-                log::info!("自动粘贴执行完成");
-            }
+            // 自动粘贴失败不影响复制功能，只记录警告日志
+            paste_to_previous_window(&app_handle_clone);
         });
     } else {
         log::debug!("自动粘贴未启用，跳过");
     }
+}
 
-    Ok(String::new())
+// 依次粘贴时每条记录写入剪贴板后等待的时间，确保剪贴板内容已经更新
+const SEQUENTIAL_PASTE_CLIPBOARD_DELAY_MS: u64 = 100;
+// 依次粘贴时两条记录之间的间隔，给目标应用处理粘贴的时间
+const SEQUENTIAL_PASTE_INTERVAL_MS: u64 = 300;
+// 正在依次粘贴时忽略新的依次粘贴请求，避免两次粘贴交错
+static SEQUENTIAL_PASTE_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// 按传入的顺序把记录逐条写入剪贴板并自动粘贴到之前获得焦点的窗口，用于依次填写表单
+/// 需要开启自动粘贴，某一条粘贴失败时停止，不再粘贴后面的记录
+#[tauri::command]
+pub async fn copy_sequential(record_ids: Vec<String>) -> Result<(), String> {
+    if record_ids.is_empty() {
+        return Err("请选择要粘贴的记录".to_string());
+    }
+    if record_ids.len() > MAX_SEQUENTIAL_PASTE_COUNT as usize {
+        return Err(format!(
+            "一次最多依次粘贴{}条记录",
+            MAX_SEQUENTIAL_PASTE_COUNT
+        ));
+    }
+    if !check_auto_paste_enabled() {
+        return Err("依次粘贴需要开启自动粘贴".to_string());
+    }
+
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let records = ClipRecord::select_by_ids(rb, &record_ids, record_ids.len() as i32, 0)
+        .await
+        .map_err(|e| {
+            log::error!("查询依次粘贴的记录失败: {}", e);
+            "粘贴记录查询失败".to_string()
+        })?;
+    if record_ids
+        .iter()
+        .any(|id| !records.iter().any(|record| &record.id == id))
+    {
+        return Err("部分记录不存在或已删除".to_string());
+    }
+
+    if SEQUENTIAL_PASTE_RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("正在依次粘贴，请稍后再试".to_string());
+    }
+    let _running = SequentialPasteRunningGuard;
+    paste_records_in_order(&record_ids).await
+}
+
+/// 依次粘贴结束时（包括出错、panic 和任务被取消）清除运行标记
+struct SequentialPasteRunningGuard;
+
+impl Drop for SequentialPasteRunningGuard {
+    fn drop(&mut self) {
+        SEQUENTIAL_PASTE_RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+async fn paste_records_in_order(record_ids: &[String]) -> Result<(), String> {
+    let app_handle = CONTEXT.get::<AppHandle>();
+    for (index, record_id) in record_ids.iter().enumerate() {
+        if index > 0 {
+            tokio::time::sleep(std::time::Duration::from_millis(
+                SEQUENTIAL_PASTE_INTERVAL_MS,
+            ))
+            .await;
+        }
+        copy_clip_record_no_paste(CopyClipRecord {
+            record_id: record_id.clone(),
+        })
+        .await?;
+        tokio::time::sleep(std::time::Duration::from_millis(
+            SEQUENTIAL_PASTE_CLIPBOARD_DELAY_MS,
+        ))
+        .await;
+
+        // auto_paste中使用了std::thread::sleep，在阻塞线程中执行
+        let app_handle_clone = app_handle.clone();
+        let pasted =
+            tokio::task::spawn_blocking(move || paste_to_previous_window(&app_handle_clone))
+                .await
+                .unwrap_or(false);
+        if !pasted {
+            return Err(format!("第{}条记录自动粘贴失败，已停止依次粘贴", index + 1));
+        }
+    }
+    log::info!("依次粘贴完成，共{}条记录", record_ids.len());
+    Ok(())
+}
+
+/// 按复制的先后顺序依次粘贴最近的几条记录，条数由设置决定，供依次粘贴快捷键调用
+pub async fn paste_recent_sequential() -> Result<(), String> {
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let records = ClipRecord::select_recent(rb, get_sequential_paste_count() as i32)
+        .await
+        .map_err(|e| {
+            log::error!("查询最近记录失败: {}", e);
+            "查询最近记录失败".to_string()
+        })?;
+    // 查询结果最新的在前，粘贴时先粘贴较早复制的记录
+    let record_ids: Vec<String> = records.into_iter().rev().map(|record| record.id).collect();
+    if record_ids.is_empty() {
+        return Ok(());
+    }
+    copy_sequential(record_ids).await
 }
 
 /// 只复制到剪贴板，不触发自动粘贴功能
@@ -190,6 +361,8 @@ pub async fn copy_clip_record_no_paste(param: CopyClipRecord) -> Result<String,
     let app_handle = CONTEXT.get::<AppHandle>();
     let clipboard = app_handle.state::<ClipboardPal>();
     let clip_type: ClipType = record.r#type.parse().unwrap_or(ClipType::Text);
+    // 敏感记录写入剪贴板后，自动粘贴时不输出剪贴板内容预览
+    set_clipboard_sensitive(record.is_sensitive());
 
     match clip_type {
         ClipType::Text => {
@@ -202,7 +375,12 @@ pub async fn copy_clip_record_no_paste(param: CopyClipRecord) -> Result<String,
                     return Err("文本解密失败".to_string());
                 }
             };
-            let _ = clipboard.write_text(content);
+            write_text_with_rich_flavor(
+                &clipboard,
+                record.rich_type.as_deref(),
+                record.rich_content.as_deref(),
+                content,
+            );
         }
         ClipType::Image => {
             if let Some(path) = record.content.as_str() {
@@ -212,7 +390,7 @@ pub async fn copy_clip_record_no_paste(param: CopyClipRecord) -> Result<String,
                         return Err("图片资源不存在，无法复制".to_string());
                     }
                     if let Ok(img_bytes) = std::fs::read(abs_path) {
-                        let _ = clipboard.write_image_binary(img_bytes);
+                        write_image_bytes(&clipboard, img_bytes);
                     } else {
                         return Err("图片资源读取失败，无法复制".to_string());
                     }
@@ -257,7 +435,10 @@ pub async fn copy_clip_record_no_paste(param: CopyClipRecord) -> Result<String,
             }
 
             // 创建临时文件链接以使用正确的文件名
-            match create_temp_files_with_correct_names(&display_list, &actual_list).await {
+            let stored_times = record_file_times(&record);
+            match create_temp_files_with_correct_names(&display_list, &actual_list, stored_times)
+                .await
+            {
                 Ok(temp_files) => {
                     let _ = clipboard.write_files_uris(temp_files);
                 }
@@ -268,9 +449,22 @@ pub async fn copy_clip_record_no_paste(param: CopyClipRecord) -> Result<String,
                 }
             }
         }
+        ClipType::Rtf | ClipType::Html => {
+            let markup = match decrypt_content(
+                ContentProcessor::process_text_content(record.content).as_str(),
+            ) {
+                Ok(markup) => markup,
+                Err(e) => {
+                    log::error!("解密富文本内容失败: {}", e);
+                    return Err("富文本解密失败".to_string());
+                }
+            };
+            write_rich_text(&clipboard, &clip_type, markup);
+        }
         _ => {}
     }
 
+    record_use(rb, &record.id).await;
     // 注意：这个函数不执行自动粘贴功能
     log::debug!("仅复制到剪贴板，不触发自动粘贴");
     Ok(String::new())
@@ -282,16 +476,116 @@ pub struct PinnedClipRecord {
     pub pinned_flag: i32,
 }
 
+/// 以纯文本粘贴：文本、HTML和RTF记录去掉格式后只写入纯文本，再按设置执行自动粘贴
+#[tauri::command]
+pub async fn copy_clip_record_plain(param: CopyClipRecord) -> Result<String, String> {
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let record = match ClipRecord::select_by_id(rb, param.record_id.as_str()).await {
+        Ok(data) => data.first().cloned().ok_or("粘贴记录不存在".to_string())?,
+        Err(_) => return Err("粘贴记录查询失败".to_string()),
+    };
+
+    let clip_type: ClipType = record.r#type.parse().unwrap_or(ClipType::Text);
+    if !matches!(clip_type, ClipType::Text | ClipType::Rtf | ClipType::Html) {
+        return Err("该类型的记录不支持以纯文本粘贴".to_string());
+    }
+    let content = match decrypt_content(
+        ContentProcessor::process_text_content(record.content.clone()).as_str(),
+    ) {
+        Ok(content) => content,
+        Err(e) => {
+            log::error!("解密文本内容失败: {}", e);
+            return Err("文本解密失败".to_string());
+        }
+    };
+    let text = match clip_type {
+        ClipType::Html => html_to_text(&content),
+        ClipType::Rtf => rtf_to_text(&content),
+        _ => content,
+    };
+
+    let app_handle = CONTEXT.get::<AppHandle>();
+    set_clipboard_sensitive(record.is_sensitive());
+    // 文本记录保存的富文本格式不写入，只写入纯文本
+    app_handle
+        .state::<ClipboardPal>()
+        .write_text(text)
+        .map_err(|e| {
+            log::error!("写入剪贴板失败: {}", e);
+            format!("写入剪贴板失败: {}", e)
+        })?;
+
+    record_use(rb, &record.id).await;
+    trigger_auto_paste(app_handle);
+    Ok(String::new())
+}
+
 #[tauri::command]
 pub async fn set_pinned(param: PinnedClipRecord) -> Result<String, String> {
+    ensure_not_kiosk_mode()?;
     let rb: &RBatis = CONTEXT.get::<RBatis>();
     let _ = ClipRecord::update_pinned(rb, &param.record_id, param.pinned_flag).await;
     Ok(String::new())
 }
 
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct SensitiveClipRecord {
+    pub record_id: String,
+    pub sensitive: bool,
+}
+
+/// 标记或取消标记敏感记录，敏感记录的内容不输出到日志，列表中默认隐藏内容
+#[tauri::command]
+pub async fn set_sensitive(param: SensitiveClipRecord) -> Result<(), String> {
+    ensure_not_kiosk_mode()?;
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    ClipRecord::update_sensitive(rb, &param.record_id, param.sensitive as i32)
+        .await
+        .map_err(|e| {
+            log::error!("设置敏感标记失败: {}, 记录ID: {}", e, param.record_id);
+            "设置敏感标记失败".to_string()
+        })?;
+    let app_handle = CONTEXT.get::<AppHandle>();
+    let _ = app_handle.emit("clip_record_change", ());
+    Ok(())
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct FavoriteClipRecord {
+    pub record_id: String,
+    pub favorite: bool,
+}
+
+/// 收藏或取消收藏记录，与置顶不同可以同时收藏多条，收藏的记录不会被自动清理
+#[tauri::command]
+pub async fn set_favorite(param: FavoriteClipRecord) -> Result<(), String> {
+    ensure_not_kiosk_mode()?;
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    ClipRecord::update_favorite(rb, &param.record_id, param.favorite as i32)
+        .await
+        .map_err(|e| {
+            log::error!("设置收藏失败: {}, 记录ID: {}", e, param.record_id);
+            "设置收藏失败".to_string()
+        })?;
+    let app_handle = CONTEXT.get::<AppHandle>();
+    let _ = app_handle.emit("clip_record_change", ());
+    Ok(())
+}
+
+// 处于撤销宽限期内的删除记录  记录ID -> (删除前的同步状态, 删除批次号)
+static PENDING_DELETES: Lazy<DashMap<String, (i32, u64)>> = Lazy::new(DashMap::new);
+static DELETE_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// 记录是否处于删除撤销宽限期内（宽限期内的删除不会同步到云端）
+pub fn is_delete_pending(record_id: &str) -> bool {
+    PENDING_DELETES.contains_key(record_id)
+}
+
 /// 删除一条记录
+/// 记录会立即逻辑删除，但同步删除和移除搜索索引会延迟到撤销宽限期结束之后
 #[tauri::command]
 pub async fn del_record(param: CopyClipRecord) -> Result<String, String> {
+    ensure_not_kiosk_mode()?;
     let rb: &RBatis = CONTEXT.get::<RBatis>();
     let ids = vec![param.record_id.clone()];
 
@@ -302,26 +596,38 @@ pub async fn del_record(param: CopyClipRecord) -> Result<String, String> {
                 // 逻辑删除 并标记为待同步状态
                 let res = ClipRecord::update_del_by_ids(rb, &ids).await;
                 if let Ok(_) = res {
-                    // 如果有删除记录，发送到异步队列   前提是开启了云同步开关
-                    if check_cloud_sync_enabled().await {
-                        let async_queue = CONTEXT.get::<AsyncQueue<ClipRecord>>();
-                        if !async_queue.is_full() {
-                            let send_res = async_queue.send_delete(records[0].clone()).await;
-                            if let Err(e) = send_res {
-                                log::error!(
-                                    "异步队列发送失败，删除的粘贴内容：{:?}, 异常:{}",
-                                    records[0],
-                                    e
-                                );
+                    reset_history_cursor();
+
+                    let undo_seconds = {
+                        let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+                        safe_read_lock(&settings_lock)
+                            .ok()
+                            .and_then(|settings| settings.delete_undo_seconds)
+                            .unwrap_or(DEFAULT_DELETE_UNDO_SECONDS)
+                    };
+
+                    if undo_seconds == 0 {
+                        propagate_delete(records[0].clone()).await;
+                    } else {
+                        let seq = DELETE_SEQ.fetch_add(1, Ordering::SeqCst);
+                        let origin_sync_flag = records[0].sync_flag.unwrap_or(NOT_SYNCHRONIZED);
+                        PENDING_DELETES.insert(param.record_id.clone(), (origin_sync_flag, seq));
+
+                        let record = records[0].clone();
+                        tokio::spawn(async move {
+                            tokio::time::sleep(tokio::time::Duration::from_secs(
+                                undo_seconds as u64,
+                            ))
+                            .await;
+                            // 只处理本次删除，期间被撤销或重新删除的不处理
+                            if PENDING_DELETES
+                                .remove_if(&record.id, |_, (_, s)| *s == seq)
+                                .is_some()
+                            {
+                                propagate_delete(record).await;
                             }
-                        }
+                        });
                     }
-                    // 异步从搜索索引中移除记录
-                    tokio::spawn(async move {
-                        if let Err(e) = remove_ids_from_index(&ids).await {
-                            log::error!("从搜索索引删除记录失败: {}", e);
-                        }
-                    });
                 }
             }
             return Ok(String::new());
@@ -330,8 +636,57 @@ pub async fn del_record(param: CopyClipRecord) -> Result<String, String> {
     };
 }
 
+/// 撤销宽限期内的删除
 #[tauri::command]
-pub async fn image_save_as(param: CopyClipRecord) -> Result<String, String> {
+pub async fn undo_delete(param: CopyClipRecord) -> Result<String, String> {
+    ensure_not_kiosk_mode()?;
+    let (_, (origin_sync_flag, _)) = PENDING_DELETES
+        .remove(&param.record_id)
+        .ok_or("删除已生效，无法撤销".to_string())?;
+
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    if let Err(e) = ClipRecord::restore_deleted(rb, &param.record_id, origin_sync_flag).await {
+        log::error!("撤销删除失败: {}, 记录ID: {}", e, param.record_id);
+        return Err("撤销删除失败".to_string());
+    }
+
+    let app_handle = CONTEXT.get::<AppHandle>();
+    let _ = app_handle.emit("clip_record_change", ());
+    log::info!("已撤销删除记录: {}", param.record_id);
+    Ok(String::new())
+}
+
+/// 删除生效：发送同步删除并从搜索索引移除
+pub(crate) async fn propagate_delete(record: ClipRecord) {
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    // 宽限期内可能因为重新复制相同内容而恢复了记录
+    match ClipRecord::select_by_id(rb, &record.id).await {
+        Ok(current) if current.first().map(|r| r.del_flag) == Some(Some(1)) => {}
+        _ => return,
+    }
+
+    // 如果有删除记录，发送到异步队列   前提是开启了云同步开关
+    if check_cloud_sync_enabled().await {
+        let async_queue = CONTEXT.get::<AsyncQueue<ClipRecord>>();
+        if !async_queue.is_full() {
+            let send_res = async_queue.send_delete(record.clone()).await;
+            if let Err(e) = send_res {
+                log::error!(
+                    "异步队列发送失败，删除的粘贴内容：{:?}, 异常:{}",
+                    record,
+                    e
+                );
+            }
+        }
+    }
+    // 从搜索索引中移除记录
+    if let Err(e) = remove_ids_from_index(&[record.id.clone()]).await {
+        log::error!("从搜索索引删除记录失败: {}", e);
+    }
+}
+
+#[tauri::command]
+pub async fn image_save_as(param: ImageSaveAsParam) -> Result<String, String> {
     let rb: &RBatis = CONTEXT.get::<RBatis>();
     let record_res = ClipRecord::select_by_id(rb, param.record_id.as_str()).await;
     match record_res {
@@ -348,28 +703,44 @@ pub async fn image_save_as(param: CopyClipRecord) -> Result<String, String> {
                 return Err("图片资源丢失".to_string());
             }
 
+            // 根据图片实际存储的格式确定默认扩展名
+            let stored_ext = stored_image_extension(&abs_path);
+
             let window_hide_flag = CONTEXT.get::<WindowHideFlag>();
             // 用Arc包裹WindowHideGuard，延长生命周期到回调闭包
             let guard = Arc::new(WindowHideGuard::new(window_hide_flag));
             let app_handle = CONTEXT.get::<AppHandle>();
             let abs_path_clone = abs_path.clone();
             let guard_clone = guard.clone();
-            app_handle
-                .dialog()
-                .file()
-                .add_filter("图片", &["png"])
-                .set_file_name(format!("clip_{}", record.id))
+            let quality = param.quality;
+            let other_exts: Vec<&str> = SAVE_AS_IMAGE_EXTENSIONS
+                .iter()
+                .copied()
+                .filter(|ext| !same_image_format(ext, stored_ext))
+                .collect();
+            // 第一个筛选项为存储格式，其次是常用的转换格式
+            let mut dialog = app_handle.dialog().file().add_filter("图片", &[stored_ext]);
+            for (name, exts) in SAVE_AS_FORMAT_FILTERS {
+                if !exts.iter().any(|ext| same_image_format(ext, stored_ext)) {
+                    dialog = dialog.add_filter(name, exts);
+                }
+            }
+            dialog
+                .add_filter("其他图片格式", &other_exts)
+                .set_file_name(format!("clip_{}.{}", record.id, stored_ext))
                 .save_file(move |file_path| {
                     // guard_clone在闭包内，作用域结束时自动drop，恢复窗口可隐藏
                     let _guard = guard_clone;
                     if let Some(select_path) = file_path {
                         let select_path = select_path.as_path();
                         if let Some(select_path) = select_path {
-                            if let Err(e) = std::fs::copy(&abs_path_clone, &select_path) {
+                            if let Err(e) =
+                                save_image_as(&abs_path_clone, stored_ext, select_path, quality)
+                            {
                                 let source_path = abs_path_clone.to_string_lossy();
                                 let dest_path = select_path.to_string_lossy();
                                 log::error!(
-                                    "复制图片失败: {}, 源文件: {}, 目标文件: {}",
+                                    "保存图片失败: {}, 源文件: {}, 目标文件: {}",
                                     e,
                                     source_path,
                                     dest_path
@@ -384,6 +755,158 @@ pub async fn image_save_as(param: CopyClipRecord) -> Result<String, String> {
     }
 }
 
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ImageSaveAsParam {
+    pub record_id: String,
+    // 保存为JPEG时的图片质量（1-100），未传时使用默认质量
+    pub quality: Option<u8>,
+}
+
+// 另存为时支持的图片格式
+const SAVE_AS_IMAGE_EXTENSIONS: [&str; 6] = ["png", "jpg", "jpeg", "webp", "gif", "bmp"];
+// 另存为对话框中单独列出的转换格式
+const SAVE_AS_FORMAT_FILTERS: [(&str, &[&str]); 3] = [
+    ("PNG 图片", &["png"]),
+    ("JPEG 图片", &["jpg", "jpeg"]),
+    ("WebP 图片", &["webp"]),
+];
+// 保存为JPEG时默认的图片质量
+const DEFAULT_JPEG_QUALITY: u8 = 90;
+
+/// 写入图片记录，GIF、APNG动图按原格式写入以保留动画
+fn write_image_bytes(clipboard: &ClipboardPal, img_bytes: Vec<u8>) {
+    let result = if detect_image_extension(&img_bytes) == Some("gif") {
+        clipboard.write_gif_binary(img_bytes)
+    } else if is_animated_png(&img_bytes) {
+        clipboard.write_apng_binary(img_bytes)
+    } else {
+        clipboard.write_image_binary(img_bytes)
+    };
+    if let Err(e) = result {
+        log::warn!("写入图片到剪贴板失败: {}", e);
+    }
+}
+
+/// 写入文本记录，记录保存了HTML/RTF格式时一起写入，粘贴到富文本编辑器时保留格式
+fn write_text_with_rich_flavor(
+    clipboard: &ClipboardPal,
+    rich_type: Option<&str>,
+    rich_content: Option<&str>,
+    text: String,
+) {
+    let rich = match (rich_type, rich_content) {
+        (Some(rich_type), Some(rich_content)) if !rich_content.is_empty() => {
+            match decrypt_content(rich_content) {
+                Ok(rich) => rich_type.parse::<ClipType>().ok().map(|t| (t, rich)),
+                Err(e) => {
+                    log::warn!("解密富文本内容失败，只写入纯文本: {}", e);
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+    let result = match rich {
+        Some((ClipType::Html, html)) => clipboard.write_html_and_text(html, text),
+        Some((ClipType::Rtf, rtf)) => clipboard.write_rtf_and_text(rtf, text),
+        _ => clipboard.write_text(text),
+    };
+    if let Err(e) = result {
+        log::warn!("写入剪贴板失败: {}", e);
+    }
+}
+
+/// 把HTML/RTF记录写入剪贴板，同时写入提取出的纯文本，供不支持富文本的应用粘贴
+fn write_rich_text(clipboard: &ClipboardPal, clip_type: &ClipType, markup: String) {
+    let result = match clip_type {
+        ClipType::Html => {
+            let text = html_to_text(&markup);
+            clipboard.write_html_and_text(markup, text)
+        }
+        _ => {
+            let text = rtf_to_text(&markup);
+            clipboard.write_rtf_and_text(markup, text)
+        }
+    };
+    if let Err(e) = result {
+        log::warn!("写入剪贴板失败: {}", e);
+    }
+}
+
+/// 读取图片文件头判断实际存储的格式，无法识别时按png处理
+fn stored_image_extension(path: &Path) -> &'static str {
+    let mut header = [0u8; 16];
+    let read_len = File::open(path)
+        .and_then(|mut file| file.read(&mut header))
+        .unwrap_or(0);
+    detect_image_extension(&header[..read_len]).unwrap_or("png")
+}
+
+/// 判断两个扩展名是否为同一种图片格式
+fn same_image_format(a: &str, b: &str) -> bool {
+    let normalize = |ext: &str| match ext.to_ascii_lowercase().as_str() {
+        "jpeg" => "jpg".to_string(),
+        other => other.to_string(),
+    };
+    normalize(a) == normalize(b)
+}
+
+/// 按用户选择的扩展名保存图片：格式与存储格式一致时直接复制，否则转码后保存
+/// 未填写扩展名时使用存储格式的扩展名，quality 只对JPEG生效（WebP只支持无损编码）
+fn save_image_as(
+    source: &Path,
+    stored_ext: &str,
+    target: &Path,
+    quality: Option<u8>,
+) -> Result<(), String> {
+    let target_ext = target
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase());
+    let (target, target_ext) = match target_ext {
+        Some(ext) => (target.to_path_buf(), ext),
+        None => (target.with_extension(stored_ext), stored_ext.to_string()),
+    };
+
+    if same_image_format(&target_ext, stored_ext) {
+        std::fs::copy(source, &target).map_err(|e| e.to_string())?;
+        return Ok(());
+    }
+
+    if !SAVE_AS_IMAGE_EXTENSIONS.contains(&target_ext.as_str()) {
+        return Err(format!("不支持的图片格式: {}", target_ext));
+    }
+    let format = image::ImageFormat::from_extension(&target_ext)
+        .ok_or_else(|| format!("不支持的图片格式: {}", target_ext))?;
+    let img = image::open(source).map_err(|e| format!("读取图片失败: {}", e))?;
+    let saved = match format {
+        image::ImageFormat::Jpeg => {
+            // jpg不支持透明通道，透明部分叠加到白色背景上
+            let rgb = flatten_alpha_onto_white(&img);
+            let quality = quality.unwrap_or(DEFAULT_JPEG_QUALITY).clamp(1, 100);
+            let file = File::create(&target).map_err(|e| format!("创建文件失败: {}", e))?;
+            let mut writer = std::io::BufWriter::new(file);
+            image::codecs::jpeg::JpegEncoder::new_with_quality(&mut writer, quality)
+                .encode_image(&rgb)
+                .map_err(|e| format!("图片转码失败: {}", e))
+                .and_then(|_| writer.flush().map_err(|e| format!("写入文件失败: {}", e)))
+        }
+        // WebP编码只支持8位RGB/RGBA
+        image::ImageFormat::WebP => image::DynamicImage::ImageRgba8(img.to_rgba8())
+            .save_with_format(&target, format)
+            .map_err(|e| format!("图片转码失败: {}", e)),
+        _ => img
+            .save_with_format(&target, format)
+            .map_err(|e| format!("图片转码失败: {}", e)),
+    };
+    if let Err(e) = saved {
+        // 转码失败时删除写了一半的文件，避免留下损坏的图片
+        let _ = std::fs::remove_file(&target);
+        return Err(e);
+    }
+    log::info!("图片已转码保存为{}格式: {:?}", target_ext, target);
+    Ok(())
+}
+
 /// 复制单个文件
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct CopySingleFileRecord {
@@ -437,6 +960,7 @@ pub async fn copy_single_file(param: CopySingleFileRecord) -> Result<String, Str
     match create_temp_files_with_correct_names(
         &[param.file_path.clone()],
         &[actual_file_path.clone()],
+        record_file_times(&record),
     )
     .await
     {
@@ -450,14 +974,33 @@ pub async fn copy_single_file(param: CopySingleFileRecord) -> Result<String, Str
         }
     }
 
+    record_use(rb, &record.id).await;
     log::debug!("已复制单个文件到剪贴板");
     Ok(String::new())
 }
 
+/// 单文件记录保存的原文件时间，多文件记录直接引用原文件，没有保存时间
+fn record_file_times(record: &ClipRecord) -> Option<FileTimestamps> {
+    let times = FileTimestamps {
+        modified: record.file_modified,
+        created: record.file_created,
+    };
+    (!times.is_empty()).then_some(times)
+}
+
+/// 把原文件的时间设置到粘贴用的临时文件上，失败时只记录日志
+fn restore_temp_file_times(temp_file_path: &Path, times: &FileTimestamps) {
+    if let Err(e) = apply_file_times(temp_file_path, times) {
+        log::warn!("还原临时文件时间失败: {:?}, 错误: {}", temp_file_path, e);
+    }
+}
+
 /// 创建临时文件，使用正确的文件名，以便粘贴时显示用户期望的文件名
+/// stored_times 为记录中保存的原文件时间，会还原到临时文件上
 async fn create_temp_files_with_correct_names(
     display_names: &[String],
     actual_paths: &[String],
+    stored_times: Option<FileTimestamps>,
 ) -> Result<Vec<String>, String> {
     use std::path::Path;
 
@@ -465,7 +1008,7 @@ async fn create_temp_files_with_correct_names(
         return Err("显示名称和实际路径数量不匹配".to_string());
     }
 
-    let temp_dir = std::env::temp_dir().join("clip_pal_temp");
+    let temp_dir = crate::utils::file_dir::get_paste_temp_dir();
 
     // 创建临时目录
     if let Err(e) = std::fs::create_dir_all(&temp_dir) {
@@ -505,6 +1048,10 @@ async fn create_temp_files_with_correct_names(
         match std::fs::hard_link(source_path, &temp_file_path) {
             Ok(_) => {
                 log::debug!("创建硬链接成功: {:?} -> {:?}", source_path, temp_file_path);
+                // 硬链接与源文件共用时间，只在记录保存了原文件时间时还原
+                if let Some(times) = &stored_times {
+                    restore_temp_file_times(&temp_file_path, times);
+                }
                 temp_file_paths.push(temp_file_path.to_string_lossy().to_string());
             }
             Err(e) => {
@@ -517,6 +1064,8 @@ async fn create_temp_files_with_correct_names(
                             source_path,
                             temp_file_path
                         );
+                        let times = stored_times.unwrap_or_else(|| read_file_times(source_path));
+                        restore_temp_file_times(&temp_file_path, &times);
                         temp_file_paths.push(temp_file_path.to_string_lossy().to_string());
                     }
                     Err(e) => {
@@ -1,20 +1,30 @@
-use std::path::PathBuf;
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
 
 use chrono::Local;
 use clipboard_listener::ClipType;
+use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter};
 use tokio::time::Duration;
 use uuid::Uuid;
 
 use crate::{
     api::cloud_sync_api::{get_dowload_url, DownloadCloudFileParam},
+    biz::background_task_status::{
+        begin_file_transfer, begin_task_tick, register_background_task, TASK_FILE_DOWNLOAD,
+    },
     biz::clip_record::{ClipRecord, SKIP_SYNC, SYNCHRONIZING},
+    biz::clip_record_sync::{compute_file_content_md5, compute_full_file_md5},
     biz::query_clip_record::get_file_info_with_paths,
+    biz::system_setting::ensure_not_kiosk_mode,
     errors::{AppError, AppResult},
     utils::{
         file_dir::get_resources_dir,
         file_ext::extract_full_extension_from_str,
         http_client,
+        path_utils::to_safe_string,
         retry_helper::{retry_with_config, RetryConfig},
         token_manager::has_valid_auth,
     },
@@ -22,6 +32,35 @@ use crate::{
 };
 use rbatis::RBatis;
 
+// 立即下载全部文件时的最大并发数
+const DOWNLOAD_ALL_CONCURRENCY: usize = 4;
+
+// 是否正在执行立即下载全部文件，执行期间定时下载任务暂停，避免重复下载同一条记录
+static DOWNLOAD_ALL_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// 立即下载全部文件的进度
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadAllProgress {
+    // 待下载的记录总数
+    pub total: usize,
+    // 下载成功数
+    pub downloaded: usize,
+    // 下载失败数
+    pub failed: usize,
+    // 成功导出到指定目录的文件数
+    pub exported: usize,
+}
+
+/// 单个云端文件的下载进度，通过 download_progress 事件通知前端
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadProgress {
+    pub record_id: String,
+    // 已下载字节数
+    pub downloaded: u64,
+    // 文件总字节数，服务器未返回 Content-Length 时为None
+    pub total: Option<u64>,
+}
+
 /// 判断下载错误是否应该重试
 fn should_retry_download_error(error: &AppError) -> bool {
     match error {
@@ -75,27 +114,32 @@ fn should_retry_download_error(error: &AppError) -> bool {
 pub async fn start_cloud_file_download_timer(app_handle: AppHandle) {
     log::info!("Starting cloud file download timer");
 
-    tokio::spawn(async move {
-        let mut interval_timer = tokio::time::interval(Duration::from_secs(30));
+    let mut interval_timer = tokio::time::interval(Duration::from_secs(30));
+    register_background_task(TASK_FILE_DOWNLOAD, 30);
 
-        loop {
-            interval_timer.tick().await;
+    loop {
+        interval_timer.tick().await;
+        let _tick = begin_task_tick(TASK_FILE_DOWNLOAD);
 
-            if !crate::biz::system_setting::check_cloud_sync_enabled().await {
-                continue;
-            }
+        if !crate::biz::system_setting::check_cloud_sync_enabled().await {
+            continue;
+        }
 
-            // 检查用户登录状态
-            if !has_valid_auth() {
-                log::debug!("用户未登录或认证已过期，跳过云文件下载任务");
-                continue;
-            }
+        // 检查用户登录状态
+        if !has_valid_auth() {
+            log::debug!("用户未登录或认证已过期，跳过云文件下载任务");
+            continue;
+        }
 
-            if let Err(e) = scan_and_download_cloud_files(&app_handle).await {
-                log::error!("Failed to scan and download cloud files: {}", e);
-            }
+        if DOWNLOAD_ALL_RUNNING.load(Ordering::SeqCst) {
+            log::debug!("正在立即下载全部文件，跳过本次定时下载");
+            continue;
         }
-    });
+
+        if let Err(e) = scan_and_download_cloud_files(&app_handle).await {
+            log::error!("Failed to scan and download cloud files: {}", e);
+        }
+    }
 }
 
 async fn scan_and_download_cloud_files(app_handle: &AppHandle) -> AppResult<()> {
@@ -135,13 +179,15 @@ async fn scan_and_download_cloud_files(app_handle: &AppHandle) -> AppResult<()>
     Ok(())
 }
 
+/// 下载记录对应的云端文件，返回下载后的本地路径（非图片、文件类型返回None）
 async fn download_cloud_file_for_record(
     app_handle: AppHandle,
     record: ClipRecord,
-) -> AppResult<()> {
+) -> AppResult<Option<String>> {
     if record.r#type != ClipType::Image.to_string() && record.r#type != ClipType::File.to_string() {
-        return Ok(());
+        return Ok(None);
     }
+    let _transfer = begin_file_transfer(TASK_FILE_DOWNLOAD, &record.id);
 
     log::info!(
         "Starting cloud file download with retry: record_id={}, type={}, md5={}",
@@ -169,12 +215,12 @@ async fn download_cloud_file_for_record(
     .await;
 
     match result {
-        Ok(_) => {
+        Ok(absolute_path) => {
             log::info!(
                 "Cloud file download ultimately succeeded: record_id={}",
                 record.id
             );
-            Ok(())
+            Ok(Some(absolute_path))
         }
         Err(e) => {
             log::error!(
@@ -183,6 +229,9 @@ async fn download_cloud_file_for_record(
                 e
             );
 
+            // 下载失败达到最大重试次数后，删除未完成的部分文件
+            remove_partial_download(&record.id).await;
+
             // 下载失败达到最大重试次数后，标记为跳过同步，避免一直重试
             if let Err(mark_err) =
                 mark_download_as_skip_sync(&record.id, &format!("下载失败: {}", e)).await
@@ -195,8 +244,8 @@ async fn download_cloud_file_for_record(
     }
 }
 
-/// 核心下载逻辑（被重试机制调用）
-async fn download_cloud_file_core(app_handle: AppHandle, record: ClipRecord) -> AppResult<()> {
+/// 核心下载逻辑（被重试机制调用），返回下载后的本地路径
+async fn download_cloud_file_core(app_handle: AppHandle, record: ClipRecord) -> AppResult<String> {
     let download_param = DownloadCloudFileParam {
         md5_str: record.md5_str.clone(),
         r#type: record.r#type.clone(),
@@ -222,10 +271,12 @@ async fn download_cloud_file_core(app_handle: AppHandle, record: ClipRecord) ->
 
     // 下载文件到本地
     let (filename, absolute_path) = download_cloud_file_to_local(
+        &app_handle,
         &download_response.url,
         &download_response.file_name,
         &record.r#type,
         &record.id,
+        &record.md5_str,
     )
     .await?;
 
@@ -268,7 +319,307 @@ async fn download_cloud_file_core(app_handle: AppHandle, record: ClipRecord) ->
         absolute_path
     );
 
-    Ok(())
+    Ok(absolute_path)
+}
+
+/// 立即下载全部等待下载的云端文件（不等待定时任务），可同时导出到指定目录
+/// 下载过程中通过 download_all_progress 事件通知进度
+#[tauri::command]
+pub async fn download_all_pending(
+    app_handle: AppHandle,
+    export_dir: Option<String>,
+) -> Result<DownloadAllProgress, String> {
+    ensure_not_kiosk_mode()?;
+    if !has_valid_auth() {
+        return Err("用户未登录或认证已过期".to_string());
+    }
+
+    let export_dir = match export_dir.filter(|dir| !dir.trim().is_empty()) {
+        Some(dir) => {
+            let dir = PathBuf::from(dir);
+            if !dir.is_dir() {
+                return Err("导出目录不存在".to_string());
+            }
+            Some(dir)
+        }
+        None => None,
+    };
+
+    if DOWNLOAD_ALL_RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("正在下载全部文件，请稍后再试".to_string());
+    }
+    let _running = DownloadAllRunningGuard;
+    download_all_pending_inner(&app_handle, export_dir)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 下载全部文件结束时（包括出错、panic 和任务被取消）清除运行标记
+struct DownloadAllRunningGuard;
+
+impl Drop for DownloadAllRunningGuard {
+    fn drop(&mut self) {
+        DOWNLOAD_ALL_RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+async fn download_all_pending_inner(
+    app_handle: &AppHandle,
+    export_dir: Option<PathBuf>,
+) -> AppResult<DownloadAllProgress> {
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+
+    // SQLite中 LIMIT -1 表示不限制条数
+    let pending_records = ClipRecord::select_by_sync_flag_limit(rb, SYNCHRONIZING, 1, -1).await?;
+
+    let total = pending_records.len();
+    log::info!("开始立即下载全部云端文件，待下载: {}条", total);
+    if total == 0 {
+        return Ok(DownloadAllProgress {
+            total,
+            downloaded: 0,
+            failed: 0,
+            exported: 0,
+        });
+    }
+
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(DOWNLOAD_ALL_CONCURRENCY));
+    let downloaded = Arc::new(AtomicUsize::new(0));
+    let failed = Arc::new(AtomicUsize::new(0));
+    let exported = Arc::new(AtomicUsize::new(0));
+    let export_dir = export_dir.map(Arc::new);
+
+    let tasks: Vec<_> = pending_records
+        .into_iter()
+        .map(|record| {
+            let app_handle = app_handle.clone();
+            let semaphore = semaphore.clone();
+            let downloaded = downloaded.clone();
+            let failed = failed.clone();
+            let exported = exported.clone();
+            let export_dir = export_dir.clone();
+
+            tokio::spawn(async move {
+                let _permit = semaphore.acquire().await.unwrap();
+                let display_name = record.content.as_str().unwrap_or_default().to_string();
+                match download_cloud_file_for_record(app_handle.clone(), record).await {
+                    Ok(local_path) => {
+                        downloaded.fetch_add(1, Ordering::SeqCst);
+                        if let (Some(dir), Some(path)) = (export_dir, local_path) {
+                            match export_downloaded_file(Path::new(&path), &display_name, &dir) {
+                                Ok(_) => {
+                                    exported.fetch_add(1, Ordering::SeqCst);
+                                }
+                                Err(e) => log::warn!("导出下载文件失败: {}, 错误: {}", path, e),
+                            }
+                        }
+                    }
+                    Err(_) => {
+                        failed.fetch_add(1, Ordering::SeqCst);
+                    }
+                }
+
+                let progress = DownloadAllProgress {
+                    total,
+                    downloaded: downloaded.load(Ordering::SeqCst),
+                    failed: failed.load(Ordering::SeqCst),
+                    exported: exported.load(Ordering::SeqCst),
+                };
+                let _ = app_handle.emit("download_all_progress", progress);
+            })
+        })
+        .collect();
+
+    for task in tasks {
+        if let Err(e) = task.await {
+            log::error!("Download task failed: {}", e);
+        }
+    }
+
+    let progress = DownloadAllProgress {
+        total,
+        downloaded: downloaded.load(Ordering::SeqCst),
+        failed: failed.load(Ordering::SeqCst),
+        exported: exported.load(Ordering::SeqCst),
+    };
+    log::info!(
+        "立即下载全部云端文件完成，总数: {}, 成功: {}, 失败: {}, 导出: {}",
+        progress.total,
+        progress.downloaded,
+        progress.failed,
+        progress.exported
+    );
+    Ok(progress)
+}
+
+/// 将下载的文件复制到导出目录，使用原始文件名，重名时追加序号
+fn export_downloaded_file(
+    source: &Path,
+    display_name: &str,
+    export_dir: &Path,
+) -> AppResult<PathBuf> {
+    let file_name = Path::new(display_name)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .filter(|name| !name.is_empty())
+        .or_else(|| {
+            source
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        })
+        .ok_or_else(|| AppError::General("无法确定导出文件名".to_string()))?;
+
+    let (target, mut output) = create_export_file(export_dir, &file_name)?;
+    let copied = File::open(source).and_then(|mut input| std::io::copy(&mut input, &mut output));
+    if let Err(e) = copied {
+        drop(output);
+        let _ = std::fs::remove_file(&target);
+        return Err(AppError::Io(e));
+    }
+    Ok(target)
+}
+
+/// 在导出目录中创建不重名的文件，例如 a.txt 已存在时创建 a (1).txt
+/// 使用 create_new 创建，检查和创建是同一个操作，不会覆盖其他程序同时创建的文件
+fn create_export_file(export_dir: &Path, file_name: &str) -> AppResult<(PathBuf, File)> {
+    let path = Path::new(file_name);
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| file_name.to_string());
+    let extension = path
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+    let mut index = 0;
+    loop {
+        let candidate = if index == 0 {
+            export_dir.join(file_name)
+        } else {
+            export_dir.join(format!("{} ({}){}", stem, index, extension))
+        };
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&candidate)
+        {
+            Ok(file) => return Ok((candidate, file)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => index += 1,
+            Err(e) => return Err(AppError::Io(e)),
+        }
+    }
+}
+
+/// 重新关联下载文件的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct RelinkResult {
+    // 是否找到了内容匹配的本地文件
+    pub found: bool,
+    // 关联到的文件路径
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelinkDownloadedFileParam {
+    pub record_id: String,
+}
+
+/// 为下载完成但数据库更新失败的记录重新关联本地文件
+/// 在资源目录中查找MD5与记录一致的文件，找到后完成下载后的记录更新，无需重新下载
+#[tauri::command]
+pub async fn relink_downloaded_file(
+    param: RelinkDownloadedFileParam,
+) -> Result<RelinkResult, String> {
+    ensure_not_kiosk_mode()?;
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let record = ClipRecord::select_by_id(rb, &param.record_id)
+        .await
+        .map_err(|e| format!("查询记录失败: {}", e))?
+        .into_iter()
+        .next()
+        .ok_or("记录不存在")?;
+
+    let is_image = record.r#type == ClipType::Image.to_string();
+    if !is_image && record.r#type != ClipType::File.to_string() {
+        return Err("只有图片和文件类型的记录需要关联本地文件".to_string());
+    }
+    if record.sync_flag != Some(SYNCHRONIZING) && record.sync_flag != Some(SKIP_SYNC) {
+        return Err("记录不处于等待下载状态".to_string());
+    }
+    if let Some(local_path) = &record.local_file_path {
+        if !local_path.is_empty() && std::path::Path::new(local_path).exists() {
+            return Err("记录已关联本地文件".to_string());
+        }
+    }
+
+    // 图片保存在资源目录下，文件保存在resources/files目录下
+    let resources_dir = get_resources_dir().ok_or("资源目录获取失败")?;
+    let search_dir = if is_image {
+        resources_dir
+    } else {
+        resources_dir.join("files")
+    };
+    let entries = match std::fs::read_dir(&search_dir) {
+        Ok(entries) => entries,
+        Err(_) => {
+            return Ok(RelinkResult {
+                found: false,
+                path: None,
+            })
+        }
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        // 与生成记录时的MD5计算方式保持一致
+        let md5_res = if is_image {
+            compute_full_file_md5(&path).await
+        } else {
+            compute_file_content_md5(&path).await
+        };
+        match md5_res {
+            Ok(md5_str) if md5_str == record.md5_str => {}
+            Ok(_) => continue,
+            Err(e) => {
+                log::warn!("计算文件MD5失败，跳过: {:?}, 错误: {}", path, e);
+                continue;
+            }
+        }
+
+        let absolute_path = to_safe_string(&path);
+        let filename = match record.content.as_str() {
+            Some(name) if !name.is_empty() => name.to_string(),
+            _ => path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        };
+        ClipRecord::update_after_cloud_download(rb, &record.id, &filename, &absolute_path)
+            .await
+            .map_err(|e| format!("更新记录失败: {}", e))?;
+
+        log::info!(
+            "已重新关联下载文件: record_id={}, path={}",
+            record.id,
+            absolute_path
+        );
+        let app_handle = CONTEXT.get::<AppHandle>();
+        let _ = app_handle.emit("clip_record_change", ());
+
+        return Ok(RelinkResult {
+            found: true,
+            path: Some(absolute_path),
+        });
+    }
+
+    log::info!("未找到与记录匹配的下载文件: record_id={}", record.id);
+    Ok(RelinkResult {
+        found: false,
+        path: None,
+    })
 }
 
 /// 标记下载记录为跳过同步状态
@@ -301,10 +652,12 @@ fn current_timestamp() -> u64 {
 }
 
 async fn download_cloud_file_to_local(
+    app_handle: &AppHandle,
     url: &str,
     cloud_file_name: &str,
     file_type: &str,
     record_id: &str,
+    md5_str: &str,
 ) -> AppResult<(String, String)> {
     // 确定保存路径 - 使用云端返回的原始文件名
     let save_path = determine_save_path_from_cloud(file_type, cloud_file_name)?;
@@ -316,11 +669,58 @@ async fn download_cloud_file_to_local(
         save_path
     );
 
-    // 使用http_client下载文件
-    http_client::download_file(url, &save_path)
+    // 使用http_client边下载边写入文件，并通知前端下载进度
+    let on_progress = |downloaded: u64, total: Option<u64>| {
+        let progress = DownloadProgress {
+            record_id: record_id.to_string(),
+            downloaded,
+            total,
+        };
+        if let Err(e) = app_handle.emit("download_progress", progress) {
+            log::debug!("Failed to emit download progress: {}", e);
+        }
+    };
+    // 先下载到按记录ID命名的部分文件，下载中断后重试时从已下载的位置继续
+    let partial_path = partial_download_path(record_id)?;
+    http_client::download_file_resumable(url, &partial_path, Some(&on_progress))
         .await
         .map_err(|e| AppError::Network(format!("File download failed: {}", e)))?;
 
+    // 与生成记录时的MD5计算方式保持一致，校验通过后才移动到保存位置
+    let md5_res = if file_type == ClipType::Image.to_string() {
+        compute_full_file_md5(&partial_path).await
+    } else {
+        compute_file_content_md5(&partial_path).await
+    };
+    match md5_res {
+        Ok(downloaded_md5) if downloaded_md5 == md5_str => {}
+        Ok(downloaded_md5) => {
+            http_client::remove_partial_file(&partial_path).await;
+            log::warn!(
+                "下载文件MD5不一致: record_id={}, 预期={}, 实际={}",
+                record_id,
+                md5_str,
+                downloaded_md5
+            );
+            return Err(AppError::General("下载失败: 文件MD5校验不一致".to_string()));
+        }
+        Err(e) => {
+            log::error!("计算下载文件MD5失败: {:?}, 错误: {}", partial_path, e);
+            return Err(AppError::Io(e));
+        }
+    }
+    tokio::fs::rename(&partial_path, &save_path)
+        .await
+        .map_err(|e| {
+            log::error!(
+                "移动下载完成的文件失败: {:?} -> {:?}, 错误: {}",
+                partial_path,
+                save_path,
+                e
+            );
+            AppError::Io(e)
+        })?;
+
     log::debug!(
         "Cloud file download completed: record_id={}, save_path={:?}",
         record_id,
@@ -343,6 +743,23 @@ async fn download_cloud_file_to_local(
     Ok((display_filename, absolute_path))
 }
 
+/// 记录对应的未下载完成的部分文件路径（resources/partial/<记录ID>.part）
+fn partial_download_path(record_id: &str) -> AppResult<PathBuf> {
+    let resources_dir = get_resources_dir()
+        .ok_or_else(|| AppError::Config("Failed to get resources directory".to_string()))?;
+    Ok(resources_dir
+        .join("partial")
+        .join(format!("{}.part", record_id)))
+}
+
+/// 删除记录对应的部分文件，下载最终失败时调用，避免残留
+async fn remove_partial_download(record_id: &str) {
+    let Ok(partial_path) = partial_download_path(record_id) else {
+        return;
+    };
+    http_client::remove_partial_file(&partial_path).await;
+}
+
 fn determine_save_path_from_cloud(file_type: &str, cloud_file_name: &str) -> AppResult<PathBuf> {
     let resources_dir = get_resources_dir()
         .ok_or_else(|| AppError::Config("Failed to get resources directory".to_string()))?;
@@ -429,6 +846,21 @@ mod tests {
         )));
     }
 
+    #[test]
+    fn test_create_export_file_appends_index() {
+        let export_dir = std::env::temp_dir().join(format!("clip_pal_export_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&export_dir).unwrap();
+
+        let (first, _) = create_export_file(&export_dir, "a.txt").unwrap();
+        let (second, _) = create_export_file(&export_dir, "a.txt").unwrap();
+        let (third, _) = create_export_file(&export_dir, "a.txt").unwrap();
+        assert_eq!(first, export_dir.join("a.txt"));
+        assert_eq!(second, export_dir.join("a (1).txt"));
+        assert_eq!(third, export_dir.join("a (2).txt"));
+
+        let _ = std::fs::remove_dir_all(&export_dir);
+    }
+
     #[test]
     fn test_current_timestamp() {
         let timestamp = current_timestamp();
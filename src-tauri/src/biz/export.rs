@@ -0,0 +1,742 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use clipboard_listener::ClipType;
+use rbatis::RBatis;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_dialog::DialogExt;
+use uuid::Uuid;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::{
+    biz::{
+        clip_record::{ClipRecord, SKIP_SYNC},
+        content_search::{add_content_to_index, searchable_text},
+        history_cursor::reset_history_cursor,
+        local_backup::{app_version, expected_table_columns, TempPath},
+        system_setting::ensure_not_kiosk_mode,
+    },
+    errors::{AppError, AppResult},
+    utils::{
+        aes_util::{
+            decrypt_raw_bytes_with_aad, encrypt_raw_bytes_with_aad, load_secret_key, KEY_SIZE,
+        },
+        device_info::GLOBAL_DEVICE_ID,
+        file_dir::get_resources_dir,
+        image_info::thumbnail_filename,
+        path_utils::to_safe_string,
+    },
+    window::{WindowHideFlag, WindowHideGuard},
+    CONTEXT,
+};
+
+// 导出文件格式版本，文件结构变化时递增
+// 2: 加密分块绑定分块序号和最后分块标记
+pub(crate) const EXPORT_FORMAT_VERSION: u32 = 2;
+// 从该版本开始加密分块绑定分块序号和最后分块标记
+const CHUNK_AAD_FORMAT_VERSION: u32 = 2;
+// 导出文件的文件头，用于识别 .clippal 文件
+pub(crate) const EXPORT_MAGIC: &[u8; 8] = b"CLIPPAL\0";
+pub(crate) const EXPORT_EXTENSION: &str = "clippal";
+
+// 加密前的归档内的文件名
+pub(crate) const MANIFEST_ENTRY: &str = "manifest.json";
+pub(crate) const RECORDS_ENTRY: &str = "records.jsonl";
+pub(crate) const RESOURCES_PREFIX: &str = "resources/";
+
+// 每批从数据库读取的记录数
+const EXPORT_BATCH_SIZE: usize = 200;
+// 每个加密分块的明文大小
+const ENCRYPT_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// 导出清单，记录导出时的应用版本和数据库结构，导入时据此迁移
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    pub created: u64,
+    // 表名 -> 列名
+    pub schema: HashMap<String, Vec<String>>,
+    pub record_count: u64,
+    pub resource_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportSummary {
+    pub path: String,
+    pub record_count: u64,
+    pub resource_count: usize,
+    pub size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportHistoryParam {
+    // .clippal 文件路径
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct ImportHistorySummary {
+    // 导入的记录数
+    pub imported: usize,
+    // 已存在相同内容或资源文件缺失而跳过的记录数
+    pub skipped: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ExportFinishedPayload {
+    // 导出成功时的结果
+    summary: Option<ExportSummary>,
+    // 导出失败时的错误信息
+    error: Option<String>,
+}
+
+fn current_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// 导出全部剪贴板历史：弹出保存对话框，选择路径后在后台导出
+/// 导出完成后发送 history_export_finished 事件
+#[tauri::command]
+pub async fn export_history() -> Result<(), String> {
+    let window_hide_flag = CONTEXT.get::<WindowHideFlag>();
+    // 用Arc包裹WindowHideGuard，延长生命周期到回调闭包
+    let guard = Arc::new(WindowHideGuard::new(window_hide_flag));
+    let app_handle = CONTEXT.get::<AppHandle>();
+    app_handle
+        .dialog()
+        .file()
+        .add_filter("ClipPal", &[EXPORT_EXTENSION])
+        .set_file_name(format!("clippal_{}.{}", current_millis(), EXPORT_EXTENSION))
+        .save_file(move |file_path| {
+            let _guard = guard;
+            let Some(path) = file_path.and_then(|p| p.as_path().map(Path::to_path_buf)) else {
+                return;
+            };
+            tauri::async_runtime::spawn(async move {
+                let payload = match do_export_history(&path).await {
+                    Ok(summary) => {
+                        log::info!(
+                            "导出剪贴板历史完成: {}, 记录数: {}, 资源文件数: {}",
+                            summary.path,
+                            summary.record_count,
+                            summary.resource_count
+                        );
+                        ExportFinishedPayload {
+                            summary: Some(summary),
+                            error: None,
+                        }
+                    }
+                    Err(e) => {
+                        log::error!("导出剪贴板历史失败: {}", e);
+                        ExportFinishedPayload {
+                            summary: None,
+                            error: Some(format!("导出剪贴板历史失败: {}", e)),
+                        }
+                    }
+                };
+                if let Some(app_handle) = CONTEXT.try_get::<AppHandle>() {
+                    let _ = app_handle.emit("history_export_finished", payload);
+                }
+            });
+        });
+    Ok(())
+}
+
+/// 收集记录引用的resources目录下的文件（相对路径）
+fn collect_record_resources(record: &ClipRecord, resources: &mut BTreeSet<String>) {
+    let content = record.content.as_str().unwrap_or_default();
+    if content.is_empty() {
+        return;
+    }
+    match record.r#type.as_str() {
+        x if x == ClipType::Image.to_string() => {
+            resources.insert(content.to_string());
+            // 同时导出缩略图，缩略图不存在时跳过
+            resources.insert(thumbnail_filename(content));
+        }
+        // 只导出复制到resources目录下的文件，不导出用户的原文件
+        x if x == ClipType::File.to_string() && content.starts_with("files/") => {
+            resources.insert(content.to_string());
+        }
+        _ => {}
+    }
+}
+
+/// 分批读取有效记录写入临时归档，再把归档分块加密写入目标文件
+async fn do_export_history(target_path: &Path) -> AppResult<ExportSummary> {
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let resources_dir =
+        get_resources_dir().ok_or_else(|| AppError::Config("无法获取资源目录".to_string()))?;
+
+    let temp_archive = TempPath(
+        std::env::temp_dir().join(format!("clip_pal_export_{}.zip", uuid::Uuid::new_v4())),
+    );
+    let mut zip = ZipWriter::new(BufWriter::new(File::create(&temp_archive.0)?));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let zip_err = |e: zip::result::ZipError| AppError::General(format!("写入导出归档失败: {}", e));
+
+    // 记录逐行写入，每次只在内存中保留一批
+    zip.start_file(RECORDS_ENTRY, options.large_file(true))
+        .map_err(zip_err)?;
+    let mut record_count = 0u64;
+    let mut resources = BTreeSet::new();
+    // 先取出导出开始时的记录ID快照，再按ID分批读取
+    // 导出期间仍在记录剪贴板，按偏移量分页时新记录会让后面的批次错位，导致漏导或重复导出
+    let ids = ClipRecord::select_active_ids(rb).await?;
+    for chunk in ids.chunks(EXPORT_BATCH_SIZE) {
+        let chunk = chunk.to_vec();
+        // 快照之后被删除的记录不再导出
+        let batch = ClipRecord::select_by_ids(rb, &chunk, chunk.len() as i32, 0).await?;
+        for record in &batch {
+            collect_record_resources(record, &mut resources);
+            serde_json::to_writer(&mut zip, record).map_err(|e| AppError::Serde(e.to_string()))?;
+            zip.write_all(b"\n")?;
+        }
+        record_count += batch.len() as u64;
+    }
+
+    let resources: Vec<(String, PathBuf)> = resources
+        .into_iter()
+        .map(|name| {
+            let path = resources_dir.join(&name);
+            (name, path)
+        })
+        .filter(|(_, path)| path.is_file())
+        .collect();
+    let manifest = ExportManifest {
+        format_version: EXPORT_FORMAT_VERSION,
+        app_version: app_version(),
+        created: current_millis(),
+        schema: expected_table_columns(),
+        record_count,
+        resource_count: resources.len(),
+    };
+
+    // 先写入临时文件，完成后再改名，避免留下不完整的导出文件
+    let partial_path = target_path.with_extension("partial");
+    let target = target_path.to_path_buf();
+    let resource_count = resources.len();
+    let size = tokio::task::spawn_blocking(move || -> AppResult<u64> {
+        for (name, path) in &resources {
+            zip.start_file(
+                format!("{}{}", RESOURCES_PREFIX, name),
+                options.large_file(std::fs::metadata(path)?.len() >= u32::MAX as u64),
+            )
+            .map_err(zip_err)?;
+            std::io::copy(&mut BufReader::new(File::open(path)?), &mut zip)?;
+        }
+        let manifest_json =
+            serde_json::to_vec_pretty(&manifest).map_err(|e| AppError::Serde(e.to_string()))?;
+        zip.start_file(MANIFEST_ENTRY, options).map_err(zip_err)?;
+        zip.write_all(&manifest_json)?;
+        zip.finish().map_err(zip_err)?.flush()?;
+
+        if let Err(e) = encrypt_archive(&temp_archive.0, &partial_path) {
+            let _ = std::fs::remove_file(&partial_path);
+            return Err(e);
+        }
+        std::fs::rename(&partial_path, &target)?;
+        Ok(std::fs::metadata(&target)?.len())
+    })
+    .await
+    .map_err(|e| AppError::General(format!("导出任务执行失败: {}", e)))??;
+
+    Ok(ExportSummary {
+        path: to_safe_string(target_path),
+        record_count,
+        resource_count,
+        size,
+    })
+}
+
+/// 把归档分块加密写入导出文件
+fn encrypt_archive(archive_path: &Path, output_path: &Path) -> AppResult<()> {
+    let key = load_secret_key()?;
+    let mut reader = BufReader::new(File::open(archive_path)?);
+    let mut writer = BufWriter::new(File::create(output_path)?);
+    write_encrypted(&mut reader, &mut writer, &key)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// 分块加密的附加认证数据：文件头 + 格式版本 + 分块序号 + 是否最后一个分块
+/// 分块被调换顺序、删除或文件被截断时，解密时的附加认证数据与加密时不一致，校验失败
+fn chunk_aad(format_version: u32, index: u64, last: bool) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(EXPORT_MAGIC.len() + 13);
+    aad.extend_from_slice(EXPORT_MAGIC);
+    aad.extend_from_slice(&format_version.to_le_bytes());
+    aad.extend_from_slice(&index.to_le_bytes());
+    aad.push(last as u8);
+    aad
+}
+
+/// 写入文件头并分块加密
+/// 文件结构：文件头 + 格式版本(u32) + 若干加密分块，每个分块为 长度(u32) + nonce + 密文
+/// 每个分块都绑定分块序号和是否最后一个分块，内容为空时也会写入一个空的最后分块
+fn write_encrypted(
+    reader: &mut impl Read,
+    writer: &mut impl Write,
+    key: &[u8; KEY_SIZE],
+) -> AppResult<()> {
+    writer.write_all(EXPORT_MAGIC)?;
+    writer.write_all(&EXPORT_FORMAT_VERSION.to_le_bytes())?;
+
+    // 预读下一个分块，用于判断当前分块是否为最后一个
+    let mut current = vec![0u8; ENCRYPT_CHUNK_SIZE];
+    let mut next = vec![0u8; ENCRYPT_CHUNK_SIZE];
+    let mut current_len = read_full(reader, &mut current)?;
+    let mut index = 0u64;
+    loop {
+        let next_len = if current_len < ENCRYPT_CHUNK_SIZE {
+            0
+        } else {
+            read_full(reader, &mut next)?
+        };
+        let last = next_len == 0;
+        let encrypted = encrypt_raw_bytes_with_aad(
+            key,
+            &current[..current_len],
+            &chunk_aad(EXPORT_FORMAT_VERSION, index, last),
+        )?;
+        writer.write_all(&(encrypted.len() as u32).to_le_bytes())?;
+        writer.write_all(&encrypted)?;
+        if last {
+            return Ok(());
+        }
+        std::mem::swap(&mut current, &mut next);
+        current_len = next_len;
+        index += 1;
+    }
+}
+
+/// 尽量读满缓冲区，返回读取的字节数，只有到达文件末尾时才会小于缓冲区大小
+fn read_full(reader: &mut impl Read, buffer: &mut [u8]) -> std::io::Result<usize> {
+    let mut total = 0;
+    while total < buffer.len() {
+        match reader.read(&mut buffer[total..])? {
+            0 => break,
+            n => total += n,
+        }
+    }
+    Ok(total)
+}
+
+/// 从 .clippal 文件导入剪贴板历史，已存在相同内容的记录跳过
+/// 导入的记录标记为跳过同步，由之后的同步决定是否上传
+#[tauri::command]
+pub async fn import_history(param: ImportHistoryParam) -> Result<ImportHistorySummary, String> {
+    ensure_not_kiosk_mode()?;
+    let path = PathBuf::from(&param.path);
+    if !path.is_file() {
+        return Err("导入文件不存在".to_string());
+    }
+
+    match do_import_history(&path).await {
+        Ok(summary) => {
+            log::info!(
+                "导入剪贴板历史完成, 导入: {}, 跳过: {}",
+                summary.imported,
+                summary.skipped
+            );
+            if summary.imported > 0 {
+                let _ = CONTEXT.get::<AppHandle>().emit("clip_record_change", ());
+            }
+            Ok(summary)
+        }
+        Err(e) => {
+            log::error!("导入剪贴板历史失败: {}", e);
+            Err(format!("导入剪贴板历史失败: {}", e))
+        }
+    }
+}
+
+/// 解密后的导出内容
+struct ExtractedExport {
+    manifest: ExportManifest,
+    records: Vec<ClipRecord>,
+    // 解压出的资源文件目录，文件按导出时的相对路径存放
+    resources: TempPath,
+}
+
+async fn do_import_history(path: &Path) -> AppResult<ImportHistorySummary> {
+    let resources_dir =
+        get_resources_dir().ok_or_else(|| AppError::Config("无法获取资源目录".to_string()))?;
+
+    let source = path.to_path_buf();
+    let extracted = tokio::task::spawn_blocking(move || extract_export(&source))
+        .await
+        .map_err(|e| AppError::General(format!("解密导入文件任务执行失败: {}", e)))??;
+    if extracted.manifest.app_version != app_version() {
+        log::info!(
+            "导入文件版本 {} 与当前版本 {} 不一致",
+            extracted.manifest.app_version,
+            app_version()
+        );
+    }
+
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let mut summary = ImportHistorySummary::default();
+    for snapshot in extracted.records {
+        let snapshot_id = snapshot.id.clone();
+        match import_record(rb, snapshot, &extracted.resources.0, &resources_dir).await {
+            Ok(true) => summary.imported += 1,
+            Ok(false) => summary.skipped += 1,
+            Err(e) => {
+                log::error!("导入记录失败: {}, 记录ID: {}", e, snapshot_id);
+                summary.skipped += 1;
+            }
+        }
+    }
+    if summary.imported > 0 {
+        reset_history_cursor();
+    }
+    Ok(summary)
+}
+
+/// 导入一条记录，返回是否导入，已存在相同内容或资源文件缺失时跳过
+async fn import_record(
+    rb: &RBatis,
+    snapshot: ClipRecord,
+    staged_dir: &Path,
+    resources_dir: &Path,
+) -> AppResult<bool> {
+    let existing =
+        ClipRecord::check_by_type_and_md5(rb, &snapshot.r#type, &snapshot.md5_str).await?;
+    if existing
+        .first()
+        .is_some_and(|record| record.del_flag.unwrap_or(0) == 0)
+    {
+        return Ok(false);
+    }
+
+    let mut record = ClipRecord {
+        id: Uuid::new_v4().to_string(),
+        local_file_path: None,
+        pinned_flag: 0,
+        sync_flag: Some(SKIP_SYNC),
+        sync_time: Some(0),
+        device_id: Some(GLOBAL_DEVICE_ID.clone()),
+        version: Some(1),
+        del_flag: Some(0),
+        cloud_source: Some(0),
+        skip_type: None,
+        del_time: None,
+        slot: None,
+        meta_only_sync: None,
+        modified_time: None,
+        ..snapshot
+    };
+
+    // 资源文件使用新的文件名复制到resources目录，避免与已有文件冲突
+    let restored = match restore_resource(&mut record, staged_dir, resources_dir)? {
+        Some(restored) => restored,
+        None if is_resource_record(&record) => {
+            log::warn!("导入文件中缺少记录的资源文件，跳过记录: {}", record.id);
+            return Ok(false);
+        }
+        None => vec![],
+    };
+
+    if let Err(e) = ClipRecord::insert_by_created_sort(rb, record.clone()).await {
+        for path in restored {
+            let _ = std::fs::remove_file(path);
+        }
+        return Err(e);
+    }
+
+    if let Some(content) = searchable_text(&record) {
+        if let Err(e) = add_content_to_index(&record.id, &content).await {
+            log::error!("添加导入记录到搜索索引失败: {}", e);
+        }
+    }
+    Ok(true)
+}
+
+/// 记录是否引用resources目录下的资源文件
+fn is_resource_record(record: &ClipRecord) -> bool {
+    let content = record.content.as_str().unwrap_or_default();
+    !content.is_empty()
+        && (record.r#type == ClipType::Image.to_string()
+            || (record.r#type == ClipType::File.to_string() && content.starts_with("files/")))
+}
+
+/// 把解压出的资源文件复制到resources目录并更新记录中的路径，返回复制的文件路径
+/// 记录没有资源文件或导入文件中缺少资源文件时返回None
+fn restore_resource(
+    record: &mut ClipRecord,
+    staged_dir: &Path,
+    resources_dir: &Path,
+) -> AppResult<Option<Vec<PathBuf>>> {
+    if !is_resource_record(record) {
+        return Ok(None);
+    }
+    let original = record.content.as_str().unwrap_or_default().to_string();
+    let source = staged_dir.join(&original);
+    if !source.is_file() {
+        return Ok(None);
+    }
+
+    let file_name = Path::new(&original)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let new_name = format!("{}_{}", Uuid::new_v4(), file_name);
+    let is_file = original.starts_with("files/");
+    let relative_path = if is_file {
+        std::fs::create_dir_all(resources_dir.join("files"))?;
+        format!("files/{}", new_name)
+    } else {
+        new_name
+    };
+    let target = resources_dir.join(&relative_path);
+    std::fs::copy(&source, &target)?;
+    let mut restored = vec![target.clone()];
+
+    // 缩略图缺失时不影响记录，读取缩略图时会重新生成
+    let thumbnail = staged_dir.join(thumbnail_filename(&original));
+    if !is_file && thumbnail.is_file() {
+        let thumbnail_target = resources_dir.join(thumbnail_filename(&relative_path));
+        if std::fs::copy(&thumbnail, &thumbnail_target).is_ok() {
+            restored.push(thumbnail_target);
+        }
+    }
+
+    record.content = serde_json::Value::String(relative_path);
+    if is_file {
+        record.local_file_path = Some(target.to_string_lossy().to_string());
+    }
+    Ok(Some(restored))
+}
+
+/// 校验文件头并解密导出文件，读取清单和记录，资源文件解压到临时目录
+fn extract_export(path: &Path) -> AppResult<ExtractedExport> {
+    let temp_archive =
+        TempPath(std::env::temp_dir().join(format!("clip_pal_import_{}.zip", Uuid::new_v4())));
+    decrypt_archive(path, &temp_archive.0)?;
+
+    let zip_err = |e: zip::result::ZipError| AppError::General(format!("读取导入文件失败: {}", e));
+    let mut archive =
+        ZipArchive::new(BufReader::new(File::open(&temp_archive.0)?)).map_err(zip_err)?;
+
+    let manifest: ExportManifest = {
+        let entry = archive
+            .by_name(MANIFEST_ENTRY)
+            .map_err(|_| AppError::General("导入文件缺少清单".to_string()))?;
+        serde_json::from_reader(entry)
+            .map_err(|e| AppError::Serde(format!("导入文件清单格式错误: {}", e)))?
+    };
+    if manifest.format_version > EXPORT_FORMAT_VERSION {
+        return Err(AppError::General(format!(
+            "导入文件由更新版本({})创建，请升级后再导入",
+            manifest.app_version
+        )));
+    }
+
+    // 旧版本导出的记录缺少的可选字段按None处理
+    let mut records = Vec::new();
+    {
+        let entry = archive
+            .by_name(RECORDS_ENTRY)
+            .map_err(|_| AppError::General("导入文件缺少记录数据".to_string()))?;
+        for line in BufReader::new(entry).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<ClipRecord>(&line) {
+                Ok(record) => records.push(record),
+                Err(e) => log::warn!("导入文件中的记录格式错误，已跳过: {}", e),
+            }
+        }
+    }
+
+    let resources =
+        TempPath(std::env::temp_dir().join(format!("clip_pal_import_{}", Uuid::new_v4())));
+    std::fs::create_dir_all(&resources.0)?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(zip_err)?;
+        if entry.is_dir() {
+            continue;
+        }
+        // enclosed_name 会拒绝包含 .. 或绝对路径的条目
+        let Some(relative) = entry
+            .enclosed_name()
+            .and_then(|name| name.strip_prefix(RESOURCES_PREFIX).ok().map(PathBuf::from))
+        else {
+            continue;
+        };
+        let target = resources.0.join(relative);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::io::copy(&mut entry, &mut BufWriter::new(File::create(&target)?))?;
+    }
+
+    Ok(ExtractedExport {
+        manifest,
+        records,
+        resources,
+    })
+}
+
+/// 校验文件头并把加密分块逐块解密还原为归档
+fn decrypt_archive(path: &Path, output_path: &Path) -> AppResult<()> {
+    let key = load_secret_key()?;
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut writer = BufWriter::new(File::create(output_path)?);
+    read_encrypted(&mut reader, &mut writer, &key)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// 校验文件头并逐块解密
+/// 旧版本(1)导出的分块没有绑定分块序号，按原方式解密
+fn read_encrypted(
+    reader: &mut impl Read,
+    writer: &mut impl Write,
+    key: &[u8; KEY_SIZE],
+) -> AppResult<()> {
+    let mut header = [0u8; 12];
+    if read_full(reader, &mut header)? < header.len() || &header[..8] != EXPORT_MAGIC {
+        return Err(AppError::General("不是有效的ClipPal导出文件".to_string()));
+    }
+    let format_version = u32::from_le_bytes([header[8], header[9], header[10], header[11]]);
+    if format_version > EXPORT_FORMAT_VERSION {
+        return Err(AppError::General(
+            "导入文件由更新版本创建，请升级后再导入".to_string(),
+        ));
+    }
+    let bind_chunks = format_version >= CHUNK_AAD_FORMAT_VERSION;
+
+    let mut next = read_chunk(reader)?;
+    if next.is_none() && bind_chunks {
+        return Err(AppError::General("导入文件已损坏".to_string()));
+    }
+    let mut index = 0u64;
+    while let Some(chunk) = next {
+        // 预读下一个分块，没有下一个分块时当前分块必须是加密时的最后一个分块
+        next = read_chunk(reader)?;
+        let aad = if bind_chunks {
+            chunk_aad(format_version, index, next.is_none())
+        } else {
+            Vec::new()
+        };
+        let plaintext = decrypt_raw_bytes_with_aad(key, &chunk, &aad).map_err(|e| {
+            log::warn!("解密导入文件分块失败: {}, 分块序号: {}", e, index);
+            AppError::General("导入文件已损坏或被篡改".to_string())
+        })?;
+        writer.write_all(&plaintext)?;
+        index += 1;
+    }
+    Ok(())
+}
+
+/// 读取一个加密分块，到达文件末尾时返回None，分块不完整时返回错误
+fn read_chunk(reader: &mut impl Read) -> AppResult<Option<Vec<u8>>> {
+    let mut length = [0u8; 4];
+    match read_full(reader, &mut length)? {
+        0 => return Ok(None),
+        4 => {}
+        _ => return Err(AppError::General("导入文件已损坏".to_string())),
+    }
+    // 加密分块不会超过明文分块大小加上nonce和认证标签，超过时说明文件已损坏
+    let chunk_len = u32::from_le_bytes(length) as usize;
+    if chunk_len > ENCRYPT_CHUNK_SIZE + 64 {
+        return Err(AppError::General("导入文件已损坏".to_string()));
+    }
+    let mut chunk = vec![0u8; chunk_len];
+    if read_full(reader, &mut chunk)? < chunk.len() {
+        return Err(AppError::General("导入文件已损坏".to_string()));
+    }
+    Ok(Some(chunk))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const TEST_KEY: [u8; KEY_SIZE] = [7u8; KEY_SIZE];
+
+    fn encrypt(data: &[u8]) -> Vec<u8> {
+        let mut output = Vec::new();
+        write_encrypted(&mut Cursor::new(data), &mut output, &TEST_KEY).unwrap();
+        output
+    }
+
+    fn decrypt(data: &[u8]) -> AppResult<Vec<u8>> {
+        let mut output = Vec::new();
+        read_encrypted(&mut Cursor::new(data), &mut output, &TEST_KEY)?;
+        Ok(output)
+    }
+
+    /// 按分块切分加密后的文件，返回每个分块（含长度前缀）的起止位置
+    fn chunk_ranges(encrypted: &[u8]) -> Vec<(usize, usize)> {
+        let mut ranges = Vec::new();
+        let mut pos = 12;
+        while pos < encrypted.len() {
+            let len = u32::from_le_bytes(encrypted[pos..pos + 4].try_into().unwrap()) as usize;
+            ranges.push((pos, pos + 4 + len));
+            pos += 4 + len;
+        }
+        ranges
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_round_trip() {
+        let data: Vec<u8> = (0..ENCRYPT_CHUNK_SIZE * 2 + 123)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        let encrypted = encrypt(&data);
+        assert_eq!(chunk_ranges(&encrypted).len(), 3);
+        assert_eq!(decrypt(&encrypted).unwrap(), data);
+
+        // 内容为空或正好是分块大小的整数倍时同样可以还原
+        assert_eq!(decrypt(&encrypt(b"")).unwrap(), b"");
+        let exact = vec![1u8; ENCRYPT_CHUNK_SIZE];
+        assert_eq!(decrypt(&encrypt(&exact)).unwrap(), exact);
+    }
+
+    #[test]
+    fn test_decrypt_rejects_truncated_file() {
+        let data = vec![3u8; ENCRYPT_CHUNK_SIZE * 2 + 10];
+        let encrypted = encrypt(&data);
+        let ranges = chunk_ranges(&encrypted);
+
+        // 在分块边界截断，去掉最后一个分块
+        assert!(decrypt(&encrypted[..ranges[2].0]).is_err());
+        // 只剩文件头
+        assert!(decrypt(&encrypted[..12]).is_err());
+        // 在分块中间截断
+        assert!(decrypt(&encrypted[..encrypted.len() - 5]).is_err());
+    }
+
+    #[test]
+    fn test_decrypt_rejects_reordered_chunks() {
+        let data: Vec<u8> = (0..ENCRYPT_CHUNK_SIZE * 2 + 10)
+            .map(|i| (i % 13) as u8)
+            .collect();
+        let encrypted = encrypt(&data);
+        let ranges = chunk_ranges(&encrypted);
+
+        let mut reordered = encrypted[..12].to_vec();
+        reordered.extend_from_slice(&encrypted[ranges[1].0..ranges[1].1]);
+        reordered.extend_from_slice(&encrypted[ranges[0].0..ranges[0].1]);
+        reordered.extend_from_slice(&encrypted[ranges[2].0..ranges[2].1]);
+        assert!(decrypt(&reordered).is_err());
+
+        // 在最后一个分块后追加分块
+        let mut appended = encrypted.clone();
+        appended.extend_from_slice(&encrypted[ranges[0].0..ranges[0].1]);
+        assert!(decrypt(&appended).is_err());
+    }
+}
@@ -0,0 +1,172 @@
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use rbatis::RBatis;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    biz::{
+        clip_record::ClipRecord,
+        copy_clip_record::{copy_clip_record, copy_clip_record_no_paste, CopyClipRecord},
+        system_setting::Settings,
+    },
+    utils::lock_utils::lock_utils::safe_read_lock,
+    CONTEXT,
+};
+
+// 游标导航时最多快照的记录数
+static NAV_SNAPSHOT_SIZE: i32 = 100;
+
+// 超过这个时间没有导航操作，下次导航时重新生成快照
+static NAV_IDLE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// 历史记录导航游标
+/// 导航开始时对列表顺序做快照，避免复制后记录排序变化导致游标错位
+#[derive(Debug, Default)]
+pub struct HistoryCursor {
+    state: Mutex<CursorState>,
+}
+
+#[derive(Debug, Default)]
+struct CursorState {
+    // 列表顺序的记录ID快照（下标0为列表最上方的记录）
+    ids: Vec<String>,
+    // 当前位置，None表示还没有开始导航
+    index: Option<usize>,
+    last_used: Option<Instant>,
+}
+
+impl HistoryCursor {
+    /// 重置游标，下次导航时从列表最上方重新开始
+    pub fn reset(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            *state = CursorState::default();
+        }
+    }
+}
+
+/// 重置历史记录导航游标（新增或删除记录时调用）
+pub fn reset_history_cursor() {
+    if let Some(cursor) = CONTEXT.try_get::<HistoryCursor>() {
+        cursor.reset();
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct HistoryNavParam {
+    // 是否自动粘贴（仍受自动粘贴设置控制）
+    pub auto_paste: Option<bool>,
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct HistoryNavResult {
+    // 本次复制的记录ID，没有可复制的记录时为None
+    pub record_id: Option<String>,
+    // 当前位置（下标0为列表最上方的记录）
+    pub index: Option<usize>,
+    pub total: usize,
+    // 是否已经到达边界且没有移动
+    pub at_boundary: bool,
+}
+
+/// 复制列表中上一条记录（向列表上方移动）
+#[tauri::command]
+pub async fn copy_prev(param: HistoryNavParam) -> Result<HistoryNavResult, String> {
+    navigate(-1, param.auto_paste.unwrap_or(false)).await
+}
+
+/// 复制列表中下一条记录（向列表下方移动）
+#[tauri::command]
+pub async fn copy_next(param: HistoryNavParam) -> Result<HistoryNavResult, String> {
+    navigate(1, param.auto_paste.unwrap_or(false)).await
+}
+
+async fn navigate(step: i64, auto_paste: bool) -> Result<HistoryNavResult, String> {
+    let wrap = {
+        let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+        safe_read_lock(&settings_lock)
+            .map(|settings| settings.history_nav_wrap.unwrap_or(0) == 1)
+            .unwrap_or(false)
+    };
+
+    let cursor = CONTEXT.get::<HistoryCursor>();
+    let need_snapshot = {
+        let state = cursor.state.lock().map_err(|e| e.to_string())?;
+        state.ids.is_empty()
+            || state
+                .last_used
+                .map(|t| t.elapsed() > NAV_IDLE_TIMEOUT)
+                .unwrap_or(true)
+    };
+
+    if need_snapshot {
+        let rb: &RBatis = CONTEXT.get::<RBatis>();
+        let ids: Vec<String> = ClipRecord::select_order_by_limit(rb, NAV_SNAPSHOT_SIZE, 0)
+            .await
+            .map_err(|e| format!("查询记录失败: {}", e))?
+            .into_iter()
+            .map(|record| record.id)
+            .collect();
+        let mut state = cursor.state.lock().map_err(|e| e.to_string())?;
+        *state = CursorState {
+            ids,
+            index: None,
+            last_used: None,
+        };
+    }
+
+    let (record_id, index, total, at_boundary) = {
+        let mut state = cursor.state.lock().map_err(|e| e.to_string())?;
+        let total = state.ids.len();
+        if total == 0 {
+            return Ok(HistoryNavResult {
+                record_id: None,
+                index: None,
+                total,
+                at_boundary: true,
+            });
+        }
+
+        // 第一次导航时复制列表最上方的记录
+        let target = match state.index {
+            None => Some(0),
+            Some(current) => {
+                let next = current as i64 + step;
+                if (0..total as i64).contains(&next) {
+                    Some(next as usize)
+                } else if wrap {
+                    Some(next.rem_euclid(total as i64) as usize)
+                } else {
+                    None
+                }
+            }
+        };
+        state.last_used = Some(Instant::now());
+
+        match target {
+            Some(index) => {
+                state.index = Some(index);
+                (Some(state.ids[index].clone()), Some(index), total, false)
+            }
+            None => (None, state.index, total, true),
+        }
+    };
+
+    if let Some(id) = &record_id {
+        let param = CopyClipRecord {
+            record_id: id.clone(),
+        };
+        if auto_paste {
+            copy_clip_record(param).await?;
+        } else {
+            copy_clip_record_no_paste(param).await?;
+        }
+    }
+
+    Ok(HistoryNavResult {
+        record_id,
+        index,
+        total,
+        at_boundary,
+    })
+}
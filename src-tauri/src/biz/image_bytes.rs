@@ -0,0 +1,121 @@
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use clipboard_listener::ClipType;
+use once_cell::sync::Lazy;
+use rbatis::RBatis;
+use serde::{Deserialize, Serialize};
+use tauri::ipc::Response;
+
+use crate::{
+    biz::{clip_record::ClipRecord, query_clip_record::select_viewable_record},
+    utils::file_dir::get_resources_dir,
+    CONTEXT,
+};
+
+// 单张图片允许返回的最大字节数，超过时由前端改用 get_image_path 的 asset 协议加载
+pub const MAX_IMAGE_BYTES: u64 = 50 * 1024 * 1024;
+
+// 缓存的有效期，详情页反复打开同一张图片时不重复读取文件
+static IMAGE_CACHE_TTL: Duration = Duration::from_secs(60);
+
+// 缓存的最大条目数和总大小
+static IMAGE_CACHE_CAPACITY: usize = 8;
+static IMAGE_CACHE_MAX_BYTES: usize = 128 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetImageBytesParam {
+    pub record_id: String,
+    // 是否允许查看敏感记录的图片，默认不允许
+    #[serde(default)]
+    pub reveal_sensitive: bool,
+}
+
+struct CachedImage {
+    record_id: String,
+    bytes: Arc<Vec<u8>>,
+    cached_at: Instant,
+}
+
+/// 最近读取的图片内容，队尾为最近使用
+static IMAGE_CACHE: Lazy<Mutex<VecDeque<CachedImage>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+
+fn cache_get(record_id: &str) -> Option<Arc<Vec<u8>>> {
+    let mut cache = IMAGE_CACHE.lock().ok()?;
+    cache.retain(|item| item.cached_at.elapsed() < IMAGE_CACHE_TTL);
+    let pos = cache.iter().position(|item| item.record_id == record_id)?;
+    let item = cache.remove(pos)?;
+    let bytes = item.bytes.clone();
+    cache.push_back(item);
+    Some(bytes)
+}
+
+fn cache_insert(record_id: &str, bytes: Arc<Vec<u8>>) {
+    let Ok(mut cache) = IMAGE_CACHE.lock() else {
+        return;
+    };
+    cache.retain(|item| item.record_id != record_id);
+    cache.push_back(CachedImage {
+        record_id: record_id.to_string(),
+        bytes,
+        cached_at: Instant::now(),
+    });
+    // 超出容量时淘汰最久未使用的图片
+    while cache.len() > IMAGE_CACHE_CAPACITY
+        || cache.iter().map(|item| item.bytes.len()).sum::<usize>() > IMAGE_CACHE_MAX_BYTES
+    {
+        if cache.pop_front().is_none() {
+            break;
+        }
+    }
+}
+
+/// 查找图片记录对应的文件，云端下载的图片优先使用 local_file_path
+pub(crate) fn resolve_image_file(record: &ClipRecord) -> Option<PathBuf> {
+    if let Some(local_path) = &record.local_file_path {
+        let path = PathBuf::from(local_path);
+        if path.exists() {
+            return Some(path);
+        }
+    }
+    let filename = record.content.as_str()?;
+    let path = get_resources_dir()?.join(filename);
+    path.exists().then_some(path)
+}
+
+/// 获取图片的原始字节，以二进制形式返回给前端，避免base64编码带来的体积膨胀
+/// 图片超过 MAX_IMAGE_BYTES 时返回错误
+#[tauri::command]
+pub async fn get_image_bytes(param: GetImageBytesParam) -> Result<Response, String> {
+    // 先检查记录，已删除、隐藏或未确认查看的敏感记录即使有缓存也不返回
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let record = select_viewable_record(rb, &param.record_id, param.reveal_sensitive).await?;
+    if record.r#type != ClipType::Image.to_string() {
+        return Err("记录不是图片类型".to_string());
+    }
+    if let Some(bytes) = cache_get(&param.record_id) {
+        return Ok(Response::new(bytes.as_ref().clone()));
+    }
+    let path = resolve_image_file(&record).ok_or("图片文件不存在")?;
+
+    let size = std::fs::metadata(&path)
+        .map_err(|e| format!("读取图片信息失败: {}", e))?
+        .len();
+    if size > MAX_IMAGE_BYTES {
+        return Err(format!(
+            "图片过大（{:.1} MB），超过{} MB的上限",
+            size as f64 / 1024.0 / 1024.0,
+            MAX_IMAGE_BYTES / 1024 / 1024
+        ));
+    }
+
+    let bytes = tokio::fs::read(&path).await.map_err(|e| {
+        log::error!("读取图片文件失败: {}, 路径: {}", e, path.display());
+        "读取图片文件失败".to_string()
+    })?;
+    let bytes = Arc::new(bytes);
+    cache_insert(&param.record_id, bytes.clone());
+    Ok(Response::new(bytes.as_ref().clone()))
+}
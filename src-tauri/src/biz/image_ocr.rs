@@ -0,0 +1,124 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use once_cell::sync::Lazy;
+use rbatis::RBatis;
+
+use crate::{
+    biz::{clip_record::ClipRecord, content_search::add_content_to_index},
+    errors::AppResult,
+    utils::{aes_util::encrypt_content, file_dir::get_resources_dir},
+    CONTEXT,
+};
+
+/// 图片文字识别引擎，识别在阻塞线程中执行，实现可以直接调用同步接口
+pub trait OcrEngine: Send + Sync {
+    /// 识别图片中的文字，没有文字时返回空字符串
+    fn recognize(&self, image_path: &Path) -> AppResult<String>;
+}
+
+// 当前使用的OCR引擎，未启用 ClipPal_ocr 特性时为None，不做识别
+static OCR_ENGINE: Lazy<Option<Arc<dyn OcrEngine>>> = Lazy::new(default_engine);
+
+#[cfg(feature = "ClipPal_ocr")]
+fn default_engine() -> Option<Arc<dyn OcrEngine>> {
+    Some(Arc::new(TesseractEngine))
+}
+
+#[cfg(not(feature = "ClipPal_ocr"))]
+fn default_engine() -> Option<Arc<dyn OcrEngine>> {
+    None
+}
+
+fn current_engine() -> Option<Arc<dyn OcrEngine>> {
+    OCR_ENGINE.clone()
+}
+
+/// 基于 tesseract 的OCR引擎，需要系统安装 tesseract 及对应语言包
+#[cfg(feature = "ClipPal_ocr")]
+struct TesseractEngine;
+
+#[cfg(feature = "ClipPal_ocr")]
+impl TesseractEngine {
+    // 优先同时识别中英文，缺少中文语言包时退回只识别英文
+    const LANGUAGES: [&'static str; 2] = ["chi_sim+eng", "eng"];
+}
+
+#[cfg(feature = "ClipPal_ocr")]
+impl OcrEngine for TesseractEngine {
+    fn recognize(&self, image_path: &Path) -> AppResult<String> {
+        use crate::errors::AppError;
+        use crate::utils::path_utils::to_safe_string;
+
+        let image_path = to_safe_string(image_path);
+        let mut last_error = String::new();
+        for language in Self::LANGUAGES {
+            let engine = match tesseract::Tesseract::new(None, Some(language)) {
+                Ok(engine) => engine,
+                Err(e) => {
+                    last_error = e.to_string();
+                    continue;
+                }
+            };
+            let mut engine = engine
+                .set_image(&image_path)
+                .map_err(|e| AppError::General(format!("OCR加载图片失败: {}", e)))?;
+            return engine
+                .get_text()
+                .map_err(|e| AppError::General(format!("OCR识别失败: {}", e)));
+        }
+        Err(AppError::General(format!(
+            "OCR引擎初始化失败: {}",
+            last_error
+        )))
+    }
+}
+
+/// 在后台识别新保存图片中的文字，识别结果加密保存到 ocr_text 并加入搜索索引
+/// 识别在独立任务中执行，不阻塞剪贴板记录流程
+pub fn spawn_image_ocr(record_id: String, filename: String) {
+    let Some(engine) = current_engine() else {
+        return;
+    };
+
+    tokio::spawn(async move {
+        let Some(image_path) = get_resources_dir().map(|dir| dir.join(&filename)) else {
+            return;
+        };
+        let text = match tokio::task::spawn_blocking(move || engine.recognize(&image_path)).await {
+            Ok(Ok(text)) => text.trim().to_string(),
+            Ok(Err(e)) => {
+                log::warn!("图片文字识别失败 - ID: {}, 错误: {}", record_id, e);
+                return;
+            }
+            Err(e) => {
+                log::error!("图片文字识别任务异常 - ID: {}, 错误: {}", record_id, e);
+                return;
+            }
+        };
+        if text.is_empty() {
+            return;
+        }
+
+        let encrypted = match encrypt_content(&text) {
+            Ok(encrypted) => encrypted,
+            Err(e) => {
+                log::error!("加密OCR文字失败 - ID: {}, 错误: {}", record_id, e);
+                return;
+            }
+        };
+        let rb: &RBatis = CONTEXT.get::<RBatis>();
+        if let Err(e) = ClipRecord::update_ocr_text(rb, &record_id, &encrypted).await {
+            log::error!("保存OCR文字失败 - ID: {}, 错误: {}", record_id, e);
+            return;
+        }
+        if let Err(e) = add_content_to_index(&record_id, &text).await {
+            log::error!("搜索索引更新失败: {}", e);
+        }
+        log::debug!(
+            "图片文字识别完成 - ID: {}, 文字长度: {}",
+            record_id,
+            text.len()
+        );
+    });
+}
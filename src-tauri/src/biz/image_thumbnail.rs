@@ -0,0 +1,142 @@
+use std::path::{Path, PathBuf};
+
+use base64::{engine::general_purpose, Engine as _};
+use clipboard_listener::ClipType;
+use rbatis::RBatis;
+
+use crate::{
+    biz::{
+        clip_record::ClipRecord, image_bytes::resolve_image_file,
+        query_clip_record::select_viewable_record,
+    },
+    utils::{file_dir::get_resources_dir, image_info::thumbnail_filename},
+    CONTEXT,
+};
+
+// 缩略图的最大宽高（像素），列表中显示缩略图，预览时显示原图
+pub const THUMBNAIL_MAX_SIZE: u32 = 256;
+
+/// 解码图片并缩小到 THUMBNAIL_MAX_SIZE 以内后编码为png，GIF动图只取第一帧
+/// 图片本身不超过最大宽高时不放大
+pub fn build_thumbnail_png(data: &[u8]) -> Option<Vec<u8>> {
+    let image = match image::load_from_memory(data) {
+        Ok(image) => image,
+        Err(e) => {
+            log::warn!("解码图片生成缩略图失败: {}", e);
+            return None;
+        }
+    };
+    let image = if image.width() > THUMBNAIL_MAX_SIZE || image.height() > THUMBNAIL_MAX_SIZE {
+        image.thumbnail(THUMBNAIL_MAX_SIZE, THUMBNAIL_MAX_SIZE)
+    } else {
+        image
+    };
+    let mut png = std::io::Cursor::new(Vec::new());
+    if let Err(e) = image.write_to(&mut png, image::ImageFormat::Png) {
+        log::warn!("缩略图编码为png失败: {}", e);
+        return None;
+    }
+    Some(png.into_inner())
+}
+
+/// 读取原图生成缩略图并保存到指定路径，返回缩略图内容
+fn generate_thumbnail(source: &Path, target: &Path) -> Option<Vec<u8>> {
+    let data = match std::fs::read(source) {
+        Ok(data) => data,
+        Err(e) => {
+            log::warn!("读取图片生成缩略图失败: {}, 路径: {}", e, source.display());
+            return None;
+        }
+    };
+    let thumbnail = build_thumbnail_png(&data)?;
+    // 缩略图保存失败不影响本次返回，下次读取时重新生成
+    if let Err(e) = std::fs::write(target, &thumbnail) {
+        log::warn!("保存缩略图失败: {}, 路径: {}", e, target.display());
+    }
+    Some(thumbnail)
+}
+
+/// 在后台为新保存的图片生成缩略图，不阻塞剪贴板记录流程
+pub fn spawn_thumbnail_generation(filename: String) {
+    tokio::spawn(async move {
+        let Some(resources_dir) = get_resources_dir() else {
+            return;
+        };
+        let source = resources_dir.join(&filename);
+        let target = resources_dir.join(thumbnail_filename(&filename));
+        let result =
+            tokio::task::spawn_blocking(move || generate_thumbnail(&source, &target)).await;
+        match result {
+            Ok(Some(_)) => log::debug!("缩略图生成完成: {}", filename),
+            Ok(None) => {}
+            Err(e) => log::error!("缩略图生成任务异常: {}, 文件名: {}", e, filename),
+        }
+    });
+}
+
+/// 获取图片记录的原图路径和缩略图路径，缩略图与原图同名（后缀为 .thumb.png）保存在resources目录
+fn thumbnail_paths(record: &ClipRecord) -> Option<(PathBuf, PathBuf)> {
+    let filename = record.content.as_str()?;
+    let thumbnail = get_resources_dir()?.join(thumbnail_filename(filename));
+    let source = resolve_image_file(record)?;
+    Some((source, thumbnail))
+}
+
+/// 获取图片记录的缩略图（png格式，base64编码），用于历史记录列表显示
+/// 缩略图不存在时（如旧记录）根据原图生成并保存
+/// 已删除、隐藏的记录不返回，敏感记录只有 reveal_sensitive 为 true 时才返回
+#[tauri::command]
+pub async fn get_thumbnail_base64(
+    record_id: String,
+    reveal_sensitive: Option<bool>,
+) -> Result<String, String> {
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let record = select_viewable_record(rb, &record_id, reveal_sensitive.unwrap_or(false)).await?;
+    if record.r#type != ClipType::Image.to_string() {
+        return Err("记录不是图片类型".to_string());
+    }
+    let (source, thumbnail) = thumbnail_paths(&record).ok_or("图片文件不存在")?;
+
+    let bytes = if thumbnail.is_file() {
+        tokio::fs::read(&thumbnail).await.map_err(|e| {
+            log::error!("读取缩略图失败: {}, 路径: {}", e, thumbnail.display());
+            "读取缩略图失败".to_string()
+        })?
+    } else {
+        tokio::task::spawn_blocking(move || generate_thumbnail(&source, &thumbnail))
+            .await
+            .map_err(|e| {
+                log::error!("缩略图生成任务异常: {}", e);
+                "生成缩略图失败".to_string()
+            })?
+            .ok_or("生成缩略图失败")?
+    };
+    Ok(general_purpose::STANDARD.encode(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_png(width: u32, height: u32) -> Vec<u8> {
+        let image = image::DynamicImage::ImageRgba8(image::RgbaImage::new(width, height));
+        let mut png = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut png, image::ImageFormat::Png).unwrap();
+        png.into_inner()
+    }
+
+    #[test]
+    fn test_build_thumbnail_png_scales_down() {
+        let thumbnail = build_thumbnail_png(&encode_png(1024, 512)).unwrap();
+        let image = image::load_from_memory(&thumbnail).unwrap();
+        assert_eq!((image.width(), image.height()), (256, 128));
+    }
+
+    #[test]
+    fn test_build_thumbnail_png_keeps_small_image() {
+        let thumbnail = build_thumbnail_png(&encode_png(100, 40)).unwrap();
+        let image = image::load_from_memory(&thumbnail).unwrap();
+        assert_eq!((image.width(), image.height()), (100, 40));
+        assert!(build_thumbnail_png(b"not an image").is_none());
+    }
+}
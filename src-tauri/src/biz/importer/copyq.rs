@@ -0,0 +1,65 @@
+use std::path::Path;
+
+use base64::{engine::general_purpose, Engine as _};
+use serde::Deserialize;
+
+use crate::{
+    biz::importer::{file_url_to_path, ImportedEntry, ImportedItem},
+    errors::AppResult,
+};
+
+// CopyQ自身的数据文件是压缩后的QDataStream格式，这里导入由CopyQ脚本导出的JSON文件：
+//
+//   copyq eval "var r=[]; for (var i=size()-1; i>=0; --i) { var it=getItem(i); r.push({
+//     text: str(it['text/plain'] || ''), uri_list: str(it['text/uri-list'] || ''),
+//     png: it['image/png'] ? toBase64(it['image/png']) : '' }) } JSON.stringify(r)" > copyq.json
+//
+// 导出结果按从旧到新排列
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct CopyQItem {
+    text: String,
+    uri_list: String,
+    // base64编码的PNG数据
+    png: String,
+}
+
+/// 读取CopyQ导出的JSON文件中的历史记录，CopyQ不保存复制时间，使用导入时的时间
+pub(crate) async fn read_items(path: &Path) -> AppResult<Vec<ImportedEntry>> {
+    let content = tokio::fs::read_to_string(path).await?;
+    let entries: Vec<CopyQItem> = serde_json::from_str(&content)?;
+    Ok(entries
+        .into_iter()
+        .map(|entry| ImportedEntry {
+            item: to_item(entry),
+            created: None,
+        })
+        .collect())
+}
+
+/// 优先级：文件 > 图片 > 文本
+fn to_item(entry: CopyQItem) -> ImportedItem {
+    let paths: Vec<String> = entry
+        .uri_list
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(file_url_to_path)
+        .collect();
+    if !paths.is_empty() {
+        return ImportedItem::Files(paths);
+    }
+    if !entry.png.is_empty() {
+        return match general_purpose::STANDARD.decode(entry.png.trim()) {
+            Ok(data) => ImportedItem::Image(data),
+            Err(e) => {
+                log::warn!("CopyQ图片数据解码失败: {}", e);
+                ImportedItem::Unsupported
+            }
+        };
+    }
+    if !entry.text.is_empty() {
+        return ImportedItem::Text(entry.text);
+    }
+    ImportedItem::Unsupported
+}
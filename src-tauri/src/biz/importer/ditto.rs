@@ -0,0 +1,205 @@
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::{
+    biz::importer::{open_external_sqlite, value_to_bytes, ImportedEntry, ImportedItem},
+    errors::AppResult,
+};
+
+// Ditto数据库中剪贴板格式名称
+const FORMAT_UNICODE_TEXT: &str = "CF_UNICODETEXT";
+const FORMAT_FILES: &str = "CF_HDROP";
+const FORMAT_PNG: &str = "PNG";
+
+// DROPFILES结构体长度（pFiles, pt.x, pt.y, fNC, fWide）
+const DROPFILES_HEADER_LEN: usize = 20;
+
+#[derive(Debug, Deserialize)]
+struct DittoRow {
+    #[serde(rename = "lID")]
+    id: i64,
+    #[serde(rename = "mText")]
+    text: Option<String>,
+    // 复制时间（Unix时间戳，秒）
+    #[serde(rename = "lDate")]
+    date: Option<i64>,
+    #[serde(rename = "strClipBoardFormat")]
+    format: Option<String>,
+    #[serde(rename = "ooData")]
+    data: Option<rbs::Value>,
+}
+
+/// 读取Ditto数据库（Ditto.db）中的历史记录
+pub(crate) async fn read_items(path: &Path) -> AppResult<Vec<ImportedEntry>> {
+    let (rb, _copy) = open_external_sqlite(path).await?;
+
+    // 每条记录可能对应多种剪贴板格式，按时间从旧到新读取
+    let rows: Vec<DittoRow> = rb
+        .query_decode(
+            "SELECT m.lID, m.mText, m.lDate, d.strClipBoardFormat, d.ooData FROM Main m \
+             LEFT JOIN Data d ON d.lParentID = m.lID \
+             WHERE m.bIsGroup = 0 ORDER BY m.lDate ASC, m.lID ASC",
+            vec![],
+        )
+        .await?;
+
+    let mut items = Vec::new();
+    let mut current: Option<(i64, Vec<DittoRow>)> = None;
+    for row in rows {
+        match &mut current {
+            Some((id, group)) if *id == row.id => group.push(row),
+            _ => {
+                if let Some((_, group)) = current.take() {
+                    items.push(to_entry(group));
+                }
+                current = Some((row.id, vec![row]));
+            }
+        }
+    }
+    if let Some((_, group)) = current {
+        items.push(to_entry(group));
+    }
+
+    Ok(items)
+}
+
+/// 把同一条记录转换为导入条目，使用Ditto保存的复制时间
+fn to_entry(rows: Vec<DittoRow>) -> ImportedEntry {
+    let created = rows
+        .first()
+        .and_then(|row| row.date)
+        .filter(|date| *date > 0)
+        .map(|date| date as u64 * 1000);
+    ImportedEntry {
+        item: to_item(rows),
+        created,
+    }
+}
+
+/// 把同一条记录的多种格式转换为导入内容，优先级：文件 > 图片 > 文本
+fn to_item(rows: Vec<DittoRow>) -> ImportedItem {
+    let find = |format: &str| {
+        rows.iter()
+            .find(|row| row.format.as_deref() == Some(format))
+            .and_then(|row| row.data.as_ref())
+            .and_then(value_to_bytes)
+    };
+
+    if let Some(paths) = find(FORMAT_FILES).and_then(|data| parse_hdrop(&data)) {
+        return ImportedItem::Files(paths);
+    }
+    if let Some(data) = find(FORMAT_PNG) {
+        return ImportedItem::Image(data);
+    }
+    if let Some(text) = find(FORMAT_UNICODE_TEXT).map(|data| decode_utf16le(&data)) {
+        return ImportedItem::Text(text);
+    }
+    // 没有可识别的数据时使用Ditto保存的文本描述，但图片等格式的描述不是真实内容
+    match rows.first().and_then(|row| row.text.clone()) {
+        Some(text) if rows.iter().all(|row| row.format.is_none()) => ImportedItem::Text(text),
+        _ => ImportedItem::Unsupported,
+    }
+}
+
+/// 解码以\0结尾的UTF-16LE文本
+fn decode_utf16le(data: &[u8]) -> String {
+    let units: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|unit| *unit != 0)
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+/// 解析CF_HDROP数据（DROPFILES结构体 + 以双\0结尾的文件路径列表）
+fn parse_hdrop(data: &[u8]) -> Option<Vec<String>> {
+    if data.len() < DROPFILES_HEADER_LEN {
+        return None;
+    }
+    let offset = u32::from_le_bytes(data[0..4].try_into().ok()?) as usize;
+    let wide = u32::from_le_bytes(data[16..20].try_into().ok()?) != 0;
+    let list = data.get(offset..)?;
+
+    let paths: Vec<String> = if wide {
+        let units: Vec<u16> = list
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        units
+            .split(|unit| *unit == 0)
+            .take_while(|path| !path.is_empty())
+            .map(String::from_utf16_lossy)
+            .collect()
+    } else {
+        list.split(|byte| *byte == 0)
+            .take_while(|path| !path.is_empty())
+            .map(|path| String::from_utf8_lossy(path).into_owned())
+            .collect()
+    };
+
+    if paths.is_empty() {
+        None
+    } else {
+        Some(paths)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hdrop(paths: &[&str], wide: bool) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&(DROPFILES_HEADER_LEN as u32).to_le_bytes());
+        data.extend_from_slice(&[0u8; 12]);
+        data.extend_from_slice(&(wide as u32).to_le_bytes());
+        for path in paths {
+            if wide {
+                for unit in path.encode_utf16().chain(std::iter::once(0)) {
+                    data.extend_from_slice(&unit.to_le_bytes());
+                }
+            } else {
+                data.extend_from_slice(path.as_bytes());
+                data.push(0);
+            }
+        }
+        if wide {
+            data.extend_from_slice(&[0, 0]);
+        } else {
+            data.push(0);
+        }
+        data
+    }
+
+    #[test]
+    fn test_parse_hdrop_wide() {
+        let data = hdrop(&["C:\\a.txt", "D:\\文档\\b.png"], true);
+        assert_eq!(
+            parse_hdrop(&data),
+            Some(vec!["C:\\a.txt".to_string(), "D:\\文档\\b.png".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_parse_hdrop_ansi() {
+        let data = hdrop(&["C:\\a.txt"], false);
+        assert_eq!(parse_hdrop(&data), Some(vec!["C:\\a.txt".to_string()]));
+    }
+
+    #[test]
+    fn test_parse_hdrop_invalid() {
+        assert_eq!(parse_hdrop(&[0u8; 4]), None);
+        assert_eq!(parse_hdrop(&hdrop(&[], true)), None);
+    }
+
+    #[test]
+    fn test_decode_utf16le() {
+        let mut data: Vec<u8> = "你好 ditto"
+            .encode_utf16()
+            .flat_map(|unit| unit.to_le_bytes())
+            .collect();
+        data.extend_from_slice(&[0, 0, 0x41, 0]);
+        assert_eq!(decode_utf16le(&data), "你好 ditto");
+    }
+}
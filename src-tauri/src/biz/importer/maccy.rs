@@ -0,0 +1,105 @@
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::{
+    biz::importer::{
+        file_url_to_path, open_external_sqlite, value_to_bytes, ImportedEntry, ImportedItem,
+    },
+    errors::AppResult,
+};
+
+// Maccy数据库中保存的内容类型（UTI）
+const TYPE_TEXT: &str = "public.utf8-plain-text";
+const TYPE_PNG: &str = "public.png";
+const TYPE_FILE_URL: &str = "public.file-url";
+
+// Core Data时间戳的起点（2001-01-01）与Unix时间戳起点相差的秒数
+const CORE_DATA_EPOCH_OFFSET_SECS: f64 = 978_307_200.0;
+
+#[derive(Debug, Deserialize)]
+struct MaccyRow {
+    #[serde(rename = "Z_PK")]
+    id: i64,
+    // 最后一次复制的时间（Core Data时间戳，秒）
+    #[serde(rename = "ZLASTCOPIEDAT")]
+    last_copied_at: Option<f64>,
+    #[serde(rename = "ZTYPE")]
+    content_type: Option<String>,
+    #[serde(rename = "ZVALUE")]
+    value: Option<rbs::Value>,
+}
+
+/// 读取Maccy数据库（Storage.sqlite）中的历史记录
+pub(crate) async fn read_items(path: &Path) -> AppResult<Vec<ImportedEntry>> {
+    let (rb, _copy) = open_external_sqlite(path).await?;
+
+    // 每条记录可能对应多种内容类型，与Maccy列表的顺序一致，按最后复制时间从旧到新读取
+    let rows: Vec<MaccyRow> = rb
+        .query_decode(
+            "SELECT i.Z_PK, i.ZLASTCOPIEDAT, c.ZTYPE, c.ZVALUE FROM ZHISTORYITEM i \
+             LEFT JOIN ZHISTORYITEMCONTENT c ON c.ZITEM = i.Z_PK \
+             ORDER BY i.ZLASTCOPIEDAT ASC, i.Z_PK ASC",
+            vec![],
+        )
+        .await?;
+
+    let mut items = Vec::new();
+    let mut current: Option<(i64, Vec<MaccyRow>)> = None;
+    for row in rows {
+        match &mut current {
+            Some((id, group)) if *id == row.id => group.push(row),
+            _ => {
+                if let Some((_, group)) = current.take() {
+                    items.push(to_entry(group));
+                }
+                current = Some((row.id, vec![row]));
+            }
+        }
+    }
+    if let Some((_, group)) = current {
+        items.push(to_entry(group));
+    }
+
+    Ok(items)
+}
+
+/// 把同一条记录转换为导入条目，使用Maccy保存的最后复制时间
+fn to_entry(rows: Vec<MaccyRow>) -> ImportedEntry {
+    let created = rows
+        .first()
+        .and_then(|row| row.last_copied_at)
+        .map(|secs| (secs + CORE_DATA_EPOCH_OFFSET_SECS) * 1000.0)
+        .filter(|millis| *millis > 0.0)
+        .map(|millis| millis as u64);
+    ImportedEntry {
+        item: to_item(rows),
+        created,
+    }
+}
+
+/// 把同一条记录的多种内容转换为导入内容，优先级：文件 > 图片 > 文本
+fn to_item(rows: Vec<MaccyRow>) -> ImportedItem {
+    let find_all = |content_type: &str| -> Vec<Vec<u8>> {
+        rows.iter()
+            .filter(|row| row.content_type.as_deref() == Some(content_type))
+            .filter_map(|row| row.value.as_ref().and_then(value_to_bytes))
+            .collect()
+    };
+
+    // 复制多个文件时每个文件各有一条file-url内容
+    let paths: Vec<String> = find_all(TYPE_FILE_URL)
+        .iter()
+        .filter_map(|data| file_url_to_path(&String::from_utf8_lossy(data)))
+        .collect();
+    if !paths.is_empty() {
+        return ImportedItem::Files(paths);
+    }
+    if let Some(data) = find_all(TYPE_PNG).into_iter().next() {
+        return ImportedItem::Image(data);
+    }
+    if let Some(data) = find_all(TYPE_TEXT).into_iter().next() {
+        return ImportedItem::Text(String::from_utf8_lossy(&data).into_owned());
+    }
+    ImportedItem::Unsupported
+}
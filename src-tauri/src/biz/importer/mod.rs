@@ -0,0 +1,179 @@
+mod copyq;
+mod ditto;
+mod maccy;
+
+use std::path::{Path, PathBuf};
+
+use rbatis::RBatis;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::{
+    biz::{
+        clip_record::ClipRecord,
+        clip_record_clean::try_clean_clip_record,
+        clip_record_sync::{handle_file, handle_image, handle_text, with_importing},
+        preview_cache::clear_preview_cache,
+        system_setting::ensure_not_kiosk_mode,
+    },
+    errors::{AppError, AppResult},
+    utils::path_utils::to_safe_string,
+    CONTEXT,
+};
+
+/// 支持导入的剪贴板管理器
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum ImportSource {
+    Ditto,
+    Maccy,
+    CopyQ,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportParam {
+    pub source: ImportSource,
+    // Ditto/Maccy为数据库文件路径，CopyQ为导出的JSON文件路径
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct ImportSummary {
+    // 读取到的条目数
+    pub total: usize,
+    pub imported: usize,
+    // 与已有记录重复或内容为空
+    pub skipped_duplicate: usize,
+    // 不支持的格式（例如非PNG图片）
+    pub skipped_unsupported: usize,
+    pub failed: usize,
+}
+
+/// 从其他剪贴板管理器读取出的条目及其复制时间
+pub(crate) struct ImportedEntry {
+    pub item: ImportedItem,
+    // 在原软件中的复制时间（毫秒），原软件没有保存时间时为None，使用导入时的时间
+    pub created: Option<u64>,
+}
+
+/// 从其他剪贴板管理器读取出的内容
+pub(crate) enum ImportedItem {
+    Text(String),
+    // PNG图片数据
+    Image(Vec<u8>),
+    Files(Vec<String>),
+    Unsupported,
+}
+
+/// 从其他剪贴板管理器导入历史记录
+#[tauri::command]
+pub async fn import_from(param: ImportParam) -> Result<ImportSummary, String> {
+    ensure_not_kiosk_mode()?;
+    let path = PathBuf::from(&param.path);
+    if !path.is_file() {
+        return Err("导入文件不存在".to_string());
+    }
+
+    // 条目按从旧到新排列，依次写入后最新的记录排在最前面
+    let entries = match param.source {
+        ImportSource::Ditto => ditto::read_items(&path).await,
+        ImportSource::Maccy => maccy::read_items(&path).await,
+        ImportSource::CopyQ => copyq::read_items(&path).await,
+    }
+    .map_err(|e| {
+        log::error!("读取{:?}历史记录失败: {}", param.source, e);
+        format!("读取历史记录失败: {}", e)
+    })?;
+
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let mut summary = ImportSummary {
+        total: entries.len(),
+        ..Default::default()
+    };
+
+    for entry in entries {
+        let sort = ClipRecord::get_next_sort(rb).await;
+        // 与已有记录内容相同时直接跳过，已有记录的位置不变
+        let result = match entry.item {
+            ImportedItem::Text(text) => with_importing(handle_text(rb, &text, sort)).await,
+            ImportedItem::Image(data) => {
+                with_importing(handle_image(rb, Some(&data), None, sort)).await
+            }
+            ImportedItem::Files(paths) => with_importing(handle_file(rb, Some(&paths), sort)).await,
+            ImportedItem::Unsupported => {
+                summary.skipped_unsupported += 1;
+                continue;
+            }
+        };
+        match result {
+            Ok(Some(record)) => {
+                if let Some(created) = entry.created {
+                    if let Err(e) = ClipRecord::update_created(rb, &record.id, created).await {
+                        log::warn!("保存导入记录的复制时间失败: {}, 记录ID: {}", e, record.id);
+                    }
+                }
+                summary.imported += 1;
+            }
+            Ok(None) => summary.skipped_duplicate += 1,
+            Err(e) => {
+                log::warn!("导入记录失败: {}", e);
+                summary.failed += 1;
+            }
+        }
+    }
+
+    log::info!("从{:?}导入历史记录完成: {:?}", param.source, summary);
+
+    if summary.imported > 0 {
+        // 导入的记录可能复用了已删除记录的ID，清空缓存的内容
+        clear_preview_cache();
+        tokio::spawn(async {
+            try_clean_clip_record().await;
+        });
+        let app_handle = CONTEXT.get::<AppHandle>();
+        let _ = app_handle.emit("clip_record_change", ());
+    }
+
+    Ok(summary)
+}
+
+/// 以副本方式打开外部SQLite数据库，避免占用或修改其他软件正在使用的数据库
+pub(crate) async fn open_external_sqlite(path: &Path) -> AppResult<(RBatis, TempDbCopy)> {
+    let copy_path =
+        std::env::temp_dir().join(format!("clip_pal_import_{}.db", uuid::Uuid::new_v4()));
+    std::fs::copy(path, &copy_path)?;
+    let copy = TempDbCopy(copy_path);
+
+    let rb = RBatis::new();
+    rb.init(
+        rbdc_sqlite::Driver {},
+        &format!("sqlite://{}", to_safe_string(&copy.0)),
+    )
+    .map_err(AppError::Database)?;
+    Ok((rb, copy))
+}
+
+/// 外部数据库的临时副本，离开作用域时自动删除
+pub(crate) struct TempDbCopy(PathBuf);
+
+impl Drop for TempDbCopy {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// 从rbs::Value中取出二进制数据（兼容以文本方式保存的数据）
+pub(crate) fn value_to_bytes(value: &rbs::Value) -> Option<Vec<u8>> {
+    match value {
+        rbs::Value::Binary(bytes) => Some(bytes.clone()),
+        rbs::Value::String(text) => Some(text.as_bytes().to_vec()),
+        _ => None,
+    }
+}
+
+/// 把file://链接转换为本地路径
+pub(crate) fn file_url_to_path(url: &str) -> Option<String> {
+    let path = url.trim().strip_prefix("file://")?;
+    // 去掉主机名部分（file://localhost/path）
+    let path = path.strip_prefix("localhost").unwrap_or(path);
+    urlencoding::decode(path).ok().map(|p| p.into_owned())
+}
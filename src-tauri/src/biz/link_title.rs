@@ -0,0 +1,245 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use once_cell::sync::Lazy;
+use rbatis::RBatis;
+use regex::Regex;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_http::reqwest::Url;
+
+use crate::{
+    biz::{clip_record::ClipRecord, system_setting::check_link_title_enabled},
+    utils::{http_client::HttpClient, rich_text::html_to_text},
+    CONTEXT,
+};
+
+// 获取网页标题的超时时间（秒），标题只是辅助信息，超时后放弃
+const TITLE_FETCH_TIMEOUT_SECS: u64 = 3;
+// 最多读取的网页内容字节数，<title> 一般位于 <head> 开头
+const TITLE_FETCH_MAX_BYTES: usize = 64 * 1024;
+// 保存的标题最大字符数
+const MAX_TITLE_CHARS: usize = 200;
+
+static TITLE_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?is)<title\b[^>]*>(.*?)</title>").expect("网页标题正则无效"));
+
+/// 判断文本是否为单个 http(s) 网址，是时返回网址
+pub fn detect_single_url(text: &str) -> Option<&str> {
+    let text = text.trim();
+    if text.is_empty() || text.contains(char::is_whitespace) {
+        return None;
+    }
+    let url = Url::parse(text).ok()?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return None;
+    }
+    Some(text)
+}
+
+/// 判断IPv4地址是否为公网地址，回环、私有、链路本地、共享（100.64.0.0/10）、测试、文档、广播、组播等地址均不是
+fn is_public_ipv4(ip: &Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        || a == 0
+        || a >= 240
+        || (a == 100 && (64..128).contains(&b))
+        || (a == 198 && (b == 18 || b == 19)))
+}
+
+/// 判断IP地址是否为公网地址，IPv6的唯一本地（fc00::/7）、链路本地（fe80::/10）、文档地址也不是
+/// IPv4映射的IPv6地址按IPv4判断
+pub fn is_public_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => {
+            if let Some(ipv4) = ip.to_ipv4_mapped() {
+                return is_public_ipv4(&ipv4);
+            }
+            let first = ip.segments()[0];
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_multicast()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80
+                || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+        }
+    }
+}
+
+/// 解析网址的域名，所有解析结果都是公网地址时返回其中一个，用于固定请求的地址
+/// 避免获取标题时访问本机或内网服务，解析失败或含非公网地址时返回None
+async fn resolve_public_addr(url: &Url) -> Option<(String, SocketAddr)> {
+    let host = url
+        .host_str()?
+        .trim_start_matches('[')
+        .trim_end_matches(']');
+    let port = url.port_or_known_default()?;
+    let addrs: Vec<SocketAddr> = match tokio::net::lookup_host((host, port)).await {
+        Ok(addrs) => addrs.collect(),
+        Err(e) => {
+            log::debug!("解析网址域名失败: {}, 域名: {}", e, host);
+            return None;
+        }
+    };
+    if addrs.is_empty() || addrs.iter().any(|addr| !is_public_ip(&addr.ip())) {
+        return None;
+    }
+    Some((host.to_string(), addrs[0]))
+}
+
+/// 从网页HTML中提取 <title> 内容，解码实体并合并空白，没有标题时返回None
+pub fn extract_html_title(html: &str) -> Option<String> {
+    let inner = TITLE_PATTERN.captures(html)?.get(1)?.as_str();
+    let title = html_to_text(inner)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if title.is_empty() {
+        return None;
+    }
+    Some(title.chars().take(MAX_TITLE_CHARS).collect())
+}
+
+/// 在后台获取网址记录的网页标题并保存到 title，获取成功后通知前端刷新
+/// 在独立任务中执行，不阻塞剪贴板记录流程，也不影响记录内容和MD5去重
+/// 只请求公网地址且不跟随重定向，本机、内网等地址的网址不获取标题
+pub fn spawn_link_title_fetch(record_id: String, url: String) {
+    if !check_link_title_enabled() {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let Ok(parsed_url) = Url::parse(&url) else {
+            return;
+        };
+        let Some((host, addr)) = resolve_public_addr(&parsed_url).await else {
+            log::debug!("网址不是公网地址，不获取网页标题 - ID: {}", record_id);
+            return;
+        };
+        // 固定使用已检查的地址，避免请求时重新解析到内网地址
+        let client = HttpClient::new()
+            .timeout(TITLE_FETCH_TIMEOUT_SECS)
+            .max_retries(0)
+            .follow_redirects(false)
+            .resolve(&host, addr);
+        let html = match client.get_text(&url, TITLE_FETCH_MAX_BYTES).await {
+            Ok(html) => html,
+            Err(e) => {
+                log::debug!("获取网页标题失败 - ID: {}, 错误: {}", record_id, e);
+                return;
+            }
+        };
+        let Some(title) = extract_html_title(&html) else {
+            log::debug!("网页没有标题 - ID: {}", record_id);
+            return;
+        };
+
+        let rb: &RBatis = CONTEXT.get::<RBatis>();
+        if let Err(e) = ClipRecord::update_title(rb, &record_id, &title).await {
+            log::error!("保存网页标题失败 - ID: {}, 错误: {}", record_id, e);
+            return;
+        }
+        let app_handle = CONTEXT.get::<AppHandle>();
+        let _ = app_handle.emit("clip_record_change", ());
+        log::debug!("网页标题获取完成 - ID: {}", record_id);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_single_url() {
+        assert_eq!(
+            detect_single_url("  https://example.com/a?b=1  "),
+            Some("https://example.com/a?b=1")
+        );
+        assert_eq!(
+            detect_single_url("http://localhost:8080"),
+            Some("http://localhost:8080")
+        );
+        assert_eq!(detect_single_url("see https://example.com"), None);
+        assert_eq!(detect_single_url("https://a.com\nhttps://b.com"), None);
+        assert_eq!(detect_single_url("ftp://example.com"), None);
+        assert_eq!(detect_single_url("example.com"), None);
+        assert_eq!(detect_single_url(""), None);
+    }
+
+    #[test]
+    fn test_is_public_ip() {
+        let public = ["93.184.216.34", "8.8.8.8", "2606:4700::1111"];
+        for ip in public {
+            assert!(is_public_ip(&ip.parse().unwrap()), "{}", ip);
+        }
+        let non_public = [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "255.255.255.255",
+            "224.0.0.1",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "::ffff:192.168.0.1",
+        ];
+        for ip in non_public {
+            assert!(!is_public_ip(&ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_public_addr_rejects_local_hosts() {
+        for url in [
+            "http://localhost:8080",
+            "http://127.0.0.1/",
+            "http://[::1]/",
+            "http://192.168.1.1/admin",
+        ] {
+            assert!(
+                resolve_public_addr(&Url::parse(url).unwrap())
+                    .await
+                    .is_none(),
+                "{}",
+                url
+            );
+        }
+    }
+
+    #[test]
+    fn test_extract_html_title() {
+        assert_eq!(
+            extract_html_title("<html><head><TITLE lang=\"en\">\n  Rust &amp; Tauri\n  Docs </TITLE></head></html>"),
+            Some("Rust & Tauri Docs".to_string())
+        );
+        assert_eq!(
+            extract_html_title("<title>&#20320;&#22909;</title>"),
+            Some("你好".to_string())
+        );
+        assert_eq!(extract_html_title("<title>   </title>"), None);
+        assert_eq!(
+            extract_html_title("<html><body>no title</body></html>"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_extract_html_title_truncates() {
+        let html = format!("<title>{}</title>", "a".repeat(MAX_TITLE_CHARS + 10));
+        assert_eq!(
+            extract_html_title(&html).map(|title| title.chars().count()),
+            Some(MAX_TITLE_CHARS)
+        );
+    }
+}
@@ -0,0 +1,429 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rbatis::RBatis;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::{
+    biz::{
+        clip_record::ClipRecord, clip_record_sync::set_capture_paused,
+        content_search::initialize_search_index, history_cursor::reset_history_cursor,
+        preview_cache::clear_preview_cache, system_setting::ensure_not_kiosk_mode,
+    },
+    errors::{AppError, AppResult},
+    sqlite_storage::{check_and_fix_database_schema, get_expected_schema},
+    utils::{file_dir::get_resources_dir, lock_utils::GlobalSyncLock, path_utils::to_safe_string},
+    CONTEXT,
+};
+
+// 备份归档格式版本，归档结构变化时递增
+const BACKUP_FORMAT_VERSION: u32 = 1;
+
+// 归档内的文件名
+const MANIFEST_ENTRY: &str = "manifest.json";
+const DATABASE_ENTRY: &str = "clip_record.db";
+const RESOURCES_PREFIX: &str = "resources/";
+
+/// 备份清单，记录备份时的应用版本和数据库结构
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    pub created: u64,
+    // 表名 -> 列名
+    pub schema: HashMap<String, Vec<String>>,
+    pub record_count: u64,
+    pub resource_count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupParam {
+    // 备份归档文件路径
+    pub path: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BackupSummary {
+    pub path: String,
+    pub app_version: String,
+    pub record_count: u64,
+    pub resource_count: usize,
+    pub size: u64,
+}
+
+/// 临时文件或目录，离开作用域时自动删除
+pub(crate) struct TempPath(pub(crate) PathBuf);
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        if self.0.is_dir() {
+            let _ = std::fs::remove_dir_all(&self.0);
+        } else {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+}
+
+fn current_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+pub(crate) fn app_version() -> String {
+    CONTEXT
+        .get::<AppHandle>()
+        .package_info()
+        .version
+        .to_string()
+}
+
+/// 当前代码期望的数据库结构（表名 -> 列名）
+pub(crate) fn expected_table_columns() -> HashMap<String, Vec<String>> {
+    get_expected_schema()
+        .into_iter()
+        .map(|(name, table)| {
+            let columns = table.columns.into_iter().map(|c| c.name).collect();
+            (name, columns)
+        })
+        .collect()
+}
+
+/// 递归收集目录下的所有文件，返回（相对路径, 绝对路径）
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<(String, PathBuf)>) -> AppResult<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(root, &path, files)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            // 归档内统一使用 / 作为分隔符
+            let name = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.push((name, path));
+        }
+    }
+    Ok(())
+}
+
+/// 把数据库快照、resources目录和清单写入归档
+fn write_archive(
+    archive_path: &Path,
+    db_snapshot: &Path,
+    resources: &[(String, PathBuf)],
+    manifest: &BackupManifest,
+) -> AppResult<()> {
+    let file = File::create(archive_path)?;
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let zip_err = |e: zip::result::ZipError| AppError::General(format!("写入备份归档失败: {}", e));
+
+    let manifest_json =
+        serde_json::to_vec_pretty(manifest).map_err(|e| AppError::Serde(e.to_string()))?;
+    zip.start_file(MANIFEST_ENTRY, options).map_err(zip_err)?;
+    zip.write_all(&manifest_json)?;
+
+    let db_size = std::fs::metadata(db_snapshot)?.len();
+    zip.start_file(
+        DATABASE_ENTRY,
+        options.large_file(db_size >= u32::MAX as u64),
+    )
+    .map_err(zip_err)?;
+    std::io::copy(&mut BufReader::new(File::open(db_snapshot)?), &mut zip)?;
+
+    for (name, path) in resources {
+        let size = std::fs::metadata(path)?.len();
+        zip.start_file(
+            format!("{}{}", RESOURCES_PREFIX, name),
+            options.large_file(size >= u32::MAX as u64),
+        )
+        .map_err(zip_err)?;
+        std::io::copy(&mut BufReader::new(File::open(path)?), &mut zip)?;
+    }
+
+    zip.finish().map_err(zip_err)?.flush()?;
+    Ok(())
+}
+
+/// 创建本地备份：数据库一致性快照（VACUUM INTO）、resources目录和清单打包为一个归档
+#[tauri::command]
+pub async fn create_backup(param: BackupParam) -> Result<BackupSummary, String> {
+    let archive_path = PathBuf::from(&param.path);
+    match do_create_backup(&archive_path).await {
+        Ok(summary) => {
+            log::info!(
+                "本地备份完成: {}, 记录数: {}, 资源文件数: {}",
+                summary.path,
+                summary.record_count,
+                summary.resource_count
+            );
+            Ok(summary)
+        }
+        Err(e) => {
+            log::error!("创建本地备份失败: {}", e);
+            Err(format!("创建本地备份失败: {}", e))
+        }
+    }
+}
+
+async fn do_create_backup(archive_path: &Path) -> AppResult<BackupSummary> {
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+
+    // VACUUM INTO 生成的快照与写入并发时依然一致
+    let snapshot =
+        TempPath(std::env::temp_dir().join(format!("clip_pal_backup_{}.db", uuid::Uuid::new_v4())));
+    rb.exec(
+        "VACUUM INTO ?",
+        vec![rbs::Value::String(to_safe_string(&snapshot.0))],
+    )
+    .await?;
+
+    let record_count = ClipRecord::count_effective(rb).await.max(0) as u64;
+
+    let resources_dir =
+        get_resources_dir().ok_or_else(|| AppError::Config("无法获取资源目录".to_string()))?;
+    let mut resources = Vec::new();
+    collect_files(&resources_dir, &resources_dir, &mut resources)?;
+
+    let manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        app_version: app_version(),
+        created: current_millis(),
+        schema: expected_table_columns(),
+        record_count,
+        resource_count: resources.len(),
+    };
+
+    // 先写入临时文件，完成后再改名，避免留下不完整的归档
+    let temp_archive = archive_path.with_extension("partial");
+    let target_path = archive_path.to_path_buf();
+    let resource_count = resources.len();
+    let app_version = manifest.app_version.clone();
+    let size = tokio::task::spawn_blocking(move || -> AppResult<u64> {
+        if let Err(e) = write_archive(&temp_archive, &snapshot.0, &resources, &manifest) {
+            let _ = std::fs::remove_file(&temp_archive);
+            return Err(e);
+        }
+        std::fs::rename(&temp_archive, &target_path)?;
+        Ok(std::fs::metadata(&target_path)?.len())
+    })
+    .await
+    .map_err(|e| AppError::General(format!("备份任务执行失败: {}", e)))??;
+
+    Ok(BackupSummary {
+        path: to_safe_string(archive_path),
+        app_version,
+        record_count,
+        resource_count,
+        size,
+    })
+}
+
+/// 解压后的备份内容
+struct ExtractedBackup {
+    manifest: BackupManifest,
+    database: TempPath,
+    resources: TempPath,
+    resource_count: usize,
+}
+
+/// 校验并解压备份归档，资源文件解压到resources目录旁的临时目录，便于恢复时直接改名替换
+fn extract_archive(archive_path: &Path, resources_dir: &Path) -> AppResult<ExtractedBackup> {
+    let zip_err = |e: zip::result::ZipError| AppError::General(format!("读取备份归档失败: {}", e));
+    let mut archive =
+        ZipArchive::new(BufReader::new(File::open(archive_path)?)).map_err(zip_err)?;
+
+    let manifest: BackupManifest = {
+        let mut entry = archive
+            .by_name(MANIFEST_ENTRY)
+            .map_err(|_| AppError::General("备份归档缺少清单文件".to_string()))?;
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content)?;
+        serde_json::from_slice(&content)
+            .map_err(|e| AppError::Serde(format!("备份清单格式错误: {}", e)))?
+    };
+    if manifest.format_version > BACKUP_FORMAT_VERSION {
+        return Err(AppError::General(format!(
+            "备份由更新版本({})创建，请升级后再恢复",
+            manifest.app_version
+        )));
+    }
+
+    let database = TempPath(
+        std::env::temp_dir().join(format!("clip_pal_restore_{}.db", uuid::Uuid::new_v4())),
+    );
+    {
+        let mut entry = archive
+            .by_name(DATABASE_ENTRY)
+            .map_err(|_| AppError::General("备份归档缺少数据库文件".to_string()))?;
+        std::io::copy(&mut entry, &mut BufWriter::new(File::create(&database.0)?))?;
+    }
+
+    let resources = TempPath(
+        resources_dir.with_file_name(format!("resources_restore_{}", uuid::Uuid::new_v4())),
+    );
+    std::fs::create_dir_all(&resources.0)?;
+    let mut resource_count = 0;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(zip_err)?;
+        if entry.is_dir() {
+            continue;
+        }
+        // enclosed_name 会拒绝包含 .. 或绝对路径的条目
+        let Some(relative) = entry
+            .enclosed_name()
+            .and_then(|name| name.strip_prefix(RESOURCES_PREFIX).ok().map(PathBuf::from))
+        else {
+            continue;
+        };
+        let target = resources.0.join(relative);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::io::copy(&mut entry, &mut BufWriter::new(File::create(&target)?))?;
+        resource_count += 1;
+    }
+
+    Ok(ExtractedBackup {
+        manifest,
+        database,
+        resources,
+        resource_count,
+    })
+}
+
+/// 把备份数据库中的数据整体替换到当前数据库
+async fn replace_database(rb: &RBatis, backup_db: &Path) -> AppResult<()> {
+    let conn = rb.acquire().await?;
+    conn.exec(
+        "ATTACH DATABASE ? AS backup",
+        vec![rbs::Value::String(to_safe_string(backup_db))],
+    )
+    .await?;
+
+    let result = async {
+        conn.exec("BEGIN IMMEDIATE", vec![]).await?;
+        for (table, columns) in expected_table_columns() {
+            let columns = columns.join(", ");
+            conn.exec(&format!("DELETE FROM main.{}", table), vec![])
+                .await?;
+            conn.exec(
+                &format!(
+                    "INSERT INTO main.{table} ({columns}) SELECT {columns} FROM backup.{table}"
+                ),
+                vec![],
+            )
+            .await?;
+        }
+        conn.exec("COMMIT", vec![]).await?;
+        Ok::<(), AppError>(())
+    }
+    .await;
+
+    if result.is_err() {
+        let _ = conn.exec("ROLLBACK", vec![]).await;
+    }
+    let _ = conn.exec("DETACH DATABASE backup", vec![]).await;
+    result
+}
+
+/// 从本地备份恢复：校验归档后暂停剪贴板记录和云同步，替换数据库和resources目录
+/// 旧版本的备份先通过数据库结构迁移升级到当前结构，再复制数据
+#[tauri::command]
+pub async fn restore_backup(param: BackupParam) -> Result<BackupManifest, String> {
+    ensure_not_kiosk_mode()?;
+    let archive_path = PathBuf::from(&param.path);
+    if !archive_path.is_file() {
+        return Err("备份文件不存在".to_string());
+    }
+
+    // 等待正在执行的同步完成，恢复期间不再同步
+    let sync_lock: &GlobalSyncLock = CONTEXT.get::<GlobalSyncLock>();
+    let _guard = sync_lock.inner().lock().await;
+    set_capture_paused(true);
+    let result = do_restore_backup(&archive_path).await;
+    set_capture_paused(false);
+
+    match result {
+        Ok(manifest) => {
+            log::info!(
+                "本地备份恢复完成，备份版本: {}, 记录数: {}, 资源文件数: {}",
+                manifest.app_version,
+                manifest.record_count,
+                manifest.resource_count
+            );
+            Ok(manifest)
+        }
+        Err(e) => {
+            log::error!("恢复本地备份失败: {}", e);
+            Err(format!("恢复本地备份失败: {}", e))
+        }
+    }
+}
+
+async fn do_restore_backup(archive_path: &Path) -> AppResult<BackupManifest> {
+    let resources_dir =
+        get_resources_dir().ok_or_else(|| AppError::Config("无法获取资源目录".to_string()))?;
+
+    let archive = archive_path.to_path_buf();
+    let dir = resources_dir.clone();
+    let extracted = tokio::task::spawn_blocking(move || extract_archive(&archive, &dir))
+        .await
+        .map_err(|e| AppError::General(format!("解压备份任务执行失败: {}", e)))??;
+    if extracted.manifest.app_version != app_version() {
+        log::info!(
+            "备份版本 {} 与当前版本 {} 不一致，将迁移数据库结构",
+            extracted.manifest.app_version,
+            app_version()
+        );
+    }
+
+    // 用迁移框架把备份数据库升级到当前结构
+    {
+        let backup_rb = RBatis::new();
+        backup_rb
+            .init(
+                rbdc_sqlite::Driver {},
+                &format!("sqlite://{}", to_safe_string(&extracted.database.0)),
+            )
+            .map_err(AppError::Database)?;
+        check_and_fix_database_schema(&backup_rb).await?;
+    }
+
+    // 先替换resources目录，数据库替换失败时再换回来
+    let old_resources =
+        resources_dir.with_file_name(format!("resources_old_{}", uuid::Uuid::new_v4()));
+    std::fs::rename(&resources_dir, &old_resources)?;
+    if let Err(e) = std::fs::rename(&extracted.resources.0, &resources_dir) {
+        let _ = std::fs::rename(&old_resources, &resources_dir);
+        return Err(e.into());
+    }
+
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    if let Err(e) = replace_database(rb, &extracted.database.0).await {
+        let _ = std::fs::rename(&resources_dir, &extracted.resources.0);
+        let _ = std::fs::rename(&old_resources, &resources_dir);
+        return Err(e);
+    }
+    let _ = std::fs::remove_dir_all(&old_resources);
+
+    // 清空旧数据库的内容缓存，重建搜索索引并通知前端刷新
+    reset_history_cursor();
+    clear_preview_cache();
+    if let Err(e) = initialize_search_index(rb).await {
+        log::error!("恢复备份后重建搜索索引失败: {}", e);
+    }
+    let _ = CONTEXT.get::<AppHandle>().emit("clip_record_change", ());
+
+    let mut manifest = extracted.manifest;
+    manifest.resource_count = extracted.resource_count;
+    Ok(manifest)
+}
@@ -1,13 +1,31 @@
+pub mod background_task_status;
 pub mod clip_async_queue;
 pub mod clip_record;
 pub mod clip_record_clean;
 pub mod clip_record_sync;
 pub mod cloud_sync_timer;
+pub mod content_migration;
 pub mod content_processor;
 pub mod content_search;
 pub mod copy_clip_record;
 pub mod download_cloud_file;
+pub mod export;
+pub mod history_cursor;
+pub mod image_bytes;
+pub mod image_ocr;
+pub mod image_thumbnail;
+pub mod importer;
+pub mod link_title;
+pub mod local_backup;
+pub mod preview_cache;
 pub mod query_clip_record;
+pub mod recently_deleted;
+pub mod record_merge;
+pub mod record_slot;
+pub mod record_tag;
+pub mod record_transfer;
+pub mod resource_maintenance;
+pub mod sync_conflict;
 pub mod sync_time;
 pub mod system_setting;
 pub mod update_checker;
@@ -0,0 +1,231 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use clipboard_listener::ClipType;
+use rbatis::RBatis;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    biz::{clip_record::ClipRecord, content_processor::ContentProcessor},
+    CONTEXT,
+};
+
+// 缓存的最大条目数
+static PREVIEW_CACHE_CAPACITY: usize = 300;
+
+// 缓存内容的总大小上限（字节）
+static PREVIEW_CACHE_MAX_BYTES: usize = 16 * 1024 * 1024;
+
+// 单条内容超过这个大小不缓存，避免少数大文本挤占整个缓存
+static PREVIEW_CACHE_MAX_ENTRY_BYTES: usize = 1024 * 1024;
+
+// 单次预取的最大记录数
+static PREFETCH_BATCH_LIMIT: usize = 100;
+
+/// 解密后的文本内容缓存（LRU）
+#[derive(Debug, Default)]
+pub struct PreviewCache {
+    state: Mutex<CacheState>,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    entries: HashMap<String, String>,
+    // 访问顺序，队尾为最近使用
+    order: VecDeque<String>,
+    total_bytes: usize,
+}
+
+impl CacheState {
+    fn touch(&mut self, id: &str) {
+        if let Some(pos) = self.order.iter().position(|key| key == id) {
+            if let Some(key) = self.order.remove(pos) {
+                self.order.push_back(key);
+            }
+        }
+    }
+
+    fn remove(&mut self, id: &str) {
+        if let Some(content) = self.entries.remove(id) {
+            self.total_bytes -= content.len();
+            self.order.retain(|key| key != id);
+        }
+    }
+}
+
+impl PreviewCache {
+    pub fn get(&self, id: &str) -> Option<String> {
+        let mut state = self.state.lock().ok()?;
+        let content = state.entries.get(id).cloned()?;
+        state.touch(id);
+        Some(content)
+    }
+
+    pub fn insert(&self, id: &str, content: String) {
+        if content.len() > PREVIEW_CACHE_MAX_ENTRY_BYTES {
+            return;
+        }
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        state.remove(id);
+        state.total_bytes += content.len();
+        state.entries.insert(id.to_string(), content);
+        state.order.push_back(id.to_string());
+
+        // 超出容量时淘汰最久未使用的条目
+        while state.entries.len() > PREVIEW_CACHE_CAPACITY
+            || state.total_bytes > PREVIEW_CACHE_MAX_BYTES
+        {
+            let Some(oldest) = state.order.pop_front() else {
+                break;
+            };
+            if let Some(content) = state.entries.remove(&oldest) {
+                state.total_bytes -= content.len();
+            }
+        }
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.state
+            .lock()
+            .map(|state| state.entries.contains_key(id))
+            .unwrap_or(false)
+    }
+
+    pub fn remove(&self, id: &str) {
+        if let Ok(mut state) = self.state.lock() {
+            state.remove(id);
+        }
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            *state = CacheState::default();
+        }
+    }
+}
+
+/// 使记录的缓存内容失效（记录编辑或删除时调用）
+pub fn invalidate_preview_cache(record_id: &str) {
+    if let Some(cache) = CONTEXT.try_get::<PreviewCache>() {
+        cache.remove(record_id);
+    }
+}
+
+/// 使多条记录的缓存内容失效（记录删除时调用）
+pub fn invalidate_preview_cache_by_ids(record_ids: &[String]) {
+    if let Some(cache) = CONTEXT.try_get::<PreviewCache>() {
+        for record_id in record_ids {
+            cache.remove(record_id);
+        }
+    }
+}
+
+/// 清空所有缓存内容（恢复备份、导入记录等整体替换记录时调用）
+pub fn clear_preview_cache() {
+    if let Some(cache) = CONTEXT.try_get::<PreviewCache>() {
+        cache.clear();
+    }
+}
+
+/// 从缓存读取记录的完整文本内容
+pub fn get_cached_preview(record_id: &str) -> Option<String> {
+    CONTEXT.try_get::<PreviewCache>()?.get(record_id)
+}
+
+/// 写入记录的完整文本内容到缓存
+pub fn cache_preview(record_id: &str, content: String) {
+    if let Some(cache) = CONTEXT.try_get::<PreviewCache>() {
+        cache.insert(record_id, content);
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PrefetchPreviewsParam {
+    pub record_ids: Vec<String>,
+}
+
+/// 预先解密并缓存一批文本记录（当前可见区域及下一页），加快后续的内容获取
+/// 返回本次新缓存的记录数
+#[tauri::command]
+pub async fn prefetch_previews(param: PrefetchPreviewsParam) -> Result<usize, String> {
+    let Some(cache) = CONTEXT.try_get::<PreviewCache>() else {
+        return Ok(0);
+    };
+
+    // 已缓存的记录不再重复查询和解密
+    let ids: Vec<String> = param
+        .record_ids
+        .into_iter()
+        .filter(|id| !cache.contains(id))
+        .take(PREFETCH_BATCH_LIMIT)
+        .collect();
+    if ids.is_empty() {
+        return Ok(0);
+    }
+
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let records = ClipRecord::select_by_ids(rb, &ids, ids.len() as i32, 0)
+        .await
+        .map_err(|e| format!("查询记录失败: {}", e))?;
+
+    let mut cached = 0;
+    for record in records {
+        if record.r#type != ClipType::Text.to_string() {
+            continue;
+        }
+        let content = ContentProcessor::process_by_clip_type(&record.r#type, record.content);
+        cache.insert(&record.id, content);
+        cached += 1;
+    }
+
+    log::debug!("预取文本内容完成，新缓存{}条", cached);
+    Ok(cached)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_cache_lru() {
+        let cache = PreviewCache::default();
+        for i in 0..PREVIEW_CACHE_CAPACITY {
+            cache.insert(&i.to_string(), format!("content-{}", i));
+        }
+        // 访问最早的条目，使其变为最近使用
+        assert_eq!(cache.get("0").as_deref(), Some("content-0"));
+
+        cache.insert("new", "new".to_string());
+        assert!(cache.contains("0"));
+        assert!(!cache.contains("1"));
+        assert!(cache.contains("new"));
+    }
+
+    #[test]
+    fn test_preview_cache_remove_and_size_limit() {
+        let cache = PreviewCache::default();
+        cache.insert("a", "aaa".to_string());
+        cache.remove("a");
+        assert!(cache.get("a").is_none());
+
+        let large = "x".repeat(PREVIEW_CACHE_MAX_ENTRY_BYTES + 1);
+        cache.insert("large", large);
+        assert!(!cache.contains("large"));
+    }
+
+    #[test]
+    fn test_preview_cache_clear() {
+        let cache = PreviewCache::default();
+        cache.insert("a", "aaa".to_string());
+        cache.insert("b", "bbb".to_string());
+        cache.clear();
+        assert!(!cache.contains("a"));
+        assert!(!cache.contains("b"));
+
+        // 清空后可以继续写入
+        cache.insert("c", "ccc".to_string());
+        assert_eq!(cache.get("c").as_deref(), Some("ccc"));
+    }
+}
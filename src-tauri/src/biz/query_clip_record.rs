@@ -1,22 +1,46 @@
 use clipboard_listener::ClipType;
 use rbatis::RBatis;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
 use crate::{
     CONTEXT,
     biz::{
-        clip_record::ClipRecord, content_processor::ContentProcessor,
-        content_search::search_ids_by_content,
+        clip_record::{ClipRecord, SyncStatus},
+        content_processor::ContentProcessor,
+        content_search::{
+            highlight_spans, search_ids_by_content, search_ranked_by_content, SearchHit,
+        },
+        preview_cache::{cache_preview, get_cached_preview},
+        record_tag::{parse_tags, search_ids_by_tag},
+    },
+    utils::{
+        aes_util::decrypt_content,
+        image_info::thumbnail_filename,
+        rich_text::{rtf_to_html, sanitize_html},
     },
 };
 
+// 敏感记录在列表中隐藏内容时显示的占位文本
+pub const SENSITIVE_MASK: &str = "••••••";
+
+// 常用记录默认返回条数和最大条数
+const DEFAULT_FREQUENT_LIMIT: i32 = 20;
+const MAX_FREQUENT_LIMIT: i32 = 100;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct QueryParam {
     pub page: i32,
     pub size: i32,
     pub search: Option<String>,
+    // 按相关度排序的搜索关键词，设置后结果按得分排序并返回匹配位置，优先于search
+    #[serde(default)]
+    pub query: Option<String>,
+    // 是否显示敏感记录的内容，默认隐藏
+    #[serde(default)]
+    pub reveal_sensitive: bool,
 }
 
 #[allow(dead_code)]
@@ -56,13 +80,39 @@ pub struct ClipRecordLiteDTO {
     pub os_type: String,
     pub created: u64,
     pub pinned_flag: i32,
+    // 是否收藏
+    pub favorite: bool,
+    // 从历史记录复制的次数
+    pub use_count: i32,
     pub file_info: Vec<FileInfo>,
     pub sync_flag: Option<i32>,
+    // 跳过同步的原因（sync_flag为3时有效）
+    pub skip_type: Option<i32>,
+    // 由sync_flag和skip_type计算的同步状态，前端无需关心具体的数值
+    pub sync_status: SyncStatus,
+    // 同步状态的展示名称
+    pub sync_status_label: String,
     pub cloud_source: Option<i32>,
     pub content_truncated: bool,
     pub original_content_length: Option<usize>,
     // 标记是否有图片（用于前端判断是否需要加载图片信息）
     pub has_image: bool,
+    // 编号槽位（1-9），未分配时为None
+    pub slot: Option<i32>,
+    // 网址记录的网页标题，未获取到时为None
+    pub title: Option<String>,
+    // 记录的标签
+    pub tags: Vec<String>,
+    // 是否为敏感记录
+    pub sensitive: bool,
+    // 敏感记录的内容是否已隐藏
+    pub masked: bool,
+    // 排序搜索的相关度得分，非排序搜索时为None
+    #[serde(default)]
+    pub score: Option<f32>,
+    // 排序搜索在 content 中命中的字节区间 (起始, 结束)，用于前端高亮
+    #[serde(default)]
+    pub highlights: Vec<(usize, usize)>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -89,11 +139,17 @@ pub struct ImageInfo {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetImageParam {
     pub record_id: String,
+    // 是否允许查看敏感记录的内容，默认不允许
+    #[serde(default)]
+    pub reveal_sensitive: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetFullContentParam {
     pub record_id: String,
+    // 是否允许查看敏感记录的内容，默认不允许
+    #[serde(default)]
+    pub reveal_sensitive: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -103,15 +159,43 @@ pub struct FullContentResponse {
     pub content_length: usize,
 }
 
+/// 查询可以查看内容的记录，与列表一致：已删除或隐藏的记录视为不存在，敏感记录只有 reveal_sensitive 为 true 时才返回
+pub(crate) async fn select_viewable_record(
+    rb: &RBatis,
+    record_id: &str,
+    reveal_sensitive: bool,
+) -> Result<ClipRecord, String> {
+    let record = ClipRecord::select_by_id(rb, record_id)
+        .await
+        .map_err(|e| format!("查询记录失败: {}", e))?
+        .into_iter()
+        .next()
+        .filter(|record| record.del_flag.unwrap_or(0) == 0)
+        .ok_or("记录不存在")?;
+    if record.is_sensitive() && !reveal_sensitive {
+        return Err("敏感记录需要确认后才能查看内容".to_string());
+    }
+    Ok(record)
+}
+
 /// 获取剪贴记录列表 - 使用轻量级 DTO，延迟加载图片信息
 #[tauri::command]
 pub async fn get_clip_records(param: QueryParam) -> Result<Vec<ClipRecordLiteDTO>, String> {
     let offset = (param.page - 1) * param.size;
     let rb: &RBatis = CONTEXT.get::<RBatis>();
+    if let Some(query) = param.query.as_deref().filter(|s| !s.trim().is_empty()) {
+        return get_ranked_clip_records(rb, query, &param, offset).await;
+    }
     // 执行数据库查询逻辑
     let query_result = match param.search.as_deref().filter(|s| !s.is_empty()) {
         Some(search) => {
-            let res_ids = search_ids_by_content(search).await;
+            let mut res_ids = search_ids_by_content(search).await;
+            // 开启标签搜索时同时返回标签匹配的记录
+            for id in search_ids_by_tag(search).await {
+                if !res_ids.contains(&id) {
+                    res_ids.push(id);
+                }
+            }
             ClipRecord::select_by_ids(rb, &res_ids, param.size, offset).await
         }
         None => ClipRecord::select_order_by_limit(rb, param.size, offset).await,
@@ -129,73 +213,239 @@ pub async fn get_clip_records(param: QueryParam) -> Result<Vec<ClipRecordLiteDTO
 
     Ok(all_data
         .into_iter()
-        .map(|item| {
-            if item.r#type == ClipType::File.to_string() {
-                let content_str = item.content.as_str().unwrap_or_default().to_string();
-                let local_paths = item
-                    .local_file_path
-                    .as_deref()
-                    .unwrap_or_default()
-                    .to_string();
-                let content =
-                    ContentProcessor::process_by_clip_type(&item.r#type, item.content.clone());
-                return ClipRecordLiteDTO {
-                    id: item.id.clone(),
-                    r#type: item.r#type.clone(),
-                    content,
-                    os_type: item.os_type.clone(),
-                    created: item.created,
-                    pinned_flag: item.pinned_flag,
-                    file_info: get_file_info_with_paths(content_str, local_paths),
-                    sync_flag: item.sync_flag,
-                    cloud_source: item.cloud_source,
-                    content_truncated: false,
-                    original_content_length: None,
-                    has_image: false,
-                };
-            } else if item.r#type == ClipType::Image.to_string() {
-                // 对于图片类型，不获取图片信息，只返回路径和标记
-                let image_path = item.content.as_str().unwrap_or_default();
-                return ClipRecordLiteDTO {
-                    id: item.id.clone(),
-                    r#type: item.r#type.clone(),
-                    content: image_path.to_string(),
-                    os_type: item.os_type.clone(),
-                    created: item.created,
-                    pinned_flag: item.pinned_flag,
-                    file_info: vec![],
-                    sync_flag: item.sync_flag,
-                    cloud_source: item.cloud_source,
-                    content_truncated: false,
-                    original_content_length: None,
-                    has_image: true, // 标记为图片，前端按需加载
-                };
-            } else {
-                // 处理文本类型，如果内容过大则截断
-                let processed_content =
-                    ContentProcessor::process_by_clip_type(&item.r#type, item.content.clone());
-                let (truncated_content, is_truncated, original_length) =
-                    truncate_large_text(&processed_content);
-
-                return ClipRecordLiteDTO {
-                    id: item.id.clone(),
-                    r#type: item.r#type.clone(),
-                    content: truncated_content,
-                    os_type: item.os_type.clone(),
-                    created: item.created,
-                    pinned_flag: item.pinned_flag,
-                    file_info: vec![],
-                    sync_flag: item.sync_flag,
-                    cloud_source: item.cloud_source,
-                    content_truncated: is_truncated,
-                    original_content_length: original_length,
-                    has_image: false,
-                };
+        .map(|item| to_lite_dto_with(item, param.reveal_sensitive))
+        .collect())
+}
+
+/// 获取收藏的记录列表
+#[tauri::command]
+pub async fn get_favorite_clip_records(
+    reveal_sensitive: Option<bool>,
+) -> Result<Vec<ClipRecordLiteDTO>, String> {
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let records = ClipRecord::select_by_favorite(rb).await.map_err(|e| {
+        log::error!("查询收藏记录失败: {:?}", e);
+        "查询收藏记录失败".to_string()
+    })?;
+    let reveal_sensitive = reveal_sensitive.unwrap_or(false);
+    Ok(records
+        .into_iter()
+        .map(|item| to_lite_dto_with(item, reveal_sensitive))
+        .collect())
+}
+
+/// 获取最常复制的记录，按复制次数倒序，只返回复制过的记录
+#[tauri::command]
+pub async fn get_frequent_records(
+    limit: Option<i32>,
+    reveal_sensitive: Option<bool>,
+) -> Result<Vec<ClipRecordLiteDTO>, String> {
+    let limit = limit
+        .unwrap_or(DEFAULT_FREQUENT_LIMIT)
+        .clamp(1, MAX_FREQUENT_LIMIT);
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let records = ClipRecord::select_order_by_use_count(rb, limit)
+        .await
+        .map_err(|e| {
+            log::error!("查询常用记录失败: {:?}", e);
+            "查询常用记录失败".to_string()
+        })?;
+    let reveal_sensitive = reveal_sensitive.unwrap_or(false);
+    Ok(records
+        .into_iter()
+        .map(|item| to_lite_dto_with(item, reveal_sensitive))
+        .collect())
+}
+
+/// 按搜索索引的相关度排序分页查询，并附带每条记录的得分和匹配位置
+async fn get_ranked_clip_records(
+    rb: &RBatis,
+    query: &str,
+    param: &QueryParam,
+    offset: i32,
+) -> Result<Vec<ClipRecordLiteDTO>, String> {
+    let mut hits = search_ranked_by_content(query).await;
+    // 只有标签匹配的记录排在内容匹配的记录之后，没有匹配位置
+    for id in search_ids_by_tag(query).await {
+        if !hits.iter().any(|hit| hit.id == id) {
+            hits.push(SearchHit { id, score: 0.0 });
+        }
+    }
+    let page_hits: Vec<SearchHit> = hits
+        .into_iter()
+        .skip(offset.max(0) as usize)
+        .take(param.size.max(0) as usize)
+        .collect();
+    if page_hits.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let page_ids: Vec<String> = page_hits.iter().map(|hit| hit.id.clone()).collect();
+    let records = ClipRecord::select_by_ids(rb, &page_ids, param.size, 0)
+        .await
+        .map_err(|e| {
+            log::error!("查询粘贴记录失败: {:?}", e);
+            "查询粘贴记录失败".to_string()
+        })?;
+    let mut records: HashMap<String, ClipRecord> = records
+        .into_iter()
+        .map(|record| (record.id.clone(), record))
+        .collect();
+
+    // 按得分顺序组装结果，已删除但仍在索引中的记录直接跳过
+    Ok(page_hits
+        .into_iter()
+        .filter_map(|hit| {
+            let record = records.remove(&hit.id)?;
+            let mut dto = to_lite_dto_with(record, param.reveal_sensitive);
+            dto.score = Some(hit.score);
+            // 隐藏内容的敏感记录不返回匹配位置，避免泄露内容
+            if !dto.masked {
+                dto.highlights = highlight_spans(&dto.content, query);
             }
+            Some(dto)
         })
         .collect())
 }
 
+/// 把记录转换为列表展示用的轻量级 DTO，敏感记录的内容默认隐藏
+pub(crate) fn to_lite_dto(item: ClipRecord) -> ClipRecordLiteDTO {
+    to_lite_dto_with(item, false)
+}
+
+/// 把记录转换为列表展示用的轻量级 DTO，reveal_sensitive 为 true 时显示敏感记录的内容
+pub(crate) fn to_lite_dto_with(item: ClipRecord, reveal_sensitive: bool) -> ClipRecordLiteDTO {
+    let sync_status = SyncStatus::from_flags(item.sync_flag, item.skip_type);
+    let sensitive = item.is_sensitive();
+    let favorite = item.is_favorite();
+    if sensitive && !reveal_sensitive {
+        // 敏感记录只返回元信息，内容、文件路径和图片都不返回
+        return ClipRecordLiteDTO {
+            id: item.id.clone(),
+            r#type: item.r#type.clone(),
+            content: SENSITIVE_MASK.to_string(),
+            os_type: item.os_type.clone(),
+            created: item.created,
+            pinned_flag: item.pinned_flag,
+            favorite,
+            use_count: item.use_count.unwrap_or(0),
+            file_info: vec![],
+            sync_flag: item.sync_flag,
+            skip_type: item.skip_type,
+            sync_status,
+            sync_status_label: sync_status.label().to_string(),
+            cloud_source: item.cloud_source,
+            content_truncated: false,
+            original_content_length: None,
+            has_image: false,
+            slot: item.slot,
+            title: None,
+            tags: parse_tags(item.tags.as_deref()),
+            sensitive,
+            masked: true,
+            score: None,
+            highlights: vec![],
+        };
+    }
+    if item.r#type == ClipType::File.to_string() {
+        let content_str = item.content.as_str().unwrap_or_default().to_string();
+        let local_paths = item
+            .local_file_path
+            .as_deref()
+            .unwrap_or_default()
+            .to_string();
+        let content = ContentProcessor::process_by_clip_type(&item.r#type, item.content.clone());
+        return ClipRecordLiteDTO {
+            id: item.id.clone(),
+            r#type: item.r#type.clone(),
+            content,
+            os_type: item.os_type.clone(),
+            created: item.created,
+            pinned_flag: item.pinned_flag,
+            favorite,
+            use_count: item.use_count.unwrap_or(0),
+            file_info: get_file_info_with_paths(content_str, local_paths),
+            sync_flag: item.sync_flag,
+            skip_type: item.skip_type,
+            sync_status,
+            sync_status_label: sync_status.label().to_string(),
+            cloud_source: item.cloud_source,
+            content_truncated: false,
+            original_content_length: None,
+            has_image: false,
+            slot: item.slot,
+            title: item.title.clone(),
+            tags: parse_tags(item.tags.as_deref()),
+            sensitive,
+            masked: false,
+            score: None,
+            highlights: vec![],
+        };
+    } else if item.r#type == ClipType::Image.to_string() {
+        // 对于图片类型，不获取图片信息，只返回路径和标记
+        let image_path = item.content.as_str().unwrap_or_default();
+        return ClipRecordLiteDTO {
+            id: item.id.clone(),
+            r#type: item.r#type.clone(),
+            content: image_path.to_string(),
+            os_type: item.os_type.clone(),
+            created: item.created,
+            pinned_flag: item.pinned_flag,
+            favorite,
+            use_count: item.use_count.unwrap_or(0),
+            file_info: vec![],
+            sync_flag: item.sync_flag,
+            skip_type: item.skip_type,
+            sync_status,
+            sync_status_label: sync_status.label().to_string(),
+            cloud_source: item.cloud_source,
+            content_truncated: false,
+            original_content_length: None,
+            has_image: true, // 标记为图片，前端按需加载
+            slot: item.slot,
+            title: item.title.clone(),
+            tags: parse_tags(item.tags.as_deref()),
+            sensitive,
+            masked: false,
+            score: None,
+            highlights: vec![],
+        };
+    } else {
+        // 处理文本类型，如果内容过大则截断；HTML/RTF记录显示提取出的纯文本，带格式的内容通过 get_rich_preview 获取
+        let processed_content =
+            ContentProcessor::plain_text_by_clip_type(&item.r#type, item.content.clone());
+        let (truncated_content, is_truncated, original_length) =
+            truncate_large_text(&processed_content);
+
+        return ClipRecordLiteDTO {
+            id: item.id.clone(),
+            r#type: item.r#type.clone(),
+            content: truncated_content,
+            os_type: item.os_type.clone(),
+            created: item.created,
+            pinned_flag: item.pinned_flag,
+            favorite,
+            use_count: item.use_count.unwrap_or(0),
+            file_info: vec![],
+            sync_flag: item.sync_flag,
+            skip_type: item.skip_type,
+            sync_status,
+            sync_status_label: sync_status.label().to_string(),
+            cloud_source: item.cloud_source,
+            content_truncated: is_truncated,
+            original_content_length: original_length,
+            has_image: false,
+            slot: item.slot,
+            title: item.title.clone(),
+            tags: parse_tags(item.tags.as_deref()),
+            sensitive,
+            masked: false,
+            score: None,
+            highlights: vec![],
+        };
+    }
+}
+
 /// 使用content（显示名称）和local_file_path（实际路径）获取文件信息
 pub fn get_file_info_with_paths(content_names: String, local_paths: String) -> Vec<FileInfo> {
     let display_names = content_names.split(":::").collect::<Vec<&str>>();
@@ -319,11 +569,7 @@ pub fn get_image_info(relative_path: &str) -> Option<ImageInfo> {
 pub async fn get_image_path(param: GetImageParam) -> Result<ImagePathInfo, String> {
     let rb: &RBatis = CONTEXT.get::<RBatis>();
 
-    let records = ClipRecord::select_by_id(rb, &param.record_id)
-        .await
-        .map_err(|e| format!("数据库查询失败: {}", e))?;
-
-    let clip_record = records.first().ok_or("记录不存在")?;
+    let clip_record = &select_viewable_record(rb, &param.record_id, param.reveal_sensitive).await?;
 
     // 检查是否是图片类型
     if clip_record.r#type != "Image" {
@@ -338,6 +584,7 @@ pub async fn get_image_path(param: GetImageParam) -> Result<ImagePathInfo, Strin
                 id: clip_record.id.clone(),
                 file_path: cache_file_path.to_string(),
                 protocol_url: format!("asset://localhost/{}", cache_file_path.replace("\\", "/")),
+                thumbnail_url: None,
             });
         }
     }
@@ -350,11 +597,17 @@ pub async fn get_image_path(param: GetImageParam) -> Result<ImagePathInfo, Strin
             let image_path = resources_dir.join(filename);
             if image_path.exists() {
                 let absolute_path = image_path.to_string_lossy().to_string();
+                let thumbnail_path = resources_dir.join(thumbnail_filename(filename));
+                let thumbnail_url = thumbnail_path.exists().then(|| {
+                    let thumbnail_path = thumbnail_path.to_string_lossy().to_string();
+                    format!("asset://localhost/{}", thumbnail_path.replace("\\", "/"))
+                });
                 // 使用Tauri内置的asset协议
                 return Ok(ImagePathInfo {
                     id: clip_record.id.clone(),
                     file_path: absolute_path.clone(),
                     protocol_url: format!("asset://localhost/{}", absolute_path.replace("\\", "/")),
+                    thumbnail_url,
                 });
             }
         }
@@ -368,6 +621,8 @@ pub struct ImagePathInfo {
     pub id: String,
     pub file_path: String,
     pub protocol_url: String,
+    // 缩略图地址（GIF动图为第一帧），列表中显示缩略图，预览时显示原图
+    pub thumbnail_url: Option<String>,
 }
 
 /// 截断大文本，返回 (截断后内容, 是否被截断, 原始长度)
@@ -430,6 +685,57 @@ pub async fn get_image_info_batch(
     Ok(result)
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RichPreviewResponse {
+    pub id: String,
+    // 预览格式: html-已过滤的HTML片段 text-纯文本
+    pub format: String,
+    pub content: String,
+}
+
+// 获取富文本记录的带格式预览，HTML/RTF记录返回过滤后的HTML片段，文本记录返回原文
+#[tauri::command]
+pub async fn get_rich_preview(param: GetFullContentParam) -> Result<RichPreviewResponse, String> {
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+
+    let record = &select_viewable_record(rb, &param.record_id, param.reveal_sensitive).await?;
+    let content = ContentProcessor::process_by_clip_type(&record.r#type, record.content.clone());
+
+    let (format, content) = match record.r#type.as_str() {
+        t if t == ClipType::Html.to_string() => ("html", sanitize_html(&content)),
+        t if t == ClipType::Rtf.to_string() => ("html", rtf_to_html(&content)),
+        t if t == ClipType::Text.to_string() => match text_rich_flavor_html(record) {
+            Some(html) => ("html", html),
+            None => ("text", content),
+        },
+        _ => return Err("该类型的记录不支持富文本预览".to_string()),
+    };
+
+    Ok(RichPreviewResponse {
+        id: param.record_id,
+        format: format.to_string(),
+        content,
+    })
+}
+
+// 文本记录保存了HTML/RTF格式时转换为过滤后的HTML片段
+fn text_rich_flavor_html(record: &ClipRecord) -> Option<String> {
+    let rich_type = record.rich_type.as_deref()?;
+    let rich_content = record.rich_content.as_deref()?;
+    let rich_content = match decrypt_content(rich_content) {
+        Ok(content) => content,
+        Err(e) => {
+            log::warn!("解密富文本内容失败，使用纯文本预览: {}", e);
+            return None;
+        }
+    };
+    match rich_type.parse::<ClipType>().ok()? {
+        ClipType::Html => Some(sanitize_html(&rich_content)),
+        ClipType::Rtf => Some(rtf_to_html(&rich_content)),
+        _ => None,
+    }
+}
+
 // 获取记录的完整文本内容
 #[tauri::command]
 pub async fn get_full_text_content(
@@ -437,21 +743,27 @@ pub async fn get_full_text_content(
 ) -> Result<FullContentResponse, String> {
     let rb: &RBatis = CONTEXT.get::<RBatis>();
 
-    // 从数据库获取记录
-    let records = ClipRecord::select_by_id(rb, &param.record_id)
-        .await
-        .map_err(|e| format!("查询记录失败: {}", e))?;
-
-    let record = records.first().ok_or("记录不存在")?;
+    // 从数据库获取记录，已删除、隐藏或未确认查看的敏感记录不返回内容
+    let record = &select_viewable_record(rb, &param.record_id, param.reveal_sensitive).await?;
 
     // 验证是否为文本类型
     if record.r#type != ClipType::Text.to_string() {
         return Err("记录类型不是文本".to_string());
     }
 
+    // 优先使用预取的缓存内容，避免重复解密
+    if let Some(full_content) = get_cached_preview(&record.id) {
+        return Ok(FullContentResponse {
+            id: param.record_id,
+            content_length: full_content.len(),
+            content: full_content,
+        });
+    }
+
     // 处理完整内容（解密等）
     let full_content =
         ContentProcessor::process_by_clip_type(&record.r#type, record.content.clone());
+    cache_preview(&record.id, full_content.clone());
 
     Ok(FullContentResponse {
         id: param.record_id,
@@ -459,3 +771,67 @@ pub async fn get_full_text_content(
         content_length: full_content.len(),
     })
 }
+
+/// 获取清理不可见字符前的原始文本，没有保留原始文本时返回None
+#[tauri::command]
+pub async fn get_raw_text_content(
+    param: GetFullContentParam,
+) -> Result<Option<FullContentResponse>, String> {
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let record = select_viewable_record(rb, &param.record_id, param.reveal_sensitive).await?;
+
+    let Some(raw_content) = record.raw_content.as_deref() else {
+        return Ok(None);
+    };
+    let raw_content = decrypt_content(raw_content).map_err(|e| {
+        log::error!("解密原始文本失败: {}", e);
+        "解密原始文本失败".to_string()
+    })?;
+
+    Ok(Some(FullContentResponse {
+        id: param.record_id,
+        content_length: raw_content.len(),
+        content: raw_content,
+    }))
+}
+
+/// 剪贴板记录统计信息
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct ClipStats {
+    // 有效记录总数
+    pub total: i64,
+    // 各类型的有效记录数量
+    pub by_type: HashMap<String, i64>,
+    // 置顶记录数量
+    pub pinned: i64,
+    // 等待同步和同步中的记录数量
+    pub pending_sync: i64,
+    // 跳过同步的记录数量
+    pub skipped_sync: i64,
+}
+
+/// 获取记录总数、各类型数量和同步状态统计
+#[tauri::command]
+pub async fn get_clip_stats() -> Result<ClipStats, String> {
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+
+    let type_counts = ClipRecord::count_by_type(rb).await.map_err(|e| {
+        log::error!("按类型统计记录失败: {}", e);
+        "统计记录失败".to_string()
+    })?;
+    let status = ClipRecord::count_status_summary(rb).await.map_err(|e| {
+        log::error!("统计记录状态失败: {}", e);
+        "统计记录失败".to_string()
+    })?;
+
+    Ok(ClipStats {
+        total: ClipRecord::count_effective(rb).await,
+        by_type: type_counts
+            .into_iter()
+            .map(|row| (row.r#type, row.count))
+            .collect(),
+        pinned: status.pinned,
+        pending_sync: status.pending_sync,
+        skipped_sync: status.skipped_sync,
+    })
+}
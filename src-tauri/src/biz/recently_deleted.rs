@@ -0,0 +1,201 @@
+use std::sync::{Arc, RwLock};
+
+use rbatis::RBatis;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::{
+    biz::{
+        clip_async_queue::AsyncQueue,
+        clip_record::{ClipRecord, NOT_SYNCHRONIZED},
+        clip_record_clean::try_clean_clip_record,
+        content_search::add_records_to_index,
+        query_clip_record::{to_lite_dto, ClipRecordLiteDTO},
+        system_setting::{
+            check_cloud_sync_enabled, ensure_not_kiosk_mode, Settings,
+            DEFAULT_TOMBSTONE_RETENTION_DAYS,
+        },
+    },
+    utils::lock_utils::lock_utils::safe_read_lock,
+    CONTEXT,
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecentlyDeletedParam {
+    pub page: i32,
+    pub size: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeletedRecordDTO {
+    #[serde(flatten)]
+    pub record: ClipRecordLiteDTO,
+    // 删除时间戳（毫秒）
+    pub del_time: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RestoreRecordsParam {
+    pub ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeletedRecordsParam {
+    pub limit: i32,
+    pub offset: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RestoreRecordParam {
+    pub record_id: String,
+}
+
+/// 获取可以恢复的最早删除时间（毫秒），保留期为0时不限制
+fn restorable_after() -> u64 {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    let retention_days = safe_read_lock(&settings_lock)
+        .map(|settings| {
+            settings
+                .tombstone_retention_days
+                .unwrap_or(DEFAULT_TOMBSTONE_RETENTION_DAYS)
+        })
+        .unwrap_or(DEFAULT_TOMBSTONE_RETENTION_DAYS);
+    if retention_days == 0 {
+        return 0;
+    }
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0);
+    now.saturating_sub(retention_days as u64 * 24 * 60 * 60 * 1000)
+}
+
+/// 获取保留期内最近删除的记录，按删除时间倒序
+#[tauri::command]
+pub async fn list_recently_deleted(
+    param: RecentlyDeletedParam,
+) -> Result<Vec<DeletedRecordDTO>, String> {
+    let offset = (param.page - 1) * param.size;
+    select_restorable(param.size, offset).await
+}
+
+/// 查询保留期内可以恢复的删除记录
+async fn select_restorable(limit: i32, offset: i32) -> Result<Vec<DeletedRecordDTO>, String> {
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let records = ClipRecord::select_recently_deleted(rb, restorable_after(), limit, offset)
+        .await
+        .map_err(|e| {
+            log::error!("查询最近删除记录失败: {}", e);
+            "查询最近删除记录失败".to_string()
+        })?;
+
+    Ok(records
+        .into_iter()
+        .map(|record| {
+            let del_time = record.del_time;
+            DeletedRecordDTO {
+                record: to_lite_dto(record),
+                del_time,
+            }
+        })
+        .collect())
+}
+
+/// 批量恢复最近删除的记录，恢复后重新加入搜索索引并等待同步到云端
+/// 返回实际恢复的记录数
+#[tauri::command]
+pub async fn restore_records(param: RestoreRecordsParam) -> Result<usize, String> {
+    ensure_not_kiosk_mode()?;
+    if param.ids.is_empty() {
+        return Ok(0);
+    }
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+
+    if let Err(e) = ClipRecord::restore_by_ids(rb, &param.ids).await {
+        log::error!("恢复删除记录失败: {}", e);
+        return Err("恢复删除记录失败".to_string());
+    }
+
+    // 查询恢复后的有效记录（已经被物理删除的记录不会返回）
+    let restored = ClipRecord::select_by_ids(rb, &param.ids, param.ids.len() as i32, 0)
+        .await
+        .map_err(|e| {
+            log::error!("查询恢复的记录失败: {}", e);
+            "查询恢复的记录失败".to_string()
+        })?;
+    let indexed = add_records_to_index(&restored);
+    log::info!(
+        "恢复删除记录完成，恢复: {}条，重新索引: {}条",
+        restored.len(),
+        indexed
+    );
+
+    // 恢复后可能超出最大记录数，交给清理逻辑处理
+    tokio::spawn(async {
+        try_clean_clip_record().await;
+    });
+    let app_handle = CONTEXT.get::<AppHandle>();
+    let _ = app_handle.emit("clip_record_change", ());
+
+    Ok(restored.len())
+}
+
+/// 回收站：分页获取保留期内逻辑删除（尚未物理清理）的记录，按删除时间倒序
+#[tauri::command]
+pub async fn get_deleted_records(
+    param: DeletedRecordsParam,
+) -> Result<Vec<DeletedRecordDTO>, String> {
+    select_restorable(param.limit, param.offset).await
+}
+
+/// 从回收站恢复单条保留期内的记录，恢复逻辑与批量恢复相同
+/// 开启云同步时立即加入同步队列
+#[tauri::command]
+pub async fn restore_record(param: RestoreRecordParam) -> Result<(), String> {
+    ensure_not_kiosk_mode()?;
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+
+    let deleted = ClipRecord::select_by_id(rb, &param.record_id)
+        .await
+        .map_err(|e| {
+            log::error!("查询删除记录失败: {}, 记录ID: {}", e, param.record_id);
+            "查询删除记录失败".to_string()
+        })?;
+    match deleted.first() {
+        Some(record) if record.del_flag != Some(1) => {
+            return Err("记录未被删除，无需恢复".to_string())
+        }
+        Some(record)
+            if record
+                .del_time
+                .is_some_and(|del_time| del_time >= restorable_after()) => {}
+        _ => return Err("记录已被清理，无法恢复".to_string()),
+    }
+
+    let restored = restore_records(RestoreRecordsParam {
+        ids: vec![param.record_id.clone()],
+    })
+    .await?;
+    if restored == 0 {
+        return Err("记录已被清理，无法恢复".to_string());
+    }
+
+    // 恢复的记录重新加入同步队列   前提是开启了云同步开关（不支持同步的记录保持跳过状态）
+    let record = ClipRecord::select_by_id(rb, &param.record_id)
+        .await
+        .ok()
+        .and_then(|records| records.into_iter().next());
+    if let Some(record) = record {
+        if record.sync_flag == Some(NOT_SYNCHRONIZED) && check_cloud_sync_enabled().await {
+            let async_queue = CONTEXT.get::<AsyncQueue<ClipRecord>>();
+            if !async_queue.is_full() {
+                if let Err(e) = async_queue.send_add(record.clone()).await {
+                    log::error!("异步队列发送失败，恢复的记录：{}, 异常:{}", record.id, e);
+                }
+            }
+        }
+    }
+    log::info!("已从回收站恢复记录: {}", param.record_id);
+
+    Ok(())
+}
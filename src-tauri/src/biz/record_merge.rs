@@ -0,0 +1,115 @@
+use std::collections::HashSet;
+
+use clipboard_listener::ClipType;
+use rbatis::RBatis;
+use tauri::{AppHandle, Emitter};
+
+use crate::{
+    biz::{
+        clip_record::ClipRecord,
+        clip_record_clean::try_clean_clip_record,
+        clip_record_sync::{save_text, send_new_record_to_sync, SavedText},
+        content_processor::ContentProcessor,
+        copy_clip_record::{del_record, CopyClipRecord},
+        history_cursor::reset_history_cursor,
+        system_setting::ensure_not_kiosk_mode,
+    },
+    utils::aes_util::decrypt_content,
+    CONTEXT,
+};
+
+// 一次最多合并的记录数
+const MAX_MERGE_RECORDS: usize = 100;
+
+/// 按传入的顺序解密文本记录，记录不存在、已删除或不是文本记录时返回错误
+/// 返回解密后的文本，以及其中是否有敏感记录
+fn collect_texts(
+    record_ids: &[String],
+    records: &[ClipRecord],
+) -> Result<(Vec<String>, bool), String> {
+    let mut texts = Vec::with_capacity(record_ids.len());
+    let mut has_sensitive = false;
+    for record_id in record_ids {
+        let record = records
+            .iter()
+            .find(|record| &record.id == record_id)
+            .ok_or("部分记录不存在或已删除".to_string())?;
+        if record.r#type != ClipType::Text.to_string() {
+            return Err("只能合并文本记录".to_string());
+        }
+        let text = decrypt_content(&ContentProcessor::process_text_content(
+            record.content.clone(),
+        ))
+        .map_err(|e| {
+            log::error!("解密文本内容失败: {}, 记录ID: {}", e, record_id);
+            "文本解密失败".to_string()
+        })?;
+        has_sensitive |= record.is_sensitive();
+        texts.push(text);
+    }
+    Ok((texts, has_sensitive))
+}
+
+/// 把多条文本记录按传入的顺序用分隔符连接，保存为一条新的文本记录，返回新记录的ID
+/// 新记录和复制的文本一样经过清理、脱敏、去重、索引和同步，合并后的内容已存在时返回已有记录的ID
+/// delete_originals 为 true 时删除被合并的记录（可在撤销宽限期内撤销）
+#[tauri::command]
+pub async fn merge_text_records(
+    record_ids: Vec<String>,
+    separator: String,
+    delete_originals: Option<bool>,
+) -> Result<String, String> {
+    ensure_not_kiosk_mode()?;
+    if record_ids.len() < 2 {
+        return Err("请至少选择两条文本记录".to_string());
+    }
+    if record_ids.len() > MAX_MERGE_RECORDS {
+        return Err(format!("一次最多合并{}条记录", MAX_MERGE_RECORDS));
+    }
+
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let records = ClipRecord::select_by_ids(rb, &record_ids, record_ids.len() as i32, 0)
+        .await
+        .map_err(|e| {
+            log::error!("查询合并的记录失败: {}", e);
+            "查询记录失败".to_string()
+        })?;
+    let (texts, has_sensitive) = collect_texts(&record_ids, &records)?;
+    let merged = texts.join(&separator);
+
+    let sort = ClipRecord::get_next_sort(rb).await;
+    // 合并的记录中有敏感记录时，合并后的记录同样标记为敏感
+    let saved = save_text(rb, &merged, sort, has_sensitive)
+        .await
+        .map_err(|e| {
+            log::error!("保存合并的记录失败: {}", e);
+            "保存合并的记录失败".to_string()
+        })?;
+    let merged_id = match saved {
+        SavedText::New(record) => {
+            send_new_record_to_sync(&record).await;
+            record.id
+        }
+        // 合并后的内容与已有记录相同时，已有记录已移到最前
+        SavedText::Existing(record_id) => record_id,
+        SavedText::Empty => return Err("合并后的内容为空".to_string()),
+    };
+    reset_history_cursor();
+
+    if delete_originals.unwrap_or(false) {
+        let mut deleted = HashSet::new();
+        for record_id in record_ids {
+            if record_id != merged_id && deleted.insert(record_id.clone()) {
+                del_record(CopyClipRecord { record_id }).await?;
+            }
+        }
+    }
+
+    tokio::spawn(async {
+        try_clean_clip_record().await;
+    });
+    let app_handle = CONTEXT.get::<AppHandle>();
+    let _ = app_handle.emit("clip_record_change", ());
+    log::info!("合并{}条文本记录为: {}", texts.len(), merged_id);
+    Ok(merged_id)
+}
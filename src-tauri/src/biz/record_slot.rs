@@ -0,0 +1,102 @@
+use rbatis::RBatis;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::{
+    biz::{
+        clip_record::ClipRecord,
+        query_clip_record::{to_lite_dto, ClipRecordLiteDTO},
+        system_setting::ensure_not_kiosk_mode,
+    },
+    CONTEXT,
+};
+
+// 编号槽位范围，对应 1-9 的编号快速粘贴
+pub const MIN_SLOT: i32 = 1;
+pub const MAX_SLOT: i32 = 9;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AssignSlotParam {
+    pub record_id: String,
+    pub slot: i32,
+}
+
+pub fn is_valid_slot(slot: i32) -> bool {
+    (MIN_SLOT..=MAX_SLOT).contains(&slot)
+}
+
+fn check_slot(slot: i32) -> Result<(), String> {
+    if is_valid_slot(slot) {
+        Ok(())
+    } else {
+        Err(format!("槽位编号必须在{}-{}之间", MIN_SLOT, MAX_SLOT))
+    }
+}
+
+fn notify_record_change() {
+    let app_handle = CONTEXT.get::<AppHandle>();
+    let _ = app_handle.emit("clip_record_change", ());
+}
+
+/// 获取槽位上的记录，供编号快速粘贴使用
+pub async fn get_slot_record(slot: i32) -> Option<ClipRecord> {
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    match ClipRecord::select_by_slot(rb, slot).await {
+        Ok(records) => records.into_iter().next(),
+        Err(e) => {
+            log::error!("查询槽位{}的记录失败: {}", slot, e);
+            None
+        }
+    }
+}
+
+/// 把记录固定到编号槽位，槽位已被其他记录占用时改为分配给当前记录
+#[tauri::command]
+pub async fn assign_slot(param: AssignSlotParam) -> Result<(), String> {
+    ensure_not_kiosk_mode()?;
+    check_slot(param.slot)?;
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let record = ClipRecord::select_by_id(rb, &param.record_id)
+        .await
+        .map_err(|e| {
+            log::error!("查询记录失败: {}", e);
+            "查询记录失败".to_string()
+        })?;
+    if !record
+        .first()
+        .is_some_and(|record| record.del_flag.unwrap_or(0) == 0)
+    {
+        return Err("记录不存在".to_string());
+    }
+
+    ClipRecord::assign_slot(rb, &param.record_id, param.slot)
+        .await
+        .map_err(|e| {
+            log::error!("分配槽位失败: {}", e);
+            "分配槽位失败".to_string()
+        })?;
+    log::info!("记录{}已固定到槽位{}", param.record_id, param.slot);
+    notify_record_change();
+    Ok(())
+}
+
+/// 取消编号槽位的分配
+#[tauri::command]
+pub async fn clear_slot(slot: i32) -> Result<(), String> {
+    ensure_not_kiosk_mode()?;
+    check_slot(slot)?;
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    ClipRecord::clear_slot(rb, slot).await.map_err(|e| {
+        log::error!("取消槽位分配失败: {}", e);
+        "取消槽位分配失败".to_string()
+    })?;
+    notify_record_change();
+    Ok(())
+}
+
+/// 获取编号槽位上的记录，槽位未分配时返回None
+#[tauri::command]
+pub async fn get_slot(slot: i32) -> Result<Option<ClipRecordLiteDTO>, String> {
+    check_slot(slot)?;
+    Ok(get_slot_record(slot).await.map(to_lite_dto))
+}
@@ -0,0 +1,256 @@
+use std::collections::HashMap;
+
+use rbatis::RBatis;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::{
+    biz::{
+        clip_record::ClipRecord,
+        query_clip_record::{to_lite_dto_with, ClipRecordLiteDTO},
+        system_setting::{check_search_tags_enabled, ensure_not_kiosk_mode},
+    },
+    CONTEXT,
+};
+
+// 标签之间的分隔符，标签本身不能包含该字符
+pub const TAG_SEPARATOR: char = ',';
+// 单个标签的最大字符数
+pub const MAX_TAG_CHARS: usize = 32;
+// 每条记录最多的标签数
+pub const MAX_TAGS_PER_RECORD: usize = 20;
+// 按标签查询时每页的最大条数
+const MAX_TAG_PAGE_SIZE: i32 = 200;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagParam {
+    pub record_id: String,
+    pub tag: String,
+}
+
+/// 标签及使用该标签的记录数
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: usize,
+}
+
+/// 校验并规范化标签：去除首尾空白，不能为空、不能包含逗号、不能超过最大长度
+pub fn normalize_tag(tag: &str) -> Result<String, String> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Err("标签不能为空".to_string());
+    }
+    if tag.contains(TAG_SEPARATOR) {
+        return Err("标签不能包含逗号".to_string());
+    }
+    if tag.chars().count() > MAX_TAG_CHARS {
+        return Err(format!("标签不能超过{}个字符", MAX_TAG_CHARS));
+    }
+    Ok(tag.to_string())
+}
+
+/// 解析逗号连接的标签，忽略空标签和重复的标签，保持原有顺序
+pub fn parse_tags(tags: Option<&str>) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    for tag in tags.unwrap_or_default().split(TAG_SEPARATOR) {
+        let tag = tag.trim();
+        if !tag.is_empty() && !result.iter().any(|existing| existing == tag) {
+            result.push(tag.to_string());
+        }
+    }
+    result
+}
+
+/// 把标签连接为保存到数据库的格式，没有标签时返回None
+pub fn join_tags(tags: &[String]) -> Option<String> {
+    if tags.is_empty() {
+        return None;
+    }
+    Some(tags.join(&TAG_SEPARATOR.to_string()))
+}
+
+/// 规范化逗号连接的标签（去除空白和重复），用于比较和保存云端同步的标签
+pub fn normalize_tags(tags: Option<&str>) -> Option<String> {
+    join_tags(&parse_tags(tags))
+}
+
+/// 统计每个标签的记录数，按数量倒序，数量相同时按标签名排序
+pub fn count_tags(tag_columns: &[String]) -> Vec<TagCount> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for column in tag_columns {
+        for tag in parse_tags(Some(column)) {
+            *counts.entry(tag).or_insert(0) += 1;
+        }
+    }
+    let mut result: Vec<TagCount> = counts
+        .into_iter()
+        .map(|(tag, count)| TagCount { tag, count })
+        .collect();
+    result.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+    result
+}
+
+fn notify_record_change() {
+    let app_handle = CONTEXT.get::<AppHandle>();
+    let _ = app_handle.emit("clip_record_change", ());
+}
+
+/// 查询有效的记录，不存在或已删除时返回错误
+async fn select_active_record(rb: &RBatis, record_id: &str) -> Result<ClipRecord, String> {
+    let records = ClipRecord::select_by_id(rb, record_id).await.map_err(|e| {
+        log::error!("查询记录失败: {}", e);
+        "查询记录失败".to_string()
+    })?;
+    records
+        .into_iter()
+        .find(|record| record.del_flag.unwrap_or(0) == 0)
+        .ok_or("记录不存在".to_string())
+}
+
+/// 保存记录的标签并通知前端刷新
+async fn save_tags(rb: &RBatis, record_id: &str, tags: &[String]) -> Result<(), String> {
+    ClipRecord::update_tags(rb, record_id, join_tags(tags).as_deref())
+        .await
+        .map_err(|e| {
+            log::error!("保存标签失败: {}, 记录ID: {}", e, record_id);
+            "保存标签失败".to_string()
+        })?;
+    notify_record_change();
+    Ok(())
+}
+
+/// 给记录添加标签，返回添加后的全部标签，标签已存在时不重复添加
+#[tauri::command]
+pub async fn add_tag(param: TagParam) -> Result<Vec<String>, String> {
+    ensure_not_kiosk_mode()?;
+    let tag = normalize_tag(&param.tag)?;
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let record = select_active_record(rb, &param.record_id).await?;
+
+    let mut tags = parse_tags(record.tags.as_deref());
+    if tags.contains(&tag) {
+        return Ok(tags);
+    }
+    if tags.len() >= MAX_TAGS_PER_RECORD {
+        return Err(format!("每条记录最多添加{}个标签", MAX_TAGS_PER_RECORD));
+    }
+    tags.push(tag);
+    save_tags(rb, &param.record_id, &tags).await?;
+    Ok(tags)
+}
+
+/// 移除记录的标签，返回移除后的全部标签
+#[tauri::command]
+pub async fn remove_tag(param: TagParam) -> Result<Vec<String>, String> {
+    ensure_not_kiosk_mode()?;
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let record = select_active_record(rb, &param.record_id).await?;
+
+    let tag = param.tag.trim();
+    let mut tags = parse_tags(record.tags.as_deref());
+    let original_len = tags.len();
+    tags.retain(|existing| existing != tag);
+    if tags.len() == original_len {
+        return Ok(tags);
+    }
+    save_tags(rb, &param.record_id, &tags).await?;
+    Ok(tags)
+}
+
+/// 按标签分页查询记录，标签需要完全匹配
+#[tauri::command]
+pub async fn get_clip_records_by_tag(
+    tag: String,
+    limit: i32,
+    offset: i32,
+    reveal_sensitive: Option<bool>,
+) -> Result<Vec<ClipRecordLiteDTO>, String> {
+    let tag = normalize_tag(&tag)?;
+    let limit = limit.clamp(1, MAX_TAG_PAGE_SIZE);
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let records = ClipRecord::select_by_tag(rb, &tag, limit, offset.max(0))
+        .await
+        .map_err(|e| {
+            log::error!("按标签查询记录失败: {:?}", e);
+            "按标签查询记录失败".to_string()
+        })?;
+    let reveal_sensitive = reveal_sensitive.unwrap_or(false);
+    Ok(records
+        .into_iter()
+        .map(|item| to_lite_dto_with(item, reveal_sensitive))
+        .collect())
+}
+
+/// 获取所有标签及使用该标签的记录数
+#[tauri::command]
+pub async fn list_tags() -> Result<Vec<TagCount>, String> {
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let tag_columns = ClipRecord::select_all_tags(rb).await.map_err(|e| {
+        log::error!("查询标签失败: {}", e);
+        "查询标签失败".to_string()
+    })?;
+    Ok(count_tags(&tag_columns))
+}
+
+/// 开启标签搜索时，返回标签中包含关键词的记录ID，未开启时返回空列表
+pub async fn search_ids_by_tag(keyword: &str) -> Vec<String> {
+    let keyword = keyword.trim();
+    if keyword.is_empty() || !check_search_tags_enabled() {
+        return Vec::new();
+    }
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    match ClipRecord::select_ids_by_tag_keyword(rb, keyword).await {
+        Ok(ids) => ids,
+        Err(e) => {
+            log::error!("按标签搜索记录失败: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_tag() {
+        assert_eq!(normalize_tag("  工作 ").unwrap(), "工作");
+        assert!(normalize_tag("   ").is_err());
+        assert!(normalize_tag("a,b").is_err());
+        assert!(normalize_tag(&"标".repeat(MAX_TAG_CHARS)).is_ok());
+        assert!(normalize_tag(&"标".repeat(MAX_TAG_CHARS + 1)).is_err());
+    }
+
+    #[test]
+    fn test_parse_and_join_tags() {
+        assert_eq!(parse_tags(Some(" a, b,,a ,c ")), vec!["a", "b", "c"]);
+        assert!(parse_tags(None).is_empty());
+        assert_eq!(join_tags(&parse_tags(Some("a,b"))), Some("a,b".to_string()));
+        assert_eq!(join_tags(&[]), None);
+        assert_eq!(normalize_tags(Some(" , ")), None);
+        assert_eq!(normalize_tags(Some("x, y,x")), Some("x,y".to_string()));
+    }
+
+    #[test]
+    fn test_count_tags() {
+        let columns = vec!["a,b".to_string(), "b".to_string(), "c,b,a".to_string()];
+        assert_eq!(
+            count_tags(&columns),
+            vec![
+                TagCount {
+                    tag: "b".to_string(),
+                    count: 3
+                },
+                TagCount {
+                    tag: "a".to_string(),
+                    count: 2
+                },
+                TagCount {
+                    tag: "c".to_string(),
+                    count: 1
+                },
+            ]
+        );
+    }
+}
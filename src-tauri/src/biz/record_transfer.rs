@@ -0,0 +1,351 @@
+use std::path::{Path, PathBuf};
+
+use clipboard_listener::ClipType;
+use rbatis::{crud, impl_select, RBatis};
+use rbs::to_value;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use uuid::Uuid;
+
+use crate::{
+    biz::{
+        clip_async_queue::AsyncQueue,
+        clip_record::{ClipRecord, NOT_SYNCHRONIZED},
+        content_processor::ContentProcessor,
+        content_search::add_content_to_index,
+        copy_clip_record::{propagate_delete, CopyClipRecord},
+        history_cursor::reset_history_cursor,
+        system_setting::{
+            check_cloud_sync_enabled, check_kiosk_mode_enabled, ensure_not_kiosk_mode,
+        },
+        user_auth::get_stored_user_info,
+    },
+    errors::{AppError, AppResult},
+    utils::{
+        device_info::GLOBAL_DEVICE_ID,
+        file_dir::{get_data_dir, get_resources_dir},
+        path_utils::to_safe_string,
+    },
+    CONTEXT,
+};
+
+// 记录转移到其他账号
+// 1. 在当前账号下暂存记录：保存记录快照并复制资源文件到暂存目录，然后删除原记录（删除会同步到当前账号的云端）
+// 2. 退出登录并登录另一个账号，登录成功后暂存的记录自动导入为新的未同步记录，同步到新账号
+// 3. 切换账号前可以取消暂存，记录重新导入到当前账号
+// 暂存期间前端根据 record_transfer_staged 事件显示待转移记录，导入完成后收到 record_transfer_applied 事件
+
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct RecordTransfer {
+    // 原记录id
+    pub id: String,
+    // 记录类型
+    pub record_type: String,
+    // 记录快照（JSON），文本内容保持加密
+    pub snapshot: String,
+    // 暂存时登录的账号
+    pub source_user_id: u64,
+    pub source_account: String,
+    // 暂存目录中的资源文件名，图片和复制到resources/files下的文件才有
+    pub staged_resource: Option<String>,
+    // 暂存时间（毫秒）
+    pub staged_time: u64,
+}
+
+crud!(RecordTransfer {}, "record_transfer");
+impl_select!(RecordTransfer{select_by_id(id: &str) =>"`where id = #{id}`"});
+impl_select!(RecordTransfer{select_order_by_staged_time() =>"`order by staged_time asc`"});
+
+/// 返回给前端的待转移记录
+#[derive(Debug, Clone, Serialize)]
+pub struct StagedTransferInfo {
+    pub record_id: String,
+    pub record_type: String,
+    pub source_account: String,
+    pub staged_time: u64,
+}
+
+impl From<&RecordTransfer> for StagedTransferInfo {
+    fn from(transfer: &RecordTransfer) -> Self {
+        StagedTransferInfo {
+            record_id: transfer.id.clone(),
+            record_type: transfer.record_type.clone(),
+            source_account: transfer.source_account.clone(),
+            staged_time: transfer.staged_time,
+        }
+    }
+}
+
+/// 暂存一条记录，切换到其他账号登录后导入
+#[tauri::command]
+pub async fn stage_record_transfer(
+    param: CopyClipRecord,
+) -> Result<Vec<StagedTransferInfo>, String> {
+    ensure_not_kiosk_mode()?;
+    let user_info = get_stored_user_info().ok_or("请先登录需要转出记录的账号".to_string())?;
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+
+    let record = ClipRecord::select_by_id(rb, &param.record_id)
+        .await
+        .map_err(|e| format!("查询记录失败: {}", e))?
+        .into_iter()
+        .next()
+        .filter(|record| record.del_flag.unwrap_or(0) == 0)
+        .ok_or("未找到该记录".to_string())?;
+
+    let staged_resource = stage_resource_file(&record).map_err(|e| {
+        log::error!("暂存记录资源文件失败: {}, 记录ID: {}", e, record.id);
+        "暂存记录资源文件失败".to_string()
+    })?;
+    let snapshot = serde_json::to_string(&record).map_err(|e| format!("序列化记录失败: {}", e))?;
+    let transfer = RecordTransfer {
+        id: record.id.clone(),
+        record_type: record.r#type.clone(),
+        snapshot,
+        source_user_id: user_info.id,
+        source_account: user_info.account,
+        staged_resource,
+        staged_time: current_timestamp(),
+    };
+    if let Err(e) = RecordTransfer::insert(rb, &transfer).await {
+        remove_staged_resource(transfer.staged_resource.as_deref());
+        log::error!("保存待转移记录失败: {}", e);
+        return Err("保存待转移记录失败".to_string());
+    }
+
+    // 从当前账号删除原记录，立即同步删除
+    let ids = vec![record.id.clone()];
+    if let Err(e) = ClipRecord::update_del_by_ids(rb, &ids).await {
+        log::error!("删除已暂存的原记录失败: {}, 记录ID: {}", e, record.id);
+    } else {
+        reset_history_cursor();
+        propagate_delete(record).await;
+    }
+    log::info!("记录已暂存等待转移到其他账号: {}", param.record_id);
+
+    let staged = list_staged_transfers().await?;
+    let app_handle = CONTEXT.get::<AppHandle>();
+    let _ = app_handle.emit("clip_record_change", ());
+    let _ = app_handle.emit("record_transfer_staged", staged.len());
+    Ok(staged)
+}
+
+/// 获取等待转移的记录
+#[tauri::command]
+pub async fn list_staged_transfers() -> Result<Vec<StagedTransferInfo>, String> {
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let transfers = RecordTransfer::select_order_by_staged_time(rb)
+        .await
+        .map_err(|e| format!("查询待转移记录失败: {}", e))?;
+    Ok(transfers.iter().map(StagedTransferInfo::from).collect())
+}
+
+/// 取消转移，记录重新导入到当前账号
+#[tauri::command]
+pub async fn cancel_record_transfer(param: CopyClipRecord) -> Result<(), String> {
+    ensure_not_kiosk_mode()?;
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let transfer = RecordTransfer::select_by_id(rb, &param.record_id)
+        .await
+        .map_err(|e| format!("查询待转移记录失败: {}", e))?
+        .into_iter()
+        .next()
+        .ok_or("未找到待转移的记录".to_string())?;
+
+    import_transfer(rb, &transfer).await.map_err(|e| {
+        log::error!("取消转移时导入记录失败: {}, 记录ID: {}", e, transfer.id);
+        "导入记录失败".to_string()
+    })?;
+
+    let app_handle = CONTEXT.get::<AppHandle>();
+    let _ = app_handle.emit("clip_record_change", ());
+    Ok(())
+}
+
+/// 登录成功后导入其他账号暂存的记录，同一账号暂存的记录继续等待
+pub async fn apply_staged_transfers(user_id: u64) {
+    // 只读模式下不导入新记录，待退出后重新登录时再转入
+    if check_kiosk_mode_enabled() {
+        log::info!("只读模式已开启，暂不转入待转移记录");
+        return;
+    }
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let transfers = match RecordTransfer::select_order_by_staged_time(rb).await {
+        Ok(transfers) => transfers,
+        Err(e) => {
+            log::error!("查询待转移记录失败: {}", e);
+            return;
+        }
+    };
+
+    let mut applied = 0;
+    for transfer in transfers
+        .iter()
+        .filter(|transfer| transfer.source_user_id != user_id)
+    {
+        match import_transfer(rb, transfer).await {
+            Ok(_) => applied += 1,
+            Err(e) => log::error!("导入转移记录失败: {}, 记录ID: {}", e, transfer.id),
+        }
+    }
+
+    if applied > 0 {
+        log::info!("已将{}条记录转移到当前账号", applied);
+        let app_handle = CONTEXT.get::<AppHandle>();
+        let _ = app_handle.emit("clip_record_change", ());
+        let _ = app_handle.emit("record_transfer_applied", applied);
+    }
+}
+
+/// 把暂存的记录导入为新的未同步记录，并删除暂存数据
+async fn import_transfer(rb: &RBatis, transfer: &RecordTransfer) -> AppResult<()> {
+    let snapshot: ClipRecord = serde_json::from_str(&transfer.snapshot)?;
+    let mut record = ClipRecord {
+        id: Uuid::new_v4().to_string(),
+        sort: ClipRecord::get_next_sort(rb).await,
+        sync_flag: Some(NOT_SYNCHRONIZED),
+        sync_time: Some(0),
+        device_id: Some(GLOBAL_DEVICE_ID.clone()),
+        version: Some(1),
+        del_flag: Some(0),
+        cloud_source: Some(0),
+        skip_type: None,
+        del_time: None,
+        slot: None,
+        meta_only_sync: None,
+        modified_time: None,
+        ..snapshot
+    };
+
+    // 资源文件复制为新文件，避免清理原记录时被删除
+    let restored_resource = match transfer.staged_resource.as_deref() {
+        Some(staged) => Some(restore_resource_file(&mut record, staged)?),
+        None => None,
+    };
+
+    if let Err(e) = ClipRecord::insert(rb, &record).await {
+        if let Some(path) = restored_resource {
+            let _ = std::fs::remove_file(path);
+        }
+        return Err(AppError::Database(e));
+    }
+    rb.exec(
+        "DELETE FROM record_transfer WHERE id = ?",
+        vec![to_value!(&transfer.id)],
+    )
+    .await?;
+    remove_staged_resource(transfer.staged_resource.as_deref());
+    reset_history_cursor();
+
+    let index_content =
+        ContentProcessor::process_by_clip_type(&record.r#type, record.content.clone());
+    if let Err(e) = add_content_to_index(&record.id, &index_content).await {
+        log::error!("添加转移记录到搜索索引失败: {}", e);
+    }
+
+    if check_cloud_sync_enabled().await {
+        let async_queue = CONTEXT.get::<AsyncQueue<ClipRecord>>();
+        if !async_queue.is_full() {
+            if let Err(e) = async_queue.send_add(record.clone()).await {
+                log::error!("异步队列发送失败，转移的记录：{}, 异常:{}", record.id, e);
+            }
+        }
+    }
+    log::info!("转移记录已导入: {} -> {}", transfer.id, record.id);
+    Ok(())
+}
+
+/// 暂存目录
+fn get_staging_dir() -> AppResult<PathBuf> {
+    let dir = get_data_dir()
+        .ok_or_else(|| AppError::Config("无法获取数据目录".to_string()))?
+        .join("transfer_staging");
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// 记录在resources目录下的资源文件（相对路径），用户原文件不需要暂存
+fn resource_relative_path(record: &ClipRecord) -> Option<String> {
+    let content = record.content.as_str().unwrap_or_default();
+    if content.is_empty() {
+        return None;
+    }
+    match record.r#type.as_str() {
+        t if t == ClipType::Image.to_string() => Some(content.to_string()),
+        t if t == ClipType::File.to_string() && content.starts_with("files/") => {
+            Some(content.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// 复制记录的资源文件到暂存目录，返回暂存的文件名
+fn stage_resource_file(record: &ClipRecord) -> AppResult<Option<String>> {
+    let Some(relative_path) = resource_relative_path(record) else {
+        return Ok(None);
+    };
+    let resources_dir =
+        get_resources_dir().ok_or_else(|| AppError::Config("无法获取资源目录".to_string()))?;
+    let source = resources_dir.join(&relative_path);
+    if !source.exists() {
+        log::warn!(
+            "记录的资源文件不存在，只转移记录: {}",
+            to_safe_string(&source)
+        );
+        return Ok(None);
+    }
+    let file_name = Path::new(&relative_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let staged_name = format!("{}_{}", Uuid::new_v4(), file_name);
+    std::fs::copy(&source, get_staging_dir()?.join(&staged_name))?;
+    Ok(Some(staged_name))
+}
+
+/// 从暂存目录复制资源文件到resources目录并更新记录中的路径，返回新文件的路径
+fn restore_resource_file(record: &mut ClipRecord, staged_name: &str) -> AppResult<PathBuf> {
+    let resources_dir =
+        get_resources_dir().ok_or_else(|| AppError::Config("无法获取资源目录".to_string()))?;
+    let original = resource_relative_path(record).unwrap_or_default();
+    // 暂存文件名为 uuid_原文件名，导入时换一个新的uuid前缀
+    let file_name = staged_name
+        .split_once('_')
+        .map(|(_, name)| name)
+        .unwrap_or(staged_name);
+    let new_name = format!("{}_{}", Uuid::new_v4(), file_name);
+    let relative_path = if original.starts_with("files/") {
+        std::fs::create_dir_all(resources_dir.join("files"))?;
+        format!("files/{}", new_name)
+    } else {
+        new_name
+    };
+    let target = resources_dir.join(&relative_path);
+    std::fs::copy(get_staging_dir()?.join(staged_name), &target)?;
+
+    record.content = serde_json::Value::String(relative_path);
+    if original.starts_with("files/") {
+        record.local_file_path = Some(target.to_string_lossy().to_string());
+    }
+    Ok(target)
+}
+
+/// 删除暂存目录中的资源文件
+fn remove_staged_resource(staged_name: Option<&str>) {
+    let Some(staged_name) = staged_name else {
+        return;
+    };
+    if let Ok(dir) = get_staging_dir() {
+        if let Err(e) = std::fs::remove_file(dir.join(staged_name)) {
+            log::warn!("删除暂存的资源文件失败: {}", e);
+        }
+    }
+}
+
+fn current_timestamp() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
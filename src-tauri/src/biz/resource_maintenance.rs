@@ -0,0 +1,364 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use clipboard_listener::ClipType;
+use rbatis::RBatis;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    biz::{
+        clip_record::{
+            ClipRecord, SKIP_SYNC, SKIP_TYPE_STALE_TRANSFER, SYNCHRONIZED, SYNCHRONIZING,
+        },
+        clip_record_sync::compute_full_file_md5,
+        download_cloud_file::{relink_downloaded_file, RelinkDownloadedFileParam},
+        system_setting::ensure_not_kiosk_mode,
+    },
+    utils::{
+        file_dir::get_resources_dir, image_info::thumbnail_filename, path_utils::to_safe_string,
+    },
+    CONTEXT,
+};
+
+// 同步中状态超过该时长（小时）的本地记录视为卡住
+const DEFAULT_STALE_SYNCHRONIZING_HOURS: u64 = 24;
+
+/// 资源文件去重结果
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct DedupeResourceResult {
+    // 扫描的资源文件数量
+    pub scanned_files: usize,
+    // 存在重复内容的分组数量
+    pub duplicate_groups: usize,
+    // 删除的重复文件数量
+    pub removed_files: usize,
+    // 重新指向保留文件的记录数量
+    pub updated_records: usize,
+    // 回收的磁盘空间（字节）
+    pub bytes_reclaimed: u64,
+}
+
+/// 检测并合并resources/files目录下内容相同的资源文件
+/// 每组重复文件只保留一个，引用其他副本的记录改为指向保留的文件
+#[tauri::command]
+pub async fn dedupe_resource_files() -> Result<DedupeResourceResult, String> {
+    ensure_not_kiosk_mode()?;
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let files_dir = get_resources_dir().ok_or("资源目录获取失败")?.join("files");
+
+    let mut result = DedupeResourceResult::default();
+    if !files_dir.exists() {
+        return Ok(result);
+    }
+
+    // 1. 按内容MD5对磁盘上的资源文件分组
+    let entries = std::fs::read_dir(&files_dir).map_err(|e| format!("读取资源目录失败: {}", e))?;
+    let mut groups: HashMap<String, Vec<PathBuf>> = HashMap::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        result.scanned_files += 1;
+        match compute_full_file_md5(&path).await {
+            Ok(md5_str) => groups.entry(md5_str).or_default().push(path),
+            Err(e) => log::warn!("计算资源文件MD5失败，跳过: {:?}, 错误: {}", path, e),
+        }
+    }
+
+    // 2. 收集引用了资源文件的记录  local_file_path -> 记录ID列表
+    let records = ClipRecord::select_order_by(rb)
+        .await
+        .map_err(|e| format!("查询记录失败: {}", e))?;
+    let mut references: HashMap<String, Vec<String>> = HashMap::new();
+    for record in records {
+        if record.r#type != ClipType::File.to_string() {
+            continue;
+        }
+        if let Some(local_path) = record.local_file_path {
+            if !local_path.contains(":::") {
+                references.entry(local_path).or_default().push(record.id);
+            }
+        }
+    }
+
+    // 3. 每组保留一个文件（优先保留已被记录引用的），其余副本重定向后删除
+    for (_, mut paths) in groups {
+        if paths.len() < 2 {
+            continue;
+        }
+        result.duplicate_groups += 1;
+
+        paths.sort_by_key(|p| !references.contains_key(&to_safe_string(p)));
+        let survivor = to_safe_string(&paths[0]);
+
+        for duplicate in &paths[1..] {
+            let duplicate_str = to_safe_string(duplicate);
+            let mut repoint_failed = false;
+            if let Some(ids) = references.get(&duplicate_str) {
+                for id in ids {
+                    match ClipRecord::update_local_file_path(rb, id, &survivor).await {
+                        Ok(_) => result.updated_records += 1,
+                        Err(e) => {
+                            log::error!("记录{}重新指向资源文件失败: {}", id, e);
+                            repoint_failed = true;
+                        }
+                    }
+                }
+            }
+
+            // 仍有记录引用该文件时不能删除
+            if repoint_failed {
+                continue;
+            }
+
+            let file_size = std::fs::metadata(duplicate).map(|m| m.len()).unwrap_or(0);
+            match std::fs::remove_file(duplicate) {
+                Ok(_) => {
+                    result.removed_files += 1;
+                    result.bytes_reclaimed += file_size;
+                }
+                Err(e) => log::error!("删除重复资源文件失败: {}, 路径: {}", e, duplicate_str),
+            }
+        }
+    }
+
+    log::info!(
+        "资源文件去重完成 - 扫描: {}, 重复分组: {}, 删除文件: {}, 更新记录: {}, 回收空间: {}字节",
+        result.scanned_files,
+        result.duplicate_groups,
+        result.removed_files,
+        result.updated_records,
+        result.bytes_reclaimed
+    );
+
+    Ok(result)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConsistencyCheckParam {
+    // 是否自动修复发现的问题
+    #[serde(default)]
+    pub fix: bool,
+    // 同步中状态超过该时长（小时）视为卡住，默认24小时
+    pub stale_synchronizing_hours: Option<u64>,
+}
+
+/// 引用的文件不存在的记录
+#[derive(Debug, Clone, Serialize)]
+pub struct MissingFileRecord {
+    pub record_id: String,
+    pub record_type: String,
+    pub path: String,
+    // 修复方式：relinked-重新关联到本地文件 skipped-标记为跳过同步 空表示未修复
+    pub fixed: Option<String>,
+}
+
+/// 资源文件和数据库一致性检查结果
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct ConsistencyReport {
+    pub scanned_records: usize,
+    pub scanned_files: usize,
+    // 没有任何记录引用的资源文件（相对resources目录）
+    pub orphan_files: Vec<String>,
+    pub removed_orphan_files: usize,
+    pub orphan_bytes: u64,
+    // 引用的文件不存在的记录
+    pub missing_files: Vec<MissingFileRecord>,
+    // 长时间处于同步中的本地记录
+    pub stale_synchronizing: Vec<String>,
+    pub skipped_stale_records: usize,
+}
+
+/// 检查资源文件和数据库记录是否一致，fix 为 true 时自动修复
+/// 孤立的资源文件会被删除；文件丢失的云端记录尝试重新关联本地文件，其他记录标记为跳过同步；卡在同步中的记录标记为跳过同步，可以再次同步
+#[tauri::command]
+pub async fn check_consistency(param: ConsistencyCheckParam) -> Result<ConsistencyReport, String> {
+    if param.fix {
+        ensure_not_kiosk_mode()?;
+    }
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let resources_dir = get_resources_dir().ok_or("资源目录获取失败")?;
+    let records = ClipRecord::select_order_by(rb)
+        .await
+        .map_err(|e| format!("查询记录失败: {}", e))?;
+
+    let mut report = ConsistencyReport {
+        scanned_records: records.len(),
+        ..Default::default()
+    };
+
+    // 1. 收集记录引用的资源文件，检查有效记录引用的文件是否存在
+    let mut referenced: HashSet<String> = HashSet::new();
+    let stale_hours = param
+        .stale_synchronizing_hours
+        .unwrap_or(DEFAULT_STALE_SYNCHRONIZING_HOURS);
+    let stale_before = current_timestamp().saturating_sub(stale_hours * 3600 * 1000);
+    for record in &records {
+        let (files, required) = referenced_files(record, &resources_dir);
+        for path in &files {
+            referenced.insert(to_safe_string(path));
+        }
+        if record.del_flag.unwrap_or(0) != 0 {
+            continue;
+        }
+
+        // 云端记录的文件下载前本地没有文件，属于正常情况
+        let waiting_download =
+            record.cloud_source == Some(1) && record.sync_flag != Some(SYNCHRONIZED);
+        if !waiting_download {
+            if let Some(missing) = required.iter().find(|path| !path.exists()) {
+                report.missing_files.push(MissingFileRecord {
+                    record_id: record.id.clone(),
+                    record_type: record.r#type.clone(),
+                    path: to_safe_string(missing),
+                    fixed: None,
+                });
+            }
+        }
+
+        if record.sync_flag == Some(SYNCHRONIZING)
+            && record.cloud_source != Some(1)
+            && record
+                .sync_time
+                .filter(|t| *t > 0)
+                .unwrap_or(record.created)
+                < stale_before
+        {
+            report.stale_synchronizing.push(record.id.clone());
+        }
+    }
+
+    // 2. 扫描资源目录（根目录下的图片和files目录下的文件），找出没有记录引用的文件
+    for dir in [resources_dir.clone(), resources_dir.join("files")] {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_file() {
+                continue;
+            }
+            report.scanned_files += 1;
+            if referenced.contains(&to_safe_string(&path)) {
+                continue;
+            }
+            let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            report.orphan_bytes += size;
+            let relative = path
+                .strip_prefix(&resources_dir)
+                .map(|p| p.to_string_lossy().replace('\\', "/"))
+                .unwrap_or_else(|_| to_safe_string(&path));
+            if param.fix {
+                match std::fs::remove_file(&path) {
+                    Ok(_) => report.removed_orphan_files += 1,
+                    Err(e) => log::error!("删除孤立资源文件失败: {}, 路径: {}", e, relative),
+                }
+            }
+            report.orphan_files.push(relative);
+        }
+    }
+
+    if param.fix {
+        fix_missing_files(rb, &records, &mut report).await;
+        for id in &report.stale_synchronizing {
+            match ClipRecord::update_sync_flag_and_skip_type(
+                rb,
+                id,
+                SKIP_SYNC,
+                Some(SKIP_TYPE_STALE_TRANSFER),
+            )
+            .await
+            {
+                Ok(_) => report.skipped_stale_records += 1,
+                Err(e) => log::error!("标记卡住的同步记录失败: {}, 记录ID: {}", e, id),
+            }
+        }
+    }
+
+    log::info!(
+        "一致性检查完成 - 记录: {}, 文件: {}, 孤立文件: {}, 文件丢失记录: {}, 同步卡住记录: {}, 自动修复: {}",
+        report.scanned_records,
+        report.scanned_files,
+        report.orphan_files.len(),
+        report.missing_files.len(),
+        report.stale_synchronizing.len(),
+        param.fix
+    );
+    Ok(report)
+}
+
+/// 修复文件丢失的记录：等待下载的云端记录尝试重新关联本地文件，其他未同步的记录标记为不支持同步
+async fn fix_missing_files(rb: &RBatis, records: &[ClipRecord], report: &mut ConsistencyReport) {
+    for missing in report.missing_files.iter_mut() {
+        let Some(record) = records.iter().find(|r| r.id == missing.record_id) else {
+            continue;
+        };
+        let relinkable = record.cloud_source == Some(1)
+            && matches!(record.sync_flag, Some(flag) if flag == SYNCHRONIZING || flag == SKIP_SYNC);
+        if relinkable {
+            if let Ok(result) = relink_downloaded_file(RelinkDownloadedFileParam {
+                record_id: record.id.clone(),
+            })
+            .await
+            {
+                if result.found {
+                    missing.fixed = Some("relinked".to_string());
+                    continue;
+                }
+            }
+        }
+        if record.sync_flag == Some(SYNCHRONIZED) || record.sync_flag == Some(SKIP_SYNC) {
+            continue;
+        }
+        match ClipRecord::update_sync_flag_and_skip_type(rb, &record.id, SKIP_SYNC, Some(1)).await {
+            Ok(_) => missing.fixed = Some("skipped".to_string()),
+            Err(e) => log::error!("标记文件丢失的记录失败: {}, 记录ID: {}", e, record.id),
+        }
+    }
+}
+
+/// 记录引用的文件，返回（需要保留的文件, 记录正常使用必须存在的文件）
+/// 图片为resources目录下的文件，云端下载的图片使用local_file_path；文件为local_file_path中的所有路径；GIF动图还引用第一帧缩略图
+fn referenced_files(record: &ClipRecord, resources_dir: &Path) -> (Vec<PathBuf>, Vec<PathBuf>) {
+    let content = record.content.as_str().unwrap_or_default();
+    let local_paths: Vec<PathBuf> = record
+        .local_file_path
+        .as_deref()
+        .filter(|paths| !paths.is_empty())
+        .map(|paths| paths.split(":::").map(PathBuf::from).collect())
+        .unwrap_or_default();
+
+    let is_image = record.r#type == ClipType::Image.to_string();
+    if !is_image && record.r#type != ClipType::File.to_string() {
+        return (Vec::new(), Vec::new());
+    }
+    // 图片和复制到resources/files下的文件，content为相对resources目录的路径
+    let content_file = if (is_image && !content.is_empty()) || content.starts_with("files/") {
+        Some(resources_dir.join(content))
+    } else {
+        None
+    };
+
+    let required = if local_paths.is_empty() {
+        content_file.iter().cloned().collect()
+    } else {
+        local_paths.clone()
+    };
+    let mut referenced = local_paths;
+    if let Some(content_file) = content_file {
+        referenced.push(content_file);
+    }
+    if is_image {
+        referenced.push(resources_dir.join(thumbnail_filename(content)));
+    }
+    (referenced, required)
+}
+
+fn current_timestamp() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
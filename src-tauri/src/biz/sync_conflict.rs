@@ -0,0 +1,513 @@
+use rbatis::{crud, impl_select, RBatis};
+use rbs::to_value;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use uuid::Uuid;
+
+use crate::{
+    api::cloud_sync_api::ClipRecordParam,
+    biz::{
+        clip_record::{ClipRecord, NOT_SYNCHRONIZED},
+        record_tag::normalize_tags,
+        system_setting::ensure_not_kiosk_mode,
+    },
+    errors::{AppError, AppResult},
+    CONTEXT,
+};
+
+// 冲突日志最多保留的条数，超过后删除最早的记录
+pub const MAX_SYNC_CONFLICTS: i32 = 500;
+
+// 冲突类型
+pub const CONFLICT_REMOTE_DELETE: &str = "remote_delete"; // 云端删除了本地置顶或有未同步修改的记录
+pub const CONFLICT_LOCAL_DELETE_KEPT: &str = "local_delete_kept"; // 本地已删除的记录在云端仍然有效
+pub const CONFLICT_PINNED_MISMATCH: &str = "pinned_mismatch"; // 云端与本地的置顶状态不一致
+
+// 冲突处理结果
+pub const RESOLUTION_REMOTE_WINS: &str = "remote_wins"; // 以云端为准，本地修改被覆盖
+pub const RESOLUTION_LOCAL_WINS: &str = "local_wins"; // 保留本地状态，云端修改被忽略
+
+/// 云端与本地同一条记录的置顶状态不一致时的处理策略
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum SyncConflictPolicy {
+    // 保留本地状态
+    #[default]
+    LocalWins,
+    // 以云端为准
+    CloudWins,
+    // 以较新的一方为准（本地比较 sync_time、created 和本地修改时间中最晚的时间）
+    NewestWins,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct SyncConflict {
+    pub id: String,
+    // 本地记录id
+    pub record_id: String,
+    // 记录类型
+    pub record_type: String,
+    // 记录内容md5值
+    pub md5_str: String,
+    // 冲突类型
+    pub conflict_type: String,
+    // 冲突时本地记录的状态
+    pub local_state: String,
+    // 冲突时云端记录的状态
+    pub remote_state: String,
+    // 处理结果
+    pub resolution: String,
+    // 云端记录来源设备
+    pub remote_device_id: Option<String>,
+    // 冲突发生时间（毫秒）
+    pub created: u64,
+}
+
+crud!(SyncConflict {}, "sync_conflict");
+impl_select!(SyncConflict{select_order_by_limit(limit: i32, offset: i32) =>"`order by created desc limit #{limit} offset #{offset}`"});
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncConflictParam {
+    pub page: i32,
+    pub size: i32,
+}
+
+impl SyncConflict {
+    /// 批量写入冲突日志，并只保留最近的 MAX_SYNC_CONFLICTS 条
+    pub async fn insert_bounded(rb: &RBatis, conflicts: &[SyncConflict]) -> AppResult<()> {
+        if conflicts.is_empty() {
+            return Ok(());
+        }
+        let tx = rb.acquire_begin().await?;
+        let result = async {
+            SyncConflict::insert_batch(&tx, conflicts, conflicts.len() as u64).await?;
+            tx.exec(
+                "DELETE FROM sync_conflict WHERE id NOT IN (SELECT id FROM sync_conflict ORDER BY created DESC LIMIT ?)",
+                vec![to_value!(MAX_SYNC_CONFLICTS)],
+            )
+            .await?;
+            Ok::<(), rbatis::Error>(())
+        }
+        .await;
+        if let Err(e) = result {
+            let _ = tx.rollback().await;
+            return Err(AppError::Database(e));
+        }
+        tx.commit()
+            .await
+            .map_err(|e| AppError::Database(rbatis::Error::from(e)))
+    }
+
+    pub async fn clear_all(rb: &RBatis) -> AppResult<()> {
+        rb.exec("DELETE FROM sync_conflict", vec![]).await?;
+        Ok(())
+    }
+}
+
+/// 描述本地记录当前的状态
+fn describe_local(record: &ClipRecord) -> String {
+    let mut states = Vec::new();
+    if record.del_flag.unwrap_or(0) == 1 {
+        states.push("已删除");
+    }
+    if record.pinned_flag == 1 {
+        states.push("置顶");
+    }
+    if record.sync_flag == Some(NOT_SYNCHRONIZED) {
+        states.push("有未同步修改");
+    }
+    if states.is_empty() {
+        states.push("正常");
+    }
+    states.join("，")
+}
+
+/// 描述云端记录的状态
+fn describe_remote(clip: &ClipRecordParam) -> String {
+    let mut states = Vec::new();
+    if clip.del_flag.unwrap_or(0) == 1 {
+        states.push("已删除");
+    }
+    if clip.pinned_flag.unwrap_or(0) == 1 {
+        states.push("置顶");
+    }
+    if states.is_empty() {
+        states.push("正常");
+    }
+    states.join("，")
+}
+
+/// 本地记录最后一次变化的时间，上次同步后在本地修改的置顶、排序、标签同样计入
+fn local_timestamp(record: &ClipRecord) -> u64 {
+    record
+        .sync_time
+        .unwrap_or(0)
+        .max(record.created)
+        .max(record.modified_time.unwrap_or(0))
+}
+
+/// 云端记录最后一次变化的时间
+fn remote_timestamp(clip: &ClipRecordParam) -> u64 {
+    clip.sync_time.unwrap_or(0).max(clip.created.unwrap_or(0))
+}
+
+/// 按冲突策略判断是否以云端为准，时间相同时保留本地状态
+fn cloud_wins(local: &ClipRecord, clip: &ClipRecordParam, policy: SyncConflictPolicy) -> bool {
+    match policy {
+        SyncConflictPolicy::LocalWins => false,
+        SyncConflictPolicy::CloudWins => true,
+        SyncConflictPolicy::NewestWins => remote_timestamp(clip) > local_timestamp(local),
+    }
+}
+
+/// 云端与本地的有效记录置顶状态不一致时，按冲突策略判断是否采用云端的置顶状态
+/// 排序值只在各设备内有意义（本地会重新编号），不参与比较
+/// 任意一方已删除时由删除规则处理，不在这里合并
+pub fn should_apply_cloud_state(
+    local: &ClipRecord,
+    clip: &ClipRecordParam,
+    policy: SyncConflictPolicy,
+) -> bool {
+    if local.del_flag.unwrap_or(0) != 0 || clip.del_flag.unwrap_or(0) != 0 {
+        return false;
+    }
+    let pinned_differs = clip
+        .pinned_flag
+        .is_some_and(|pinned| pinned != local.pinned_flag);
+    pinned_differs && cloud_wins(local, clip, policy)
+}
+
+/// 云端与本地的有效记录标签不一致时，判断是否采用云端的标签，返回需要保存的标签
+/// 云端未携带标签字段时不处理；本地没有未同步的修改时直接采用云端标签，否则按冲突策略处理
+pub fn cloud_tags_to_apply(
+    local: &ClipRecord,
+    clip: &ClipRecordParam,
+    policy: SyncConflictPolicy,
+) -> Option<Option<String>> {
+    if local.del_flag.unwrap_or(0) != 0 || clip.del_flag.unwrap_or(0) != 0 {
+        return None;
+    }
+    let cloud_tags = normalize_tags(Some(clip.tags.as_deref()?));
+    if cloud_tags == normalize_tags(local.tags.as_deref()) {
+        return None;
+    }
+    if local.sync_flag == Some(NOT_SYNCHRONIZED) && !cloud_wins(local, clip, policy) {
+        return None;
+    }
+    Some(cloud_tags)
+}
+
+/// 比较云端拉取的记录与本地已存在的记录，返回合并时发生的冲突（没有冲突返回None）
+/// 合并规则：云端删除以云端为准，置顶状态按冲突策略处理，其余字段保留本地状态
+pub fn detect_conflict(
+    local: &ClipRecord,
+    clip: &ClipRecordParam,
+    policy: SyncConflictPolicy,
+) -> Option<SyncConflict> {
+    let local_deleted = local.del_flag.unwrap_or(0) == 1;
+    let remote_deleted = clip.del_flag.unwrap_or(0) == 1;
+
+    let (conflict_type, resolution) = if remote_deleted {
+        // 本地未删除，且本地置顶或有未同步修改时，云端删除会覆盖这些修改
+        if local_deleted || (local.pinned_flag != 1 && local.sync_flag != Some(NOT_SYNCHRONIZED)) {
+            return None;
+        }
+        (CONFLICT_REMOTE_DELETE, RESOLUTION_REMOTE_WINS)
+    } else if local_deleted {
+        (CONFLICT_LOCAL_DELETE_KEPT, RESOLUTION_LOCAL_WINS)
+    } else if clip
+        .pinned_flag
+        .is_some_and(|pinned| pinned != local.pinned_flag)
+    {
+        // 本地置顶的记录保留本地状态时，只是云端还没有这次置顶，不算冲突
+        if cloud_wins(local, clip, policy) {
+            (CONFLICT_PINNED_MISMATCH, RESOLUTION_REMOTE_WINS)
+        } else if local.pinned_flag != 1 {
+            (CONFLICT_PINNED_MISMATCH, RESOLUTION_LOCAL_WINS)
+        } else {
+            return None;
+        }
+    } else {
+        return None;
+    };
+
+    Some(SyncConflict {
+        id: Uuid::new_v4().to_string(),
+        record_id: local.id.clone(),
+        record_type: local.r#type.clone(),
+        md5_str: local.md5_str.clone(),
+        conflict_type: conflict_type.to_string(),
+        local_state: describe_local(local),
+        remote_state: describe_remote(clip),
+        resolution: resolution.to_string(),
+        remote_device_id: clip.device_id.clone(),
+        created: current_timestamp(),
+    })
+}
+
+/// 保存本次同步产生的冲突，并通知前端
+pub async fn record_sync_conflicts(rb: &RBatis, conflicts: Vec<SyncConflict>) {
+    if conflicts.is_empty() {
+        return;
+    }
+    let count = conflicts.len();
+    if let Err(e) = SyncConflict::insert_bounded(rb, &conflicts).await {
+        log::error!("保存同步冲突日志失败: {}", e);
+        return;
+    }
+    log::info!("本次云同步记录了{}条冲突", count);
+    if let Some(app_handle) = CONTEXT.try_get::<AppHandle>() {
+        let _ = app_handle.emit("sync_conflicts_recorded", count);
+    }
+}
+
+/// 获取同步冲突日志，按发生时间倒序
+#[tauri::command]
+pub async fn list_sync_conflicts(param: SyncConflictParam) -> Result<Vec<SyncConflict>, String> {
+    let offset = (param.page - 1) * param.size;
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    SyncConflict::select_order_by_limit(rb, param.size, offset)
+        .await
+        .map_err(|e| {
+            log::error!("查询同步冲突日志失败: {}", e);
+            "查询同步冲突日志失败".to_string()
+        })
+}
+
+/// 清空同步冲突日志
+#[tauri::command]
+pub async fn clear_sync_conflicts() -> Result<(), String> {
+    ensure_not_kiosk_mode()?;
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    SyncConflict::clear_all(rb).await.map_err(|e| {
+        log::error!("清空同步冲突日志失败: {}", e);
+        "清空同步冲突日志失败".to_string()
+    })
+}
+
+fn current_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local_record(
+        pinned_flag: i32,
+        sort: i32,
+        created: u64,
+        sync_time: Option<u64>,
+    ) -> ClipRecord {
+        ClipRecord {
+            id: "local".to_string(),
+            r#type: "Text".to_string(),
+            md5_str: "md5".to_string(),
+            created,
+            sort,
+            pinned_flag,
+            sync_flag: Some(2),
+            sync_time,
+            del_flag: Some(0),
+            ..Default::default()
+        }
+    }
+
+    fn cloud_record(
+        pinned_flag: i32,
+        sort: i32,
+        created: u64,
+        sync_time: Option<u64>,
+    ) -> ClipRecordParam {
+        let mut clip: ClipRecordParam = local_record(pinned_flag, sort, created, sync_time).into();
+        clip.id = None;
+        clip
+    }
+
+    #[test]
+    fn test_local_wins_keeps_local_state() {
+        let local = local_record(1, 10, 1_000, Some(2_000));
+        let clip = cloud_record(0, 20, 1_000, Some(5_000));
+        assert!(!should_apply_cloud_state(
+            &local,
+            &clip,
+            SyncConflictPolicy::LocalWins
+        ));
+        // 只在本地置顶、云端还没有这次置顶时不记录冲突
+        assert!(detect_conflict(&local, &clip, SyncConflictPolicy::LocalWins).is_none());
+
+        // 云端置顶被忽略时记录冲突
+        let local = local_record(0, 10, 1_000, Some(2_000));
+        let clip = cloud_record(1, 20, 1_000, Some(5_000));
+        let conflict = detect_conflict(&local, &clip, SyncConflictPolicy::LocalWins).unwrap();
+        assert_eq!(conflict.conflict_type, CONFLICT_PINNED_MISMATCH);
+        assert_eq!(conflict.resolution, RESOLUTION_LOCAL_WINS);
+    }
+
+    #[test]
+    fn test_cloud_wins_applies_cloud_state() {
+        // 云端时间较早时也以云端为准
+        let local = local_record(0, 10, 1_000, Some(5_000));
+        let clip = cloud_record(1, 10, 1_000, Some(2_000));
+        assert!(should_apply_cloud_state(
+            &local,
+            &clip,
+            SyncConflictPolicy::CloudWins
+        ));
+        let conflict = detect_conflict(&local, &clip, SyncConflictPolicy::CloudWins).unwrap();
+        assert_eq!(conflict.resolution, RESOLUTION_REMOTE_WINS);
+
+        // 只有排序不一致时不合并，排序值在各设备内独立编号
+        let clip = cloud_record(0, 30, 1_000, Some(2_000));
+        assert!(!should_apply_cloud_state(
+            &local,
+            &clip,
+            SyncConflictPolicy::CloudWins
+        ));
+        assert!(detect_conflict(&local, &clip, SyncConflictPolicy::CloudWins).is_none());
+    }
+
+    #[test]
+    fn test_newest_wins_compares_timestamps() {
+        let local = local_record(0, 10, 1_000, Some(3_000));
+
+        let newer_clip = cloud_record(1, 10, 1_000, Some(4_000));
+        assert!(should_apply_cloud_state(
+            &local,
+            &newer_clip,
+            SyncConflictPolicy::NewestWins
+        ));
+        let conflict =
+            detect_conflict(&local, &newer_clip, SyncConflictPolicy::NewestWins).unwrap();
+        assert_eq!(conflict.resolution, RESOLUTION_REMOTE_WINS);
+
+        let older_clip = cloud_record(1, 10, 1_000, Some(2_000));
+        assert!(!should_apply_cloud_state(
+            &local,
+            &older_clip,
+            SyncConflictPolicy::NewestWins
+        ));
+        let conflict =
+            detect_conflict(&local, &older_clip, SyncConflictPolicy::NewestWins).unwrap();
+        assert_eq!(conflict.resolution, RESOLUTION_LOCAL_WINS);
+
+        // 时间相同时保留本地状态
+        let same_clip = cloud_record(1, 10, 1_000, Some(3_000));
+        assert!(!should_apply_cloud_state(
+            &local,
+            &same_clip,
+            SyncConflictPolicy::NewestWins
+        ));
+
+        // 没有同步时间时使用创建时间比较
+        let local = local_record(0, 10, 1_000, None);
+        let clip = cloud_record(1, 10, 6_000, None);
+        assert!(should_apply_cloud_state(
+            &local,
+            &clip,
+            SyncConflictPolicy::NewestWins
+        ));
+
+        // 上次同步后在本地修改的置顶比云端更新时保留本地状态
+        let mut local = local_record(0, 10, 1_000, Some(3_000));
+        local.modified_time = Some(5_000);
+        assert!(!should_apply_cloud_state(
+            &local,
+            &newer_clip,
+            SyncConflictPolicy::NewestWins
+        ));
+        let conflict =
+            detect_conflict(&local, &newer_clip, SyncConflictPolicy::NewestWins).unwrap();
+        assert_eq!(conflict.resolution, RESOLUTION_LOCAL_WINS);
+    }
+
+    #[test]
+    fn test_deleted_records_are_not_merged() {
+        let mut local = local_record(0, 10, 1_000, Some(1_000));
+        let mut clip = cloud_record(1, 20, 1_000, Some(9_000));
+        clip.del_flag = Some(1);
+        assert!(!should_apply_cloud_state(
+            &local,
+            &clip,
+            SyncConflictPolicy::CloudWins
+        ));
+
+        clip.del_flag = Some(0);
+        local.del_flag = Some(1);
+        assert!(!should_apply_cloud_state(
+            &local,
+            &clip,
+            SyncConflictPolicy::CloudWins
+        ));
+    }
+
+    #[test]
+    fn test_cloud_tags_to_apply() {
+        let mut local = local_record(0, 10, 1_000, Some(3_000));
+        local.tags = Some("a".to_string());
+        let mut clip = cloud_record(0, 10, 1_000, Some(2_000));
+
+        // 云端未携带标签或标签相同时不处理
+        clip.tags = None;
+        assert!(cloud_tags_to_apply(&local, &clip, SyncConflictPolicy::LocalWins).is_none());
+        clip.tags = Some(" a ".to_string());
+        assert!(cloud_tags_to_apply(&local, &clip, SyncConflictPolicy::CloudWins).is_none());
+
+        // 本地已同步时直接采用云端标签，云端清空标签时本地同样清空
+        clip.tags = Some("a,b".to_string());
+        assert_eq!(
+            cloud_tags_to_apply(&local, &clip, SyncConflictPolicy::LocalWins),
+            Some(Some("a,b".to_string()))
+        );
+        clip.tags = Some(String::new());
+        assert_eq!(
+            cloud_tags_to_apply(&local, &clip, SyncConflictPolicy::LocalWins),
+            Some(None)
+        );
+
+        // 本地有未同步的修改时按冲突策略处理
+        local.sync_flag = Some(NOT_SYNCHRONIZED);
+        clip.tags = Some("b".to_string());
+        assert!(cloud_tags_to_apply(&local, &clip, SyncConflictPolicy::LocalWins).is_none());
+        assert!(cloud_tags_to_apply(&local, &clip, SyncConflictPolicy::NewestWins).is_none());
+        assert_eq!(
+            cloud_tags_to_apply(&local, &clip, SyncConflictPolicy::CloudWins),
+            Some(Some("b".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_newest_wins_keeps_newer_local_tag_edit() {
+        // 本地上次同步早于云端，但同步后在本地修改了标签，本地的修改更新
+        let mut local = local_record(0, 10, 1_000, Some(2_000));
+        local.tags = Some("local".to_string());
+        local.sync_flag = Some(NOT_SYNCHRONIZED);
+        local.modified_time = Some(5_000);
+        let mut clip = cloud_record(0, 10, 1_000, Some(3_000));
+        clip.tags = Some("cloud".to_string());
+        assert!(cloud_tags_to_apply(&local, &clip, SyncConflictPolicy::NewestWins).is_none());
+
+        // 云端在本地修改之后再次同步时采用云端标签
+        clip.sync_time = Some(6_000);
+        assert_eq!(
+            cloud_tags_to_apply(&local, &clip, SyncConflictPolicy::NewestWins),
+            Some(Some("cloud".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_same_state_needs_no_merge() {
+        let local = local_record(1, 10, 1_000, Some(1_000));
+        let clip = cloud_record(1, 10, 1_000, Some(9_000));
+        for policy in [
+            SyncConflictPolicy::LocalWins,
+            SyncConflictPolicy::CloudWins,
+            SyncConflictPolicy::NewestWins,
+        ] {
+            assert!(!should_apply_cloud_state(&local, &clip, policy));
+            assert!(detect_conflict(&local, &clip, policy).is_none());
+        }
+    }
+}
@@ -1,4 +1,9 @@
+use crate::api::cloud_sync_api::sync_server_time;
+use crate::biz::cloud_sync_timer::trigger_immediate_sync;
+use crate::biz::system_setting::ensure_not_kiosk_mode;
 use crate::errors::{AppError, AppResult};
+use crate::utils::lock_utils::GlobalSyncLock;
+use crate::CONTEXT;
 use rbatis::{crud, impl_select, RBatis};
 use rbs::to_value;
 use serde::{Deserialize, Serialize};
@@ -12,6 +17,22 @@ pub struct SyncTime {
 
 pub const TABLE_KEY: &str = "last_sync_ts";
 
+#[derive(Debug, Serialize)]
+pub struct SyncWatermark {
+    // 本地保存的最后同步时间（服务器时间戳）
+    pub last_sync_time: u64,
+    // 当前服务器时间，云服务不可用时为None
+    pub server_time: Option<u64>,
+    // 同步水位是否超前于服务器时间，超前时会漏拉云端记录
+    pub ahead_of_server: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResetSyncWatermarkParam {
+    // 从该时间戳开始重新拉取，为空时从0开始全量同步
+    pub timestamp: Option<u64>,
+}
+
 crud!(SyncTime {}, "sync_time");
 impl_select!(SyncTime{select_by_id(id: &str) =>"`where id = #{id}`"});
 impl_select!(SyncTime{select_last() =>"`order by last_time desc`"});
@@ -58,3 +79,58 @@ impl SyncTime {
         }
     }
 }
+
+/// 获取当前的同步水位，并与服务器时间比较
+#[tauri::command]
+pub async fn get_sync_watermark() -> Result<SyncWatermark, String> {
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let last_sync_time = SyncTime::select_last_time(rb).await;
+    let server_time = match sync_server_time().await {
+        Ok(time) => time,
+        Err(e) => {
+            log::warn!("获取服务器时间失败: {}", e);
+            None
+        }
+    };
+    Ok(SyncWatermark {
+        last_sync_time,
+        server_time,
+        ahead_of_server: server_time.is_some_and(|time| last_sync_time > time),
+    })
+}
+
+/// 重置同步水位并立即同步，从指定时间戳（为空时从0全量）重新拉取云端记录
+/// 拉取的记录按类型和md5与本地合并，重复拉取不会产生重复记录
+#[tauri::command]
+pub async fn reset_sync_watermark(param: ResetSyncWatermarkParam) -> Result<u64, String> {
+    ensure_not_kiosk_mode()?;
+    let timestamp = param.timestamp.unwrap_or(0);
+    if timestamp > 0 {
+        if let Ok(Some(server_time)) = sync_server_time().await {
+            if timestamp > server_time {
+                return Err("同步时间不能晚于服务器当前时间".to_string());
+            }
+        }
+    }
+
+    {
+        // 等待正在执行的同步完成，避免同步结束时把水位覆盖回去
+        let sync_lock: &GlobalSyncLock = CONTEXT.get::<GlobalSyncLock>();
+        let _guard = sync_lock.inner().lock().await;
+        let rb: &RBatis = CONTEXT.get::<RBatis>();
+        // 确保水位记录存在
+        let previous = SyncTime::select_last_time(rb).await;
+        SyncTime::update_last_time(rb, timestamp)
+            .await
+            .map_err(|e| {
+                log::error!("重置同步水位失败: {}", e);
+                "重置同步水位失败".to_string()
+            })?;
+        log::info!("同步水位已从{}重置为{}", previous, timestamp);
+    }
+
+    if let Err(e) = trigger_immediate_sync() {
+        log::warn!("重置同步水位后触发同步失败: {}", e);
+    }
+    Ok(timestamp)
+}
@@ -3,24 +3,39 @@ use std::{
     fs,
     marker::{Send, Sync},
     path::PathBuf,
-    sync::{Arc, RwLock},
+    sync::{atomic::Ordering, Arc, RwLock},
+    time::{Duration, Instant},
 };
 
+use clipboard_listener::ClipType;
+use rbatis::RBatis;
 use serde::{Deserialize, Serialize};
-use tauri::AppHandle;
+use tauri::{AppHandle, Emitter};
 use tauri_plugin_autostart::ManagerExt;
 use tauri_plugin_global_shortcut::GlobalShortcutExt;
+use tauri_plugin_http::reqwest;
 
 use crate::{
-    biz::cloud_sync_timer::trigger_immediate_sync,
+    api::{cloud_sync_api::check_sync_health, get_api_domain},
+    biz::background_task_status::set_background_tasks_paused,
+    biz::clip_record::ClipRecord,
+    biz::clip_record_clean::count_records_to_clean,
+    biz::cloud_sync_timer::{
+        reload_sync_interval, resume_sync_text_limit_skipped, trigger_immediate_sync,
+    },
+    biz::sync_conflict::SyncConflictPolicy,
+    biz::user_auth::{clear_stored_auth_data, notify_forced_logout},
     biz::vip_checker::VipChecker,
     errors::{AppError, AppResult},
-    global_shortcut::parse_shortcut,
+    global_shortcut::{parse_shortcut, register_sequential_paste_shortcut},
+    tray::{refresh_tray_monitoring_state, refresh_tray_tooltip},
     utils::{
         file_dir::get_config_dir,
+        kiosk_pin::{hash_pin, pin_lockout_millis, verify_pin},
         lock_utils::lock_utils::{safe_read_lock, safe_write_lock},
+        secure_store::SECURE_STORE,
     },
-    CONTEXT,
+    CAPTURE_ENABLED, CONTEXT,
 };
 
 // 默认超过这个大小的内容，使用布隆过滤器进行搜索   不会进行contains
@@ -32,6 +47,60 @@ pub static DEFAULT_DIRECT_CONTAINS_THRESHOLD: usize = 128 * 1024;
 // 定时任务间隔（秒）
 pub static SYNC_INTERVAL_SECONDS: u32 = 30;
 
+// 云同步定时任务的最小间隔（秒），防止设置过小频繁请求服务器
+pub static MIN_SYNC_INTERVAL_SECONDS: u32 = 5;
+
+// 删除记录后可撤销的宽限时间（秒）
+pub static DEFAULT_DELETE_UNDO_SECONDS: u32 = 5;
+
+// 逻辑删除记录的默认保留天数
+pub static DEFAULT_TOMBSTONE_RETENTION_DAYS: u32 = 30;
+
+// 单条剪贴板内容中耗时文件操作（计算MD5、复制文件）的默认最长等待时间（秒）
+pub static DEFAULT_EVENT_PROCESS_TIMEOUT_SECS: u32 = 30;
+
+// 收到剪贴板事件后等待合并后续事件的默认时间（毫秒）
+pub static DEFAULT_CAPTURE_COALESCE_MS: u32 = 150;
+
+// 排序值超过该值时重新编号的默认阈值
+pub static DEFAULT_SORT_NORMALIZE_THRESHOLD: u32 = 1_000_000;
+
+// 动图按原格式保存的默认最大字节数，超过时只保存第一帧（20MB）
+pub static DEFAULT_MAX_ANIMATED_IMAGE_BYTES: u64 = 20 * 1024 * 1024;
+
+// 同时上传文件的默认数量和最大数量
+pub static DEFAULT_FILE_UPLOAD_CONCURRENCY: u32 = 3;
+pub static MAX_FILE_UPLOAD_CONCURRENCY: u32 = 8;
+
+// 依次粘贴快捷键默认粘贴的最近记录条数及上限
+pub static DEFAULT_SEQUENTIAL_PASTE_COUNT: u32 = 3;
+pub static MAX_SEQUENTIAL_PASTE_COUNT: u32 = 20;
+
+// VIP降级后超出记录数限制的记录保留宽限期（天）
+pub static DEFAULT_VIP_DOWNGRADE_GRACE_DAYS: u32 = 7;
+
+// 符号链接处理方式
+pub const SYMLINK_MODE_FOLLOW: u32 = 0;
+pub const SYMLINK_MODE_REFERENCE: u32 = 1;
+pub const SYMLINK_MODE_SKIP: u32 = 2;
+
+// 单条文件记录默认最多包含的文件数
+pub static DEFAULT_MAX_FILES_PER_RECORD: u32 = 500;
+
+// 复制的文件数超过上限时的处理方式
+pub const FILES_OVERFLOW_TRUNCATE: u32 = 0;
+pub const FILES_OVERFLOW_SPLIT: u32 = 1;
+
+// 窗口显示时云端拉取记录的插入方式
+pub const CLOUD_INSERT_BY_TIME: u32 = 0;
+pub const CLOUD_INSERT_BELOW_TOP: u32 = 1;
+pub const CLOUD_INSERT_BOTTOM: u32 = 2;
+
+// macOS 自动粘贴方式
+pub const PASTE_METHOD_AUTO: u32 = 0; // 优先发送按键，判断按键可能无效时点击菜单
+pub const PASTE_METHOD_KEY_EVENT: u32 = 1; // 使用 CGEvent 发送 Cmd+V
+pub const PASTE_METHOD_MENU_ITEM: u32 = 2; // 通过辅助功能点击目标应用的“粘贴”菜单
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Settings {
     // 最大记录条数
@@ -52,6 +121,90 @@ pub struct Settings {
     pub direct_contains_threshold: Option<usize>,
     // 拉取云端记录的定时任务间隔时间
     pub cloud_sync_interval: u32,
+    // 删除记录后可撤销的宽限时间（秒），0 表示立即生效
+    pub delete_undo_seconds: Option<u32>,
+    // VIP降级后超限记录的保留宽限期（天），宽限期内超限记录仅隐藏不删除，0 表示立即删除
+    pub vip_downgrade_grace_days: Option<u32>,
+    // 是否为不支持的剪贴板类型保存文本占位记录 0 关闭 1 开启
+    pub store_unknown_types: Option<u32>,
+    // 上一条/下一条导航到达列表两端时是否循环 0 停止 1 循环
+    pub history_nav_wrap: Option<u32>,
+    // 记录自动过期天数，创建时间超过该天数且未置顶的记录会被删除（删除会同步到云端），0 表示不按时间过期
+    pub auto_expire_days: Option<u32>,
+    // 逻辑删除记录的保留天数，超过后本地物理删除并通知云端清理，0 表示不清理
+    pub tombstone_retention_days: Option<u32>,
+    // 是否对云同步请求进行HMAC签名（用于自建服务端校验请求完整性）0 关闭 1 开启
+    pub request_signing: Option<u32>,
+    // 需要记录的剪贴板类型（Text/Image/File），未包含的类型不会保存到本地，None 表示全部记录
+    pub capture_types: Option<Vec<String>>,
+    // 不记录的来源应用（进程名或应用名，不区分大小写，可省略 .exe 后缀），复制时前台为这些应用的内容会被丢弃
+    pub app_denylist: Option<Vec<String>>,
+    // 是否遵循密码管理器等应用设置的隐藏提示（ConcealedType 等），带提示的内容不记录 0 关闭 1 开启
+    pub respect_concealed_hint: Option<u32>,
+    // 文本脱敏规则（正则表达式），匹配的内容在保存前替换为 [REDACTED]，无法编译的规则会被跳过
+    pub redaction_rules: Option<Vec<String>>,
+    // 保存复制的文本前依次应用的转换：trim_trailing_whitespace 去除行尾空白、normalize_newlines 统一换行符为 \n、collapse_blank_lines 合并连续空行
+    pub text_transforms: Option<Vec<String>>,
+    // 复制的文件是符号链接时的处理方式 0 复制链接指向的内容 1 只记录链接目标路径 2 跳过
+    pub symlink_mode: Option<u32>,
+    // 单条文件记录最多包含的文件数，0 表示不限制
+    pub max_files_per_record: Option<u32>,
+    // 复制的文件数超过上限时的处理方式 0 截断只保留前面的文件 1 拆分为多条记录
+    pub files_overflow_mode: Option<u32>,
+    // 是否清理复制文本中的零宽字符、控制字符等不可见字符 0 关闭 1 开启
+    pub text_sanitize: Option<u32>,
+    // 清理文本时是否同时保留原始文本 0 关闭 1 开启
+    pub keep_raw_text: Option<u32>,
+    // 是否尝试恢复按错误编码解码的乱码文本（GBK、UTF-8被按Latin-1解码）0 关闭 1 开启
+    pub text_encoding_recovery: Option<u32>,
+    // 复制单个网址时是否在后台获取网页标题 0 关闭 1 开启
+    pub link_title: Option<u32>,
+    // 搜索时是否同时匹配记录的标签 0 关闭 1 开启
+    pub search_tags: Option<u32>,
+    // 图片最小字节数，小于该值的图片不记录，0 表示不限制
+    pub min_image_bytes: Option<u64>,
+    // 图片最小宽高（像素），宽或高小于该值的图片不记录，0 表示不限制
+    pub min_image_dimensions: Option<u32>,
+    // 参与云同步的单条文本最大字节数（加密后），超过的文本只保留在本地，0 表示不限制
+    pub max_sync_text_bytes: Option<u64>,
+    // 参与云同步的单个图片、文件最大字节数，在VIP限制内进一步限制，0 表示只受VIP限制
+    pub max_sync_image_bytes: Option<u64>,
+    pub max_sync_file_bytes: Option<u64>,
+    // 窗口显示时云端拉取记录的插入方式 0 按同步时间合并 1 不排到第一条之前 2 插入到最后
+    pub cloud_insert_policy: Option<u32>,
+    // 云端与本地同一条记录的置顶、排序不一致时的处理策略 LocalWins 保留本地 CloudWins 以云端为准 NewestWins 以较新的为准
+    pub sync_conflict_policy: Option<SyncConflictPolicy>,
+    // macOS 自动粘贴方式 0 自动 1 发送按键 2 点击粘贴菜单
+    pub paste_method: Option<u32>,
+    // 处理单条剪贴板内容时耗时文件操作的最长等待时间（秒），超时跳过该内容，0 表示不限制
+    pub event_process_timeout_secs: Option<u32>,
+    // 文本达到该字节数时压缩后再加密保存，减小数据库体积，0 表示不压缩
+    pub text_compression_min_bytes: Option<u64>,
+    // 开发者模式：暂停云同步、文件上传下载定时任务和同步队列，不再自动记录剪贴板，只响应手动命令 0 关闭 1 开启
+    pub developer_mode: Option<u32>,
+    // 是否监听剪贴板，关闭后不记录也不同步，重启后保持 0 关闭 1 开启
+    pub monitoring_enabled: Option<u32>,
+    // 收到剪贴板事件后等待的时间（毫秒），期间的连续事件合并为一条记录并优先保存HTML/RTF格式，0 表示不等待
+    pub capture_coalesce_ms: Option<u32>,
+    // 复制的GIF、APNG动图是否按原格式保存，关闭时只保存第一帧 0 关闭 1 开启
+    pub keep_animated_images: Option<u32>,
+    // 按原格式保存的动图最大字节数，超过时只保存第一帧，0 表示不限制
+    pub max_animated_image_bytes: Option<u64>,
+    // 去重时间窗口（秒），再次复制的内容对应的记录早于该时间时按新记录保存，窗口内只移到最前，0 表示始终只移到最前
+    // 旧版本按小时保存在 dedup_window_hours 中，加载设置时换算为秒
+    pub dedup_window_secs: Option<u32>,
+    // 排序值超过该值时在清理任务中重新编号为连续值，防止排序值无限增长，0 表示只在接近溢出时整理
+    pub sort_normalize_threshold: Option<u32>,
+    // 只读模式（共享电脑使用）：可以查看和粘贴历史记录，但不记录新内容、不能删除或修改记录、不进行云同步 0 关闭 1 开启
+    pub kiosk_mode: Option<u32>,
+    // 自建云同步服务地址（必须是 https 地址，如 https://sync.example.com），为空时使用内置服务地址
+    pub sync_base_url: Option<String>,
+    // 同时上传的文件数（1-8），待上传的图片、文件较多时加快上传速度
+    pub file_upload_concurrency: Option<u32>,
+    // 依次粘贴最近几条记录的快捷键（如 Ctrl+Shift+V），为空时不注册
+    pub sequential_paste_shortcut: Option<String>,
+    // 依次粘贴快捷键粘贴的最近记录条数（1-20），按复制的先后顺序粘贴
+    pub sequential_paste_count: Option<u32>,
 }
 
 unsafe impl Send for Settings {}
@@ -79,6 +232,48 @@ impl Default for Settings {
             bloom_filter_trust_threshold: Some(DEFAULT_BLOOM_FILTER_TRUST_THRESHOLD), // 默认1MB
             direct_contains_threshold: Some(DEFAULT_DIRECT_CONTAINS_THRESHOLD), // 默认128KB
             cloud_sync_interval: SYNC_INTERVAL_SECONDS, // 默认30秒
+            delete_undo_seconds: Some(DEFAULT_DELETE_UNDO_SECONDS), // 默认5秒
+            vip_downgrade_grace_days: Some(DEFAULT_VIP_DOWNGRADE_GRACE_DAYS), // 默认7天
+            store_unknown_types: Some(0), // 默认不记录不支持的类型
+            history_nav_wrap: Some(0), // 默认到达两端时停止
+            auto_expire_days: Some(0), // 默认不按时间过期
+            tombstone_retention_days: Some(DEFAULT_TOMBSTONE_RETENTION_DAYS), // 默认30天
+            request_signing: Some(0), // 默认不签名
+            capture_types: None,   // 默认记录全部类型
+            app_denylist: None,    // 默认不排除任何应用
+            respect_concealed_hint: Some(1), // 默认不记录隐藏内容
+            redaction_rules: None, // 默认不脱敏
+            text_transforms: None, // 默认不转换
+            symlink_mode: Some(SYMLINK_MODE_FOLLOW), // 默认复制链接指向的内容
+            max_files_per_record: Some(DEFAULT_MAX_FILES_PER_RECORD), // 默认500个
+            files_overflow_mode: Some(FILES_OVERFLOW_TRUNCATE), // 默认截断
+            text_sanitize: Some(0), // 默认不清理
+            keep_raw_text: Some(0), // 默认不保留原始文本
+            text_encoding_recovery: Some(0), // 默认不恢复
+            link_title: Some(0),   // 默认不获取网页标题
+            search_tags: Some(0),  // 默认只搜索内容
+            min_image_bytes: Some(0), // 默认不限制
+            min_image_dimensions: Some(0), // 默认不限制
+            max_sync_text_bytes: Some(0), // 默认不限制
+            max_sync_image_bytes: Some(0), // 默认只受VIP限制
+            max_sync_file_bytes: Some(0), // 默认只受VIP限制
+            cloud_insert_policy: Some(CLOUD_INSERT_BY_TIME), // 默认按同步时间合并
+            sync_conflict_policy: Some(SyncConflictPolicy::LocalWins), // 默认保留本地状态
+            paste_method: Some(PASTE_METHOD_AUTO), // 默认自动选择
+            event_process_timeout_secs: Some(DEFAULT_EVENT_PROCESS_TIMEOUT_SECS), // 默认30秒
+            text_compression_min_bytes: Some(0), // 默认不压缩
+            developer_mode: Some(0), // 默认关闭
+            monitoring_enabled: Some(1), // 默认开启
+            capture_coalesce_ms: Some(DEFAULT_CAPTURE_COALESCE_MS), // 默认150毫秒
+            keep_animated_images: Some(1), // 默认按原格式保存
+            max_animated_image_bytes: Some(DEFAULT_MAX_ANIMATED_IMAGE_BYTES), // 默认20MB
+            dedup_window_secs: Some(0), // 默认始终只移到最前
+            sort_normalize_threshold: Some(DEFAULT_SORT_NORMALIZE_THRESHOLD), // 默认100万
+            kiosk_mode: Some(0),   // 默认关闭
+            sync_base_url: None,   // 默认使用内置服务地址
+            file_upload_concurrency: Some(DEFAULT_FILE_UPLOAD_CONCURRENCY), // 默认同时上传3个
+            sequential_paste_shortcut: None, // 默认不注册
+            sequential_paste_count: Some(DEFAULT_SEQUENTIAL_PASTE_COUNT), // 默认3条
         }
     }
 }
@@ -89,6 +284,9 @@ pub fn init_settings() {
     // 把系统配置存储到上下文中，使用 RwLock 允许并发读取
     CONTEXT.set(Arc::new(RwLock::new(settings.clone())));
 
+    // 开发者模式或关闭监听时后台任务启动后保持暂停
+    refresh_background_tasks_paused();
+
     // 如果配置文件不存在，使用已加载的设置创建默认配置文件
     create_default_config_if_not_exists(&settings);
 }
@@ -120,8 +318,11 @@ pub fn load_settings() -> Settings {
     if let Some(path) = get_settings_file_path() {
         if path.exists() {
             let data = fs::read_to_string(&path).unwrap_or_default();
-            if let Ok(settings) = serde_json::from_str(&data) {
-                return settings;
+            if let Ok(mut value) = serde_json::from_str::<serde_json::Value>(&data) {
+                migrate_settings(&mut value);
+                if let Ok(settings) = serde_json::from_value(value) {
+                    return settings;
+                }
             }
         }
     }
@@ -129,8 +330,29 @@ pub fn load_settings() -> Settings {
     Settings::default()
 }
 
+/// 迁移旧版本的设置项，下次保存设置时写入新的字段
+fn migrate_settings(value: &mut serde_json::Value) {
+    let Some(settings) = value.as_object_mut() else {
+        return;
+    };
+    // 去重时间窗口由小时改为秒，已设置秒数时以秒数为准
+    if let Some(hours) = settings.remove("dedup_window_hours") {
+        let secs = settings
+            .get("dedup_window_secs")
+            .and_then(|secs| secs.as_u64())
+            .unwrap_or(0);
+        if secs == 0 {
+            let hours = hours.as_u64().unwrap_or(0);
+            let secs = hours.saturating_mul(3600).min(u32::MAX as u64);
+            settings.insert("dedup_window_secs".to_string(), secs.into());
+        }
+    }
+}
+
 #[tauri::command]
-pub async fn save_settings(settings: Settings) -> Result<(), String> {
+pub async fn save_settings(mut settings: Settings) -> Result<(), String> {
+    ensure_not_kiosk_mode()?;
+
     // 1. 验证设置的有效性
     validate_settings(&settings)
         .await
@@ -142,6 +364,15 @@ pub async fn save_settings(settings: Settings) -> Result<(), String> {
         let current = safe_read_lock(&lock).map_err(|e| e.to_string())?;
         current.clone()
     };
+    // 只读模式只能通过专门的命令开启和退出
+    settings.kiosk_mode = current_settings.kiosk_mode;
+
+    // 同步服务地址变化时强制退出登录并关闭云同步，需要在新的服务地址重新登录
+    let sync_base_url_changed =
+        effective_sync_base_url(&settings) != effective_sync_base_url(&current_settings);
+    if sync_base_url_changed {
+        settings.cloud_sync = 0;
+    }
 
     // 3. 尝试应用新设置（按顺序执行，失败时回滚）
     let mut applied_settings = Vec::new();
@@ -160,6 +391,34 @@ pub async fn save_settings(settings: Settings) -> Result<(), String> {
         }
     }
 
+    // 3.1.1 更新依次粘贴快捷键，主快捷键更新时已取消注册所有快捷键，需要重新注册
+    let sequential_shortcut = settings
+        .sequential_paste_shortcut
+        .clone()
+        .unwrap_or_default();
+    let current_sequential_shortcut = current_settings
+        .sequential_paste_shortcut
+        .clone()
+        .unwrap_or_default();
+    let shortcut_changed = settings.shortcut_key != current_settings.shortcut_key;
+    if shortcut_changed || sequential_shortcut != current_sequential_shortcut {
+        let app_handle = CONTEXT.get::<AppHandle>();
+        if !shortcut_changed && !current_sequential_shortcut.is_empty() {
+            let _ = app_handle
+                .global_shortcut()
+                .unregister(parse_shortcut(&current_sequential_shortcut));
+        }
+        if !sequential_shortcut.is_empty() {
+            if let Err(e) = register_sequential_paste_shortcut(app_handle, &sequential_shortcut) {
+                log::error!("注册依次粘贴快捷键失败: {}", e);
+                if let Err(rollback_err) = rollback_settings(&applied_settings).await {
+                    log::error!("回滚设置失败: {}", rollback_err);
+                }
+                return Err(format!("依次粘贴快捷键设置失败: {}", e));
+            }
+        }
+    }
+
     // 3.2 验证云同步权限
     if settings.cloud_sync != current_settings.cloud_sync && settings.cloud_sync == 1 {
         // 用户尝试开启云同步，需要验证登录状态
@@ -202,17 +461,68 @@ pub async fn save_settings(settings: Settings) -> Result<(), String> {
         }
     }
 
+    // 3.5 切换服务地址前清除登录令牌，避免旧的令牌发送到新的服务地址
+    if sync_base_url_changed {
+        if let Err(e) = clear_stored_auth_data() {
+            if let Err(rollback_err) = rollback_settings(&applied_settings).await {
+                log::error!("回滚设置失败: {}", rollback_err);
+            }
+            return Err(format!("清除登录状态失败: {}", e));
+        }
+        log::info!("同步服务地址已变化，已退出登录");
+    }
+
     // 4. 先更新上下文中的设置
     let need_trigger_sync =
         settings.cloud_sync != current_settings.cloud_sync && settings.cloud_sync == 1;
+    let sync_interval_changed =
+        settings.cloud_sync_interval != current_settings.cloud_sync_interval;
+    let capture_types_changed = settings.capture_types != current_settings.capture_types;
+    let sync_text_limit_changed =
+        settings.max_sync_text_bytes != current_settings.max_sync_text_bytes;
+    let developer_mode_changed = settings.developer_mode != current_settings.developer_mode;
+    let developer_mode = settings.developer_mode.unwrap_or(0) == 1;
+    let monitoring_changed = settings.monitoring_enabled != current_settings.monitoring_enabled;
+    let monitoring_enabled = settings.monitoring_enabled.unwrap_or(1) == 1;
     {
         let lock = CONTEXT.get::<Arc<RwLock<Settings>>>().clone();
         let mut current = safe_write_lock(&lock).map_err(|e| e.to_string())?;
         *current = settings;
     }
 
+    // 通知前端已退出登录、云同步已关闭
+    if sync_base_url_changed {
+        notify_forced_logout().await;
+    }
+
+    // 记录类型变化后更新托盘提示，提醒用户哪些类型不会被记录
+    if capture_types_changed {
+        refresh_tray_tooltip();
+    }
+
+    // 开发者模式切换后暂停或恢复后台任务
+    if developer_mode_changed {
+        apply_developer_mode(developer_mode);
+    }
+
+    // 监听状态切换后暂停或恢复后台任务，并更新托盘图标
+    if monitoring_changed {
+        apply_monitoring_enabled(monitoring_enabled);
+    }
+
+    // 同步间隔变化后，定时任务从下一次执行开始使用新的间隔
+    if sync_interval_changed {
+        reload_sync_interval();
+    }
+
+    // 同步文本大小限制变化后，恢复不再超限的文本记录
+    let mut resumed_sync_text = 0;
+    if sync_text_limit_changed {
+        resumed_sync_text = resume_sync_text_limit_skipped().await;
+    }
+
     // 5. 检查是否需要触发立即云同步（在设置更新后）
-    if need_trigger_sync {
+    if need_trigger_sync || resumed_sync_text > 0 {
         if let Err(e) = trigger_immediate_sync() {
             log::warn!("触发立即云同步失败: {}", e);
             // 不返回错误，因为设置保存成功了，只是立即同步失败
@@ -267,9 +577,53 @@ async fn validate_settings(settings: &Settings) -> AppResult<()> {
         ));
     }
 
+    // 5. 验证依次粘贴快捷键，不能与打开窗口的快捷键相同
+    if let Some(shortcut) = settings.sequential_paste_shortcut.as_deref() {
+        if !shortcut.is_empty() {
+            if !is_valid_shortcut_format(shortcut) {
+                return Err(AppError::Config(
+                    "依次粘贴快捷键格式错误，请使用如 Ctrl+Shift+V 的组合键".to_string(),
+                ));
+            }
+            if shortcut == settings.shortcut_key {
+                return Err(AppError::Config(
+                    "依次粘贴快捷键不能与打开窗口的快捷键相同".to_string(),
+                ));
+            }
+        }
+    }
+
+    // 6. 验证自建同步服务地址
+    if let Some(base_url) = settings.sync_base_url.as_deref() {
+        if !base_url.trim().is_empty() {
+            normalize_sync_base_url(base_url)?;
+        }
+    }
+
     Ok(())
 }
 
+/// 校验自建同步服务地址，必须是带主机名的 https 地址且不含查询参数，返回去掉末尾 / 的地址
+pub fn normalize_sync_base_url(base_url: &str) -> AppResult<String> {
+    let base_url = base_url.trim();
+    let url = reqwest::Url::parse(base_url)
+        .map_err(|e| AppError::Config(format!("同步服务地址格式错误: {}", e)))?;
+    if url.scheme() != "https" {
+        return Err(AppError::Config(
+            "同步服务地址必须以 https:// 开头".to_string(),
+        ));
+    }
+    if url.host_str().map_or(true, |host| host.is_empty()) {
+        return Err(AppError::Config("同步服务地址缺少主机名".to_string()));
+    }
+    if url.query().is_some() || url.fragment().is_some() {
+        return Err(AppError::Config(
+            "同步服务地址不能包含查询参数或锚点".to_string(),
+        ));
+    }
+    Ok(base_url.trim_end_matches('/').to_string())
+}
+
 // 验证快捷键格式
 fn is_valid_shortcut_format(shortcut: &str) -> bool {
     let parts: Vec<&str> = shortcut.split('+').collect();
@@ -439,11 +793,833 @@ pub async fn validate_shortcut(shortcut: String) -> Result<bool, String> {
     Ok(true)
 }
 
+/// 设置变更预览结果，保存前告知用户新设置会带来的影响
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct SettingsChangePreview {
+    // 新设置是否能通过校验
+    pub valid: bool,
+    // 校验失败的原因
+    pub validation_error: Option<String>,
+    // 保存后超过最大记录数或自动过期、将被清理的记录条数
+    pub records_to_trim: i64,
+    // 云同步是否将被开启
+    pub cloud_sync_will_enable: bool,
+    // 云同步是否将被关闭
+    pub cloud_sync_will_disable: bool,
+    // 开启云同步的权限检查失败原因
+    pub cloud_sync_error: Option<String>,
+    // 快捷键是否变化
+    pub shortcut_changed: bool,
+    // 新快捷键是否可用
+    pub shortcut_valid: bool,
+    // 开机自启是否变化
+    pub autostart_changed: bool,
+    // 是否将停止剪贴板监听
+    pub monitoring_will_stop: bool,
+    // 是否将开启开发者模式（后台任务暂停）
+    pub developer_mode_will_enable: bool,
+    // 按新的保留天数下次清理时将物理删除的删除记录条数
+    pub tombstones_to_purge: usize,
+    // 其他提示信息
+    pub warnings: Vec<String>,
+}
+
+/// 预览设置变更的影响，只做检查不应用任何设置
+#[tauri::command]
+pub async fn preview_settings_change(settings: Settings) -> Result<SettingsChangePreview, String> {
+    let current_settings = {
+        let lock = CONTEXT.get::<Arc<RwLock<Settings>>>().clone();
+        let current = safe_read_lock(&lock).map_err(|e| e.to_string())?;
+        current.clone()
+    };
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let mut preview = SettingsChangePreview::default();
+
+    // 1. 校验设置
+    match validate_settings(&settings).await {
+        Ok(_) => preview.valid = true,
+        Err(e) => preview.validation_error = Some(e.to_string()),
+    }
+
+    // 2. 超过最大记录数或自动过期将被清理的记录，收藏的记录不会被清理
+    preview.records_to_trim = count_records_to_clean(
+        rb,
+        settings.max_records,
+        settings.auto_expire_days.unwrap_or(0),
+    )
+    .await;
+    if preview.records_to_trim > 0 {
+        preview
+            .warnings
+            .push(format!("保存后将清理{}条记录", preview.records_to_trim));
+    }
+
+    // 3. 云同步开关
+    if settings.cloud_sync != current_settings.cloud_sync {
+        if settings.cloud_sync == 1 {
+            preview.cloud_sync_will_enable = true;
+            if let Err(e) = validate_cloud_sync_permission().await {
+                preview.cloud_sync_error = Some(e);
+            }
+        } else {
+            preview.cloud_sync_will_disable = true;
+            preview
+                .warnings
+                .push("关闭云同步后，本地新记录不会再同步到其他设备".to_string());
+        }
+    }
+
+    // 4. 快捷键
+    preview.shortcut_changed = settings.shortcut_key != current_settings.shortcut_key;
+    preview.shortcut_valid = validate_shortcut(settings.shortcut_key.clone())
+        .await
+        .unwrap_or(false);
+
+    // 5. 开机自启、监听状态和开发者模式
+    preview.autostart_changed = settings.auto_start != current_settings.auto_start;
+    preview.monitoring_will_stop = current_settings.monitoring_enabled.unwrap_or(1) == 1
+        && settings.monitoring_enabled.unwrap_or(1) == 0;
+    if preview.monitoring_will_stop {
+        preview
+            .warnings
+            .push("停止监听后将不再记录新的剪贴板内容".to_string());
+    }
+    preview.developer_mode_will_enable = current_settings.developer_mode.unwrap_or(0) == 0
+        && settings.developer_mode.unwrap_or(0) == 1;
+    if preview.developer_mode_will_enable {
+        preview
+            .warnings
+            .push("开启开发者模式后将暂停云同步等后台任务".to_string());
+    }
+
+    // 6. 删除记录保留天数
+    let retention_days = settings
+        .tombstone_retention_days
+        .unwrap_or(DEFAULT_TOMBSTONE_RETENTION_DAYS);
+    let current_retention_days = current_settings
+        .tombstone_retention_days
+        .unwrap_or(DEFAULT_TOMBSTONE_RETENTION_DAYS);
+    if retention_days > 0 && retention_days != current_retention_days {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or(0);
+        let before = now.saturating_sub(retention_days as u64 * 24 * 60 * 60 * 1000);
+        // 开启云同步时，未同步到云端的删除记录会被保留
+        let expired = if settings.cloud_sync == 1 {
+            ClipRecord::select_expired_synced_tombstones(rb, before).await
+        } else {
+            ClipRecord::select_expired_tombstones(rb, before).await
+        };
+        preview.tombstones_to_purge = expired.map(|data| data.len()).unwrap_or(0);
+    }
+
+    Ok(preview)
+}
+
+/// 获取请求签名密钥（自建服务端需要配置相同的密钥用于校验签名）
+#[tauri::command]
+pub async fn get_request_sign_secret() -> Result<String, String> {
+    let mut store = SECURE_STORE
+        .write()
+        .map_err(|e| format!("获取安全存储锁失败: {}", e))?;
+    store
+        .get_or_create_request_sign_secret()
+        .map_err(|e| e.to_string())
+}
+
+/// 重新生成请求签名密钥
+#[tauri::command]
+pub async fn reset_request_sign_secret() -> Result<String, String> {
+    ensure_not_kiosk_mode()?;
+    let mut store = SECURE_STORE
+        .write()
+        .map_err(|e| format!("获取安全存储锁失败: {}", e))?;
+    let secret = store
+        .reset_request_sign_secret()
+        .map_err(|e| e.to_string())?;
+    log::info!("请求签名密钥已重新生成");
+    Ok(secret)
+}
+
 /// 检查是否开启了云同步功能
 pub async fn check_cloud_sync_enabled() -> bool {
     let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
     if let Ok(settings) = safe_read_lock(&settings_lock) {
-        return settings.cloud_sync == 1;
+        // 只读模式下不进行云同步
+        return settings.cloud_sync == 1 && settings.kiosk_mode.unwrap_or(0) == 0;
+    }
+    false
+}
+
+/// 检查是否需要为不支持的剪贴板类型保存占位记录
+pub fn check_store_unknown_types_enabled() -> bool {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    if let Ok(settings) = safe_read_lock(&settings_lock) {
+        return settings.store_unknown_types.unwrap_or(0) == 1;
+    }
+    false
+}
+
+/// 检查剪贴板类型是否需要记录
+/// 只控制文本、图片、文件三种类型，HTML/RTF跟随文本设置，其他类型由 store_unknown_types 控制
+pub fn check_capture_type_enabled(clip_type: &ClipType) -> bool {
+    if matches!(clip_type, ClipType::Rtf | ClipType::Html) {
+        return check_capture_type_enabled(&ClipType::Text);
+    }
+    if !matches!(clip_type, ClipType::Text | ClipType::Image | ClipType::File) {
+        return true;
+    }
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    if let Ok(settings) = safe_read_lock(&settings_lock) {
+        return match &settings.capture_types {
+            Some(types) => types.iter().any(|t| *t == clip_type.to_string()),
+            None => true,
+        };
+    }
+    true
+}
+
+/// 检查是否遵循剪贴板内容的隐藏提示
+pub fn check_respect_concealed_hint() -> bool {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    if let Ok(settings) = safe_read_lock(&settings_lock) {
+        return settings.respect_concealed_hint.unwrap_or(1) == 1;
+    }
+    true
+}
+
+/// 获取保存文本前需要应用的文本转换名称
+pub fn get_text_transforms() -> Vec<String> {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    if let Ok(settings) = safe_read_lock(&settings_lock) {
+        return settings.text_transforms.clone().unwrap_or_default();
+    }
+    Vec::new()
+}
+
+/// 获取文本脱敏规则
+pub fn get_redaction_rules() -> Vec<String> {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    if let Ok(settings) = safe_read_lock(&settings_lock) {
+        return settings.redaction_rules.clone().unwrap_or_default();
+    }
+    Vec::new()
+}
+
+/// 检查来源应用是否在不记录名单中
+pub fn check_source_app_denied(source_app: &str) -> bool {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    let Ok(settings) = safe_read_lock(&settings_lock) else {
+        return false;
+    };
+    let Some(denylist) = &settings.app_denylist else {
+        return false;
+    };
+    let source_app = normalize_app_name(source_app);
+    denylist
+        .iter()
+        .any(|app| !app.trim().is_empty() && normalize_app_name(app) == source_app)
+}
+
+// 应用名统一转小写并去掉 .exe 后缀，便于跨平台配置
+fn normalize_app_name(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    match name.strip_suffix(".exe") {
+        Some(stripped) => stripped.to_string(),
+        None => name,
+    }
+}
+
+/// 获取不记录的剪贴板类型
+pub fn get_disabled_capture_types() -> Vec<ClipType> {
+    [ClipType::Text, ClipType::Image, ClipType::File]
+        .into_iter()
+        .filter(|clip_type| !check_capture_type_enabled(clip_type))
+        .collect()
+}
+
+/// 获取符号链接的处理方式
+pub fn get_symlink_mode() -> u32 {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    if let Ok(settings) = safe_read_lock(&settings_lock) {
+        return settings.symlink_mode.unwrap_or(SYMLINK_MODE_FOLLOW);
+    }
+    SYMLINK_MODE_FOLLOW
+}
+
+/// 获取单条文件记录最多包含的文件数，None 表示不限制
+pub fn get_max_files_per_record() -> Option<usize> {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    let max_files = match safe_read_lock(&settings_lock) {
+        Ok(settings) => settings
+            .max_files_per_record
+            .unwrap_or(DEFAULT_MAX_FILES_PER_RECORD),
+        Err(_) => DEFAULT_MAX_FILES_PER_RECORD,
+    };
+    if max_files == 0 {
+        None
+    } else {
+        Some(max_files as usize)
+    }
+}
+
+/// 获取复制的文件数超过上限时的处理方式
+pub fn get_files_overflow_mode() -> u32 {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    if let Ok(settings) = safe_read_lock(&settings_lock) {
+        return settings
+            .files_overflow_mode
+            .unwrap_or(FILES_OVERFLOW_TRUNCATE);
+    }
+    FILES_OVERFLOW_TRUNCATE
+}
+
+/// 检查是否开启了文本不可见字符清理
+pub fn check_text_sanitize_enabled() -> bool {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    if let Ok(settings) = safe_read_lock(&settings_lock) {
+        return settings.text_sanitize.unwrap_or(0) == 1;
+    }
+    false
+}
+
+/// 检查清理文本时是否保留原始文本
+pub fn check_keep_raw_text_enabled() -> bool {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    if let Ok(settings) = safe_read_lock(&settings_lock) {
+        return settings.keep_raw_text.unwrap_or(0) == 1;
+    }
+    false
+}
+
+/// 检查是否开启了乱码文本恢复
+pub fn check_text_encoding_recovery_enabled() -> bool {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    if let Ok(settings) = safe_read_lock(&settings_lock) {
+        return settings.text_encoding_recovery.unwrap_or(0) == 1;
+    }
+    false
+}
+
+/// 检查复制网址时是否获取网页标题
+pub fn check_link_title_enabled() -> bool {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    if let Ok(settings) = safe_read_lock(&settings_lock) {
+        return settings.link_title.unwrap_or(0) == 1;
+    }
+    false
+}
+
+/// 检查搜索时是否匹配标签
+pub fn check_search_tags_enabled() -> bool {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    if let Ok(settings) = safe_read_lock(&settings_lock) {
+        return settings.search_tags.unwrap_or(0) == 1;
+    }
+    false
+}
+
+/// 获取图片记录的最小字节数和最小宽高
+pub fn get_min_image_limits() -> (u64, u32) {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    if let Ok(settings) = safe_read_lock(&settings_lock) {
+        return (
+            settings.min_image_bytes.unwrap_or(0),
+            settings.min_image_dimensions.unwrap_or(0),
+        );
+    }
+    (0, 0)
+}
+
+/// 获取窗口显示时云端拉取记录的插入方式
+pub fn get_cloud_insert_policy() -> u32 {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    if let Ok(settings) = safe_read_lock(&settings_lock) {
+        return settings.cloud_insert_policy.unwrap_or(CLOUD_INSERT_BY_TIME);
+    }
+    CLOUD_INSERT_BY_TIME
+}
+
+/// 获取云同步定时任务的间隔（秒），不小于 MIN_SYNC_INTERVAL_SECONDS
+pub fn get_cloud_sync_interval() -> u64 {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    let interval = match safe_read_lock(&settings_lock) {
+        Ok(settings) => settings.cloud_sync_interval,
+        Err(e) => {
+            log::warn!("无法获取设置: {}", e);
+            SYNC_INTERVAL_SECONDS
+        }
+    };
+    interval.max(MIN_SYNC_INTERVAL_SECONDS) as u64
+}
+
+/// 获取自建云同步服务地址，未设置或地址无效时返回None（使用内置服务地址）
+pub fn get_sync_base_url() -> Option<String> {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    let settings = safe_read_lock(&settings_lock).ok()?;
+    effective_sync_base_url(&settings)
+}
+
+/// 设置中实际生效的自建同步服务地址，未设置或地址无效时返回None
+fn effective_sync_base_url(settings: &Settings) -> Option<String> {
+    let base_url = settings.sync_base_url.as_deref()?;
+    if base_url.trim().is_empty() {
+        return None;
+    }
+    match normalize_sync_base_url(base_url) {
+        Ok(base_url) => Some(base_url),
+        Err(e) => {
+            log::warn!("自建同步服务地址无效，使用内置服务地址: {}", e);
+            None
+        }
+    }
+}
+
+/// 获取依次粘贴快捷键粘贴的记录条数，限制在 1 到 MAX_SEQUENTIAL_PASTE_COUNT 之间
+pub fn get_sequential_paste_count() -> u32 {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    let count = safe_read_lock(&settings_lock)
+        .ok()
+        .and_then(|settings| settings.sequential_paste_count)
+        .unwrap_or(DEFAULT_SEQUENTIAL_PASTE_COUNT);
+    count.clamp(1, MAX_SEQUENTIAL_PASTE_COUNT)
+}
+
+/// 获取同时上传的文件数，限制在 1 到 MAX_FILE_UPLOAD_CONCURRENCY 之间
+pub fn get_file_upload_concurrency() -> usize {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    let concurrency = safe_read_lock(&settings_lock)
+        .ok()
+        .and_then(|settings| settings.file_upload_concurrency)
+        .unwrap_or(DEFAULT_FILE_UPLOAD_CONCURRENCY);
+    concurrency.clamp(1, MAX_FILE_UPLOAD_CONCURRENCY) as usize
+}
+
+/// 获取云同步置顶、排序冲突的处理策略
+pub fn get_sync_conflict_policy() -> SyncConflictPolicy {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    if let Ok(settings) = safe_read_lock(&settings_lock) {
+        return settings.sync_conflict_policy.unwrap_or_default();
+    }
+    SyncConflictPolicy::default()
+}
+
+/// 获取处理剪贴板内容时耗时文件操作的最长等待时间，None 表示不限制
+pub fn get_event_process_timeout() -> Option<Duration> {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    let secs = safe_read_lock(&settings_lock)
+        .map(|settings| {
+            settings
+                .event_process_timeout_secs
+                .unwrap_or(DEFAULT_EVENT_PROCESS_TIMEOUT_SECS)
+        })
+        .unwrap_or(DEFAULT_EVENT_PROCESS_TIMEOUT_SECS);
+    if secs == 0 {
+        return None;
+    }
+    Some(Duration::from_secs(secs as u64))
+}
+
+/// 获取合并连续剪贴板事件的等待时间，None 表示不等待
+pub fn get_capture_coalesce_delay() -> Option<Duration> {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    let millis = safe_read_lock(&settings_lock)
+        .map(|settings| {
+            settings
+                .capture_coalesce_ms
+                .unwrap_or(DEFAULT_CAPTURE_COALESCE_MS)
+        })
+        .unwrap_or(DEFAULT_CAPTURE_COALESCE_MS);
+    if millis == 0 {
+        return None;
+    }
+    Some(Duration::from_millis(millis as u64))
+}
+
+/// 判断动图是否按原格式保存，关闭或超过大小限制时只保存第一帧
+pub fn should_keep_animated_image(size: u64) -> bool {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    let Ok(settings) = safe_read_lock(&settings_lock) else {
+        return true;
+    };
+    if settings.keep_animated_images.unwrap_or(1) != 1 {
+        return false;
+    }
+    let max_bytes = settings
+        .max_animated_image_bytes
+        .unwrap_or(DEFAULT_MAX_ANIMATED_IMAGE_BYTES);
+    max_bytes == 0 || size <= max_bytes
+}
+
+/// 获取去重时间窗口，None 表示不限制（相同内容始终只更新排序）
+pub fn get_dedup_window() -> Option<Duration> {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    let secs = safe_read_lock(&settings_lock)
+        .map(|settings| settings.dedup_window_secs.unwrap_or(0))
+        .unwrap_or(0);
+    if secs == 0 {
+        return None;
+    }
+    Some(Duration::from_secs(secs as u64))
+}
+
+/// 获取文本压缩保存的最小字节数，0 表示不压缩
+pub fn get_text_compression_min_bytes() -> u64 {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    if let Ok(settings) = safe_read_lock(&settings_lock) {
+        return settings.text_compression_min_bytes.unwrap_or(0);
+    }
+    0
+}
+
+/// 检查是否开启了开发者模式
+pub fn check_developer_mode_enabled() -> bool {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    if let Ok(settings) = safe_read_lock(&settings_lock) {
+        return settings.developer_mode.unwrap_or(0) == 1;
+    }
+    false
+}
+
+/// 开发者模式开启时暂停后台任务，关闭时重新启动，并通知前端显示或隐藏提示横幅
+fn apply_developer_mode(enabled: bool) {
+    refresh_background_tasks_paused();
+    log::info!("开发者模式已{}", if enabled { "开启" } else { "关闭" });
+    if let Some(app_handle) = CONTEXT.try_get::<AppHandle>() {
+        let _ = app_handle.emit("developer_mode_changed", enabled);
+    }
+}
+
+/// 检查是否开启了剪贴板监听
+pub fn check_monitoring_enabled() -> bool {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    if let Ok(settings) = safe_read_lock(&settings_lock) {
+        return settings.monitoring_enabled.unwrap_or(1) == 1;
+    }
+    true
+}
+
+/// 开发者模式、只读模式开启或关闭监听时暂停后台任务，否则恢复
+fn refresh_background_tasks_paused() {
+    set_background_tasks_paused(
+        check_developer_mode_enabled() || !check_monitoring_enabled() || check_kiosk_mode_enabled(),
+    );
+}
+
+/// 监听状态变化后暂停或恢复后台任务，更新托盘图标并通知前端
+fn apply_monitoring_enabled(enabled: bool) {
+    refresh_background_tasks_paused();
+    refresh_tray_monitoring_state();
+    log::info!("剪贴板监听已{}", if enabled { "开启" } else { "关闭" });
+    if let Some(app_handle) = CONTEXT.try_get::<AppHandle>() {
+        let _ = app_handle.emit("monitoring_state_changed", enabled);
+    }
+}
+
+/// 查询剪贴板监听是否开启
+#[tauri::command]
+pub fn get_monitoring_enabled() -> bool {
+    check_monitoring_enabled()
+}
+
+/// 开启或关闭剪贴板监听，设置会保存到配置文件，重启后保持
+#[tauri::command]
+pub fn set_monitoring_enabled(enabled: bool) -> Result<(), String> {
+    ensure_not_kiosk_mode()?;
+    let settings = {
+        let lock = CONTEXT.get::<Arc<RwLock<Settings>>>().clone();
+        let mut current = safe_write_lock(&lock).map_err(|e| e.to_string())?;
+        if current.monitoring_enabled.unwrap_or(1) == enabled as u32 {
+            return Ok(());
+        }
+        current.monitoring_enabled = Some(enabled as u32);
+        current.clone()
+    };
+    save_settings_to_file(&settings).map_err(|e| e.to_string())?;
+    apply_monitoring_enabled(enabled);
+    Ok(())
+}
+
+/// 检查是否记录剪贴板内容（未临时暂停记录）
+pub fn check_capture_enabled() -> bool {
+    CAPTURE_ENABLED.load(Ordering::SeqCst)
+}
+
+/// 查询是否记录剪贴板内容
+#[tauri::command]
+pub fn get_capture_enabled() -> bool {
+    check_capture_enabled()
+}
+
+/// 临时暂停或恢复记录剪贴板内容，只保存在内存中，重启后恢复为记录
+/// 与关闭监听不同，暂停记录时云同步等后台任务继续运行
+#[tauri::command]
+pub fn set_capture_enabled(enabled: bool) {
+    if CAPTURE_ENABLED.swap(enabled, Ordering::SeqCst) == enabled {
+        return;
+    }
+    refresh_tray_monitoring_state();
+    log::info!("剪贴板记录已{}", if enabled { "恢复" } else { "暂停" });
+    if let Some(app_handle) = CONTEXT.try_get::<AppHandle>() {
+        let _ = app_handle.emit("capture_state_changed", enabled);
+    }
+}
+
+/// 检查是否处于只读模式
+pub fn check_kiosk_mode_enabled() -> bool {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    if let Ok(settings) = safe_read_lock(&settings_lock) {
+        return settings.kiosk_mode.unwrap_or(0) == 1;
+    }
+    false
+}
+
+/// 只读模式下拒绝修改记录和设置的命令
+pub fn ensure_not_kiosk_mode() -> Result<(), String> {
+    if check_kiosk_mode_enabled() {
+        return Err("当前为只读模式，不能执行该操作".to_string());
+    }
+    Ok(())
+}
+
+/// 只读模式切换后暂停或恢复后台任务，更新托盘图标并通知前端
+fn apply_kiosk_mode(enabled: bool) {
+    refresh_background_tasks_paused();
+    refresh_tray_monitoring_state();
+    log::info!("只读模式已{}", if enabled { "开启" } else { "关闭" });
+    if let Some(app_handle) = CONTEXT.try_get::<AppHandle>() {
+        let _ = app_handle.emit("kiosk_mode_changed", enabled);
+    }
+}
+
+fn save_kiosk_mode(enabled: bool) -> Result<(), String> {
+    let settings = {
+        let lock = CONTEXT.get::<Arc<RwLock<Settings>>>().clone();
+        let mut current = safe_write_lock(&lock).map_err(|e| e.to_string())?;
+        current.kiosk_mode = Some(enabled as u32);
+        current.clone()
+    };
+    save_settings_to_file(&settings).map_err(|e| e.to_string())?;
+    apply_kiosk_mode(enabled);
+    Ok(())
+}
+
+/// 查询是否处于只读模式
+#[tauri::command]
+pub fn get_kiosk_mode() -> bool {
+    check_kiosk_mode_enabled()
+}
+
+// 退出只读模式时校验PIN的锁，保证错误次数按顺序累计
+static KIOSK_PIN_VERIFY_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// 进入只读模式，设置PIN后退出时需要输入PIN
+#[tauri::command]
+pub async fn enter_kiosk_mode(pin: Option<String>) -> Result<(), String> {
+    if check_kiosk_mode_enabled() {
+        return Ok(());
+    }
+    // PIN摘要计算较慢，放到阻塞线程中执行
+    let pin_hash = match pin.filter(|pin| !pin.is_empty()) {
+        Some(pin) => Some(
+            tokio::task::spawn_blocking(move || hash_pin(&pin))
+                .await
+                .map_err(|e| format!("计算PIN摘要失败: {}", e))?
+                .map_err(|e| e.to_string())?,
+        ),
+        None => None,
+    };
+    {
+        let mut store = SECURE_STORE
+            .write()
+            .map_err(|e| format!("获取安全存储锁失败: {}", e))?;
+        store
+            .set_kiosk_pin_hash(pin_hash)
+            .map_err(|e| e.to_string())?;
+        store
+            .set_kiosk_pin_failures(0, None)
+            .map_err(|e| e.to_string())?;
+    }
+    save_kiosk_mode(true)
+}
+
+/// 退出只读模式，设置了PIN时需要校验
+/// PIN连续错误过多时锁定一段时间，锁定期间不再校验
+#[tauri::command]
+pub async fn exit_kiosk_mode(pin: Option<String>) -> Result<(), String> {
+    // 同一时间只校验一次，避免并发请求绕过错误次数限制
+    let _guard = KIOSK_PIN_VERIFY_LOCK.lock().await;
+    if !check_kiosk_mode_enabled() {
+        return Ok(());
+    }
+    let (pin_hash, failures, locked_until) = {
+        let mut store = SECURE_STORE
+            .write()
+            .map_err(|e| format!("获取安全存储锁失败: {}", e))?;
+        let pin_hash = store.get_kiosk_pin_hash().map_err(|e| e.to_string())?;
+        let (failures, locked_until) = store.get_kiosk_pin_failures().map_err(|e| e.to_string())?;
+        (pin_hash, failures, locked_until)
+    };
+
+    if let Some(pin_hash) = pin_hash {
+        let now = current_millis();
+        if let Some(locked_until) = locked_until.filter(|locked_until| *locked_until > now) {
+            let wait_seconds = (locked_until - now).div_ceil(1000);
+            return Err(format!("PIN错误次数过多，请{}秒后再试", wait_seconds));
+        }
+
+        let input = pin.unwrap_or_default();
+        let matched = tokio::task::spawn_blocking(move || verify_pin(&input, &pin_hash))
+            .await
+            .map_err(|e| format!("校验PIN失败: {}", e))?;
+        if !matched {
+            let failures = failures.saturating_add(1);
+            let lockout = pin_lockout_millis(failures);
+            let locked_until = Some(now + lockout).filter(|_| lockout > 0);
+            let mut store = SECURE_STORE
+                .write()
+                .map_err(|e| format!("获取安全存储锁失败: {}", e))?;
+            store
+                .set_kiosk_pin_failures(failures, locked_until)
+                .map_err(|e| e.to_string())?;
+            log::warn!("退出只读模式失败，PIN错误，连续错误{}次", failures);
+            return Err("PIN错误".to_string());
+        }
+    }
+
+    {
+        let mut store = SECURE_STORE
+            .write()
+            .map_err(|e| format!("获取安全存储锁失败: {}", e))?;
+        store.set_kiosk_pin_hash(None).map_err(|e| e.to_string())?;
+        store
+            .set_kiosk_pin_failures(0, None)
+            .map_err(|e| e.to_string())?;
+    }
+    save_kiosk_mode(false)
+}
+
+fn current_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// 获取 macOS 自动粘贴方式
+pub fn get_paste_method() -> u32 {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    if let Ok(settings) = safe_read_lock(&settings_lock) {
+        return settings.paste_method.unwrap_or(PASTE_METHOD_AUTO);
+    }
+    PASTE_METHOD_AUTO
+}
+
+/// 同步服务连接测试结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncConnectionResult {
+    // 测试的服务地址
+    pub base_url: String,
+    // HTTP状态码
+    pub status: u16,
+    // 请求耗时（毫秒）
+    pub latency_ms: u64,
+    // 状态码是否为2xx
+    pub ok: bool,
+}
+
+/// 测试同步服务连接，不传地址时测试当前使用的服务地址
+/// 用于开启云同步前确认自建服务地址可以访问
+#[tauri::command]
+pub async fn test_sync_connection(
+    base_url: Option<String>,
+) -> Result<SyncConnectionResult, String> {
+    let base_url = match base_url.as_deref().map(str::trim) {
+        Some(base_url) if !base_url.is_empty() => {
+            normalize_sync_base_url(base_url).map_err(|e| e.to_string())?
+        }
+        _ => get_api_domain().map_err(|e| e.to_string())?,
+    };
+
+    let start = Instant::now();
+    let status = check_sync_health(&base_url).await.map_err(|e| {
+        log::error!("同步服务连接测试失败 - 地址: {}, 错误: {}", base_url, e);
+        format!("无法连接同步服务: {}", e)
+    })?;
+    let latency_ms = start.elapsed().as_millis() as u64;
+    log::info!(
+        "同步服务连接测试完成 - 地址: {}, 状态码: {}, 耗时: {}ms",
+        base_url,
+        status,
+        latency_ms
+    );
+
+    Ok(SyncConnectionResult {
+        base_url,
+        status,
+        latency_ms,
+        ok: (200..300).contains(&status),
+    })
+}
+
+/// 获取用户设置的图片同步大小上限，0 表示只受VIP限制
+pub fn get_max_sync_image_bytes() -> u64 {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    if let Ok(settings) = safe_read_lock(&settings_lock) {
+        return settings.max_sync_image_bytes.unwrap_or(0);
+    }
+    0
+}
+
+/// 获取用户设置的文件同步大小上限，0 表示只受VIP限制
+pub fn get_max_sync_file_bytes() -> u64 {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    if let Ok(settings) = safe_read_lock(&settings_lock) {
+        return settings.max_sync_file_bytes.unwrap_or(0);
+    }
+    0
+}
+
+/// 获取参与云同步的单条文本最大字节数，0 表示不限制
+pub fn get_max_sync_text_bytes() -> u64 {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    if let Ok(settings) = safe_read_lock(&settings_lock) {
+        return settings.max_sync_text_bytes.unwrap_or(0);
+    }
+    0
+}
+
+/// 设置参与云同步的单条文本最大字节数（0 表示不限制），返回恢复同步的记录数
+#[tauri::command]
+pub async fn set_max_sync_text_bytes(max_bytes: u64) -> Result<usize, String> {
+    ensure_not_kiosk_mode()?;
+    let settings = {
+        let lock = CONTEXT.get::<Arc<RwLock<Settings>>>().clone();
+        let mut current = safe_write_lock(&lock).map_err(|e| e.to_string())?;
+        if current.max_sync_text_bytes.unwrap_or(0) == max_bytes {
+            return Ok(0);
+        }
+        current.max_sync_text_bytes = Some(max_bytes);
+        current.clone()
+    };
+    save_settings_to_file(&settings).map_err(|e| e.to_string())?;
+    log::info!("同步文本大小限制已更新为{}字节", max_bytes);
+
+    let resumed = resume_sync_text_limit_skipped().await;
+    if resumed > 0 {
+        if let Err(e) = trigger_immediate_sync() {
+            log::warn!("触发立即云同步失败: {}", e);
+        }
+    }
+    Ok(resumed)
+}
+
+/// 检查是否开启了请求签名
+pub fn check_request_signing_enabled() -> bool {
+    let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+    if let Ok(settings) = safe_read_lock(&settings_lock) {
+        return settings.request_signing.unwrap_or(0) == 1;
     }
     false
 }
@@ -1,15 +1,29 @@
 use clipboard_listener::ClipType;
 use rbatis::RBatis;
+use serde::Serialize;
 use std::path::PathBuf;
+use std::sync::Arc;
 use tauri::{AppHandle, Emitter};
-use tokio::task;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 use tokio::time::{sleep, Duration};
 
-use crate::api::cloud_sync_api::{get_upload_file_url, sync_upload_success, FileCloudSyncParam};
-use crate::biz::clip_record::{ClipRecord, SKIP_SYNC, SYNCHRONIZED, SYNCHRONIZING};
-use crate::biz::system_setting::check_cloud_sync_enabled;
+use crate::api::cloud_sync_api::{
+    get_dowload_url, get_upload_file_url, sync_upload_success, DownloadCloudFileParam,
+    FileCloudSyncParam,
+};
+use crate::biz::background_task_status::{
+    begin_file_transfer, begin_task_tick, register_background_task, spawn_supervised,
+    TASK_FILE_UPLOAD,
+};
+use crate::biz::clip_record::{ClipRecord, SyncStatus, SKIP_SYNC, SYNCHRONIZED, SYNCHRONIZING};
+use crate::biz::copy_clip_record::CopyClipRecord;
+use crate::biz::system_setting::{
+    check_cloud_sync_enabled, ensure_not_kiosk_mode, get_file_upload_concurrency,
+};
 use crate::biz::vip_checker::VipChecker;
 use crate::errors::{AppError, AppResult};
+use crate::utils::aes_util::decrypt_content;
 use crate::utils::file_dir::get_resources_dir;
 use crate::utils::retry_helper::{retry_with_config, RetryConfig};
 use crate::utils::token_manager::has_valid_auth;
@@ -17,6 +31,9 @@ use crate::CONTEXT;
 
 /// 这个定时任务是云同步上传记录时，文件类型的内容上传到云端的任务
 
+// 每轮取出的待上传记录数为并发数的倍数，同一批记录全部处理完后才取下一批
+const UPLOAD_BATCH_FACTOR: usize = 4;
+
 /// 内部文件上传参数（包含文件路径）
 #[derive(Debug, Clone)]
 struct InternalFileUploadParam {
@@ -27,50 +44,95 @@ struct InternalFileUploadParam {
 
 /// 启动文件同步定时任务
 pub fn start_upload_cloud_timer() {
-    task::spawn(async move {
-        log::info!("文件同步定时任务已启动");
-
-        loop {
-            // 检查云同步是否开启
-            if !check_cloud_sync_enabled().await {
-                log::debug!("云同步未开启，跳过文件同步任务");
-                sleep(Duration::from_secs(5)).await;
-                continue;
-            }
+    spawn_supervised(TASK_FILE_UPLOAD, run_upload_cloud_timer);
+}
 
-            // 检查用户登录状态
-            if !has_valid_auth() {
-                log::debug!("用户未登录或认证已过期，跳过文件同步任务");
-                sleep(Duration::from_secs(5)).await;
-                continue;
-            }
+/// 文件同步定时任务主循环
+async fn run_upload_cloud_timer() {
+    log::info!("文件同步定时任务已启动");
+    register_background_task(TASK_FILE_UPLOAD, 5);
 
-            // 执行文件同步任务
-            if let Err(e) = process_one_file_sync().await {
-                log::error!("文件同步任务执行失败: {}", e);
-            }
+    loop {
+        let tick = begin_task_tick(TASK_FILE_UPLOAD);
 
-            // 等待一段时间后继续下一轮
-            sleep(Duration::from_secs(1)).await;
+        // 检查云同步是否开启
+        if !check_cloud_sync_enabled().await {
+            log::debug!("云同步未开启，跳过文件同步任务");
+            drop(tick);
+            sleep(Duration::from_secs(5)).await;
+            continue;
         }
-    });
+
+        // 检查用户登录状态
+        if !has_valid_auth() {
+            log::debug!("用户未登录或认证已过期，跳过文件同步任务");
+            drop(tick);
+            sleep(Duration::from_secs(5)).await;
+            continue;
+        }
+
+        // 执行文件同步任务
+        if let Err(e) = process_file_sync_batch().await {
+            log::error!("文件同步任务执行失败: {}", e);
+        }
+        drop(tick);
+
+        // 等待一段时间后继续下一轮
+        sleep(Duration::from_secs(1)).await;
+    }
 }
 
-/// 处理一个文件同步任务
-/// 每次只处理一条SYNCHRONIZING状态的记录
-async fn process_one_file_sync() -> AppResult<()> {
+/// 处理一批文件同步任务
+/// 每次取出一批SYNCHRONIZING状态的记录，按设置的并发数同时上传，每条记录的状态独立更新
+async fn process_file_sync_batch() -> AppResult<()> {
     let rb: &RBatis = CONTEXT.get::<RBatis>();
-
-    // 查找一条sync_flag为SYNCHRONIZING的记录，但是需要是本地自己的记录，而不是云端同步下来的
-    let pending_records = ClipRecord::select_by_sync_flag_limit(rb, SYNCHRONIZING, 0, 1).await?;
+    let concurrency = get_file_upload_concurrency();
+
+    // 查找sync_flag为SYNCHRONIZING的记录，但是需要是本地自己的记录，而不是云端同步下来的
+    let pending_records = ClipRecord::select_by_sync_flag_limit(
+        rb,
+        SYNCHRONIZING,
+        0,
+        (concurrency * UPLOAD_BATCH_FACTOR) as i32,
+    )
+    .await?;
 
     if pending_records.is_empty() {
         log::debug!("没有发现待同步文件的记录");
         return Ok(());
     }
 
-    // 只处理第一条记录
-    let record = &pending_records[0];
+    log::info!(
+        "发现 {} 条待同步文件的记录，并发数: {}",
+        pending_records.len(),
+        concurrency
+    );
+
+    // 上传任务由本批次持有，暂停时中止批次会同时取消（JoinSet 被丢弃时中止其中的任务）
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+    let mut tasks = JoinSet::new();
+    for record in pending_records {
+        let semaphore = semaphore.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire().await;
+            if let Err(e) = process_record_file_sync(&record).await {
+                log::error!("文件同步失败，记录ID: {}, 错误: {}", record.id, e);
+            }
+        });
+    }
+
+    while let Some(result) = tasks.join_next().await {
+        if let Err(e) = result {
+            log::error!("文件同步任务异常: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+/// 处理单条记录的文件同步
+async fn process_record_file_sync(record: &ClipRecord) -> AppResult<()> {
+    let _transfer = begin_file_transfer(TASK_FILE_UPLOAD, &record.id);
     log::info!(
         "开始处理文件同步，记录ID: {}, 类型: {}",
         record.id,
@@ -82,6 +144,7 @@ async fn process_one_file_sync() -> AppResult<()> {
         t if t == ClipType::File.to_string() => process_file_sync(record).await,
         _ => {
             // 其他类型不需要文件同步，直接标记为已同步
+            let rb: &RBatis = CONTEXT.get::<RBatis>();
             let ids = vec![record.id.clone()];
             let current_time = current_timestamp();
             ClipRecord::update_sync_flag(rb, &ids, SYNCHRONIZED, current_time).await?;
@@ -478,6 +541,22 @@ async fn notify_frontend_sync_status(ids: Vec<String>, sync_flag: i32) {
 
 /// 直接上传文件到OSS（使用预签名URL）
 async fn upload_file_to_oss(upload_url: &str, file_path: &PathBuf) -> AppResult<()> {
+    let (status, error_text) = put_file_to_oss(upload_url, file_path).await?;
+
+    if (200..300).contains(&status) {
+        log::info!("文件上传到OSS成功: {:?}, 状态码: {}", file_path, status);
+        Ok(())
+    } else {
+        log::error!("OSS详细错误响应: {}", error_text);
+
+        let error_message = format!("OSS上传失败，状态码: {} - {}", status, error_text);
+
+        Err(AppError::General(error_message))
+    }
+}
+
+/// 使用PUT方法把文件发送到预签名URL，返回状态码和失败时的响应内容
+async fn put_file_to_oss(upload_url: &str, file_path: &PathBuf) -> AppResult<(u16, String)> {
     // 检查文件是否存在
     if !file_path.exists() {
         return Err(AppError::General(format!("文件不存在: {:?}", file_path)));
@@ -505,20 +584,235 @@ async fn upload_file_to_oss(upload_url: &str, file_path: &PathBuf) -> AppResult<
     let status = response.status();
 
     if status.is_success() {
-        log::info!("文件上传到OSS成功: {:?}, 状态码: {}", file_path, status);
-        Ok(())
+        Ok((status.as_u16(), String::new()))
     } else {
         let error_text = response
             .text()
             .await
             .unwrap_or_else(|_| "无法读取错误响应".to_string());
+        Ok((status.as_u16(), error_text))
+    }
+}
 
-        log::error!("OSS详细错误响应: {}", error_text);
+/// 文件同步诊断结果，记录上传流程中每一步的执行情况
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct FileSyncTestResult {
+    pub record_id: String,
+    pub record_type: String,
+    // 本次测试上传的文件
+    pub file_path: Option<String>,
+    pub file_size: Option<u64>,
+    // 步骤1: 是否获取到预签名上传URL
+    pub got_upload_url: bool,
+    pub upload_url_error: Option<String>,
+    // 步骤2: 上传到OSS的HTTP状态码
+    pub oss_status_code: Option<u16>,
+    pub oss_error: Option<String>,
+    // 步骤3: 通知服务端上传完成的结果
+    pub notify_success: Option<bool>,
+    pub notify_error: Option<String>,
+    // 步骤4: 本地同步状态是否已更新为已同步
+    pub marked_synced: bool,
+}
 
-        let error_message = format!("OSS上传失败，状态码: {} - {}", status, error_text);
+/// 调试用：对单条记录执行一次完整的文件上传流程（不重试），返回每一步的结果
+/// 多文件记录只测试第一个存在的文件
+#[tauri::command]
+pub async fn test_file_sync(param: CopyClipRecord) -> Result<FileSyncTestResult, String> {
+    ensure_not_kiosk_mode()?;
+    if !has_valid_auth() {
+        return Err("用户未登录或认证已过期".to_string());
+    }
 
-        Err(AppError::General(error_message))
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let record = ClipRecord::select_by_id(rb, &param.record_id)
+        .await
+        .map_err(|e| format!("查询记录失败: {}", e))?
+        .into_iter()
+        .next()
+        .ok_or("未找到该记录".to_string())?;
+
+    let file_path = match record.r#type.as_str() {
+        t if t == ClipType::Image.to_string() => {
+            let image_filename = record.content.as_str().unwrap_or_default();
+            get_resources_dir().map(|dir| dir.join(image_filename))
+        }
+        t if t == ClipType::File.to_string() => record
+            .local_file_path
+            .as_deref()
+            .unwrap_or_default()
+            .split(":::")
+            .map(PathBuf::from)
+            .find(|path| path.exists()),
+        _ => return Err("只有图片和文件类型的记录需要上传文件".to_string()),
+    }
+    .filter(|path| path.is_file())
+    .ok_or("记录对应的本地文件不存在".to_string())?;
+
+    let mut result = FileSyncTestResult {
+        record_id: record.id.clone(),
+        record_type: record.r#type.clone(),
+        file_path: Some(file_path.to_string_lossy().to_string()),
+        file_size: std::fs::metadata(&file_path).map(|m| m.len()).ok(),
+        ..Default::default()
+    };
+
+    // 步骤1: 获取预签名上传URL
+    let sync_param = FileCloudSyncParam {
+        md5_str: record.md5_str.clone(),
+        r#type: record.r#type.clone(),
+    };
+    let upload_url = match get_upload_file_url(&sync_param).await {
+        Ok(Some(response)) => response.url,
+        Ok(None) => {
+            result.upload_url_error = Some("服务端返回空响应".to_string());
+            return Ok(result);
+        }
+        Err(e) => {
+            result.upload_url_error = Some(e.to_string());
+            return Ok(result);
+        }
+    };
+    result.got_upload_url = true;
+
+    // 步骤2: 上传文件到OSS
+    match put_file_to_oss(&upload_url, &file_path).await {
+        Ok((status, error_text)) => {
+            result.oss_status_code = Some(status);
+            if !(200..300).contains(&status) {
+                result.oss_error = Some(error_text);
+                return Ok(result);
+            }
+        }
+        Err(e) => {
+            result.oss_error = Some(e.to_string());
+            return Ok(result);
+        }
+    }
+
+    // 步骤3: 通知服务端上传完成
+    match sync_upload_success(&sync_param).await {
+        Ok(Some(success)) => result.notify_success = Some(success),
+        Ok(None) => result.notify_success = Some(false),
+        Err(e) => result.notify_error = Some(e.to_string()),
+    }
+    if result.notify_success != Some(true) {
+        return Ok(result);
     }
+
+    // 步骤4: 更新本地同步状态（多文件记录只测试了一个文件，不更新状态）
+    let is_single_file = !record
+        .local_file_path
+        .as_deref()
+        .unwrap_or_default()
+        .contains(":::");
+    if is_single_file {
+        let ids = vec![record.id.clone()];
+        if ClipRecord::update_sync_flag(rb, &ids, SYNCHRONIZED, current_timestamp())
+            .await
+            .is_ok()
+        {
+            notify_frontend_sync_status(ids, SYNCHRONIZED).await;
+            result.marked_synced = true;
+        }
+    }
+
+    log::info!("文件同步诊断完成: {:?}", result);
+    Ok(result)
+}
+
+/// 单条记录的同步信息，汇总记录同步状态、内容加密前后大小和云端文件状态，用于排查记录未同步的原因
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct RecordSyncInfo {
+    pub record_id: String,
+    pub record_type: String,
+    // 同步状态及说明
+    pub sync_status: Option<SyncStatus>,
+    pub sync_status_label: String,
+    // 是否已上传到云端
+    pub uploaded: bool,
+    // 是否是从云端同步下来的记录
+    pub from_cloud: bool,
+    pub sync_time: Option<u64>,
+    // 服务端存储的key（内容md5值）
+    pub md5_key: String,
+    // 同步时发送的内容大小（文本为加密后的内容）
+    pub stored_size: usize,
+    // 明文大小：文本为解密后的内容，图片和文件为本地文件大小
+    pub plaintext_size: Option<u64>,
+    // 加密带来的额外大小，只有文本记录有
+    pub encryption_overhead: Option<i64>,
+    // 图片和文件：云端是否存在该文件，未登录或查询失败时为空
+    pub oss_object_exists: Option<bool>,
+    pub oss_error: Option<String>,
+}
+
+/// 获取单条记录的同步信息
+#[tauri::command]
+pub async fn get_record_sync_info(param: CopyClipRecord) -> Result<RecordSyncInfo, String> {
+    let rb: &RBatis = CONTEXT.get::<RBatis>();
+    let record = ClipRecord::select_by_id(rb, &param.record_id)
+        .await
+        .map_err(|e| format!("查询记录失败: {}", e))?
+        .into_iter()
+        .next()
+        .ok_or("未找到该记录".to_string())?;
+
+    let sync_status = SyncStatus::from_flags(record.sync_flag, record.skip_type);
+    let stored_content = record.content.as_str().unwrap_or_default();
+    let mut info = RecordSyncInfo {
+        record_id: record.id.clone(),
+        record_type: record.r#type.clone(),
+        sync_status: Some(sync_status),
+        sync_status_label: sync_status.label().to_string(),
+        uploaded: sync_status == SyncStatus::Synced,
+        from_cloud: record.cloud_source == Some(1),
+        sync_time: record.sync_time,
+        md5_key: record.md5_str.clone(),
+        stored_size: stored_content.len(),
+        ..Default::default()
+    };
+
+    match record.r#type.as_str() {
+        t if t == ClipType::Image.to_string() => {
+            info.plaintext_size = get_resources_dir()
+                .and_then(|dir| std::fs::metadata(dir.join(stored_content)).ok())
+                .map(|m| m.len());
+        }
+        t if t == ClipType::File.to_string() => {
+            info.plaintext_size = record.local_file_path.as_deref().map(|paths| {
+                paths
+                    .split(":::")
+                    .filter_map(|path| std::fs::metadata(path).ok())
+                    .map(|m| m.len())
+                    .sum()
+            });
+        }
+        _ => match decrypt_content(stored_content) {
+            Ok(plaintext) => {
+                info.plaintext_size = Some(plaintext.len() as u64);
+                info.encryption_overhead =
+                    Some(stored_content.len() as i64 - plaintext.len() as i64);
+            }
+            Err(e) => log::warn!("解密记录内容失败，记录ID: {}, 错误: {}", record.id, e),
+        },
+    }
+
+    // 图片和文件记录查询云端是否已存在该文件
+    let is_file_record =
+        record.r#type == ClipType::Image.to_string() || record.r#type == ClipType::File.to_string();
+    if is_file_record && has_valid_auth() {
+        let download_param = DownloadCloudFileParam {
+            md5_str: record.md5_str.clone(),
+            r#type: record.r#type.clone(),
+        };
+        match get_dowload_url(&download_param).await {
+            Ok(response) => info.oss_object_exists = Some(response.is_some()),
+            Err(e) => info.oss_error = Some(e.to_string()),
+        }
+    }
+
+    Ok(info)
 }
 
 /// 获取当前时间戳
@@ -8,6 +8,7 @@ use crate::{
         EmailCodeRequestParam, LoginRequestParam, RegisterRequestParam, UpdateUserInfoParam,
         UserInfo as ApiUserInfo,
     },
+    biz::system_setting::ensure_not_kiosk_mode,
     utils::secure_store::SECURE_STORE,
     utils::token_manager::has_valid_auth,
     CONTEXT,
@@ -116,6 +117,7 @@ impl From<ApiUserInfo> for UserInfo {
 
 #[tauri::command]
 pub async fn login(param: FrontendLoginRequest) -> Result<LoginResponse, String> {
+    ensure_not_kiosk_mode()?;
     log::info!("用户登录请求: {}", param.account);
 
     // 转换为API请求参数
@@ -133,6 +135,12 @@ pub async fn login(param: FrontendLoginRequest) -> Result<LoginResponse, String>
                     return Err(format!("存储认证数据失败: {}", e));
                 }
 
+                // 导入切换账号前暂存的其他账号记录
+                let user_id = auth_response.user_info.id;
+                tokio::spawn(async move {
+                    crate::biz::record_transfer::apply_staged_transfers(user_id).await;
+                });
+
                 // 登录成功后，触发VIP状态检查
                 tokio::spawn(async {
                     log::info!("用户登录成功，触发VIP状态检查");
@@ -168,6 +176,7 @@ pub async fn login(param: FrontendLoginRequest) -> Result<LoginResponse, String>
 
 #[tauri::command]
 pub async fn user_register(param: FrontendRegisterRequest) -> Result<UserInfo, String> {
+    ensure_not_kiosk_mode()?;
     log::info!("用户注册请求: {}", param.account);
 
     // 转换为API请求参数
@@ -199,6 +208,7 @@ pub async fn user_register(param: FrontendRegisterRequest) -> Result<UserInfo, S
 
 #[tauri::command]
 pub async fn send_email_code(param: FrontendSendEmailCodeRequest) -> Result<String, String> {
+    ensure_not_kiosk_mode()?;
     // 转换为API请求参数
     let api_param: EmailCodeRequestParam = param.into();
 
@@ -319,6 +329,7 @@ pub fn clear_stored_auth_data() -> Result<(), String> {
 /// 用户登出
 #[tauri::command]
 pub async fn logout() -> Result<String, String> {
+    ensure_not_kiosk_mode()?;
     log::info!("用户登出请求");
 
     // 如果有有效的认证状态，先调用后端退出登录接口
@@ -447,6 +458,12 @@ async fn notify_auth_cleared() {
     }
 }
 
+/// 同步服务地址变化强制退出登录后，通知前端登录状态已清除、云同步已关闭
+pub async fn notify_forced_logout() {
+    notify_auth_cleared().await;
+    notify_cloud_sync_disabled().await;
+}
+
 /// 通知前端云同步已被禁用
 async fn notify_cloud_sync_disabled() {
     log::info!("通知前端云同步已被禁用");
@@ -468,6 +485,7 @@ pub struct UpdateNicknameRequest {
 /// 更新用户昵称
 #[tauri::command]
 pub async fn update_user_info(nick_name: String) -> Result<bool, String> {
+    ensure_not_kiosk_mode()?;
     log::info!("更新用户昵称请求: {}", nick_name);
 
     let trimmed_nickname = nick_name.trim();
@@ -2,17 +2,86 @@ use crate::{
     api::vip_api::{user_vip_check, UserVipInfoResponse},
     biz::{
         clip_record::{ClipRecord, NOT_SYNCHRONIZED, SKIP_SYNC},
-        system_setting::{load_settings, save_settings, save_settings_to_file, Settings},
+        system_setting::{
+            get_max_sync_file_bytes, get_max_sync_image_bytes, load_settings, save_settings,
+            save_settings_to_file, Settings, DEFAULT_VIP_DOWNGRADE_GRACE_DAYS,
+        },
     },
     errors::{AppError, AppResult},
-    utils::secure_store::{VipInfo, VipType, SECURE_STORE},
+    utils::{
+        lock_utils::lock_utils::safe_read_lock,
+        secure_store::{VipInfo, VipType, SECURE_STORE},
+    },
     CONTEXT,
 };
 use log;
 use rbatis::RBatis;
+use std::sync::{Arc, RwLock};
 
 pub struct VipChecker;
 
+/// 各类型内容参与云同步的大小上限（字节），0 表示不支持同步
+/// 文本使用VIP限制（用户设置的文本上限 max_sync_text_bytes 单独检查），图片和文件在VIP限制内还受用户设置的上限约束
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SizeLimits {
+    pub text: u64,
+    pub image: u64,
+    pub file: u64,
+}
+
+impl SizeLimits {
+    /// 在VIP限制的基础上应用用户设置的图片、文件上限，未设置（0）时与VIP限制相同
+    pub fn new(vip_max: u64, image_limit: u64, file_limit: u64) -> Self {
+        Self {
+            text: vip_max,
+            image: bounded_limit(vip_max, image_limit),
+            file: bounded_limit(vip_max, file_limit),
+        }
+    }
+
+    /// 按VIP限制和当前设置计算
+    pub fn from_vip_limit(vip_max: u64) -> Self {
+        Self::new(
+            vip_max,
+            get_max_sync_image_bytes(),
+            get_max_sync_file_bytes(),
+        )
+    }
+}
+
+// 用户上限只能比VIP限制更小，VIP不支持同步（0）时保持为0
+fn bounded_limit(vip_max: u64, user_limit: u64) -> u64 {
+    if user_limit == 0 || vip_max == 0 {
+        vip_max
+    } else {
+        vip_max.min(user_limit)
+    }
+}
+
+/// 从服务器刷新VIP状态的结果
+#[derive(Debug, Clone)]
+pub struct VipRefreshOutcome {
+    /// VIP信息是否来自服务端（false表示使用本地缓存）
+    pub from_server: bool,
+    /// VIP状态是否发生变化
+    pub vip_changed: bool,
+    /// 本地记录条数设置是否因超过限制被下调
+    pub records_limit_trimmed: bool,
+    /// 刷新失败原因
+    pub failure_reason: Option<String>,
+}
+
+impl VipRefreshOutcome {
+    fn from_cache(reason: String) -> Self {
+        Self {
+            from_server: false,
+            vip_changed: false,
+            records_limit_trimmed: false,
+            failure_reason: Some(reason),
+        }
+    }
+}
+
 impl VipChecker {
     /// 检查用户是否为VIP - 必须调用服务端验证，同时处理权益更新
     pub async fn is_vip_user() -> AppResult<bool> {
@@ -141,6 +210,11 @@ impl VipChecker {
     /// 从服务器刷新VIP状态 - 调用现有的user_vip_check方法
     /// 如果服务器获取失败，返回成功状态，让前端继续显示本地缓存
     pub async fn refresh_vip_from_server() -> AppResult<bool> {
+        Ok(Self::refresh_vip_from_server_detailed().await?.from_server)
+    }
+
+    /// 从服务器刷新VIP状态，并返回刷新过程的详细结果
+    pub async fn refresh_vip_from_server_detailed() -> AppResult<VipRefreshOutcome> {
         log::info!("从服务器刷新VIP状态");
 
         // 调用现有的user_vip_check API
@@ -165,7 +239,8 @@ impl VipChecker {
                 } // store在这里被drop
 
                 // 处理本地记录条数限制 - VIP状态变化时自动调整max_records设置
-                Self::enforce_local_records_limit(&vip_response).await?;
+                let records_limit_trimmed =
+                    Self::enforce_local_records_limit(&vip_response).await?;
 
                 // 如果VIP状态发生变化，更新跳过的记录
                 if vip_changed {
@@ -174,16 +249,23 @@ impl VipChecker {
                 }
 
                 log::info!("VIP状态已从服务器更新");
-                Ok(true)
+                Ok(VipRefreshOutcome {
+                    from_server: true,
+                    vip_changed,
+                    records_limit_trimmed,
+                    failure_reason: None,
+                })
             }
             Ok(None) => {
                 log::warn!("服务器返回空的VIP信息，使用本地缓存");
-                Ok(false)
+                Ok(VipRefreshOutcome::from_cache(
+                    "服务器返回空的VIP信息".to_string(),
+                ))
             }
             Err(e) => {
                 log::warn!("从服务器获取VIP状态失败，将使用本地缓存: {:?}", e);
-                // 服务器获取失败时，返回false但不抛出错误，让前端继续显示本地缓存
-                Ok(false)
+                // 服务器获取失败时不抛出错误，让前端继续显示本地缓存
+                Ok(VipRefreshOutcome::from_cache(e.to_string()))
             }
         }
     }
@@ -273,15 +355,15 @@ impl VipChecker {
     }
 
     /// 强制执行本地记录条数限制（仅更新本地设置，避免递归）
-    async fn enforce_local_records_limit(vip_response: &UserVipInfoResponse) -> AppResult<()> {
-        use std::sync::Arc;
-
+    /// 返回本地记录条数设置是否被下调
+    async fn enforce_local_records_limit(vip_response: &UserVipInfoResponse) -> AppResult<bool> {
         let mut settings = load_settings();
         let current_max = settings.max_records;
         let server_max = vip_response.max_records;
+        let trimmed = current_max > server_max;
 
         // 如果当前设置超过服务器允许的最大值，强制调整
-        if current_max > server_max {
+        if trimmed {
             log::warn!(
                 "本地记录条数({})超过服务端限制({})，自动调整",
                 current_max,
@@ -290,7 +372,7 @@ impl VipChecker {
             settings.max_records = server_max;
 
             // 更新内存设置
-            let settings_lock = CONTEXT.get::<Arc<std::sync::RwLock<Settings>>>();
+            let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
             match settings_lock.write() {
                 Ok(mut guard) => {
                     *guard = settings.clone();
@@ -314,7 +396,7 @@ impl VipChecker {
         // 处理因VIP变化需要更新的记录
         Self::update_skipped_records_after_vip_change(vip_response).await?;
 
-        Ok(())
+        Ok(trimmed)
     }
 
     /// VIP状态变化后，更新跳过的记录
@@ -451,6 +533,11 @@ impl VipChecker {
         }
     }
 
+    /// 获取各类型内容的同步大小上限（仅使用本地VIP缓存）
+    pub fn get_cached_size_limits() -> SizeLimits {
+        SizeLimits::from_vip_limit(Self::get_cached_max_file_size().unwrap_or(0))
+    }
+
     /// 获取文件复制大小限制（基于同步文件限制）
     /// 优先检查本地VIP缓存，无缓存时尝试获取服务器配置，最后使用5MB硬编码限制
     pub async fn get_file_copy_size_limit() -> u64 {
@@ -541,23 +628,92 @@ impl VipChecker {
         let current_count = ClipRecord::count_all_records(rb)
             .await
             .map_err(|e| AppError::Config(format!("查询记录总数失败: {}", e)))?;
+        let hidden_count = ClipRecord::count_hidden_records(rb).await;
+
+        if current_count <= max_allowed as i64 {
+            // 限制已放宽（例如续费），优先恢复宽限期内被隐藏的记录
+            let capacity = max_allowed as i64 - current_count;
+            if hidden_count > 0 && capacity > 0 {
+                let restore_count = hidden_count.min(capacity);
+                ClipRecord::restore_hidden_records(rb, restore_count as i32)
+                    .await
+                    .map_err(|e| AppError::Config(format!("恢复隐藏记录失败: {}", e)))?;
+                log::info!("记录数限制已放宽，恢复{}条被隐藏的记录", restore_count);
+            }
+            if hidden_count <= capacity {
+                Self::clear_vip_downgrade_since()?;
+                return Ok(());
+            }
+        }
 
-        if current_count > max_allowed as i64 {
+        let grace_days = {
+            let settings_lock = CONTEXT.get::<Arc<RwLock<Settings>>>();
+            safe_read_lock(&settings_lock)
+                .ok()
+                .and_then(|settings| settings.vip_downgrade_grace_days)
+                .unwrap_or(DEFAULT_VIP_DOWNGRADE_GRACE_DAYS)
+        };
+        let grace_expired = if grace_days == 0 {
+            true
+        } else {
+            let since = {
+                let mut store = SECURE_STORE
+                    .write()
+                    .map_err(|_| AppError::Config("获取存储锁失败".to_string()))?;
+                store.get_or_mark_vip_downgrade_since()?
+            };
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs();
+            now.saturating_sub(since) >= grace_days as u64 * 24 * 60 * 60
+        };
+
+        let excess_count = current_count - max_allowed as i64;
+        if grace_expired {
+            // 宽限期已过且未续费，删除隐藏记录和超出部分
+            if hidden_count > 0 {
+                log::warn!("VIP降级宽限期已过，删除{}条被隐藏的记录", hidden_count);
+                ClipRecord::delete_hidden_records(rb)
+                    .await
+                    .map_err(|e| AppError::Config(format!("删除隐藏记录失败: {}", e)))?;
+            }
+            if excess_count > 0 {
+                log::warn!(
+                    "数据库记录数({})超过VIP限制({})，执行清理",
+                    current_count,
+                    max_allowed
+                );
+                // 保留最新的记录，删除超出部分
+                ClipRecord::delete_oldest_records(rb, excess_count as i32)
+                    .await
+                    .map_err(|e| AppError::Config(format!("清理超出记录失败: {}", e)))?;
+            }
+            Self::clear_vip_downgrade_since()?;
+        } else if excess_count > 0 {
+            // 宽限期内只隐藏超出部分，续费后可恢复
             log::warn!(
-                "数据库记录数({})超过VIP限制({})，执行清理",
+                "数据库记录数({})超过VIP限制({})，宽限期内隐藏超出的{}条记录",
                 current_count,
-                max_allowed
+                max_allowed,
+                excess_count
             );
-            // 保留最新的记录，删除超出部分
-            let excess_count = current_count - max_allowed as i64;
-            ClipRecord::delete_oldest_records(rb, excess_count as i32)
+            ClipRecord::hide_oldest_records(rb, excess_count as i32)
                 .await
-                .map_err(|e| AppError::Config(format!("清理超出记录失败: {}", e)))?;
+                .map_err(|e| AppError::Config(format!("隐藏超出记录失败: {}", e)))?;
         }
 
         Ok(())
     }
 
+    /// 清除记录数超出VIP限制的开始时间
+    fn clear_vip_downgrade_since() -> AppResult<()> {
+        let mut store = SECURE_STORE
+            .write()
+            .map_err(|_| AppError::Config("获取存储锁失败".to_string()))?;
+        store.clear_vip_downgrade_since()
+    }
+
     /// 启动时初始化VIP状态并执行限制检查
     pub async fn initialize_vip_and_enforce_limits() -> AppResult<()> {
         log::info!("初始化VIP状态并执行权益限制检查");
@@ -58,6 +58,49 @@ pub async fn get_vip_limits() -> Result<serde_json::Value, String> {
     }))
 }
 
+/// 当前VIP等级可用的功能及由缓存的VIP信息推导出的能力
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnabledFeatures {
+    pub is_vip: bool,
+    pub vip_type: Option<VipType>,
+    // 服务端返回的功能列表
+    pub features: Vec<String>,
+    // 本地最大记录数
+    pub max_records: u32,
+    // 可同步的单个文件大小上限（MB），0表示不支持文件同步
+    pub max_file_size_mb: f64,
+    // 是否可以同步图片和文件
+    pub can_sync_files: bool,
+}
+
+/// 获取当前VIP等级解锁的功能，只使用本地缓存的VIP信息，不请求服务器
+/// 前端根据返回值控制功能入口，避免在前端硬编码各等级的权益
+#[tauri::command]
+pub async fn get_enabled_features() -> Result<EnabledFeatures, String> {
+    let vip_info = VipChecker::get_local_vip_info().map_err(|e| e.to_string())?;
+    let max_records = VipChecker::get_cached_max_records_limit().map_err(|e| e.to_string())?;
+    let max_file_size = VipChecker::get_cached_max_file_size().map_err(|e| e.to_string())?;
+
+    let (is_vip, vip_type, features) = match vip_info {
+        Some(info) => (
+            info.vip_flag,
+            Some(info.vip_type),
+            info.features.unwrap_or_default(),
+        ),
+        None => (false, None, Vec::new()),
+    };
+
+    Ok(EnabledFeatures {
+        is_vip,
+        vip_type,
+        features,
+        max_records,
+        max_file_size_mb: max_file_size as f64 / 1024.0 / 1024.0,
+        can_sync_files: max_file_size > 0,
+    })
+}
+
 #[tauri::command]
 pub async fn open_vip_purchase_page(app_handle: AppHandle) -> Result<(), String> {
     let url = "https://jingchuanyuexiang.com";
@@ -102,6 +145,70 @@ pub async fn refresh_vip_status(app_handle: AppHandle) -> Result<bool, String> {
     }
 }
 
+#[derive(Serialize, Clone)]
+pub struct VipRefreshResult {
+    is_vip: bool,
+    vip_type: Option<VipType>,
+    expire_time: Option<u64>,
+    max_records: u32,
+    // 最大文件大小限制(字节)
+    max_file_size: u64,
+    // 数据来源: server-服务端 cache-本地缓存 none-无可用数据
+    source: String,
+    vip_changed: bool,
+    // 本地记录条数设置是否因超过限制被下调
+    records_limit_trimmed: bool,
+    failure_reason: Option<String>,
+}
+
+/// 强制从服务器刷新VIP状态，返回详细的刷新结果
+#[tauri::command]
+pub async fn force_refresh_vip_status(app_handle: AppHandle) -> Result<VipRefreshResult, String> {
+    let outcome = VipChecker::refresh_vip_from_server_detailed()
+        .await
+        .map_err(|e| {
+            log::error!("刷新VIP状态失败: {}", e);
+            e.to_string()
+        })?;
+
+    let vip_info = VipChecker::get_local_vip_info().map_err(|e| e.to_string())?;
+    let source = match (&vip_info, outcome.from_server) {
+        (Some(_), true) => "server",
+        (Some(_), false) => "cache",
+        (None, _) => "none",
+    };
+
+    if outcome.from_server {
+        if let Some(info) = &vip_info {
+            let payload = VipStatusChangedPayload {
+                is_vip: info.vip_flag,
+                vip_type: Some(info.vip_type.clone()),
+                expire_time: info.expire_time,
+                max_records: info.max_records,
+            };
+            let _ = app_handle.emit("vip-status-changed", payload);
+        }
+    }
+
+    Ok(VipRefreshResult {
+        is_vip: vip_info.as_ref().map(|info| info.vip_flag).unwrap_or(false),
+        vip_type: vip_info.as_ref().map(|info| info.vip_type.clone()),
+        expire_time: vip_info.as_ref().and_then(|info| info.expire_time),
+        max_records: vip_info
+            .as_ref()
+            .map(|info| info.max_records)
+            .unwrap_or(300),
+        max_file_size: vip_info
+            .as_ref()
+            .map(|info| info.max_file_size * 1024)
+            .unwrap_or(0),
+        source: source.to_string(),
+        vip_changed: outcome.vip_changed,
+        records_limit_trimmed: outcome.records_limit_trimmed,
+        failure_reason: outcome.failure_reason,
+    })
+}
+
 #[tauri::command]
 pub async fn get_server_config() -> Result<
     Option<std::collections::HashMap<VipType, crate::api::vip_api::ServerConfigResponse>>,
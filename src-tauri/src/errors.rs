@@ -53,6 +53,26 @@ pub enum AppError {
     General(String),
 }
 
+impl AppError {
+    /// 是否为数据库被锁定导致的暂时性错误，稍后重试即可成功
+    pub fn is_db_locked(&self) -> bool {
+        match self {
+            AppError::Database(e) => is_db_locked_message(&e.to_string()),
+            _ => false,
+        }
+    }
+}
+
+/// 根据错误信息判断是否为SQLite的SQLITE_BUSY/SQLITE_LOCKED错误
+pub fn is_db_locked_message(message: &str) -> bool {
+    let message = message.to_lowercase();
+    message.contains("database is locked")
+        || message.contains("database table is locked")
+        || message.contains("database is busy")
+        || message.contains("sqlite_busy")
+        || message.contains("sqlite_locked")
+}
+
 /// String 类型的错误转换
 impl From<AppError> for String {
     fn from(err: AppError) -> Self {
@@ -1,7 +1,10 @@
 use crate::auto_paste;
-use crate::{biz::system_setting::Settings, CONTEXT};
+use crate::{
+    biz::{copy_clip_record::paste_recent_sequential, system_setting::Settings},
+    CONTEXT,
+};
 use std::sync::{Arc, RwLock};
-use tauri::{App, Manager};
+use tauri::{App, AppHandle, Manager};
 use tauri_plugin_global_shortcut::GlobalShortcutExt;
 
 pub fn init_global_shortcut(app: &App) -> tauri::Result<()> {
@@ -53,10 +56,46 @@ pub fn init_global_shortcut(app: &App) -> tauri::Result<()> {
             })?;
 
         log::info!("全局快捷键初始化成功: {}", shortcut_str);
+
+        // 依次粘贴快捷键注册失败不影响打开窗口的快捷键
+        let sequential_shortcut = settings.sequential_paste_shortcut.unwrap_or_default();
+        if !sequential_shortcut.is_empty() {
+            if let Err(e) = register_sequential_paste_shortcut(app.handle(), &sequential_shortcut) {
+                log::error!("依次粘贴快捷键注册失败: {}", e);
+            }
+        }
     }
     Ok(())
 }
 
+/// 注册依次粘贴快捷键，按下时把最近几条记录按复制的先后顺序依次粘贴到当前窗口
+pub fn register_sequential_paste_shortcut(
+    app_handle: &AppHandle,
+    shortcut_str: &str,
+) -> Result<(), tauri_plugin_global_shortcut::Error> {
+    app_handle.global_shortcut().on_shortcut(
+        parse_shortcut(shortcut_str),
+        |_app, shortcut, event| {
+            log::debug!(
+                "依次粘贴快捷键触发: {:?}, 状态: {:?}",
+                shortcut,
+                event.state()
+            );
+            if event.state() == tauri_plugin_global_shortcut::ShortcutState::Pressed {
+                // 粘贴目标是按下快捷键时获得焦点的窗口
+                auto_paste::save_foreground_window();
+                tauri::async_runtime::spawn(async {
+                    if let Err(e) = paste_recent_sequential().await {
+                        log::warn!("依次粘贴失败: {}", e);
+                    }
+                });
+            }
+        },
+    )?;
+    log::info!("依次粘贴快捷键注册成功: {}", shortcut_str);
+    Ok(())
+}
+
 // 解析快捷键字符串（保持向后兼容）
 pub fn parse_shortcut(shortcut_str: &str) -> tauri_plugin_global_shortcut::Shortcut {
     use tauri_plugin_global_shortcut::{Code, Modifiers};
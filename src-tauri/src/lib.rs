@@ -1,29 +1,67 @@
-use std::sync::Arc;
+use std::sync::{atomic::AtomicBool, Arc};
 
 use crate::{
+    auto_paste::{clear_paste_target, get_paste_target},
     biz::{
+        background_task_status::{
+            get_background_tasks_status, get_sync_queue_status, spawn_supervised, TASK_CLOUD_SYNC,
+            TASK_FILE_DOWNLOAD,
+        },
         clip_async_queue::{AsyncQueue, consume_clip_record_queue},
         clip_record::ClipRecord,
-        cloud_sync_timer::start_cloud_sync_timer,
-        content_search::initialize_search_index,
+        clip_record_clean::get_record_limit_status,
+        cloud_sync_timer::{start_cloud_sync_timer, sync_now},
+        content_migration::migrate_legacy_content,
+        content_search::{
+            cancel_search_index_build, get_search_index_status, initialize_search_index,
+        },
         copy_clip_record::{
-            copy_clip_record, copy_clip_record_no_paste, copy_single_file, del_record,
-            image_save_as, set_pinned,
+            copy_clip_record, copy_clip_record_no_paste, copy_clip_record_plain, copy_sequential,
+            copy_single_file, del_record, image_save_as, set_favorite, set_pinned, set_sensitive,
+            undo_delete,
+        },
+        download_cloud_file::{
+            download_all_pending, relink_downloaded_file, start_cloud_file_download_timer,
         },
-        download_cloud_file::start_cloud_file_download_timer,
+        export::{export_history, import_history},
+        history_cursor::{copy_next, copy_prev, HistoryCursor},
+        image_bytes::get_image_bytes,
+        image_thumbnail::get_thumbnail_base64,
+        importer::import_from,
+        local_backup::{create_backup, restore_backup},
+        preview_cache::{prefetch_previews, PreviewCache},
         query_clip_record::{
-            get_clip_records, get_full_text_content, get_image_info_batch, get_image_path,
+            get_clip_records, get_clip_stats, get_favorite_clip_records, get_frequent_records,
+            get_full_text_content, get_image_info_batch, get_image_path, get_raw_text_content,
+            get_rich_preview,
+        },
+        recently_deleted::{
+            get_deleted_records, list_recently_deleted, restore_record, restore_records,
+        },
+        record_merge::merge_text_records,
+        record_slot::{assign_slot, clear_slot, get_slot},
+        record_tag::{add_tag, get_clip_records_by_tag, list_tags, remove_tag},
+        record_transfer::{cancel_record_transfer, list_staged_transfers, stage_record_transfer},
+        resource_maintenance::{check_consistency, dedupe_resource_files},
+        sync_conflict::{clear_sync_conflicts, list_sync_conflicts},
+        sync_time::{get_sync_watermark, reset_sync_watermark},
+        system_setting::{
+            enter_kiosk_mode, exit_kiosk_mode, get_capture_enabled, get_kiosk_mode,
+            get_monitoring_enabled, get_request_sign_secret, init_settings, load_settings,
+            preview_settings_change, reset_request_sign_secret, save_settings, set_capture_enabled,
+            set_max_sync_text_bytes, set_monitoring_enabled, test_sync_connection,
+            validate_shortcut,
         },
-        system_setting::{init_settings, load_settings, save_settings, validate_shortcut},
         update_checker::check_update_on_startup,
-        upload_cloud_timer::start_upload_cloud_timer,
+        upload_cloud_timer::{get_record_sync_info, start_upload_cloud_timer, test_file_sync},
         user_auth::{
             check_login_status, check_username, get_user_info, login, logout, send_email_code,
             update_user_info, user_register, validate_token,
         },
         vip_management::{
-            check_vip_permission, get_pay_result, get_pay_url, get_server_config, get_vip_limits,
-            get_vip_status, open_vip_purchase_page, refresh_vip_status,
+            check_vip_permission, force_refresh_vip_status, get_enabled_features, get_pay_result,
+            get_pay_url, get_server_config, get_vip_limits, get_vip_status, open_vip_purchase_page,
+            refresh_vip_status,
         },
     },
     log_config::init_logging,
@@ -31,7 +69,10 @@ use crate::{
     utils::lock_utils::create_global_sync_lock,
 };
 
-use biz::clip_record_sync::ClipboardEventTigger;
+use biz::clip_record_sync::{
+    capture_now, get_capture_status, get_ignored_clip_type_stats, is_capture_ready,
+    mark_capture_ready, ClipboardEventTigger,
+};
 use clipboard_listener::{ClipboardEvent, EventManager};
 use log::LevelFilter;
 use state::TypeMap;
@@ -54,6 +95,9 @@ mod window;
 // 全局上下文存储
 pub static CONTEXT: TypeMap![Send + Sync] = <TypeMap![Send + Sync]>::new();
 
+// 是否记录剪贴板内容，用于处理敏感数据时临时暂停记录，只保存在内存中，重启后恢复为记录
+pub static CAPTURE_ENABLED: AtomicBool = AtomicBool::new(true);
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
     // 初始化日志
@@ -78,18 +122,6 @@ pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
-    // 初始化搜索索引
-    let all_clips = ClipRecord::select_order_by(&rb_res)
-        .await
-        .unwrap_or_else(|e| {
-            log::error!("获取剪贴板记录失败: {}", e);
-            vec![]
-        });
-
-    if let Err(e) = initialize_search_index(all_clips).await {
-        log::error!("搜索索引初始化失败: {}", e);
-    }
-
     // 为不同的地方克隆RBatis实例
     let rb_for_setup = rb_res.clone();
     let rb_for_run = rb_res.clone();
@@ -124,6 +156,8 @@ pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
         }))
         .setup(move |app| {
             CONTEXT.set(app.handle().clone());
+            CONTEXT.set(HistoryCursor::default());
+            CONTEXT.set(PreviewCache::default());
 
             // 初始化菜单栏（macOS 最小化菜单）
             let _ = menu::init_menu(&app);
@@ -140,17 +174,28 @@ pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
             // 初始化剪贴板监听器
             let _ = clip_board_listener::init_clip_board_listener(&app, m1);
 
-            // 启动云同步定时任务
+            // 后台分批初始化搜索索引，不阻塞启动流程
+            let rb_for_index = rb_for_setup.clone();
+            tokio::spawn(async move {
+                if let Err(e) = initialize_search_index(&rb_for_index).await {
+                    log::error!("搜索索引初始化失败: {}", e);
+                }
+
+                // 数据库和搜索索引准备完成，开始处理剪贴板事件
+                mark_capture_ready();
+            });
+
+            // 启动云同步定时任务（异常退出后自动重启）
             let app_handle = app.handle().clone();
             let rb = rb_for_setup.clone();
-            tokio::spawn(async move {
-                start_cloud_sync_timer(app_handle, rb).await;
+            spawn_supervised(TASK_CLOUD_SYNC, move || {
+                start_cloud_sync_timer(app_handle.clone(), rb.clone())
             });
 
-            // 启动云文件下载定时任务
+            // 启动云文件下载定时任务（异常退出后自动重启）
             let app_handle_download = app.handle().clone();
-            tokio::spawn(async move {
-                start_cloud_file_download_timer(app_handle_download).await;
+            spawn_supervised(TASK_FILE_DOWNLOAD, move || {
+                start_cloud_file_download_timer(app_handle_download.clone())
             });
 
             // 应用启动时检查一次更新（5 秒后在后台执行）
@@ -164,18 +209,81 @@ pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
         })
         .invoke_handler(tauri::generate_handler![
             get_clip_records,
+            get_clip_stats,
+            get_favorite_clip_records,
+            get_frequent_records,
             get_image_path,
+            get_image_bytes,
+            get_thumbnail_base64,
             get_image_info_batch,
             get_full_text_content,
+            get_raw_text_content,
+            get_rich_preview,
+            prefetch_previews,
+            get_search_index_status,
+            cancel_search_index_build,
+            get_background_tasks_status,
+            get_sync_queue_status,
+            migrate_legacy_content,
             copy_clip_record,
             copy_clip_record_no_paste,
+            copy_clip_record_plain,
+            copy_sequential,
             copy_single_file,
+            copy_prev,
+            copy_next,
             load_settings,
             save_settings,
+            set_max_sync_text_bytes,
+            test_sync_connection,
+            get_monitoring_enabled,
+            set_monitoring_enabled,
+            get_capture_enabled,
+            set_capture_enabled,
+            get_kiosk_mode,
+            enter_kiosk_mode,
+            exit_kiosk_mode,
             validate_shortcut,
+            get_request_sign_secret,
+            reset_request_sign_secret,
             set_pinned,
+            set_favorite,
+            set_sensitive,
             del_record,
+            undo_delete,
+            list_recently_deleted,
+            get_deleted_records,
+            restore_record,
+            restore_records,
+            assign_slot,
+            clear_slot,
+            get_slot,
+            add_tag,
+            remove_tag,
+            get_clip_records_by_tag,
+            list_tags,
+            merge_text_records,
+            list_sync_conflicts,
+            clear_sync_conflicts,
+            get_sync_watermark,
+            reset_sync_watermark,
             image_save_as,
+            dedupe_resource_files,
+            check_consistency,
+            preview_settings_change,
+            get_record_limit_status,
+            sync_now,
+            get_ignored_clip_type_stats,
+            get_capture_status,
+            capture_now,
+            get_paste_target,
+            clear_paste_target,
+            is_capture_ready,
+            import_from,
+            create_backup,
+            export_history,
+            import_history,
+            restore_backup,
             login,
             user_register,
             send_email_code,
@@ -189,8 +297,17 @@ pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
             get_vip_status,
             check_vip_permission,
             get_vip_limits,
+            get_enabled_features,
             open_vip_purchase_page,
             refresh_vip_status,
+            force_refresh_vip_status,
+            test_file_sync,
+            get_record_sync_info,
+            stage_record_transfer,
+            list_staged_transfers,
+            cancel_record_transfer,
+            relink_downloaded_file,
+            download_all_pending,
             get_server_config,
             get_pay_url,
             get_pay_result,
@@ -23,7 +23,7 @@ pub struct TableSchema {
 }
 
 /// 当前代码中期望的数据库结构
-fn get_expected_schema() -> HashMap<String, TableSchema> {
+pub(crate) fn get_expected_schema() -> HashMap<String, TableSchema> {
     let mut schema = HashMap::new();
 
     // clip_record 表的期望结构
@@ -32,6 +32,12 @@ fn get_expected_schema() -> HashMap<String, TableSchema> {
     // sync_time 表的期望结构
     get_sync_time_record_schema(&mut schema);
 
+    // sync_conflict 表的期望结构
+    get_sync_conflict_schema(&mut schema);
+
+    // record_transfer 表的期望结构
+    get_record_transfer_schema(&mut schema);
+
     schema
 }
 
@@ -62,6 +68,76 @@ fn get_sync_time_record_schema(schema: &mut HashMap<String, TableSchema>) {
     );
 }
 
+fn get_sync_conflict_schema(schema: &mut HashMap<String, TableSchema>) {
+    let text_column = |name: &str, not_null: bool, primary_key: bool| ColumnInfo {
+        name: name.to_string(),
+        r#type: "TEXT".to_string(),
+        not_null,
+        default_value: None,
+        primary_key,
+    };
+    let sync_conflict_columns = vec![
+        text_column("id", true, true),
+        text_column("record_id", true, false),
+        text_column("record_type", true, false),
+        text_column("md5_str", true, false),
+        text_column("conflict_type", true, false),
+        text_column("local_state", true, false),
+        text_column("remote_state", true, false),
+        text_column("resolution", true, false),
+        text_column("remote_device_id", false, false),
+        ColumnInfo {
+            name: "created".to_string(),
+            r#type: "INTEGER".to_string(),
+            not_null: true,
+            default_value: None,
+            primary_key: false,
+        },
+    ];
+
+    schema.insert(
+        "sync_conflict".to_string(),
+        TableSchema {
+            name: "sync_conflict".to_string(),
+            columns: sync_conflict_columns,
+        },
+    );
+}
+
+fn get_record_transfer_schema(schema: &mut HashMap<String, TableSchema>) {
+    let text_column = |name: &str, not_null: bool, primary_key: bool| ColumnInfo {
+        name: name.to_string(),
+        r#type: "TEXT".to_string(),
+        not_null,
+        default_value: None,
+        primary_key,
+    };
+    let integer_column = |name: &str| ColumnInfo {
+        name: name.to_string(),
+        r#type: "INTEGER".to_string(),
+        not_null: true,
+        default_value: None,
+        primary_key: false,
+    };
+    let record_transfer_columns = vec![
+        text_column("id", true, true),
+        text_column("record_type", true, false),
+        text_column("snapshot", true, false),
+        integer_column("source_user_id"),
+        text_column("source_account", true, false),
+        text_column("staged_resource", false, false),
+        integer_column("staged_time"),
+    ];
+
+    schema.insert(
+        "record_transfer".to_string(),
+        TableSchema {
+            name: "record_transfer".to_string(),
+            columns: record_transfer_columns,
+        },
+    );
+}
+
 fn get_clip_pal_record_schema(schema: &mut HashMap<String, TableSchema>) {
     // clip_record 表的期望结构
     let clip_record_columns = vec![
@@ -177,6 +253,118 @@ fn get_clip_pal_record_schema(schema: &mut HashMap<String, TableSchema>) {
             default_value: None,
             primary_key: false,
         },
+        ColumnInfo {
+            name: "del_time".to_string(),
+            r#type: "INTEGER".to_string(),
+            not_null: false,
+            default_value: None,
+            primary_key: false,
+        },
+        ColumnInfo {
+            name: "raw_content".to_string(),
+            r#type: "TEXT".to_string(),
+            not_null: false,
+            default_value: None,
+            primary_key: false,
+        },
+        ColumnInfo {
+            name: "slot".to_string(),
+            r#type: "INTEGER".to_string(),
+            not_null: false,
+            default_value: None,
+            primary_key: false,
+        },
+        ColumnInfo {
+            name: "file_modified".to_string(),
+            r#type: "INTEGER".to_string(),
+            not_null: false,
+            default_value: None,
+            primary_key: false,
+        },
+        ColumnInfo {
+            name: "file_created".to_string(),
+            r#type: "INTEGER".to_string(),
+            not_null: false,
+            default_value: None,
+            primary_key: false,
+        },
+        ColumnInfo {
+            name: "sensitive".to_string(),
+            r#type: "INTEGER".to_string(),
+            not_null: false,
+            default_value: Some("0".to_string()),
+            primary_key: false,
+        },
+        ColumnInfo {
+            name: "rich_type".to_string(),
+            r#type: "TEXT".to_string(),
+            not_null: false,
+            default_value: None,
+            primary_key: false,
+        },
+        ColumnInfo {
+            name: "rich_content".to_string(),
+            r#type: "TEXT".to_string(),
+            not_null: false,
+            default_value: None,
+            primary_key: false,
+        },
+        ColumnInfo {
+            name: "ocr_text".to_string(),
+            r#type: "TEXT".to_string(),
+            not_null: false,
+            default_value: None,
+            primary_key: false,
+        },
+        ColumnInfo {
+            name: "favorite_flag".to_string(),
+            r#type: "INTEGER".to_string(),
+            not_null: false,
+            default_value: Some("0".to_string()),
+            primary_key: false,
+        },
+        ColumnInfo {
+            name: "source_app".to_string(),
+            r#type: "TEXT".to_string(),
+            not_null: false,
+            default_value: None,
+            primary_key: false,
+        },
+        ColumnInfo {
+            name: "title".to_string(),
+            r#type: "TEXT".to_string(),
+            not_null: false,
+            default_value: None,
+            primary_key: false,
+        },
+        ColumnInfo {
+            name: "use_count".to_string(),
+            r#type: "INTEGER".to_string(),
+            not_null: false,
+            default_value: Some("0".to_string()),
+            primary_key: false,
+        },
+        ColumnInfo {
+            name: "tags".to_string(),
+            r#type: "TEXT".to_string(),
+            not_null: false,
+            default_value: None,
+            primary_key: false,
+        },
+        ColumnInfo {
+            name: "meta_only_sync".to_string(),
+            r#type: "INTEGER".to_string(),
+            not_null: false,
+            default_value: None,
+            primary_key: false,
+        },
+        ColumnInfo {
+            name: "modified_time".to_string(),
+            r#type: "INTEGER".to_string(),
+            not_null: false,
+            default_value: None,
+            primary_key: false,
+        },
     ];
 
     schema.insert(
@@ -347,12 +535,18 @@ async fn create_indexes(rb: &RBatis) -> AppResult<()> {
         vec![],
     )
     .await?;
+    // 每个编号槽位只能分配给一条记录
+    conn.exec(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_clip_record_slot ON clip_record(slot) WHERE slot IS NOT NULL",
+        vec![],
+    )
+    .await?;
 
     Ok(())
 }
 
 /// 检查并修复数据库结构
-async fn check_and_fix_database_schema(rb: &RBatis) -> AppResult<()> {
+pub(crate) async fn check_and_fix_database_schema(rb: &RBatis) -> AppResult<()> {
     log::debug!("检查数据库结构...");
 
     // 获取期望的结构
@@ -381,6 +575,17 @@ async fn check_and_fix_database_schema(rb: &RBatis) -> AppResult<()> {
     Ok(())
 }
 
+/// 开启WAL日志模式（设置会持久化到数据库文件）
+/// WAL模式下读操作不会阻塞写操作，剪贴板监听、同步队列、云同步定时任务等并发写入时更不容易出现锁冲突
+async fn enable_wal_mode(rb: &RBatis) {
+    let res: Result<String, rbs::Error> =
+        rb.query_decode("PRAGMA journal_mode = WAL", vec![]).await;
+    match res {
+        Ok(mode) => log::info!("SQLite日志模式: {}", mode),
+        Err(e) => log::warn!("开启WAL模式失败，继续使用默认日志模式: {}", e),
+    }
+}
+
 pub async fn init_sqlite() -> AppResult<RBatis> {
     // 创建sqlite链接
     let rb = RBatis::new();
@@ -397,6 +602,9 @@ pub async fn init_sqlite() -> AppResult<RBatis> {
     rb.init(rbdc_sqlite::Driver {}, &format!("sqlite://{}", db_path_str))
         .map_err(|e| AppError::Database(e))?;
 
+    // 开启WAL模式，减少多个后台任务并发写入时的锁冲突
+    enable_wal_mode(&rb).await;
+
     // 检查并修复数据库结构
     check_and_fix_database_schema(&rb).await?;
 
@@ -407,3 +615,92 @@ pub async fn init_sqlite() -> AppResult<RBatis> {
 
     Ok(rb)
 }
+
+/// 测试使用的临时数据库
+#[cfg(test)]
+pub(crate) mod test_db {
+    use super::*;
+    use std::path::{Path, PathBuf};
+
+    /// 在临时目录创建数据库，开启WAL并初始化表结构
+    pub(crate) async fn open_temp_db(name: &str) -> (RBatis, PathBuf) {
+        let path =
+            std::env::temp_dir().join(format!("clip_pal_{}_{}.db", name, uuid::Uuid::new_v4()));
+        let rb = RBatis::new();
+        rb.init(
+            rbdc_sqlite::Driver {},
+            &format!("sqlite://{}", to_safe_string(&path)),
+        )
+        .unwrap();
+        enable_wal_mode(&rb).await;
+        check_and_fix_database_schema(&rb).await.unwrap();
+        (rb, path)
+    }
+
+    /// 关闭临时数据库，并删除数据库文件以及-wal、-shm文件
+    pub(crate) fn remove_temp_db(rb: RBatis, path: &Path) {
+        drop(rb);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", to_safe_string(path), suffix));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_db::{open_temp_db, remove_temp_db};
+    use crate::biz::clip_record::ClipRecord;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_writes_not_lost() {
+        let (rb, db_path) = open_temp_db("stress").await;
+
+        const TASKS: usize = 8;
+        const RECORDS_PER_TASK: usize = 20;
+
+        // 多个任务并发插入并更新记录，模拟剪贴板监听、同步队列、定时任务同时写入
+        let mut handles = Vec::new();
+        for task in 0..TASKS {
+            let rb = rb.clone();
+            handles.push(tokio::spawn(async move {
+                for i in 0..RECORDS_PER_TASK {
+                    let id = format!("{}-{}", task, i);
+                    let record = ClipRecord {
+                        id: id.clone(),
+                        r#type: "Text".to_string(),
+                        content: serde_json::Value::String("origin".to_string()),
+                        md5_str: id.clone(),
+                        created: (task * RECORDS_PER_TASK + i) as u64,
+                        sync_flag: Some(0),
+                        del_flag: Some(0),
+                        ..Default::default()
+                    };
+                    ClipRecord::insert_by_created_sort(&rb, record)
+                        .await
+                        .unwrap();
+                    ClipRecord::update_content(&rb, &id, &format!("updated-{}", id))
+                        .await
+                        .unwrap();
+                    ClipRecord::update_sync_flag(&rb, &vec![id.clone()], 2, 1)
+                        .await
+                        .unwrap();
+                }
+            }));
+        }
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        let records = ClipRecord::select_order_by(&rb).await.unwrap();
+        assert_eq!(records.len(), TASKS * RECORDS_PER_TASK);
+        for record in records {
+            assert_eq!(
+                record.content.as_str(),
+                Some(format!("updated-{}", record.id).as_str())
+            );
+            assert_eq!(record.sync_flag, Some(2));
+        }
+
+        remove_temp_db(rb, &db_path);
+    }
+}
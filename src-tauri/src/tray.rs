@@ -6,7 +6,15 @@ use tauri::{AppHandle, Emitter};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 
-use crate::{auto_paste, CONTEXT};
+use crate::{
+    auto_paste,
+    biz::system_setting::{
+        check_capture_enabled, check_kiosk_mode_enabled, check_monitoring_enabled,
+        get_disabled_capture_types, set_capture_enabled,
+    },
+    CONTEXT,
+};
+use clipboard_listener::ClipType;
 
 /// 防抖控制结构
 #[derive(Debug)]
@@ -56,18 +64,120 @@ impl TrayClickDebounce {
     }
 }
 
-pub fn create_tray<R: Runtime>(app: &tauri::AppHandle<R>) -> tauri::Result<()> {
-    // 为系统创建托盘图标
-    let icon = Image::from_bytes(include_bytes!("../icons/icon_128x128.png"))?;
+static TRAY_ICON_BYTES: &[u8] = include_bytes!("../icons/icon_128x128.png");
+
+/// 生成托盘提示文本，有未记录的剪贴板类型时一并提示
+fn build_tooltip() -> String {
+    if check_kiosk_mode_enabled() {
+        return "ClipPal（只读模式）".to_string();
+    }
+    if !check_monitoring_enabled() {
+        return "ClipPal（已关闭监听）".to_string();
+    }
+    if !check_capture_enabled() {
+        return "ClipPal（已暂停记录）".to_string();
+    }
+    let disabled: Vec<&str> = get_disabled_capture_types()
+        .iter()
+        .map(|clip_type| match clip_type {
+            ClipType::Text => "文本",
+            ClipType::Image => "图片",
+            ClipType::File => "文件",
+            _ => "其他",
+        })
+        .collect();
+    if disabled.is_empty() {
+        "ClipPal".to_string()
+    } else {
+        format!("ClipPal（不记录: {}）", disabled.join("、"))
+    }
+}
+
+/// 刷新托盘提示文本（记录类型设置变化时调用）
+pub fn refresh_tray_tooltip() {
+    let Some(app_handle) = CONTEXT.try_get::<AppHandle>() else {
+        return;
+    };
+    if let Some(tray) = app_handle.tray_by_id("tray") {
+        if let Err(e) = tray.set_tooltip(Some(build_tooltip())) {
+            log::warn!("更新托盘提示失败: {}", e);
+        }
+    }
+}
+
+/// 生成关闭监听时的托盘图标（灰度半透明）
+fn build_disabled_icon() -> Option<Image<'static>> {
+    let mut rgba = image::load_from_memory(TRAY_ICON_BYTES).ok()?.to_rgba8();
+    for pixel in rgba.pixels_mut() {
+        let [r, g, b, a] = pixel.0;
+        let gray = ((r as u32 * 30 + g as u32 * 59 + b as u32 * 11) / 100) as u8;
+        pixel.0 = [gray, gray, gray, a / 2];
+    }
+    let (width, height) = rgba.dimensions();
+    Some(Image::new_owned(rgba.into_raw(), width, height))
+}
+
+/// 根据剪贴板监听状态生成托盘图标，关闭监听、暂停记录或只读模式时使用灰色图标
+fn build_tray_icon() -> tauri::Result<Image<'static>> {
+    if !check_monitoring_enabled() || !check_capture_enabled() || check_kiosk_mode_enabled() {
+        if let Some(icon) = build_disabled_icon() {
+            return Ok(icon);
+        }
+    }
+    Image::from_bytes(TRAY_ICON_BYTES)
+}
+
+/// 生成托盘菜单，暂停记录菜单项的文字随当前状态变化
+fn build_tray_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<Menu<R>> {
+    let capture_text = if check_capture_enabled() {
+        "暂停记录"
+    } else {
+        "恢复记录"
+    };
+    let toggle_capture = MenuItem::with_id(app, "toggleCapture", capture_text, true, None::<&str>)?;
     let quit_i = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
     let set_sys = MenuItem::with_id(app, "setSys", "设置", true, None::<&str>)?;
-    let menu = Menu::with_items(app, &[&set_sys, &quit_i])?;
+    Menu::with_items(app, &[&toggle_capture, &set_sys, &quit_i])
+}
+
+/// 刷新托盘图标、提示文本和菜单（剪贴板监听开启或关闭、暂停或恢复记录时调用）
+pub fn refresh_tray_monitoring_state() {
+    let Some(app_handle) = CONTEXT.try_get::<AppHandle>() else {
+        return;
+    };
+    if let Some(tray) = app_handle.tray_by_id("tray") {
+        match build_tray_icon() {
+            Ok(icon) => {
+                if let Err(e) = tray.set_icon(Some(icon)) {
+                    log::warn!("更新托盘图标失败: {}", e);
+                }
+            }
+            Err(e) => log::warn!("生成托盘图标失败: {}", e),
+        }
+        if let Err(e) = tray.set_tooltip(Some(build_tooltip())) {
+            log::warn!("更新托盘提示失败: {}", e);
+        }
+        match build_tray_menu(app_handle) {
+            Ok(menu) => {
+                if let Err(e) = tray.set_menu(Some(menu)) {
+                    log::warn!("更新托盘菜单失败: {}", e);
+                }
+            }
+            Err(e) => log::warn!("生成托盘菜单失败: {}", e),
+        }
+    }
+}
+
+pub fn create_tray<R: Runtime>(app: &tauri::AppHandle<R>) -> tauri::Result<()> {
+    // 为系统创建托盘图标，关闭监听时使用灰色图标
+    let icon = build_tray_icon()?;
+    let menu = build_tray_menu(app)?;
 
     // 创建防抖控制器
     let debounce = TrayClickDebounce::new();
 
     let _ = TrayIconBuilder::with_id("tray")
-        .tooltip("ClipPal")
+        .tooltip(build_tooltip())
         .icon(icon)
         // 设置托盘图标菜单
         .menu(&menu)
@@ -78,6 +188,9 @@ pub fn create_tray<R: Runtime>(app: &tauri::AppHandle<R>) -> tauri::Result<()> {
             "quit" => {
                 app.exit(0);
             }
+            "toggleCapture" => {
+                set_capture_enabled(!check_capture_enabled());
+            }
             "setSys" => {
                 // 通知前端显示系统设置窗口
                 let app_handle = CONTEXT.get::<AppHandle>();
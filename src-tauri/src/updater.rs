@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
 use tauri_plugin_updater::UpdaterExt;
 
-use crate::CONTEXT;
+use crate::{biz::system_setting::ensure_not_kiosk_mode, CONTEXT};
 
 /// 更新信息结构体
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -91,6 +91,7 @@ pub async fn check_soft_version() -> Result<UpdateInfo, String> {
 /// 下载并安装更新
 #[tauri::command]
 pub async fn download_and_install_update() -> Result<bool, String> {
+    ensure_not_kiosk_mode()?;
     let app_handle = CONTEXT.get::<AppHandle>();
     let updater_res = app_handle.updater();
 
@@ -1,4 +1,4 @@
-use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::aead::{Aead, KeyInit, Payload};
 use aes_gcm::{Aes256Gcm, Key, Nonce};
 use base64::{engine::general_purpose, Engine as _};
 use rand::rngs::OsRng;
@@ -6,21 +6,52 @@ use rand::TryRngCore;
 
 use crate::{
     errors::{AppError, AppResult},
-    utils::app_secret_key::get_decoded_secret_key,
+    utils::{
+        app_secret_key::get_decoded_secret_key,
+        text_compression::{compress_text, decompress_text, is_compressed},
+    },
 };
 
-const KEY_SIZE: usize = 32; // 256-bit
+pub const KEY_SIZE: usize = 32; // 256-bit
 const NONCE_SIZE: usize = 12;
+// AES-GCM 认证标签长度
+const TAG_SIZE: usize = 16;
 
 /// 内容加密
 pub fn encrypt_content(content: &str) -> AppResult<String> {
+    encrypt_bytes(content.as_bytes())
+}
+
+/// 内容压缩后加密，内容达到 threshold 字节时才压缩，threshold 为0时不压缩
+/// 解密时 decrypt_content 会自动解压
+pub fn encrypt_content_compressed(content: &str, threshold: u64) -> AppResult<String> {
+    encrypt_bytes(&compress_text(content, threshold))
+}
+
+fn encrypt_bytes(plaintext: &[u8]) -> AppResult<String> {
+    Ok(general_purpose::STANDARD.encode(encrypt_raw_bytes(plaintext)?))
+}
+
+/// 二进制数据加密，返回 nonce + 密文，不做Base64编码，用于加密导出文件等大块数据
+pub fn encrypt_raw_bytes(plaintext: &[u8]) -> AppResult<Vec<u8>> {
+    encrypt_raw_bytes_with_aad(&load_secret_key()?, plaintext, &[])
+}
+
+/// 加载并解码全局加密密钥，分块加密大文件时只需加载一次
+pub fn load_secret_key() -> AppResult<[u8; KEY_SIZE]> {
     // 加载配置
     let app_config = get_decoded_secret_key()?;
+    decode_base64_key(&app_config.secret_key)
+        .map_err(|e| AppError::Crypto(format!("密钥解码失败: {}", e)))
+}
 
-    let decode_res = decode_base64_key(&app_config.secret_key)
-        .map_err(|e| AppError::Crypto(format!("密钥解码失败: {}", e)))?;
-
-    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&decode_res));
+/// 使用指定密钥加密二进制数据，aad 作为附加认证数据参与校验但不写入结果，返回 nonce + 密文
+pub fn encrypt_raw_bytes_with_aad(
+    key: &[u8; KEY_SIZE],
+    plaintext: &[u8],
+    aad: &[u8],
+) -> AppResult<Vec<u8>> {
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
 
     let mut nonce_bytes = [0u8; NONCE_SIZE];
     OsRng
@@ -30,19 +61,67 @@ pub fn encrypt_content(content: &str) -> AppResult<String> {
     let nonce = Nonce::from_slice(&nonce_bytes);
 
     let ciphertext = cipher
-        .encrypt(nonce, content.as_bytes())
+        .encrypt(
+            nonce,
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
         .map_err(|e| AppError::Crypto(format!("加密失败: {}", e)))?;
 
     // 拼接 nonce + ciphertext
-    let mut result = Vec::new();
+    let mut result = Vec::with_capacity(NONCE_SIZE + ciphertext.len());
     result.extend_from_slice(&nonce_bytes);
     result.extend_from_slice(&ciphertext);
 
-    Ok(general_purpose::STANDARD.encode(result))
+    Ok(result)
+}
+
+/// 解密 encrypt_raw_bytes_with_aad 生成的数据，aad 必须与加密时一致
+pub fn decrypt_raw_bytes_with_aad(
+    key: &[u8; KEY_SIZE],
+    data: &[u8],
+    aad: &[u8],
+) -> AppResult<Vec<u8>> {
+    if data.len() < NONCE_SIZE {
+        return Err(AppError::Crypto("数据长度不足".to_string()));
+    }
+
+    let (nonce_bytes, ciphertext) = data.split_at(NONCE_SIZE);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    cipher
+        .decrypt(
+            Nonce::from_slice(nonce_bytes),
+            Payload {
+                msg: ciphertext,
+                aad,
+            },
+        )
+        .map_err(|e| AppError::Crypto(format!("解密失败: {}", e)))
 }
 
 /// 内容解密
 pub fn decrypt_content(encoded: &str) -> AppResult<String> {
+    // 压缩存储的大文本需要先解压
+    let decrypted_bytes = decompress_text(decrypt_bytes(encoded)?)?;
+
+    String::from_utf8(decrypted_bytes)
+        .map_err(|e| AppError::Crypto(format!("UTF-8转换失败: {}", e)))
+}
+
+/// 压缩存储的密文解压后重新加密，未压缩的密文原样返回
+/// 压缩格式只在本地使用，上传云端前转换，避免不支持压缩格式的旧版本客户端解密出乱码
+pub fn to_uncompressed_ciphertext(encoded: &str) -> AppResult<String> {
+    let decrypted_bytes = decrypt_bytes(encoded)?;
+    if !is_compressed(&decrypted_bytes) {
+        return Ok(encoded.to_string());
+    }
+    encrypt_bytes(&decompress_text(decrypted_bytes)?)
+}
+
+/// 解密 Base64 编码的 nonce + 密文，返回的数据可能带有压缩标记
+fn decrypt_bytes(encoded: &str) -> AppResult<Vec<u8>> {
     // 加载配置
     let app_config = get_decoded_secret_key()?;
 
@@ -61,12 +140,18 @@ pub fn decrypt_content(encoded: &str) -> AppResult<String> {
     let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&decode_res));
     let nonce = Nonce::from_slice(nonce_bytes);
 
-    let decrypted_bytes = cipher
+    cipher
         .decrypt(nonce, ciphertext)
-        .map_err(|e| AppError::Crypto(format!("解密失败: {}", e)))?;
+        .map_err(|e| AppError::Crypto(format!("解密失败: {}", e)))
+}
 
-    String::from_utf8(decrypted_bytes)
-        .map_err(|e| AppError::Crypto(format!("UTF-8转换失败: {}", e)))
+/// 判断内容在格式上是否像当前方案生成的密文（Base64编码的 nonce + 密文 + 认证标签）
+/// 用于区分未加密的旧数据和无法解密的密文
+pub fn looks_like_ciphertext(content: &str) -> bool {
+    match general_purpose::STANDARD.decode(content) {
+        Ok(data) => data.len() >= NONCE_SIZE + TAG_SIZE,
+        Err(_) => false,
+    }
 }
 
 #[allow(dead_code)]
@@ -59,3 +59,22 @@ pub fn get_logs_dir() -> Option<PathBuf> {
         path
     })
 }
+
+/// 粘贴文件时用于还原显示文件名的临时目录
+pub fn get_paste_temp_dir() -> PathBuf {
+    std::env::temp_dir().join("clip_pal_temp")
+}
+
+/// 判断路径是否位于粘贴临时目录下（即ClipPal自己生成的临时文件）
+pub fn is_in_paste_temp_dir(path: &Path) -> bool {
+    let temp_dir = get_paste_temp_dir();
+    if path.starts_with(&temp_dir) {
+        return true;
+    }
+
+    // 临时目录可能包含符号链接（例如macOS的/var -> /private/var），规范化后再比较
+    match (fs::canonicalize(path), fs::canonicalize(&temp_dir)) {
+        (Ok(path), Ok(temp_dir)) => path.starts_with(temp_dir),
+        _ => false,
+    }
+}
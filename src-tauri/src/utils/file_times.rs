@@ -0,0 +1,83 @@
+use std::fs::{File, FileTimes};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// 文件时间戳工具
+// 复制到resources目录或粘贴时生成的临时文件默认使用当前时间，需要还原为原文件的时间
+
+/// 文件的修改时间和创建时间（毫秒时间戳）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FileTimestamps {
+    pub modified: Option<u64>,
+    pub created: Option<u64>,
+}
+
+impl FileTimestamps {
+    pub fn is_empty(&self) -> bool {
+        self.modified.is_none() && self.created.is_none()
+    }
+}
+
+fn to_millis(time: SystemTime) -> Option<u64> {
+    time.duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|duration| duration.as_millis() as u64)
+}
+
+fn from_millis(millis: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(millis)
+}
+
+/// 读取文件的修改时间和创建时间，平台不支持的时间为None
+pub fn read_file_times(path: &Path) -> FileTimestamps {
+    match std::fs::metadata(path) {
+        Ok(metadata) => FileTimestamps {
+            modified: metadata.modified().ok().and_then(to_millis),
+            created: metadata.created().ok().and_then(to_millis),
+        },
+        Err(_) => FileTimestamps::default(),
+    }
+}
+
+/// 设置文件的修改时间和创建时间，创建时间只在Windows和macOS上支持
+pub fn apply_file_times(path: &Path, times: &FileTimestamps) -> std::io::Result<()> {
+    if times.is_empty() {
+        return Ok(());
+    }
+    let mut file_times = FileTimes::new();
+    if let Some(modified) = times.modified {
+        file_times = file_times.set_modified(from_millis(modified));
+    }
+    #[cfg(windows)]
+    if let Some(created) = times.created {
+        use std::os::windows::fs::FileTimesExt;
+        file_times = file_times.set_created(from_millis(created));
+    }
+    #[cfg(target_os = "macos")]
+    if let Some(created) = times.created {
+        use std::os::macos::fs::FileTimesExt;
+        file_times = file_times.set_created(from_millis(created));
+    }
+    let file = File::options().write(true).open(path)?;
+    file.set_times(file_times)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_file_times_restores_modified_time() {
+        let path = std::env::temp_dir().join(format!("clip_pal_file_times_{}", std::process::id()));
+        std::fs::write(&path, b"test").unwrap();
+
+        let times = FileTimestamps {
+            modified: Some(1_600_000_000_000),
+            created: None,
+        };
+        apply_file_times(&path, &times).unwrap();
+        assert_eq!(read_file_times(&path).modified, Some(1_600_000_000_000));
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
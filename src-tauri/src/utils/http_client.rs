@@ -1,14 +1,21 @@
 #![allow(dead_code)]
 
+use crate::utils::request_signer::HEADER_SIGNATURE;
+use crate::utils::retry_helper::{retry_with_config, RetryConfig};
+use crate::utils::sensitive_log::is_content_log_suppressed;
+use crate::utils::text_redaction::REDACTION_MASK;
+use once_cell::sync::Lazy;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::Write;
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
 use tauri_plugin_http::{
     reqwest,
     reqwest::header::{HeaderMap, HeaderName, HeaderValue},
 };
+use tokio::io::AsyncWriteExt;
 
 /// 统一API响应结构体
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +41,14 @@ pub struct HttpConfig {
     pub timeout: Option<u64>,
     pub headers: Option<HashMap<String, String>>,
     pub user_agent: Option<String>,
+    /// 是否在日志中输出请求头、请求体和响应内容（令牌等敏感字段会被替换），默认关闭
+    pub log_bodies: bool,
+    /// GET请求遇到超时、连接中断等网络错误时的最大重试次数，0 表示不重试（POST等非幂等请求不重试）
+    pub max_retries: usize,
+    /// 是否自动跟随重定向，默认开启
+    pub follow_redirects: bool,
+    /// 指定域名解析到的地址，不再通过DNS解析（用于固定已检查过的地址）
+    pub resolve_overrides: Vec<(String, SocketAddr)>,
 }
 
 impl Default for HttpConfig {
@@ -42,10 +57,70 @@ impl Default for HttpConfig {
             timeout: Some(30),
             headers: None,
             user_agent: Some("ClipPal/1.0".to_string()),
+            log_bodies: false,
+            max_retries: DEFAULT_GET_MAX_RETRIES,
+            follow_redirects: true,
+            resolve_overrides: Vec::new(),
         }
     }
 }
 
+// GET请求网络错误的默认重试次数
+const DEFAULT_GET_MAX_RETRIES: usize = 2;
+// GET请求重试的初始延迟和最大延迟（毫秒），间隔较短，避免界面长时间等待
+const GET_RETRY_INITIAL_DELAY_MS: u64 = 300;
+const GET_RETRY_MAX_DELAY_MS: u64 = 2000;
+
+// 日志中需要隐藏值的请求头，不区分大小写
+const SENSITIVE_HEADERS: [&str; 4] = ["authorization", "cookie", "set-cookie", HEADER_SIGNATURE];
+
+// 日志中需要隐藏的令牌：JSON中令牌、密码类字段的值，Bearer令牌，JWT
+static TOKEN_FIELD_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)("[\w]*(?:token|password|secret|authorization)[\w]*"\s*:\s*")[^"]*(")"#)
+        .expect("令牌字段正则无效")
+});
+static BEARER_TOKEN_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\bbearer\s+[A-Za-z0-9\-._~+/]+=*").expect("Bearer令牌正则无效"));
+static JWT_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\beyJ[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+\.[A-Za-z0-9_-]*").expect("JWT正则无效")
+});
+
+/// 替换日志文本中的令牌、密码等敏感内容
+fn redact_log_text(text: &str) -> Cow<'_, str> {
+    let mut result = Cow::Borrowed(text);
+    let field_mask = format!("${{1}}{}${{2}}", REDACTION_MASK);
+    if let Cow::Owned(replaced) = TOKEN_FIELD_PATTERN.replace_all(&result, field_mask.as_str()) {
+        result = Cow::Owned(replaced);
+    }
+    let bearer_mask = format!("Bearer {}", REDACTION_MASK);
+    if let Cow::Owned(replaced) = BEARER_TOKEN_PATTERN.replace_all(&result, bearer_mask.as_str()) {
+        result = Cow::Owned(replaced);
+    }
+    if let Cow::Owned(replaced) = JWT_PATTERN.replace_all(&result, REDACTION_MASK) {
+        result = Cow::Owned(replaced);
+    }
+    result
+}
+
+/// 格式化请求头用于日志输出，Authorization 等请求头的值会被隐藏
+fn format_headers_for_log(headers: &HeaderMap) -> String {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if SENSITIVE_HEADERS
+                .iter()
+                .any(|header| name.as_str().eq_ignore_ascii_case(header))
+            {
+                REDACTION_MASK.to_string()
+            } else {
+                redact_log_text(value.to_str().unwrap_or("<非文本>")).into_owned()
+            };
+            format!("{}: {}", name, value)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// HTTP请求错误
 #[derive(Debug, thiserror::Error)]
 pub enum HttpError {
@@ -71,6 +146,68 @@ pub enum HttpError {
     DownloadFailed(String),
 }
 
+impl HttpError {
+    /// 是否为可以重试的临时网络错误（超时、连接失败或中断）
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, HttpError::Timeout(_) | HttpError::NetworkError(_))
+    }
+}
+
+/// 文件下载进度回调，参数为已下载字节数和 Content-Length 总字节数（服务器未返回时为None）
+pub type DownloadProgressFn = dyn Fn(u64, Option<u64>) + Send + Sync;
+
+// 下载进度回调的最小间隔字节数
+const DOWNLOAD_PROGRESS_STEP_BYTES: u64 = 256 * 1024;
+
+/// 解析 Content-Range 响应头（如 bytes 100-999/1000），返回起始位置和文件总大小
+/// 总大小为 * 时返回None
+fn parse_content_range(value: &str) -> Option<(u64, Option<u64>)> {
+    let range = value.trim().strip_prefix("bytes")?.trim_start();
+    let (range, total) = range.split_once('/')?;
+    let (start, _end) = range.split_once('-')?;
+    let start = start.trim().parse::<u64>().ok()?;
+    let total = match total.trim() {
+        "*" => None,
+        total => Some(total.parse::<u64>().ok()?),
+    };
+    Some((start, total))
+}
+
+/// 部分文件对应的校验信息文件路径，保存下载开始时服务器返回的 ETag 或 Last-Modified
+fn resume_validator_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".validator");
+    path.with_file_name(file_name)
+}
+
+/// 从响应头中取出续传时用于 If-Range 的校验值，优先使用强ETag，弱ETag不能用于 If-Range
+fn resume_validator(headers: &HeaderMap) -> Option<String> {
+    let etag = headers
+        .get(reqwest::header::ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|etag| !etag.is_empty() && !etag.starts_with("W/"));
+    let last_modified = || {
+        headers
+            .get(reqwest::header::LAST_MODIFIED)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+    etag.or_else(last_modified).map(str::to_string)
+}
+
+/// 删除下载失败或已损坏的部分文件及其校验信息
+pub(crate) async fn remove_partial_file(path: &Path) {
+    for path in [path.to_path_buf(), resume_validator_path(path)] {
+        if let Err(e) = tokio::fs::remove_file(&path).await {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                log::warn!("删除不完整的下载文件失败: {:?}, {}", path, e);
+            }
+        }
+    }
+}
+
 /// 请求数据类型枚举
 enum RequestData {
     Json(String),
@@ -115,6 +252,68 @@ impl HttpClient {
         self
     }
 
+    /// 设置GET请求遇到网络错误时的最大重试次数，0 表示不重试
+    pub fn max_retries(mut self, max_retries: usize) -> Self {
+        self.config.max_retries = max_retries;
+        self
+    }
+
+    /// 设置是否自动跟随重定向，关闭后重定向响应按非成功状态处理
+    pub fn follow_redirects(mut self, follow_redirects: bool) -> Self {
+        self.config.follow_redirects = follow_redirects;
+        self
+    }
+
+    /// 将域名固定解析到指定地址，请求时不再进行DNS解析
+    pub fn resolve(mut self, host: &str, addr: SocketAddr) -> Self {
+        self.config.resolve_overrides.push((host.to_string(), addr));
+        self
+    }
+
+    /// GET请求的重试配置：次数由 HttpConfig 决定，退避间隔较短且有上限
+    fn get_retry_config(&self) -> RetryConfig {
+        RetryConfig::new(self.config.max_retries, GET_RETRY_INITIAL_DELAY_MS)
+            .with_backoff_multiplier(2.0)
+            .with_max_delay(GET_RETRY_MAX_DELAY_MS)
+            .with_jitter(true)
+    }
+
+    /// 设置是否在日志中输出请求和响应内容
+    pub fn log_bodies(mut self, log_bodies: bool) -> Self {
+        self.config.log_bodies = log_bodies;
+        self
+    }
+
+    /// 是否输出请求和响应内容，处理敏感记录时始终不输出
+    fn should_log_bodies(&self) -> bool {
+        self.config.log_bodies && !is_content_log_suppressed()
+    }
+
+    /// 输出请求头和请求体日志，未开启内容日志时只输出请求体长度
+    fn log_request(&self, headers: &HeaderMap, data: &RequestData) {
+        let body_len = match data {
+            RequestData::Json(json_str) => Some(json_str.len()),
+            _ => None,
+        };
+        if !self.should_log_bodies() {
+            if let Some(len) = body_len {
+                log::debug!("请求体: 已隐藏（{} 字节）", len);
+            }
+            return;
+        }
+        log::debug!("请求头: {}", format_headers_for_log(headers));
+        if let RequestData::Json(json_str) = data {
+            log::debug!("请求体: {}", redact_log_text(json_str));
+        }
+    }
+
+    /// 输出响应内容日志，未开启内容日志时不输出
+    fn log_response_body(&self, label: &str, response_text: &str) {
+        if self.should_log_bodies() {
+            log::debug!("{}: {}", label, redact_log_text(response_text));
+        }
+    }
+
     // ========== ApiResponse格式的请求方法 ==========
 
     /// 发起GET请求（返回ApiResponse格式）
@@ -229,6 +428,26 @@ impl HttpClient {
             .await
     }
 
+    /// 发起带自定义请求头且请求体已序列化为JSON的请求（返回ApiResponse格式）
+    /// 用于需要对请求体原文签名的场景，保证签名内容与实际发送的内容一致
+    pub async fn request_json_with_headers<U>(
+        &self,
+        method: &str,
+        url: &str,
+        body: Option<String>,
+        headers: Option<HashMap<String, String>>,
+    ) -> Result<ApiResponse<U>, HttpError>
+    where
+        U: for<'de> Deserialize<'de>,
+    {
+        let request_data = match body {
+            Some(json_str) => RequestData::Json(json_str),
+            None => RequestData::None,
+        };
+        self.execute_api_request(method, url, request_data, headers)
+            .await
+    }
+
     // ========== 原始响应格式的请求方法 ==========
 
     /// 发起GET请求（返回原始响应格式）
@@ -312,22 +531,126 @@ impl HttpClient {
             .await
     }
 
+    // ========== 文本响应的请求方法 ==========
+
+    /// 发起GET请求并读取文本响应（如网页HTML），最多读取 max_bytes 字节，超出的部分不再下载
+    /// 非UTF-8的内容按有损方式转换，该方法不重试
+    pub async fn get_text(&self, url: &str, max_bytes: usize) -> Result<String, HttpError> {
+        log::debug!("HTTP文本请求: GET {}", url);
+
+        // 验证URL
+        let _parsed_url = reqwest::Url::parse(url)
+            .map_err(|e| HttpError::InvalidUrl(format!("无效的URL: {}", e)))?;
+
+        let client = self.build_client()?;
+        let headers = self.build_headers(None)?;
+        let mut response = client
+            .get(url)
+            .headers(headers)
+            .send()
+            .await
+            .map_err(|e| self.classify_network_error(e, url))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(HttpError::RequestFailed(format!("HTTP {}", status)));
+        }
+
+        // 分块读取，达到上限后停止读取剩余内容
+        let mut body: Vec<u8> = Vec::new();
+        while body.len() < max_bytes {
+            let Some(chunk) = response
+                .chunk()
+                .await
+                .map_err(|e| HttpError::NetworkError(format!("读取响应数据失败: {}", e)))?
+            else {
+                break;
+            };
+            let remaining = max_bytes - body.len();
+            body.extend_from_slice(&chunk[..chunk.len().min(remaining)]);
+        }
+
+        log::debug!("文本响应读取长度: {} 字节, 状态码: {}", body.len(), status);
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+
     // ========== 文件下载方法 ==========
 
     /// 下载文件到指定路径
     pub async fn download_file(&self, url: &str, save_path: &Path) -> Result<PathBuf, HttpError> {
-        self.download_file_internal(url, save_path).await
+        self.download_file_internal(url, save_path, None).await
     }
 
-    /// 下载文件并获取响应头信息
+    /// 下载文件到指定路径，下载过程中回调进度（已下载字节数，Content-Length 总字节数）
+    pub async fn download_file_with_progress(
+        &self,
+        url: &str,
+        save_path: &Path,
+        on_progress: &DownloadProgressFn,
+    ) -> Result<PathBuf, HttpError> {
+        self.download_file_internal(url, save_path, Some(on_progress))
+            .await
+    }
+
+    /// 下载文件并获取响应头信息，支持断点续传
+    /// save_path 已存在时视为上次未完成的部分文件，通过 Range 请求从已下载的位置继续下载
     pub async fn download_file_with_info(
         &self,
         url: &str,
         save_path: &Path,
     ) -> Result<(PathBuf, HashMap<String, String>), HttpError> {
-        // 构建HTTP客户端
+        self.download_file_resumable(url, save_path, None).await
+    }
+
+    /// 断点续传下载文件，下载过程中回调进度
+    /// 续传时通过 If-Range 带上下载开始时的 ETag 或 Last-Modified，服务器上的文件已变化时返回200，重新下载完整文件
+    /// 服务器返回206时追加写入已有的部分文件，没有保存校验信息的部分文件不续传
+    /// 下载失败时保留部分文件用于下次续传，文件大小与服务器返回的总大小不一致时删除
+    pub async fn download_file_resumable(
+        &self,
+        url: &str,
+        save_path: &Path,
+        on_progress: Option<&DownloadProgressFn>,
+    ) -> Result<(PathBuf, HashMap<String, String>), HttpError> {
+        // 确保目录存在
+        if let Some(parent_dir) = save_path.parent() {
+            if !parent_dir.exists() {
+                std::fs::create_dir_all(parent_dir)
+                    .map_err(|e| HttpError::FileError(format!("创建目录失败: {}", e)))?;
+            }
+        }
+
+        let mut offset = tokio::fs::metadata(save_path)
+            .await
+            .map(|metadata| metadata.len())
+            .unwrap_or(0);
+        let validator_path = resume_validator_path(save_path);
+        let if_range = if offset > 0 {
+            tokio::fs::read_to_string(&validator_path)
+                .await
+                .ok()
+                .and_then(|validator| HeaderValue::from_str(validator.trim()).ok())
+                .filter(|validator| !validator.is_empty())
+        } else {
+            None
+        };
+        // 无法确认部分文件与服务器上的文件是同一个版本时，不续传
+        if offset > 0 && if_range.is_none() {
+            log::warn!("部分文件没有校验信息，重新下载: {:?}", save_path);
+            remove_partial_file(save_path).await;
+            offset = 0;
+        }
+
+        // 构建HTTP客户端，已有部分文件时只请求剩余的部分
         let client = self.build_client()?;
-        let headers = self.build_headers(None)?;
+        let mut headers = self.build_headers(None)?;
+        if let Some(if_range) = if_range {
+            log::info!("从 {} 字节处继续下载: {:?}", offset, save_path);
+            let range = HeaderValue::from_str(&format!("bytes={}-", offset))
+                .map_err(|e| HttpError::RequestFailed(format!("无效的Range请求头: {}", e)))?;
+            headers.insert(reqwest::header::RANGE, range);
+            headers.insert(reqwest::header::IF_RANGE, if_range);
+        }
 
         // 发送请求获取响应头信息
         let response = client
@@ -337,45 +660,87 @@ impl HttpClient {
             .await
             .map_err(|e| self.classify_network_error(e, url))?;
 
-        if !response.status().is_success() {
+        let status = response.status();
+        if offset > 0 && status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+            // 部分文件比服务器上的文件还大，说明不是同一个文件，删除后重新下载
+            log::warn!("续传位置超出文件大小，重新下载: {:?}", save_path);
+            remove_partial_file(save_path).await;
+            return Box::pin(self.download_file_resumable(url, save_path, on_progress)).await;
+        }
+        if !status.is_success() {
             return Err(HttpError::DownloadFailed(format!(
                 "下载失败: HTTP {}",
-                response.status()
+                status
             )));
         }
 
         // 提取响应头
         let response_headers = self.extract_headers(&response);
 
-        // 下载文件内容
-        let bytes = response
-            .bytes()
-            .await
-            .map_err(|e| HttpError::NetworkError(format!("读取响应数据失败: {}", e)))?;
-
-        // 确保目录存在并写入文件
-        if let Some(parent_dir) = save_path.parent() {
-            if !parent_dir.exists() {
-                std::fs::create_dir_all(parent_dir)
-                    .map_err(|e| HttpError::FileError(format!("创建目录失败: {}", e)))?;
+        // 206 时从 Content-Range 获取续传起点和文件总大小，200 时从头写入
+        let (start, expected_total) = if status == reqwest::StatusCode::PARTIAL_CONTENT {
+            let content_range = response
+                .headers()
+                .get(reqwest::header::CONTENT_RANGE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_content_range);
+            match content_range {
+                Some((range_start, total)) if range_start == offset => (offset, total),
+                _ => {
+                    log::warn!(
+                        "服务器返回的续传范围与本地文件不一致，重新下载: {:?}",
+                        save_path
+                    );
+                    remove_partial_file(save_path).await;
+                    return Box::pin(self.download_file_resumable(url, save_path, on_progress))
+                        .await;
+                }
             }
-        }
-
-        let mut file = File::create(save_path)
-            .map_err(|e| HttpError::FileError(format!("创建文件失败: {}", e)))?;
+        } else {
+            if offset > 0 {
+                log::info!(
+                    "服务器文件已变化或不支持断点续传，重新下载完整文件: {:?}",
+                    save_path
+                );
+            }
+            // 从头下载时保存本次的校验信息，下次续传时用于确认服务器上的文件没有变化
+            match resume_validator(response.headers()) {
+                Some(validator) => {
+                    if let Err(e) = tokio::fs::write(&validator_path, validator).await {
+                        log::warn!("保存续传校验信息失败: {:?}, {}", validator_path, e);
+                    }
+                }
+                None => {
+                    let _ = tokio::fs::remove_file(&validator_path).await;
+                }
+            }
+            (0, response.content_length())
+        };
 
-        file.write_all(&bytes)
-            .map_err(|e| HttpError::FileError(format!("写入文件失败: {}", e)))?;
+        // 边下载边写入文件，失败时保留已写入的部分
+        let written = self
+            .write_response_to_file(response, save_path, start, expected_total, on_progress)
+            .await?;
 
-        file.flush()
-            .map_err(|e| HttpError::FileError(format!("文件刷新失败: {}", e)))?;
+        if let Some(expected) = expected_total {
+            if written != expected {
+                remove_partial_file(save_path).await;
+                return Err(HttpError::DownloadFailed(format!(
+                    "下载文件大小不一致: 预期 {} 字节，实际 {} 字节",
+                    expected, written
+                )));
+            }
+        }
 
+        // 下载完成后不再需要续传校验信息
+        let _ = tokio::fs::remove_file(&validator_path).await;
         Ok((save_path.to_path_buf(), response_headers))
     }
 
     // ========== 内部实现方法 ==========
 
     /// 统一的HTTP请求执行方法 - ApiResponse格式
+    /// GET请求遇到临时网络错误时按配置退避重试，其他方法只请求一次
     async fn execute_api_request<T>(
         &self,
         method: &str,
@@ -383,6 +748,46 @@ impl HttpClient {
         data: RequestData,
         custom_headers: Option<HashMap<String, String>>,
     ) -> Result<ApiResponse<T>, HttpError>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        if !method.eq_ignore_ascii_case("GET") || self.config.max_retries == 0 {
+            return self
+                .execute_api_request_once(method, url, data, custom_headers)
+                .await;
+        }
+        let body = match data {
+            RequestData::None => None,
+            RequestData::Json(json_str) => Some(json_str),
+            other => {
+                return self
+                    .execute_api_request_once(method, url, other, custom_headers)
+                    .await;
+            }
+        };
+
+        retry_with_config(
+            self.get_retry_config(),
+            || {
+                let data = body
+                    .clone()
+                    .map(RequestData::Json)
+                    .unwrap_or(RequestData::None);
+                self.execute_api_request_once(method, url, data, custom_headers.clone())
+            },
+            HttpError::is_retryable,
+        )
+        .await
+    }
+
+    /// 执行一次HTTP请求并解析为ApiResponse格式
+    async fn execute_api_request_once<T>(
+        &self,
+        method: &str,
+        url: &str,
+        data: RequestData,
+        custom_headers: Option<HashMap<String, String>>,
+    ) -> Result<ApiResponse<T>, HttpError>
     where
         T: for<'de> Deserialize<'de>,
     {
@@ -396,7 +801,7 @@ impl HttpClient {
 
         serde_json::from_str(&response_text).map_err(|e| {
             log::error!("反序列化失败 - URL: {}, 错误: {}", url, e);
-            log::error!("服务器返回原始数据: {}", response_text);
+            self.log_response_body("服务器返回原始数据", &response_text);
             self.handle_deserialization_error(e, url, &response_text)
         })
     }
@@ -416,11 +821,6 @@ impl HttpClient {
         log::debug!("请求方法: {}", method);
         log::debug!("请求URL: {}", url);
 
-        // 记录请求体（如果有的话）
-        if let RequestData::Json(ref json_str) = data {
-            log::debug!("请求体: {}", json_str);
-        }
-
         // 验证URL
         let _parsed_url = reqwest::Url::parse(url)
             .map_err(|e| HttpError::InvalidUrl(format!("无效的URL: {}", e)))?;
@@ -443,11 +843,14 @@ impl HttpClient {
             }
         };
 
+        // 构建请求头，并在请求体被消耗前记录请求日志
+        let headers = self.build_headers(custom_headers.as_ref())?;
+        self.log_request(&headers, &data);
+
         // 设置请求体
         request_builder = self.apply_request_data(request_builder, data)?;
 
         // 设置请求头
-        let headers = self.build_headers(custom_headers.as_ref())?;
         request_builder = request_builder.headers(headers);
 
         // 发送请求
@@ -476,6 +879,7 @@ impl HttpClient {
             response_text.len(),
             status
         );
+        self.log_response_body("响应数据", &response_text);
 
         let response_data: T = if response_text.is_empty() {
             serde_json::from_str("null").map_err(|e| {
@@ -494,7 +898,7 @@ impl HttpClient {
                     status,
                     e
                 );
-                log::error!("服务器返回原始数据: {}", response_text);
+                self.log_response_body("服务器返回原始数据", &response_text);
                 HttpError::DeserializationFailed(format!("反序列化响应失败: {}", e))
             })?
         };
@@ -539,11 +943,14 @@ impl HttpClient {
             }
         };
 
+        // 构建请求头，并在请求体被消耗前记录请求日志
+        let headers = self.build_headers(custom_headers.as_ref())?;
+        self.log_request(&headers, &data);
+
         // 设置请求体
         request_builder = self.apply_request_data(request_builder, data)?;
 
         // 设置请求头
-        let headers = self.build_headers(custom_headers.as_ref())?;
         request_builder = request_builder.headers(headers);
 
         // 发送请求
@@ -574,11 +981,11 @@ impl HttpClient {
             response_text.len(),
             status_code
         );
+        self.log_response_body("服务器返回数据", &response_text);
 
         // 如果状态码不是成功状态，记录错误信息
         if !status_code.is_success() {
             log::error!("HTTP请求状态码错误 - URL: {}, 状态码: {}", url, status_code);
-            log::debug!("服务器返回数据: {}", response_text);
         }
 
         Ok(response_text)
@@ -589,6 +996,7 @@ impl HttpClient {
         &self,
         url: &str,
         save_path: &Path,
+        on_progress: Option<&DownloadProgressFn>,
     ) -> Result<PathBuf, HttpError> {
         log::info!("开始下载文件: {} -> {:?}", url, save_path);
 
@@ -618,24 +1026,84 @@ impl HttpClient {
             )));
         }
 
-        // 读取响应体并写入文件
-        let bytes = response
-            .bytes()
+        // 边读取响应体边写入文件，失败时删除不完整的文件
+        let total = response.content_length();
+        let downloaded = match self
+            .write_response_to_file(response, save_path, 0, total, on_progress)
             .await
-            .map_err(|e| HttpError::NetworkError(format!("读取响应数据失败: {}", e)))?;
+        {
+            Ok(downloaded) => downloaded,
+            Err(e) => {
+                remove_partial_file(save_path).await;
+                return Err(e);
+            }
+        };
 
-        let mut file = File::create(save_path)
-            .map_err(|e| HttpError::FileError(format!("创建文件失败: {}", e)))?;
+        log::info!("文件下载完成: {} 字节 -> {:?}", downloaded, save_path);
 
-        file.write_all(&bytes)
-            .map_err(|e| HttpError::FileError(format!("写入文件失败: {}", e)))?;
+        Ok(save_path.to_path_buf())
+    }
 
-        file.flush()
-            .map_err(|e| HttpError::FileError(format!("文件刷新失败: {}", e)))?;
+    /// 分块读取响应体并写入文件，不在内存中保存完整文件，返回写入后的文件大小
+    /// offset 大于0时追加到已有文件末尾，total 为完整文件的字节数
+    async fn write_response_to_file(
+        &self,
+        mut response: reqwest::Response,
+        save_path: &Path,
+        offset: u64,
+        total: Option<u64>,
+        on_progress: Option<&DownloadProgressFn>,
+    ) -> Result<u64, HttpError> {
+        let mut file = if offset > 0 {
+            tokio::fs::OpenOptions::new()
+                .append(true)
+                .open(save_path)
+                .await
+                .map_err(|e| HttpError::FileError(format!("打开文件失败: {}", e)))?
+        } else {
+            tokio::fs::File::create(save_path)
+                .await
+                .map_err(|e| HttpError::FileError(format!("创建文件失败: {}", e)))?
+        };
 
-        log::info!("文件下载完成: {} 字节 -> {:?}", bytes.len(), save_path);
+        let mut downloaded: u64 = offset;
+        let mut last_reported: u64 = offset;
+        if let Some(on_progress) = on_progress {
+            on_progress(offset, total);
+        }
 
-        Ok(save_path.to_path_buf())
+        let result: Result<(), HttpError> = async {
+            while let Some(chunk) = response
+                .chunk()
+                .await
+                .map_err(|e| HttpError::NetworkError(format!("读取响应数据失败: {}", e)))?
+            {
+                file.write_all(&chunk)
+                    .await
+                    .map_err(|e| HttpError::FileError(format!("写入文件失败: {}", e)))?;
+                downloaded += chunk.len() as u64;
+
+                // 按固定字节数间隔回调进度，避免小块数据频繁通知
+                if let Some(on_progress) = on_progress {
+                    if downloaded - last_reported >= DOWNLOAD_PROGRESS_STEP_BYTES {
+                        last_reported = downloaded;
+                        on_progress(downloaded, total);
+                    }
+                }
+            }
+            file.flush()
+                .await
+                .map_err(|e| HttpError::FileError(format!("文件刷新失败: {}", e)))
+        }
+        .await;
+        result?;
+
+        if let Some(on_progress) = on_progress {
+            if downloaded != last_reported {
+                on_progress(downloaded, total);
+            }
+        }
+        Ok(downloaded)
     }
 
     /// 构建HTTP客户端
@@ -645,6 +1113,12 @@ impl HttpClient {
         if let Some(timeout) = self.config.timeout {
             client_builder = client_builder.timeout(std::time::Duration::from_secs(timeout));
         }
+        if !self.config.follow_redirects {
+            client_builder = client_builder.redirect(reqwest::redirect::Policy::none());
+        }
+        for (host, addr) in &self.config.resolve_overrides {
+            client_builder = client_builder.resolve(host, *addr);
+        }
 
         client_builder
             .build()
@@ -1007,6 +1481,28 @@ pub async fn download_file(url: &str, save_path: &Path) -> Result<PathBuf, HttpE
     HttpClient::new().download_file(url, save_path).await
 }
 
+/// 便捷的文件下载函数（带下载进度回调）
+pub async fn download_file_with_progress(
+    url: &str,
+    save_path: &Path,
+    on_progress: &DownloadProgressFn,
+) -> Result<PathBuf, HttpError> {
+    HttpClient::new()
+        .download_file_with_progress(url, save_path, on_progress)
+        .await
+}
+
+/// 便捷的断点续传下载函数（带下载进度回调）
+pub async fn download_file_resumable(
+    url: &str,
+    save_path: &Path,
+    on_progress: Option<&DownloadProgressFn>,
+) -> Result<(PathBuf, HashMap<String, String>), HttpError> {
+    HttpClient::new()
+        .download_file_resumable(url, save_path, on_progress)
+        .await
+}
+
 /// 便捷的文件下载函数（带响应头信息）
 pub async fn download_file_with_info(
     url: &str,
@@ -1120,3 +1616,95 @@ fn get_mime_type_from_extension(file_path: &Path) -> String {
     }
     .to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_token_fields() {
+        let body =
+            r#"{"accessToken":"abc.def","refresh_token": "xyz","password":"123","name":"clip"}"#;
+        assert_eq!(
+            redact_log_text(body),
+            r#"{"accessToken":"[REDACTED]","refresh_token": "[REDACTED]","password":"[REDACTED]","name":"clip"}"#
+        );
+    }
+
+    #[test]
+    fn test_redact_bearer_and_jwt() {
+        assert_eq!(
+            redact_log_text("Authorization: Bearer abc123=="),
+            "Authorization: Bearer [REDACTED]"
+        );
+        assert_eq!(
+            redact_log_text("token=eyJhbGciOiJIUzI1NiJ9.eyJzdWIiOiIxIn0.sig_1"),
+            "token=[REDACTED]"
+        );
+        assert!(matches!(redact_log_text("hello"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_only_network_errors_are_retryable() {
+        assert!(HttpError::Timeout("超时".to_string()).is_retryable());
+        assert!(HttpError::NetworkError("连接中断".to_string()).is_retryable());
+        assert!(!HttpError::RequestFailed("参数错误".to_string()).is_retryable());
+        assert!(!HttpError::DeserializationFailed("格式错误".to_string()).is_retryable());
+        assert!(!HttpError::InvalidUrl("地址错误".to_string()).is_retryable());
+    }
+
+    #[test]
+    fn test_parse_content_range() {
+        assert_eq!(
+            parse_content_range("bytes 100-999/1000"),
+            Some((100, Some(1000)))
+        );
+        assert_eq!(parse_content_range("bytes 0-499/*"), Some((0, None)));
+        assert_eq!(parse_content_range("bytes */1000"), None);
+        assert_eq!(parse_content_range("items 0-1/2"), None);
+    }
+
+    #[test]
+    fn test_resume_validator() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(resume_validator(&headers), None);
+
+        headers.insert(
+            reqwest::header::LAST_MODIFIED,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        // 弱ETag不能用于 If-Range，使用 Last-Modified
+        headers.insert(reqwest::header::ETAG, HeaderValue::from_static("W/\"abc\""));
+        assert_eq!(
+            resume_validator(&headers).as_deref(),
+            Some("Wed, 21 Oct 2015 07:28:00 GMT")
+        );
+
+        headers.insert(reqwest::header::ETAG, HeaderValue::from_static("\"abc\""));
+        assert_eq!(resume_validator(&headers).as_deref(), Some("\"abc\""));
+    }
+
+    #[test]
+    fn test_resume_validator_path() {
+        assert_eq!(
+            resume_validator_path(Path::new("/tmp/partial/1.part")),
+            PathBuf::from("/tmp/partial/1.part.validator")
+        );
+    }
+
+    #[test]
+    fn test_format_headers_hides_authorization() {
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", HeaderValue::from_static("Bearer secret"));
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+        headers.insert(
+            HeaderName::from_bytes(HEADER_SIGNATURE.as_bytes()).unwrap(),
+            HeaderValue::from_static("signature"),
+        );
+        let formatted = format_headers_for_log(&headers);
+        assert!(formatted.contains("authorization: [REDACTED]"));
+        assert!(formatted.contains("x-clippal-signature: [REDACTED]"));
+        assert!(formatted.contains("content-type: application/json"));
+        assert!(!formatted.contains("secret"));
+    }
+}
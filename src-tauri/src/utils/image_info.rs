@@ -0,0 +1,178 @@
+// APNG动图判断与剪贴板插件读取动图时共用同一个实现
+pub use tauri_plugin_clipboard_pal::desktop::is_animated_png;
+
+// PNG文件签名
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+/// 从PNG数据的IHDR块中读取图片宽高，不需要解码整张图片
+pub fn png_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    if data.len() < 24 || data[..8] != PNG_SIGNATURE || &data[12..16] != b"IHDR" {
+        return None;
+    }
+    let width = u32::from_be_bytes(data[16..20].try_into().ok()?);
+    let height = u32::from_be_bytes(data[20..24].try_into().ok()?);
+    Some((width, height))
+}
+
+/// 从GIF数据的逻辑屏幕描述符中读取图片宽高
+pub fn gif_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    if data.len() < 10 || !(data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a")) {
+        return None;
+    }
+    let width = u16::from_le_bytes([data[6], data[7]]) as u32;
+    let height = u16::from_le_bytes([data[8], data[9]]) as u32;
+    Some((width, height))
+}
+
+/// 读取PNG或GIF图片的宽高，其他格式返回None
+pub fn image_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    png_dimensions(data).or_else(|| gif_dimensions(data))
+}
+
+/// 动图第一帧缩略图的文件名，与原图保存在同一目录
+pub fn thumbnail_filename(filename: &str) -> String {
+    let stem = filename
+        .rsplit_once('.')
+        .map(|(stem, _)| stem)
+        .unwrap_or(filename);
+    format!("{}.thumb.png", stem)
+}
+
+/// 根据文件头判断图片格式，返回对应的文件扩展名
+pub fn detect_image_extension(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(&PNG_SIGNATURE) {
+        Some("png")
+    } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("jpg")
+    } else if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        Some("webp")
+    } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        Some("gif")
+    } else if data.starts_with(b"BM") {
+        Some("bmp")
+    } else {
+        None
+    }
+}
+
+/// 判断图片是否为动图，返回按原格式保存时的扩展名（GIF为gif，APNG仍为png），不是动图时返回None
+/// mime 为剪贴板提供的原始格式，没有时根据文件头判断
+pub fn animated_image_extension(data: &[u8], mime: Option<&str>) -> Option<&'static str> {
+    match mime {
+        Some("image/gif") => Some("gif"),
+        Some("image/apng") => Some("png"),
+        _ if detect_image_extension(data) == Some("gif") => Some("gif"),
+        _ if is_animated_png(data) => Some("png"),
+        _ => None,
+    }
+}
+
+/// 把图片的透明部分叠加到白色背景上，用于保存为不支持透明通道的JPEG
+pub fn flatten_alpha_onto_white(img: &image::DynamicImage) -> image::RgbImage {
+    let rgba = img.to_rgba8();
+    let mut rgb = image::RgbImage::new(rgba.width(), rgba.height());
+    for (src, dst) in rgba.pixels().zip(rgb.pixels_mut()) {
+        let alpha = src[3] as u32;
+        for channel in 0..3 {
+            dst[channel] = ((src[channel] as u32 * alpha + 255 * (255 - alpha) + 127) / 255) as u8;
+        }
+    }
+    rgb
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_image_extension() {
+        assert_eq!(detect_image_extension(&PNG_SIGNATURE), Some("png"));
+        assert_eq!(
+            detect_image_extension(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            Some("jpg")
+        );
+        assert_eq!(
+            detect_image_extension(b"RIFF\x10\0\0\0WEBPVP8 "),
+            Some("webp")
+        );
+        assert_eq!(detect_image_extension(b"GIF89a"), Some("gif"));
+        assert_eq!(detect_image_extension(b"RIFF\x10\0\0\0WAVE"), None);
+        assert_eq!(detect_image_extension(b""), None);
+    }
+
+    #[test]
+    fn test_png_dimensions() {
+        let mut data = PNG_SIGNATURE.to_vec();
+        data.extend_from_slice(&13u32.to_be_bytes());
+        data.extend_from_slice(b"IHDR");
+        data.extend_from_slice(&640u32.to_be_bytes());
+        data.extend_from_slice(&1u32.to_be_bytes());
+        data.extend_from_slice(&[8, 6, 0, 0, 0]);
+        assert_eq!(png_dimensions(&data), Some((640, 1)));
+
+        assert_eq!(png_dimensions(&data[..20]), None);
+        assert_eq!(png_dimensions(b"GIF89a not a png file at all"), None);
+    }
+
+    #[test]
+    fn test_gif_dimensions() {
+        let mut data = b"GIF89a".to_vec();
+        data.extend_from_slice(&320u16.to_le_bytes());
+        data.extend_from_slice(&200u16.to_le_bytes());
+        assert_eq!(gif_dimensions(&data), Some((320, 200)));
+        assert_eq!(image_dimensions(&data), Some((320, 200)));
+
+        assert_eq!(gif_dimensions(&data[..8]), None);
+        assert_eq!(gif_dimensions(&PNG_SIGNATURE), None);
+    }
+
+    #[test]
+    fn test_thumbnail_filename() {
+        assert_eq!(
+            thumbnail_filename("20250101_abc.gif"),
+            "20250101_abc.thumb.png"
+        );
+        assert_eq!(thumbnail_filename("noext"), "noext.thumb.png");
+    }
+
+    fn png_with_chunks(chunks: &[&[u8; 4]]) -> Vec<u8> {
+        let mut data = PNG_SIGNATURE.to_vec();
+        for chunk in chunks {
+            data.extend_from_slice(&1u32.to_be_bytes());
+            data.extend_from_slice(*chunk);
+            data.push(0);
+            data.extend_from_slice(&[0; 4]);
+        }
+        data
+    }
+
+    #[test]
+    fn test_animated_image_detection() {
+        let apng = png_with_chunks(&[b"IHDR", b"acTL", b"IDAT", b"IEND"]);
+        let png = png_with_chunks(&[b"IHDR", b"IDAT", b"acTL", b"IEND"]);
+        assert!(is_animated_png(&apng));
+        assert!(!is_animated_png(&png));
+        assert!(!is_animated_png(b"GIF89a"));
+
+        assert_eq!(animated_image_extension(&apng, None), Some("png"));
+        assert_eq!(animated_image_extension(&png, None), None);
+        assert_eq!(animated_image_extension(b"GIF89a", None), Some("gif"));
+        assert_eq!(
+            animated_image_extension(&png, Some("image/apng")),
+            Some("png")
+        );
+        assert_eq!(animated_image_extension(&png, Some("image/png")), None);
+    }
+
+    #[test]
+    fn test_flatten_alpha_onto_white() {
+        let mut rgba = image::RgbaImage::new(3, 1);
+        rgba.put_pixel(0, 0, image::Rgba([0, 0, 0, 0]));
+        rgba.put_pixel(1, 0, image::Rgba([10, 20, 30, 255]));
+        rgba.put_pixel(2, 0, image::Rgba([0, 0, 0, 128]));
+        let rgb = flatten_alpha_onto_white(&image::DynamicImage::ImageRgba8(rgba));
+        assert_eq!(rgb.get_pixel(0, 0), &image::Rgb([255, 255, 255]));
+        assert_eq!(rgb.get_pixel(1, 0), &image::Rgb([10, 20, 30]));
+        assert_eq!(rgb.get_pixel(2, 0), &image::Rgb([127, 127, 127]));
+    }
+}
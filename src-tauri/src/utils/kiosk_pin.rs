@@ -0,0 +1,154 @@
+use base64::{engine::general_purpose, Engine as _};
+use hmac::{Hmac, Mac};
+use rand::rngs::OsRng;
+use rand::TryRngCore;
+use sha2::{Digest, Sha256};
+
+use crate::errors::{AppError, AppResult};
+
+type HmacSha256 = Hmac<Sha256>;
+
+// 退出只读模式的PIN摘要
+// 使用加盐的 PBKDF2-HMAC-SHA256 计算，保存格式为 pbkdf2-sha256$迭代次数$盐(Base64)$摘要(Base64)
+
+const PIN_HASH_SCHEME: &str = "pbkdf2-sha256";
+// PBKDF2迭代次数，PIN位数较少，靠迭代次数增加离线穷举的成本
+const PIN_HASH_ITERATIONS: u32 = 200_000;
+const PIN_SALT_BYTES: usize = 16;
+
+// 连续输错达到该次数后开始锁定
+const PIN_FAILURES_BEFORE_LOCKOUT: u32 = 5;
+// 首次锁定的时长（毫秒），之后每多错一次翻倍
+const PIN_LOCKOUT_BASE_MILLIS: u64 = 30 * 1000;
+// 锁定时长上限（毫秒）
+const PIN_LOCKOUT_MAX_MILLIS: u64 = 15 * 60 * 1000;
+
+/// 计算 PBKDF2-HMAC-SHA256，输出长度与SHA-256相同，只需计算一个分块
+fn pbkdf2_sha256(password: &[u8], salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mac = HmacSha256::new_from_slice(password).expect("HMAC可以接受任意长度的密钥");
+    let mut block = mac.clone();
+    block.update(salt);
+    block.update(&1u32.to_be_bytes());
+    let mut u: [u8; 32] = block.finalize().into_bytes().into();
+    let mut output = u;
+    for _ in 1..iterations {
+        let mut round = mac.clone();
+        round.update(&u);
+        u = round.finalize().into_bytes().into();
+        for (out, byte) in output.iter_mut().zip(u.iter()) {
+            *out ^= byte;
+        }
+    }
+    output
+}
+
+/// 比较两段字节是否相同，耗时与内容无关，避免通过响应时间猜测摘要
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter()
+        .zip(b.iter())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+        == 0
+}
+
+/// 生成随机盐并计算PIN摘要
+pub fn hash_pin(pin: &str) -> AppResult<String> {
+    let mut salt = [0u8; PIN_SALT_BYTES];
+    OsRng
+        .try_fill_bytes(&mut salt)
+        .map_err(|e| AppError::Crypto(format!("生成随机数失败: {}", e)))?;
+    let hash = pbkdf2_sha256(pin.as_bytes(), &salt, PIN_HASH_ITERATIONS);
+    Ok(format!(
+        "{}${}${}${}",
+        PIN_HASH_SCHEME,
+        PIN_HASH_ITERATIONS,
+        general_purpose::STANDARD.encode(salt),
+        general_purpose::STANDARD.encode(hash)
+    ))
+}
+
+/// 校验PIN是否与保存的摘要一致
+/// 兼容旧版本保存的不加盐SHA-256摘要（小写十六进制），摘要格式无法识别时校验失败
+pub fn verify_pin(pin: &str, stored: &str) -> bool {
+    let parts: Vec<&str> = stored.split('$').collect();
+    if let [PIN_HASH_SCHEME, iterations, salt, hash] = parts.as_slice() {
+        let (Ok(iterations), Ok(salt), Ok(hash)) = (
+            iterations.parse::<u32>(),
+            general_purpose::STANDARD.decode(salt),
+            general_purpose::STANDARD.decode(hash),
+        ) else {
+            return false;
+        };
+        if iterations == 0 {
+            return false;
+        }
+        return constant_time_eq(&pbkdf2_sha256(pin.as_bytes(), &salt, iterations), &hash);
+    }
+
+    let legacy = format!("{:x}", Sha256::digest(pin.as_bytes()));
+    constant_time_eq(legacy.as_bytes(), stored.as_bytes())
+}
+
+/// 连续输错指定次数后的锁定时长（毫秒），未达到锁定次数时为0
+pub fn pin_lockout_millis(failures: u32) -> u64 {
+    if failures < PIN_FAILURES_BEFORE_LOCKOUT {
+        return 0;
+    }
+    let doublings = (failures - PIN_FAILURES_BEFORE_LOCKOUT).min(16);
+    PIN_LOCKOUT_BASE_MILLIS
+        .saturating_mul(1 << doublings)
+        .min(PIN_LOCKOUT_MAX_MILLIS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_pbkdf2_sha256_known_vectors() {
+        assert_eq!(
+            to_hex(&pbkdf2_sha256(b"password", b"salt", 1)),
+            "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b"
+        );
+        assert_eq!(
+            to_hex(&pbkdf2_sha256(b"password", b"salt", 2)),
+            "ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43"
+        );
+    }
+
+    #[test]
+    fn test_hash_and_verify_pin() {
+        let hash = hash_pin("1234").unwrap();
+        assert!(hash.starts_with("pbkdf2-sha256$"));
+        assert!(verify_pin("1234", &hash));
+        assert!(!verify_pin("1235", &hash));
+        assert!(!verify_pin("", &hash));
+        // 每次使用不同的盐
+        assert_ne!(hash, hash_pin("1234").unwrap());
+    }
+
+    #[test]
+    fn test_verify_legacy_and_invalid_hash() {
+        let legacy = format!("{:x}", Sha256::digest(b"1234"));
+        assert!(verify_pin("1234", &legacy));
+        assert!(!verify_pin("4321", &legacy));
+        assert!(!verify_pin("1234", "pbkdf2-sha256$0$c2FsdA==$AAAA"));
+        assert!(!verify_pin("1234", "pbkdf2-sha256$abc$c2FsdA==$AAAA"));
+        assert!(!verify_pin("1234", ""));
+    }
+
+    #[test]
+    fn test_pin_lockout_millis() {
+        assert_eq!(pin_lockout_millis(0), 0);
+        assert_eq!(pin_lockout_millis(4), 0);
+        assert_eq!(pin_lockout_millis(5), 30 * 1000);
+        assert_eq!(pin_lockout_millis(6), 60 * 1000);
+        assert_eq!(pin_lockout_millis(100), PIN_LOCKOUT_MAX_MILLIS);
+    }
+}
@@ -4,9 +4,19 @@ pub mod config;
 pub mod device_info;
 pub mod file_dir;
 pub mod file_ext;
+pub mod file_times;
 pub mod http_client;
+pub mod image_info;
+pub mod kiosk_pin;
 pub mod lock_utils;
 pub mod path_utils;
+pub mod request_signer;
 pub mod retry_helper;
+pub mod rich_text;
 pub mod secure_store;
+pub mod sensitive_log;
+pub mod text_compression;
+pub mod text_encoding;
+pub mod text_redaction;
+pub mod text_sanitizer;
 pub mod token_manager;
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use base64::{engine::general_purpose, Engine as _};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::{
+    errors::{AppError, AppResult},
+    utils::{device_info::get_device_id, secure_store::SECURE_STORE},
+};
+
+type HmacSha256 = Hmac<Sha256>;
+
+// 签名相关请求头
+pub const HEADER_DEVICE_ID: &str = "X-ClipPal-Device-Id";
+pub const HEADER_TIMESTAMP: &str = "X-ClipPal-Timestamp";
+pub const HEADER_NONCE: &str = "X-ClipPal-Nonce";
+pub const HEADER_SIGNATURE: &str = "X-ClipPal-Signature";
+
+/// 拼接待签名内容：METHOD\nPATH\nTIMESTAMP\nNONCE\nBODY
+/// 服务端需按相同规则拼接并校验，同时检查时间戳是否在允许范围内、nonce是否重复使用
+pub fn build_sign_payload(
+    method: &str,
+    path: &str,
+    timestamp: u64,
+    nonce: &str,
+    body: &str,
+) -> String {
+    format!(
+        "{}\n/{}\n{}\n{}\n{}",
+        method.to_uppercase(),
+        path.trim_start_matches('/'),
+        timestamp,
+        nonce,
+        body
+    )
+}
+
+/// 计算HMAC-SHA256签名，返回小写十六进制字符串
+pub fn compute_signature(secret: &[u8], payload: &str) -> AppResult<String> {
+    let mut mac = HmacSha256::new_from_slice(secret)
+        .map_err(|e| AppError::Crypto(format!("初始化HMAC失败: {}", e)))?;
+    mac.update(payload.as_bytes());
+    Ok(mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+/// 为请求生成签名相关的请求头
+pub fn build_sign_headers(
+    method: &str,
+    path: &str,
+    body: &str,
+) -> AppResult<HashMap<String, String>> {
+    let secret = {
+        let mut store = SECURE_STORE
+            .write()
+            .map_err(|e| AppError::Lock(format!("获取安全存储锁失败: {}", e)))?;
+        store.get_or_create_request_sign_secret()?
+    };
+    let secret = general_purpose::STANDARD.decode(secret)?;
+
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_err(|e| AppError::General(format!("获取系统时间失败: {}", e)))?
+        .as_millis() as u64;
+    let nonce = uuid::Uuid::new_v4().simple().to_string();
+
+    let payload = build_sign_payload(method, path, timestamp, &nonce, body);
+    let signature = compute_signature(&secret, &payload)?;
+
+    let mut headers = HashMap::new();
+    headers.insert(HEADER_DEVICE_ID.to_string(), get_device_id());
+    headers.insert(HEADER_TIMESTAMP.to_string(), timestamp.to_string());
+    headers.insert(HEADER_NONCE.to_string(), nonce);
+    headers.insert(HEADER_SIGNATURE.to_string(), signature);
+    Ok(headers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_sign_payload() {
+        let payload = build_sign_payload("post", "clipPal-sync/sync", 1700000000000, "abc", "{}");
+        assert_eq!(payload, "POST\n/clipPal-sync/sync\n1700000000000\nabc\n{}");
+        assert_eq!(
+            build_sign_payload("GET", "/a/b", 1, "n", ""),
+            build_sign_payload("GET", "a/b", 1, "n", "")
+        );
+    }
+
+    #[test]
+    fn test_compute_signature() {
+        // RFC 4231 测试用例2
+        let signature = compute_signature(b"Jefe", "what do ya want for nothing?").unwrap();
+        assert_eq!(
+            signature,
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
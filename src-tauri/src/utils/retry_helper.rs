@@ -1,5 +1,6 @@
 #![allow(dead_code)]
 
+use crate::errors::{AppError, AppResult};
 use backon::{ExponentialBuilder, Retryable};
 use log::{debug, info, warn};
 use std::time::Duration;
@@ -164,6 +165,27 @@ where
     retry_with_backon(config, operation, should_retry).await
 }
 
+/// 数据库锁冲突的重试配置：间隔短、次数多，锁通常很快就会释放
+pub fn db_lock_retry_config() -> RetryConfig {
+    RetryConfig::new(8, 20)
+        .with_backoff_multiplier(2.0)
+        .with_max_delay(1000)
+        .with_jitter(true)
+}
+
+/// 执行数据库写入操作，仅在数据库被锁定（SQLITE_BUSY/SQLITE_LOCKED）时退避重试
+/// 其他数据库错误直接返回，不做重试
+pub async fn retry_on_db_locked<T, F, Fut>(operation: F) -> AppResult<T>
+where
+    F: Fn() -> Fut + Send + Sync,
+    Fut: std::future::Future<Output = AppResult<T>> + Send,
+{
+    retry_with_backon(db_lock_retry_config(), operation, |e: &AppError| {
+        e.is_db_locked()
+    })
+    .await
+}
+
 /// 便捷的重试宏，提供更简洁的使用方式
 ///
 /// # 示例
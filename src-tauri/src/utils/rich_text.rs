@@ -0,0 +1,835 @@
+/// 富文本（HTML/RTF）预览处理工具模块
+/// 预览内容会直接渲染到webview中，所有输出都必须经过白名单过滤，防止脚本注入
+
+/// 允许保留的标签（全部使用小写进行匹配）
+const ALLOWED_TAGS: &[&str] = &[
+    "b",
+    "strong",
+    "i",
+    "em",
+    "u",
+    "s",
+    "strike",
+    "del",
+    "ins",
+    "sub",
+    "sup",
+    "small",
+    "big",
+    "mark",
+    "code",
+    "pre",
+    "kbd",
+    "blockquote",
+    "p",
+    "div",
+    "span",
+    "br",
+    "hr",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "ul",
+    "ol",
+    "li",
+    "dl",
+    "dt",
+    "dd",
+    "table",
+    "thead",
+    "tbody",
+    "tfoot",
+    "tr",
+    "td",
+    "th",
+    "caption",
+    "font",
+];
+
+/// 提取纯文本时结束后换行的块级标签
+const TEXT_BLOCK_TAGS: &[&str] = &[
+    "p",
+    "div",
+    "pre",
+    "blockquote",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "li",
+    "dt",
+    "dd",
+    "tr",
+    "table",
+    "ul",
+    "ol",
+    "dl",
+    "caption",
+];
+
+/// 没有结束标签的元素
+const VOID_TAGS: &[&str] = &["br", "hr"];
+
+/// 连同内容一起丢弃的标签
+const DROP_CONTENT_TAGS: &[&str] = &[
+    "script",
+    "style",
+    "iframe",
+    "frame",
+    "frameset",
+    "object",
+    "embed",
+    "applet",
+    "noscript",
+    "noembed",
+    "noframes",
+    "template",
+    "svg",
+    "math",
+    "title",
+    "head",
+    "textarea",
+    "select",
+    "xmp",
+    "plaintext",
+];
+
+/// style属性中允许保留的样式
+const ALLOWED_STYLE_PROPS: &[&str] = &[
+    "color",
+    "background-color",
+    "font-weight",
+    "font-style",
+    "font-size",
+    "font-family",
+    "text-decoration",
+    "text-align",
+];
+
+/// 样式值中出现这些关键字时整条样式丢弃
+const BLOCKED_STYLE_KEYWORDS: &[&str] = &["url", "expression", "javascript", "import", "behavior"];
+
+/// RTF中需要整体跳过的目标组（字体表、样式表、图片等不参与预览）
+const RTF_SKIP_DESTINATIONS: &[&str] = &[
+    "fonttbl",
+    "stylesheet",
+    "info",
+    "pict",
+    "header",
+    "footer",
+    "headerl",
+    "headerr",
+    "footerl",
+    "footerr",
+    "object",
+    "themedata",
+    "colorschememapping",
+    "latentstyles",
+    "datastore",
+    "xmlnstbl",
+    "listtable",
+    "listoverridetable",
+    "rsidtbl",
+    "generator",
+    "filetbl",
+    "revtbl",
+    "fldinst",
+];
+
+/// 过滤HTML片段，只保留白名单内的标签和样式
+///
+/// - 脚本、样式表、iframe等标签连同内容一起移除
+/// - 未知标签只移除标签本身，保留其中的文本
+/// - 事件属性、链接等属性全部移除
+pub fn sanitize_html(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut open_tags: Vec<String> = Vec::new();
+    // 处于需要丢弃内容的标签内部时，记录该标签名
+    let mut skip_until: Option<String> = None;
+    let mut pos = 0;
+
+    while pos < input.len() {
+        let rest = &input[pos..];
+        let Some(ch) = rest.chars().next() else {
+            break;
+        };
+
+        if ch != '<' {
+            if skip_until.is_none() {
+                push_escaped_char(&mut output, ch);
+            }
+            pos += ch.len_utf8();
+            continue;
+        }
+
+        // 注释
+        if rest.starts_with("<!--") {
+            pos += rest[4..].find("-->").map(|i| i + 7).unwrap_or(rest.len());
+            continue;
+        }
+
+        // DOCTYPE、CDATA、处理指令
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            pos += rest.find('>').map(|i| i + 1).unwrap_or(rest.len());
+            continue;
+        }
+
+        let Some((tag, consumed)) = parse_tag(rest) else {
+            // 不是合法的标签，按普通文本处理
+            if skip_until.is_none() {
+                output.push_str("&lt;");
+            }
+            pos += 1;
+            continue;
+        };
+        pos += consumed;
+
+        if let Some(skip_name) = &skip_until {
+            if tag.closing && &tag.name == skip_name {
+                skip_until = None;
+            }
+            continue;
+        }
+
+        if tag.closing {
+            if let Some(index) = open_tags.iter().rposition(|name| name == &tag.name) {
+                while open_tags.len() > index {
+                    if let Some(name) = open_tags.pop() {
+                        output.push_str(&format!("</{}>", name));
+                    }
+                }
+            }
+            continue;
+        }
+
+        if DROP_CONTENT_TAGS.contains(&tag.name.as_str()) {
+            if !tag.self_closing {
+                skip_until = Some(tag.name);
+            }
+            continue;
+        }
+
+        if !ALLOWED_TAGS.contains(&tag.name.as_str()) {
+            continue;
+        }
+
+        output.push('<');
+        output.push_str(&tag.name);
+        for (name, value) in &tag.attrs {
+            if let Some(value) = sanitize_attr(&tag.name, name, value) {
+                output.push_str(&format!(" {}=\"{}\"", name, value));
+            }
+        }
+        output.push('>');
+
+        if !tag.self_closing && !VOID_TAGS.contains(&tag.name.as_str()) {
+            open_tags.push(tag.name);
+        }
+    }
+
+    // 补全未闭合的标签
+    while let Some(name) = open_tags.pop() {
+        output.push_str(&format!("</{}>", name));
+    }
+
+    output
+}
+
+/// 转义纯文本，使其可以安全地嵌入HTML
+pub fn escape_html(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    for ch in text.chars() {
+        push_escaped_char(&mut output, ch);
+    }
+    output
+}
+
+/// 把RTF转换为HTML片段，只保留段落、粗体、斜体、下划线、删除线和文字颜色
+/// 转换结果同样会经过sanitize_html过滤
+pub fn rtf_to_html(rtf: &str) -> String {
+    let mut converter = RtfConverter::default();
+    converter.convert(rtf);
+    sanitize_html(&converter.finish())
+}
+
+/// 从HTML中提取纯文本，用于列表展示、搜索索引和粘贴时的纯文本备用格式
+/// 块级标签转换为换行，表格单元格之间用制表符分隔
+pub fn html_to_text(html: &str) -> String {
+    let sanitized = sanitize_html(html);
+    let mut output = String::with_capacity(sanitized.len());
+    let mut pos = 0;
+
+    while pos < sanitized.len() {
+        let rest = &sanitized[pos..];
+        let Some(ch) = rest.chars().next() else {
+            break;
+        };
+        if ch == '<' {
+            if let Some((tag, consumed)) = parse_tag(rest) {
+                pos += consumed;
+                match tag.name.as_str() {
+                    "br" | "hr" => output.push('\n'),
+                    "td" | "th" if tag.closing => output.push('\t'),
+                    name if tag.closing && TEXT_BLOCK_TAGS.contains(&name) => {
+                        if !output.is_empty() && !output.ends_with('\n') {
+                            output.push('\n');
+                        }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+        }
+        if ch == '&' {
+            if let Some((decoded, consumed)) = decode_entity(rest) {
+                output.push(decoded);
+                pos += consumed;
+                continue;
+            }
+        }
+        output.push(ch);
+        pos += ch.len_utf8();
+    }
+
+    output
+        .lines()
+        .map(|line| line.trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// 从RTF中提取纯文本
+pub fn rtf_to_text(rtf: &str) -> String {
+    html_to_text(&rtf_to_html(rtf))
+}
+
+/// 解析以'&'开头的HTML实体，返回字符和消耗的字节数
+fn decode_entity(input: &str) -> Option<(char, usize)> {
+    let end = input.char_indices().take(12).find(|(_, c)| *c == ';')?.0;
+    let entity = &input[1..end];
+    let ch = match entity {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        _ => {
+            let code = if let Some(hex) = entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+            {
+                u32::from_str_radix(hex, 16).ok()?
+            } else {
+                entity.strip_prefix('#')?.parse().ok()?
+            };
+            char::from_u32(code)?
+        }
+    };
+    Some((ch, end + 1))
+}
+
+struct HtmlTag {
+    name: String,
+    closing: bool,
+    self_closing: bool,
+    attrs: Vec<(String, String)>,
+}
+
+/// 解析以'<'开头的标签，返回标签和消耗的字节数
+fn parse_tag(input: &str) -> Option<(HtmlTag, usize)> {
+    let bytes = input.as_bytes();
+    let mut i = 1;
+    let closing = bytes.get(i) == Some(&b'/');
+    if closing {
+        i += 1;
+    }
+
+    let name_start = i;
+    if !bytes
+        .get(i)
+        .map(|b| b.is_ascii_alphabetic())
+        .unwrap_or(false)
+    {
+        return None;
+    }
+    while bytes
+        .get(i)
+        .map(|b| b.is_ascii_alphanumeric() || *b == b':' || *b == b'-')
+        .unwrap_or(false)
+    {
+        i += 1;
+    }
+    let name = input[name_start..i].to_ascii_lowercase();
+
+    // 找到标签结束位置，引号内的'>'不算结束
+    let mut quote: Option<u8> = None;
+    let mut end = None;
+    let mut j = i;
+    while j < bytes.len() {
+        match (quote, bytes[j]) {
+            (Some(q), b) if b == q => quote = None,
+            (Some(_), _) => {}
+            (None, b'"') | (None, b'\'') => quote = Some(bytes[j]),
+            (None, b'>') => {
+                end = Some(j);
+                break;
+            }
+            _ => {}
+        }
+        j += 1;
+    }
+    // 没有结束的标签，丢弃剩余内容
+    let end = end.unwrap_or(bytes.len());
+    let consumed = (end + 1).min(bytes.len());
+
+    let body = &input[i..end];
+    let self_closing = body.trim_end().ends_with('/');
+    let attrs = if closing {
+        Vec::new()
+    } else {
+        parse_attrs(body)
+    };
+
+    Some((
+        HtmlTag {
+            name,
+            closing,
+            self_closing,
+            attrs,
+        },
+        consumed,
+    ))
+}
+
+/// 解析标签中的属性列表
+fn parse_attrs(body: &str) -> Vec<(String, String)> {
+    let chars: Vec<char> = body.chars().collect();
+    let mut attrs = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        while i < chars.len() && (chars[i].is_whitespace() || chars[i] == '/') {
+            i += 1;
+        }
+        let name_start = i;
+        while i < chars.len() && !chars[i].is_whitespace() && chars[i] != '=' && chars[i] != '/' {
+            i += 1;
+        }
+        if name_start == i {
+            i += 1;
+            continue;
+        }
+        let name: String = chars[name_start..i]
+            .iter()
+            .collect::<String>()
+            .to_lowercase();
+
+        while i < chars.len() && chars[i].is_whitespace() {
+            i += 1;
+        }
+        let mut value = String::new();
+        if i < chars.len() && chars[i] == '=' {
+            i += 1;
+            while i < chars.len() && chars[i].is_whitespace() {
+                i += 1;
+            }
+            if i < chars.len() && (chars[i] == '"' || chars[i] == '\'') {
+                let quote = chars[i];
+                i += 1;
+                let value_start = i;
+                while i < chars.len() && chars[i] != quote {
+                    i += 1;
+                }
+                value = chars[value_start..i].iter().collect();
+                i += 1;
+            } else {
+                let value_start = i;
+                while i < chars.len() && !chars[i].is_whitespace() {
+                    i += 1;
+                }
+                value = chars[value_start..i].iter().collect();
+            }
+        }
+        attrs.push((name, value));
+    }
+
+    attrs
+}
+
+/// 过滤属性，返回可以安全输出的属性值
+fn sanitize_attr(tag: &str, name: &str, value: &str) -> Option<String> {
+    match (tag, name) {
+        (_, "style") => sanitize_style(value),
+        ("font", "color") | ("font", "face") | ("font", "size") => sanitize_plain_value(value),
+        ("td", "colspan") | ("td", "rowspan") | ("th", "colspan") | ("th", "rowspan") => {
+            value.parse::<u32>().ok().map(|n| n.min(100).to_string())
+        }
+        ("p", "align") | ("div", "align") | ("td", "align") | ("th", "align") => {
+            sanitize_plain_value(value)
+        }
+        _ => None,
+    }
+}
+
+/// 普通属性值只允许字母数字和少量符号
+fn sanitize_plain_value(value: &str) -> Option<String> {
+    let value = value.trim();
+    let valid = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_alphanumeric() || " #%.,-_".contains(c));
+    valid.then(|| value.to_string())
+}
+
+/// 过滤style属性，只保留白名单内的样式
+fn sanitize_style(style: &str) -> Option<String> {
+    let declarations: Vec<String> = style
+        .split(';')
+        .filter_map(|declaration| {
+            let (prop, value) = declaration.split_once(':')?;
+            let prop = prop.trim().to_ascii_lowercase();
+            let value = value.trim();
+            if !ALLOWED_STYLE_PROPS.contains(&prop.as_str()) || value.is_empty() {
+                return None;
+            }
+
+            let lower = value.to_ascii_lowercase();
+            if BLOCKED_STYLE_KEYWORDS.iter().any(|k| lower.contains(k)) {
+                return None;
+            }
+            if !value
+                .chars()
+                .all(|c| c.is_alphanumeric() || " #%.,-_()'".contains(c))
+            {
+                return None;
+            }
+            Some(format!("{}:{}", prop, value))
+        })
+        .collect();
+
+    (!declarations.is_empty()).then(|| declarations.join(";"))
+}
+
+fn push_escaped_char(output: &mut String, ch: char) {
+    match ch {
+        '<' => output.push_str("&lt;"),
+        '>' => output.push_str("&gt;"),
+        '"' => output.push_str("&quot;"),
+        _ => output.push(ch),
+    }
+}
+
+#[derive(Clone, Default, PartialEq)]
+struct RtfFormat {
+    bold: bool,
+    italic: bool,
+    underline: bool,
+    strike: bool,
+    color: usize,
+}
+
+#[derive(Clone)]
+struct RtfGroupState {
+    format: RtfFormat,
+    skip: bool,
+    in_color_table: bool,
+    unicode_skip: usize,
+}
+
+impl Default for RtfGroupState {
+    fn default() -> Self {
+        Self {
+            format: RtfFormat::default(),
+            skip: false,
+            in_color_table: false,
+            unicode_skip: 1,
+        }
+    }
+}
+
+#[derive(Default)]
+struct RtfConverter {
+    output: String,
+    state: RtfGroupState,
+    stack: Vec<RtfGroupState>,
+    colors: Vec<Option<String>>,
+    current_color: (u8, u8, u8, bool),
+    open_format: Option<RtfFormat>,
+    // \u之后需要跳过的替代字符数
+    pending_skip: usize,
+}
+
+impl RtfConverter {
+    fn convert(&mut self, rtf: &str) {
+        let chars: Vec<char> = rtf.chars().collect();
+        let mut i = 0;
+
+        while i < chars.len() {
+            let ch = chars[i];
+            match ch {
+                '{' => {
+                    self.stack.push(self.state.clone());
+                    i += 1;
+                }
+                '}' => {
+                    if let Some(state) = self.stack.pop() {
+                        self.state = state;
+                    }
+                    i += 1;
+                }
+                '\\' => {
+                    i = self.handle_control(&chars, i + 1);
+                }
+                '\r' | '\n' => {
+                    i += 1;
+                }
+                ';' if self.state.in_color_table => {
+                    let (r, g, b, defined) = self.current_color;
+                    self.colors
+                        .push(defined.then(|| format!("#{:02x}{:02x}{:02x}", r, g, b)));
+                    self.current_color = (0, 0, 0, false);
+                    i += 1;
+                }
+                _ => {
+                    self.push_text(ch);
+                    i += 1;
+                }
+            }
+        }
+    }
+
+    /// 处理控制字，返回处理后的位置
+    fn handle_control(&mut self, chars: &[char], mut i: usize) -> usize {
+        let Some(&ch) = chars.get(i) else {
+            return i;
+        };
+
+        if !ch.is_ascii_alphabetic() {
+            match ch {
+                '\\' | '{' | '}' => self.push_text(ch),
+                '~' => self.push_text('\u{a0}'),
+                '_' => self.push_text('-'),
+                '\r' | '\n' => self.push_html("<br>"),
+                '*' => self.state.skip = true,
+                '\'' => {
+                    let hex: String = chars.iter().skip(i + 1).take(2).collect();
+                    if let Ok(byte) = u8::from_str_radix(&hex, 16) {
+                        self.push_text(decode_cp1252(byte));
+                    }
+                    return i + 3;
+                }
+                _ => {}
+            }
+            return i + 1;
+        }
+
+        let word_start = i;
+        while i < chars.len() && chars[i].is_ascii_alphabetic() {
+            i += 1;
+        }
+        let word: String = chars[word_start..i].iter().collect();
+
+        let param_start = i;
+        if i < chars.len() && chars[i] == '-' {
+            i += 1;
+        }
+        while i < chars.len() && chars[i].is_ascii_digit() {
+            i += 1;
+        }
+        let param: Option<i32> = chars[param_start..i]
+            .iter()
+            .collect::<String>()
+            .parse()
+            .ok();
+
+        // 控制字后的单个空格是分隔符
+        if i < chars.len() && chars[i] == ' ' {
+            i += 1;
+        }
+
+        self.apply_control_word(&word, param);
+        i
+    }
+
+    fn apply_control_word(&mut self, word: &str, param: Option<i32>) {
+        let enabled = param.map(|p| p != 0).unwrap_or(true);
+        match word {
+            w if RTF_SKIP_DESTINATIONS.contains(&w) => self.state.skip = true,
+            "colortbl" => self.state.in_color_table = true,
+            "red" => self.current_color.0 = param.unwrap_or(0).clamp(0, 255) as u8,
+            "green" => self.current_color.1 = param.unwrap_or(0).clamp(0, 255) as u8,
+            "blue" => {
+                self.current_color.2 = param.unwrap_or(0).clamp(0, 255) as u8;
+                self.current_color.3 = true;
+            }
+            "par" | "line" => self.push_html("<br>"),
+            "tab" => self.push_text('\t'),
+            "b" => self.state.format.bold = enabled,
+            "i" => self.state.format.italic = enabled,
+            "ul" => self.state.format.underline = enabled,
+            "ulnone" => self.state.format.underline = false,
+            "strike" => self.state.format.strike = enabled,
+            "cf" => self.state.format.color = param.unwrap_or(0).max(0) as usize,
+            "plain" => self.state.format = RtfFormat::default(),
+            "uc" => self.state.unicode_skip = param.unwrap_or(1).max(0) as usize,
+            "u" => {
+                if let Some(code) = param {
+                    // 负数表示超过i16范围的码点
+                    let code = if code < 0 { code + 65536 } else { code };
+                    if let Some(ch) = char::from_u32(code as u32) {
+                        self.push_text(ch);
+                    }
+                    self.pending_skip = self.state.unicode_skip;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn push_text(&mut self, ch: char) {
+        if self.state.skip || self.state.in_color_table {
+            return;
+        }
+        if self.pending_skip > 0 {
+            self.pending_skip -= 1;
+            return;
+        }
+        self.switch_format();
+        push_escaped_char(&mut self.output, ch);
+    }
+
+    fn push_html(&mut self, html: &str) {
+        if self.state.skip || self.state.in_color_table {
+            return;
+        }
+        self.pending_skip = 0;
+        self.switch_format();
+        self.output.push_str(html);
+    }
+
+    /// 格式发生变化时关闭上一段span并开启新的span
+    fn switch_format(&mut self) {
+        let format = &self.state.format;
+        if self.open_format.as_ref() == Some(format) {
+            return;
+        }
+        if self.open_format.take().is_some() {
+            self.output.push_str("</span>");
+        }
+
+        let mut styles = Vec::new();
+        if format.bold {
+            styles.push("font-weight:bold".to_string());
+        }
+        if format.italic {
+            styles.push("font-style:italic".to_string());
+        }
+        match (format.underline, format.strike) {
+            (true, true) => styles.push("text-decoration:underline line-through".to_string()),
+            (true, false) => styles.push("text-decoration:underline".to_string()),
+            (false, true) => styles.push("text-decoration:line-through".to_string()),
+            _ => {}
+        }
+        if let Some(Some(color)) = self.colors.get(format.color) {
+            styles.push(format!("color:{}", color));
+        }
+
+        if !styles.is_empty() {
+            self.output
+                .push_str(&format!("<span style=\"{}\">", styles.join(";")));
+            self.open_format = Some(format.clone());
+        }
+    }
+
+    fn finish(mut self) -> String {
+        if self.open_format.take().is_some() {
+            self.output.push_str("</span>");
+        }
+        self.output
+    }
+}
+
+/// 把Windows-1252编码的字节转换为字符（\'hh转义）
+fn decode_cp1252(byte: u8) -> char {
+    const HIGH: [char; 32] = [
+        '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8d}', 'Ž',
+        '\u{8f}', '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9d}',
+        'ž', 'Ÿ',
+    ];
+    match byte {
+        0x80..=0x9f => HIGH[(byte - 0x80) as usize],
+        _ => byte as char,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_removes_scripts_and_events() {
+        let html = r#"<p onclick="alert(1)">hi<script>alert('x')</script></p><img src=x onerror=alert(1)>"#;
+        assert_eq!(sanitize_html(html), "<p>hi</p>");
+    }
+
+    #[test]
+    fn test_sanitize_keeps_formatting() {
+        let html = r#"<b>bold</b> <span style="color: red; position: fixed">red</span>"#;
+        assert_eq!(
+            sanitize_html(html),
+            r#"<b>bold</b> <span style="color:red">red</span>"#
+        );
+    }
+
+    #[test]
+    fn test_sanitize_blocks_style_injection() {
+        let html = r#"<div style="background-color: url(javascript:alert(1))">x</div>"#;
+        assert_eq!(sanitize_html(html), "<div>x</div>");
+        let html = r#"<span style="color:red&quot; onmouseover=&quot;alert(1)">x</span>"#;
+        assert_eq!(sanitize_html(html), "<span>x</span>");
+    }
+
+    #[test]
+    fn test_sanitize_unknown_tags_and_unclosed() {
+        let html = "<html><body><a href=\"javascript:alert(1)\">link</a><i>open";
+        assert_eq!(sanitize_html(html), "link<i>open</i>");
+        assert_eq!(sanitize_html("1 < 2 > 0"), "1 &lt; 2 &gt; 0");
+        assert_eq!(sanitize_html("<!-- <script> -->ok"), "ok");
+    }
+
+    #[test]
+    fn test_rtf_to_html() {
+        let rtf = r"{\rtf1\ansi{\fonttbl{\f0 Arial;}}{\colortbl;\red255\green0\blue0;}\f0 Hello \b bold\b0  \cf1 red\cf0\par caf\'e9 \u20320?}";
+        assert_eq!(
+            rtf_to_html(rtf),
+            r#"Hello <span style="font-weight:bold">bold</span> <span style="color:#ff0000">red</span><br>café 你"#
+        );
+    }
+
+    #[test]
+    fn test_html_to_text() {
+        let html = "<html><head><style>p{}</style></head><body><p>Hello <b>world</b></p><p>a &amp; b &lt;c&gt;&nbsp;&#20320;</p><table><tr><td>1</td><td>2</td></tr></table></body></html>";
+        assert_eq!(html_to_text(html), "Hello world\na & b <c> 你\n1\t2");
+        assert_eq!(html_to_text("<script>alert(1)</script>"), "");
+    }
+
+    #[test]
+    fn test_rtf_to_text() {
+        let rtf = r"{\rtf1\ansi{\fonttbl{\f0 Arial;}}\f0 Hello \b bold\b0\par caf\'e9}";
+        assert_eq!(rtf_to_text(rtf), "Hello bold\ncafé");
+    }
+
+    #[test]
+    fn test_rtf_escapes_text() {
+        let rtf = r"{\rtf1 <script>alert(1)</script>}";
+        assert_eq!(rtf_to_html(rtf), "&lt;script&gt;alert(1)&lt;/script&gt;");
+    }
+}
@@ -6,6 +6,8 @@ use crate::utils::file_dir::get_data_dir;
 use base64::engine::general_purpose::STANDARD;
 use base64::Engine;
 use once_cell::sync::Lazy;
+use rand::rngs::OsRng;
+use rand::TryRngCore;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -21,9 +23,14 @@ pub struct SecureData {
     pub token_expires: Option<i32>,
 
     // 新增VIP相关字段
-    pub vip_info: Option<String>,      // JSON序列化的VIP信息
-    pub vip_last_check: Option<u64>,   // 上次检查VIP状态的时间戳
-    pub server_config: Option<String>, // 服务器配置信息
+    pub vip_info: Option<String>,            // JSON序列化的VIP信息
+    pub vip_last_check: Option<u64>,         // 上次检查VIP状态的时间戳
+    pub server_config: Option<String>,       // 服务器配置信息
+    pub vip_downgrade_since: Option<u64>,    // 记录数超出VIP限制的开始时间戳(秒)
+    pub request_sign_secret: Option<String>, // 请求签名使用的设备密钥(Base64)
+    pub kiosk_pin_hash: Option<String>,      // 退出只读模式的PIN摘要
+    pub kiosk_pin_failures: Option<u32>,     // 退出只读模式时PIN连续错误的次数
+    pub kiosk_pin_locked_until: Option<u64>, // PIN错误过多时锁定到的时间戳(毫秒)
 }
 
 pub struct SecureStore {
@@ -267,6 +274,123 @@ impl SecureStore {
         self.data.vip_last_check = Some(current_time);
         self.save()
     }
+
+    /// 获取记录数超出VIP限制的开始时间，未记录时以当前时间作为开始时间
+    pub fn get_or_mark_vip_downgrade_since(&mut self) -> AppResult<u64> {
+        if !self.loaded {
+            self.load()?;
+        }
+
+        if let Some(since) = self.data.vip_downgrade_since {
+            return Ok(since);
+        }
+
+        let current_time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        self.data.vip_downgrade_since = Some(current_time);
+        self.save()?;
+        Ok(current_time)
+    }
+
+    /// 清除记录数超出VIP限制的开始时间
+    pub fn clear_vip_downgrade_since(&mut self) -> AppResult<()> {
+        if !self.loaded {
+            self.load()?;
+        }
+
+        if self.data.vip_downgrade_since.is_none() {
+            return Ok(());
+        }
+
+        self.data.vip_downgrade_since = None;
+        self.save()
+    }
+
+    /// 获取请求签名密钥，不存在时生成新的随机密钥并保存
+    pub fn get_or_create_request_sign_secret(&mut self) -> AppResult<String> {
+        if !self.loaded {
+            self.load()?;
+        }
+
+        if let Some(secret) = &self.data.request_sign_secret {
+            return Ok(secret.clone());
+        }
+
+        let secret = generate_sign_secret()?;
+        self.data.request_sign_secret = Some(secret.clone());
+        self.save()?;
+        Ok(secret)
+    }
+
+    /// 重新生成请求签名密钥（密钥泄露时使用，服务端需要同步更新）
+    pub fn reset_request_sign_secret(&mut self) -> AppResult<String> {
+        if !self.loaded {
+            self.load()?;
+        }
+
+        let secret = generate_sign_secret()?;
+        self.data.request_sign_secret = Some(secret.clone());
+        self.save()?;
+        Ok(secret)
+    }
+
+    /// 获取退出只读模式的PIN摘要
+    pub fn get_kiosk_pin_hash(&mut self) -> AppResult<Option<String>> {
+        if !self.loaded {
+            self.load()?;
+        }
+
+        Ok(self.data.kiosk_pin_hash.clone())
+    }
+
+    /// 设置退出只读模式的PIN摘要，None 表示不需要PIN
+    pub fn set_kiosk_pin_hash(&mut self, pin_hash: Option<String>) -> AppResult<()> {
+        if !self.loaded {
+            self.load()?;
+        }
+
+        self.data.kiosk_pin_hash = pin_hash;
+        self.save()
+    }
+
+    /// 获取PIN连续错误的次数和锁定到的时间戳(毫秒)
+    pub fn get_kiosk_pin_failures(&mut self) -> AppResult<(u32, Option<u64>)> {
+        if !self.loaded {
+            self.load()?;
+        }
+
+        Ok((
+            self.data.kiosk_pin_failures.unwrap_or(0),
+            self.data.kiosk_pin_locked_until,
+        ))
+    }
+
+    /// 设置PIN连续错误的次数和锁定到的时间戳，PIN正确或重新设置PIN时清零
+    pub fn set_kiosk_pin_failures(
+        &mut self,
+        failures: u32,
+        locked_until: Option<u64>,
+    ) -> AppResult<()> {
+        if !self.loaded {
+            self.load()?;
+        }
+
+        self.data.kiosk_pin_failures = Some(failures).filter(|failures| *failures > 0);
+        self.data.kiosk_pin_locked_until = locked_until;
+        self.save()
+    }
+}
+
+/// 生成32字节的随机签名密钥
+fn generate_sign_secret() -> AppResult<String> {
+    let mut bytes = [0u8; 32];
+    OsRng
+        .try_fill_bytes(&mut bytes)
+        .map_err(|e| AppError::Crypto(format!("生成随机数失败: {}", e)))?;
+    Ok(STANDARD.encode(bytes))
 }
 
 pub static SECURE_STORE: Lazy<RwLock<SecureStore>> =
@@ -0,0 +1,35 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// 敏感记录的日志控制
+// 处理敏感记录时（同步请求、复制粘贴）日志中不输出内容，只输出长度等元信息
+
+tokio::task_local! {
+    // 当前异步任务是否禁止在日志中输出内容
+    static CONTENT_LOG_SUPPRESSED: bool;
+}
+
+// 剪贴板当前内容是否来自敏感记录，自动粘贴在独立线程中执行，无法使用task_local
+static CLIPBOARD_SENSITIVE: AtomicBool = AtomicBool::new(false);
+
+/// 在禁止输出内容日志的范围内执行异步任务，suppressed 为 false 时正常输出
+pub async fn with_content_log_suppressed<F: Future>(suppressed: bool, fut: F) -> F::Output {
+    CONTENT_LOG_SUPPRESSED.scope(suppressed, fut).await
+}
+
+/// 当前异步任务是否禁止在日志中输出内容
+pub fn is_content_log_suppressed() -> bool {
+    CONTENT_LOG_SUPPRESSED
+        .try_with(|suppressed| *suppressed)
+        .unwrap_or(false)
+}
+
+/// 记录写入剪贴板的内容是否来自敏感记录
+pub fn set_clipboard_sensitive(sensitive: bool) {
+    CLIPBOARD_SENSITIVE.store(sensitive, Ordering::SeqCst);
+}
+
+/// 剪贴板当前内容是否来自敏感记录
+pub fn is_clipboard_sensitive() -> bool {
+    CLIPBOARD_SENSITIVE.load(Ordering::SeqCst)
+}
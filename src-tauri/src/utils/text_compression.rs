@@ -0,0 +1,71 @@
+use crate::errors::{AppError, AppResult};
+
+// 文本压缩工具
+// 大文本在加密前先压缩（先压缩后加密），解密后根据标记判断是否需要解压
+
+// 压缩数据的标记，正常文本不会以 0x00 开头
+const COMPRESSED_MAGIC: &[u8] = b"\0CPZ1";
+
+// zstd 压缩级别，兼顾速度和压缩率
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// 文本达到阈值时压缩，压缩后没有变小则返回原始字节，threshold 为0时不压缩
+pub fn compress_text(content: &str, threshold: u64) -> Vec<u8> {
+    let bytes = content.as_bytes();
+    if threshold == 0 || (bytes.len() as u64) < threshold {
+        return bytes.to_vec();
+    }
+    match zstd::encode_all(bytes, COMPRESSION_LEVEL) {
+        Ok(compressed) if compressed.len() + COMPRESSED_MAGIC.len() < bytes.len() => {
+            let mut result = Vec::with_capacity(COMPRESSED_MAGIC.len() + compressed.len());
+            result.extend_from_slice(COMPRESSED_MAGIC);
+            result.extend_from_slice(&compressed);
+            result
+        }
+        Ok(_) => bytes.to_vec(),
+        Err(e) => {
+            log::warn!("文本压缩失败，按原文保存: {}", e);
+            bytes.to_vec()
+        }
+    }
+}
+
+/// 数据是否带有压缩标记
+pub fn is_compressed(data: &[u8]) -> bool {
+    data.starts_with(COMPRESSED_MAGIC)
+}
+
+/// 带有压缩标记的数据解压，没有标记的数据原样返回
+pub fn decompress_text(data: Vec<u8>) -> AppResult<Vec<u8>> {
+    match data.strip_prefix(COMPRESSED_MAGIC) {
+        Some(compressed) => zstd::decode_all(compressed)
+            .map_err(|e| AppError::Crypto(format!("文本解压失败: {}", e))),
+        None => Ok(data),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compress_text_round_trip() {
+        let content = "剪贴板内容 clipboard content\n".repeat(1000);
+        let compressed = compress_text(&content, 1024);
+        assert!(is_compressed(&compressed));
+        assert!(compressed.len() < content.len());
+        assert_eq!(decompress_text(compressed).unwrap(), content.as_bytes());
+    }
+
+    #[test]
+    fn test_compress_text_keeps_small_text() {
+        let content = "short text";
+        assert_eq!(compress_text(content, 1024), content.as_bytes());
+        assert_eq!(compress_text(content, 0), content.as_bytes());
+        assert!(!is_compressed(content.as_bytes()));
+        assert_eq!(
+            decompress_text(content.as_bytes().to_vec()).unwrap(),
+            content.as_bytes()
+        );
+    }
+}
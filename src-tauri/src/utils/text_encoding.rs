@@ -0,0 +1,101 @@
+use encoding_rs::{GBK, WINDOWS_1252};
+
+// 乱码文本恢复工具
+// 部分旧程序按本地编码（GBK等）输出文本，被按Latin-1/Windows-1252解码后变成 "ÖÐÎÄ" 这样的乱码
+
+/// 判断字符是否为中文字符或中文标点
+fn is_cjk_char(c: char) -> bool {
+    matches!(
+        c,
+        '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' | '\u{3000}'..='\u{303F}' | '\u{FF00}'..='\u{FFEF}'
+    )
+}
+
+/// 检查非ASCII字节是否都是成对出现的双字节字符（常用汉字的首尾字节都不小于0x80）
+/// 西文重音字符后面通常跟着ASCII字母，例如 "crème" 中的 0xE8 0x6D
+fn is_double_byte_sequence(bytes: &[u8]) -> bool {
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] < 0x80 {
+            index += 1;
+            continue;
+        }
+        match bytes.get(index + 1) {
+            Some(trail) if *trail >= 0x80 => index += 2,
+            _ => return false,
+        }
+    }
+    true
+}
+
+/// 尝试恢复被错误按Windows-1252解码的文本，返回恢复后的文本和原始编码名称
+/// 文本中不是乱码特征时返回None，避免误改正常的西文内容
+pub fn recover_mojibake(text: &str) -> Option<(String, &'static str)> {
+    if text.is_ascii() {
+        return None;
+    }
+
+    // 乱码文本中的字符都能还原为单字节，包含其他字符说明不是这种乱码
+    let (bytes, _, had_unmappable) = WINDOWS_1252.encode(text);
+    if had_unmappable {
+        return None;
+    }
+
+    // 原始内容是UTF-8
+    if let Ok(utf8) = std::str::from_utf8(&bytes) {
+        if utf8 != text {
+            return Some((utf8.to_string(), "UTF-8"));
+        }
+        return None;
+    }
+
+    // 原始内容是GBK，要求恢复后的非ASCII字符全部是中文，避免把正常的西文重音字符误判为GBK
+    if !is_double_byte_sequence(&bytes) {
+        return None;
+    }
+    let (decoded, had_errors) = GBK.decode_without_bom_handling(&bytes);
+    if had_errors {
+        return None;
+    }
+    let all_cjk = decoded.chars().filter(|c| !c.is_ascii()).all(is_cjk_char);
+    if all_cjk {
+        return Some((decoded.into_owned(), "GBK"));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 模拟旧程序输出的字节被按Windows-1252解码
+    fn garble(bytes: &[u8]) -> String {
+        WINDOWS_1252
+            .decode_without_bom_handling(bytes)
+            .0
+            .into_owned()
+    }
+
+    #[test]
+    fn test_recover_mojibake() {
+        let (gbk_bytes, _, _) = GBK.encode("中文测试");
+        assert_eq!(
+            recover_mojibake(&garble(&gbk_bytes)),
+            Some(("中文测试".to_string(), "GBK"))
+        );
+
+        let utf8_garbled = garble("café 中文".as_bytes());
+        assert_eq!(
+            recover_mojibake(&utf8_garbled),
+            Some(("café 中文".to_string(), "UTF-8"))
+        );
+    }
+
+    #[test]
+    fn test_recover_mojibake_keeps_normal_text() {
+        assert_eq!(recover_mojibake("hello world"), None);
+        assert_eq!(recover_mojibake("café crème"), None);
+        assert_eq!(recover_mojibake("crème"), None);
+        assert_eq!(recover_mojibake("正常的中文"), None);
+    }
+}
@@ -0,0 +1,61 @@
+use std::borrow::Cow;
+
+// 文本清理工具
+// 网页复制的文本中经常夹带零宽字符、方向控制符等不可见字符，会影响搜索和粘贴
+
+/// 判断字符是否为需要清理的不可见字符
+/// 零宽连接符（U+200D）和零宽非连接符（U+200C）用于组合表情和部分文字的正确显示，予以保留
+fn is_invisible_char(c: char) -> bool {
+    match c {
+        // 保留制表符和换行
+        '\t' | '\n' | '\r' => false,
+        // 软连字符
+        '\u{00AD}' => true,
+        // 蒙古文元音分隔符
+        '\u{180E}' => true,
+        // 零宽空格
+        '\u{200B}' => true,
+        // 从左到右/从右到左标记
+        '\u{200E}' | '\u{200F}' => true,
+        // 方向嵌入与覆盖控制符
+        '\u{202A}'..='\u{202E}' => true,
+        // 单词连接符及不可见运算符
+        '\u{2060}'..='\u{2064}' => true,
+        // 方向隔离控制符
+        '\u{2066}'..='\u{2069}' => true,
+        // 字节顺序标记（零宽不换行空格）
+        '\u{FEFF}' => true,
+        // 其他控制字符
+        c => c.is_control(),
+    }
+}
+
+/// 清理文本中的不可见字符，没有需要清理的字符时不分配新字符串
+pub fn sanitize_text(text: &str) -> Cow<'_, str> {
+    if !text.chars().any(is_invisible_char) {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(text.chars().filter(|c| !is_invisible_char(*c)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_text_strips_invisible_chars() {
+        assert_eq!(sanitize_text("hello\u{200B}world"), "helloworld");
+        assert_eq!(sanitize_text("\u{FEFF}abc\u{200E}"), "abc");
+        assert_eq!(sanitize_text("a\u{0000}b\u{0007}c\u{007F}"), "abc");
+        assert_eq!(sanitize_text("soft\u{00AD}hyphen"), "softhyphen");
+    }
+
+    #[test]
+    fn test_sanitize_text_keeps_visible_content() {
+        let text = "第一行\n\tsecond line\r\n";
+        assert!(matches!(sanitize_text(text), Cow::Borrowed(_)));
+        // 组合表情中的零宽连接符需要保留
+        let emoji = "👨\u{200D}👩\u{200D}👧";
+        assert_eq!(sanitize_text(emoji), emoji);
+    }
+}
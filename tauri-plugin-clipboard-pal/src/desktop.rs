@@ -9,6 +9,41 @@ use image::EncodableLayout;
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex};
 
+// GIF图片在各平台剪贴板中的格式名称，读取时依次尝试
+const GIF_FORMATS: [&str; 3] = ["image/gif", "com.compuserve.gif", "GIF"];
+
+// 写入GIF时使用当前平台的格式名称
+#[cfg(target_os = "macos")]
+const GIF_FORMAT: &str = "com.compuserve.gif";
+#[cfg(target_os = "windows")]
+const GIF_FORMAT: &str = "GIF";
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const GIF_FORMAT: &str = "image/gif";
+
+// APNG图片在各平台剪贴板中的格式名称，读取时依次尝试
+const APNG_FORMATS: [&str; 4] = ["image/apng", "image/png", "public.png", "PNG"];
+
+// 写入APNG时使用当前平台的PNG格式名称，APNG本身是合法的PNG，不支持动画的应用显示第一帧
+#[cfg(target_os = "macos")]
+const APNG_FORMAT: &str = "public.png";
+#[cfg(target_os = "windows")]
+const APNG_FORMAT: &str = "PNG";
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const APNG_FORMAT: &str = "image/png";
+
+// 密码管理器等应用标记“不要记录”的剪贴板格式，只要存在即视为隐藏内容
+// macOS: http://nspasteboard.org  Windows: 剪贴板历史和监视程序约定的格式
+#[cfg(target_os = "macos")]
+const CONCEALED_FORMATS: [&str; 1] = ["org.nspasteboard.ConcealedType"];
+#[cfg(target_os = "windows")]
+const CONCEALED_FORMATS: [&str; 1] = ["ExcludeClipboardContentFromMonitorProcessing"];
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const CONCEALED_FORMATS: [&str; 0] = [];
+
+// KDE/KeePassXC 在Linux上使用的密码提示格式，值为 secret 时视为隐藏内容
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const KDE_PASSWORD_HINT_FORMAT: &str = "x-kde-passwordManagerHint";
+
 pub fn init() -> crate::Result<ClipboardPal> {
     let clipboard_context = ClipboardRsContext::new().map_err(|e| {
         crate::Error::Io(std::io::Error::new(
@@ -101,6 +136,17 @@ impl ClipboardPal {
             .map_err(|err| err.to_string())
     }
 
+    pub fn write_rtf_and_text(&self, rtf: String, text: String) -> Result<(), String> {
+        self.clipboard
+            .lock()
+            .map_err(|err| err.to_string())?
+            .set(vec![
+                ClipboardContent::Text(text),
+                ClipboardContent::Rtf(rtf),
+            ])
+            .map_err(|err| err.to_string())
+    }
+
     pub fn write_rtf(&self, rtf: String) -> Result<(), String> {
         self.clipboard
             .lock()
@@ -129,6 +175,32 @@ impl ClipboardPal {
         Ok(())
     }
 
+    /// 按原格式写入GIF图片，同时写入第一帧作为不支持GIF的应用的备用格式
+    pub fn write_gif_binary(&self, bytes: Vec<u8>) -> Result<(), String> {
+        let first_frame =
+            RustImageData::from_bytes(bytes.as_bytes()).map_err(|err| err.to_string())?;
+        self.clipboard
+            .lock()
+            .map_err(|err| err.to_string())?
+            .set(vec![
+                ClipboardContent::Other(GIF_FORMAT.to_string(), bytes),
+                ClipboardContent::Image(first_frame),
+            ])
+            .map_err(|err| err.to_string())
+    }
+
+    /// 按原格式写入APNG图片，保留动画
+    pub fn write_apng_binary(&self, bytes: Vec<u8>) -> Result<(), String> {
+        self.clipboard
+            .lock()
+            .map_err(|err| err.to_string())?
+            .set(vec![ClipboardContent::Other(
+                APNG_FORMAT.to_string(),
+                bytes,
+            )])
+            .map_err(|err| err.to_string())
+    }
+
     pub fn start_monitor(&self, manager: Arc<EventManager<ClipboardEvent>>) -> Result<(), String> {
         let clipboard = ClipboardMonitor::new(self.clipboard.clone(), manager);
         let mut watcher = ClipboardWatcherContext::new()
@@ -160,6 +232,35 @@ impl ClipboardPal {
         Ok(())
     }
 
+    /// 读取剪贴板当前内容，转换为与监听器相同的剪贴板事件
+    pub fn read_current_event(&self) -> Result<Option<ClipboardEvent>, String> {
+        let clipboard_context = self.clipboard.lock().map_err(|err| err.to_string())?;
+        Ok(read_clipboard_event(&clipboard_context))
+    }
+
+    /// 读取剪贴板当前内容，文本内容同时带有HTML或RTF格式时一并读取（HTML优先），纯文本作为粘贴时的备用格式
+    pub fn read_current_rich_event(&self) -> Result<Option<ClipboardEvent>, String> {
+        let clipboard_context = self.clipboard.lock().map_err(|err| err.to_string())?;
+        let mut event = read_clipboard_event(&clipboard_context);
+        if let Some(event) = event
+            .as_mut()
+            .filter(|event| matches!(event.r#type, ClipType::Text))
+        {
+            if clipboard_context.has(ContentFormat::Html) {
+                if let Ok(html) = clipboard_context.get_html() {
+                    event.rich_type = Some(ClipType::Html);
+                    event.rich_content = Some(html);
+                }
+            } else if clipboard_context.has(ContentFormat::Rtf) {
+                if let Ok(rtf) = clipboard_context.get_rich_text() {
+                    event.rich_type = Some(ClipType::Rtf);
+                    event.rich_content = Some(rtf);
+                }
+            }
+        }
+        Ok(event)
+    }
+
     pub fn is_monitor_running(&self) -> bool {
         self.watcher_shutdown
             .lock()
@@ -192,75 +293,197 @@ impl ClipboardHandler for ClipboardMonitor {
             }
         };
 
-        // 先判断是不是图片   不管clipboard_context.get_image()得到的是什么类型的图片，统一使用image.to_png()转为png格式
-        // 其实大多数情况是针对截图软件的截图功能，截图软件截取的图片是没有形成实际的图片文件的，只有图片二进制数据
-        if clipboard_context.has(ContentFormat::Image) {
-            let img_context = clipboard_context.get_image().map_err(|err| err.to_string());
-            if let Ok(image) = img_context {
-                if let Ok(png) = image.to_png() {
-                    self.manager.emit(ClipboardEvent {
-                        r#type: ClipType::Image,
-                        content: "".to_string(),
-                        file: Some(png.get_bytes().to_vec()),
-                        file_path_vec: None,
-                    });
-                }
-                return;
-            }
+        if let Some(event) = read_clipboard_event(&clipboard_context) {
+            self.manager.emit(event);
+        }
+    }
+}
+
+/// 读取剪贴板中指定格式的原始数据，依次尝试各个格式名称，返回第一个满足条件的数据
+fn read_raw_buffer(
+    clipboard_context: &ClipboardRsContext,
+    formats: &[&str],
+    accept: fn(&[u8]) -> bool,
+) -> Option<Vec<u8>> {
+    formats.iter().find_map(|format| {
+        if !clipboard_context.has(ContentFormat::Other(format.to_string())) {
+            return None;
+        }
+        clipboard_context
+            .get_buffer(format)
+            .ok()
+            .filter(|bytes| accept(bytes))
+    })
+}
+
+fn is_gif(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a")
+}
+
+/// 判断是否为APNG动图：PNG数据在第一个IDAT块之前带有acTL块
+/// 应用保存和复制图片时也使用该函数判断，两处的判断保持一致
+pub fn is_animated_png(bytes: &[u8]) -> bool {
+    const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+    if !bytes.starts_with(&PNG_SIGNATURE) {
+        return false;
+    }
+    let mut offset = PNG_SIGNATURE.len();
+    // 每个块为 长度(4) + 类型(4) + 数据 + CRC(4)
+    while offset + 8 <= bytes.len() {
+        let length = u32::from_be_bytes([
+            bytes[offset],
+            bytes[offset + 1],
+            bytes[offset + 2],
+            bytes[offset + 3],
+        ]) as usize;
+        match &bytes[offset + 4..offset + 8] {
+            b"acTL" => return true,
+            b"IDAT" | b"IEND" => return false,
+            _ => {}
         }
-        // 再判断是不是文件   这个文件包含了各种类型的文件，比如图片、视频、文件夹等等，是实际存在于我们硬盘中的文件
-        if clipboard_context.has(ContentFormat::Files) {
-            let file_context = clipboard_context.get_files().map_err(|err| err.to_string());
-            if let Ok(content) = file_context {
-                self.manager.emit(ClipboardEvent {
-                    r#type: ClipType::File,
-                    content: "".to_string(),
+        offset = match offset.checked_add(12 + length) {
+            Some(next) => next,
+            None => return false,
+        };
+    }
+    false
+}
+
+/// 读取剪贴板中原始的GIF、APNG动图数据，返回数据和MIME类型
+/// 浏览器等应用复制动图时会同时提供原格式，APNG只在PNG格式的数据带有动画时保留
+fn read_animated_image_bytes(
+    clipboard_context: &ClipboardRsContext,
+) -> Option<(Vec<u8>, &'static str)> {
+    if let Some(gif) = read_raw_buffer(clipboard_context, &GIF_FORMATS, is_gif) {
+        return Some((gif, "image/gif"));
+    }
+    read_raw_buffer(clipboard_context, &APNG_FORMATS, is_animated_png)
+        .map(|apng| (apng, "image/apng"))
+}
+
+/// 检查剪贴板内容是否带有隐藏提示
+fn is_concealed(clipboard_context: &ClipboardRsContext) -> bool {
+    if CONCEALED_FORMATS
+        .iter()
+        .any(|format| clipboard_context.has(ContentFormat::Other(format.to_string())))
+    {
+        return true;
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        if clipboard_context.has(ContentFormat::Other(KDE_PASSWORD_HINT_FORMAT.to_string())) {
+            return clipboard_context
+                .get_buffer(KDE_PASSWORD_HINT_FORMAT)
+                .map(|hint| hint.trim_ascii() == b"secret")
+                .unwrap_or(false);
+        }
+    }
+    false
+}
+
+/// 读取剪贴板当前内容，并标记是否带有隐藏提示
+fn read_clipboard_event(clipboard_context: &ClipboardRsContext) -> Option<ClipboardEvent> {
+    let mut event = read_clipboard_content(clipboard_context)?;
+    event.concealed = is_concealed(clipboard_context);
+    Some(event)
+}
+
+/// 按 图片 > 文件 > 富文本（没有纯文本时） > 文本 的优先级读取剪贴板当前内容
+fn read_clipboard_content(clipboard_context: &ClipboardRsContext) -> Option<ClipboardEvent> {
+    // 剪贴板中有GIF、APNG动图时保留原始数据，转为png会丢失动画
+    if let Some((bytes, mime)) = read_animated_image_bytes(clipboard_context) {
+        return Some(ClipboardEvent {
+            r#type: ClipType::Image,
+            content: "".to_string(),
+            file: Some(bytes),
+            file_path_vec: None,
+            rich_type: None,
+            rich_content: None,
+            concealed: false,
+            image_format: Some(mime.to_string()),
+        });
+    }
+    // 先判断是不是图片   不管clipboard_context.get_image()得到的是什么类型的图片，统一使用image.to_png()转为png格式
+    // 其实大多数情况是针对截图软件的截图功能，截图软件截取的图片是没有形成实际的图片文件的，只有图片二进制数据
+    if clipboard_context.has(ContentFormat::Image) {
+        let img_context = clipboard_context.get_image().map_err(|err| err.to_string());
+        if let Ok(image) = img_context {
+            return image.to_png().ok().map(|png| ClipboardEvent {
+                r#type: ClipType::Image,
+                content: "".to_string(),
+                file: Some(png.get_bytes().to_vec()),
+                file_path_vec: None,
+                rich_type: None,
+                rich_content: None,
+                concealed: false,
+                image_format: None,
+            });
+        }
+    }
+    // 再判断是不是文件   这个文件包含了各种类型的文件，比如图片、视频、文件夹等等，是实际存在于我们硬盘中的文件
+    if clipboard_context.has(ContentFormat::Files) {
+        let file_context = clipboard_context.get_files().map_err(|err| err.to_string());
+        if let Ok(content) = file_context {
+            return Some(ClipboardEvent {
+                r#type: ClipType::File,
+                content: "".to_string(),
+                file: None,
+                file_path_vec: Some(content),
+                rich_type: None,
+                rich_content: None,
+                concealed: false,
+                image_format: None,
+            });
+        }
+    }
+    // 文件类型的就判断完了
+
+    // 再判断是不是只有富文本没有纯文本的内容（同时带有纯文本时按文本记录，富文本格式由 read_current_rich_event 一并读取）
+    if !clipboard_context.has(ContentFormat::Text) {
+        if clipboard_context.has(ContentFormat::Html) {
+            if let Ok(html) = clipboard_context.get_html() {
+                return Some(ClipboardEvent {
+                    r#type: ClipType::Html,
+                    content: html,
                     file: None,
-                    file_path_vec: Some(content),
+                    file_path_vec: None,
+                    rich_type: None,
+                    rich_content: None,
+                    concealed: false,
+                    image_format: None,
                 });
-                return;
             }
         }
-        // 文件类型的就判断完了
-
-        // 再判断是不是富文本内容
-        // if clipboard_context.has(ContentFormat::Rtf) {
-        //     let text_context = clipboard_context
-        //         .get_rich_text()
-        //         .map_err(|err| err.to_string());
-        //     if let Ok(content) = text_context {
-        //         self.manager.emit(ClipboardEvent {
-        //             r#type: ClipType::Rtf,
-        //             content: content,
-        //             file: None,
-        //         });
-        //         return;
-        //     }
-        // }
-        // // 再判断是不是html
-        // if clipboard_context.has(ContentFormat::Html) {
-        //     let text_context = clipboard_context.get_html().map_err(|err| err.to_string());
-        //     if let Ok(content) = text_context {
-        //         self.manager.emit(ClipboardEvent {
-        //             r#type: ClipType::Html,
-        //             content: content,
-        //             file: None,
-        //         });
-        //         return;
-        //     }
-        // }
-        // 最后判断是不是普通文本
-        if clipboard_context.has(ContentFormat::Text) {
-            let text_context = clipboard_context.get_text().map_err(|err| err.to_string());
-            if let Ok(text) = text_context {
-                self.manager.emit(ClipboardEvent {
-                    r#type: ClipType::Text,
-                    content: text,
+        if clipboard_context.has(ContentFormat::Rtf) {
+            if let Ok(rtf) = clipboard_context.get_rich_text() {
+                return Some(ClipboardEvent {
+                    r#type: ClipType::Rtf,
+                    content: rtf,
                     file: None,
                     file_path_vec: None,
+                    rich_type: None,
+                    rich_content: None,
+                    concealed: false,
+                    image_format: None,
                 });
-                return;
             }
         }
     }
+    // 最后判断是不是普通文本
+    if clipboard_context.has(ContentFormat::Text) {
+        let text_context = clipboard_context.get_text().map_err(|err| err.to_string());
+        if let Ok(text) = text_context {
+            return Some(ClipboardEvent {
+                r#type: ClipType::Text,
+                content: text,
+                file: None,
+                file_path_vec: None,
+                rich_type: None,
+                rich_content: None,
+                concealed: false,
+                image_format: None,
+            });
+        }
+    }
+    None
 }